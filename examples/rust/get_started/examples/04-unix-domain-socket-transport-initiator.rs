@@ -1,7 +1,7 @@
-// This node routes a message, to a worker on a different node, over the tcp transport.
+// This node routes a message, to a worker on a different node, over the uds transport.
 
 use ockam::{node, route, Context, Result};
-use ockam_transport_uds::{UdsTransportExtension, UDS};
+use ockam_transport_uds::{UdsConnectionOptions, UdsTransportExtension};
 
 #[ockam::node]
 async fn main(ctx: Context) -> Result<()> {
@@ -11,13 +11,13 @@ async fn main(ctx: Context) -> Result<()> {
     // Initialize the UDS Transport
     let uds = node.create_uds_transport().await?;
 
-    let connection = uds.connect("/tmp/ockam-example-echoer").await;
+    // Create a UDS connection to a different node.
+    let connection = uds
+        .connect("/tmp/ockam-example-echoer", UdsConnectionOptions::new())
+        .await?;
 
-    if let Err(e) = connection {
-        println!("Error connecting to echoer {e}");
-    }
     // Send a message to the "echoer" worker, on a different node, over a uds transport.
-    let r = route![(UDS, "/tmp/ockam-example-echoer"), "echoer"];
+    let r = route![connection, "echoer"];
     node.send(r, "Hello Ockam!".to_string()).await?;
 
     // Wait to receive a reply and print it.
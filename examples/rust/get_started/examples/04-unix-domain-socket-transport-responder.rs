@@ -3,7 +3,7 @@
 
 use hello_ockam::Echoer;
 use ockam::{node, Context, Result};
-use ockam_transport_uds::UdsTransportExtension;
+use ockam_transport_uds::{UdsListenerOptions, UdsTransportExtension};
 
 #[ockam::node]
 async fn main(ctx: Context) -> Result<()> {
@@ -14,11 +14,16 @@ async fn main(ctx: Context) -> Result<()> {
     let uds = node.create_uds_transport().await?;
 
     // Create a Uds listener and wait for incoming connections.
-    uds.listen("/tmp/ockam-example-echoer").await?;
+    let listener = uds
+        .listen("/tmp/ockam-example-echoer", UdsListenerOptions::new())
+        .await?;
 
     // Create an echoer worker
     node.start_worker("echoer", Echoer).await?;
 
+    // Allow access to the Echoer via UDS connections from the UDS listener
+    node.flow_controls().add_consumer("echoer", listener.flow_control_id());
+
     // Don't call node.stop() here so this node runs forever.
     Ok(())
 }
@@ -1,12 +1,13 @@
+use crate::forwarding_service::DEREGISTRATION_PAYLOAD;
 use crate::Context;
 use ockam_core::compat::sync::Arc;
-use ockam_core::compat::{boxed::Box, vec::Vec};
+use ockam_core::compat::{boxed::Box, string::String, vec::Vec};
 use ockam_core::{
-    Address, AllowAll, AllowOnwardAddress, Any, IncomingAccessControl, LocalMessage,
+    Address, AllowAll, AllowOnwardAddress, Any, Decodable, IncomingAccessControl, LocalMessage,
     OutgoingAccessControl, Result, Route, Routed, TransportMessage, Worker,
 };
 use ockam_node::WorkerBuilder;
-use tracing::info;
+use tracing::{info, warn};
 
 pub(super) struct Forwarder {
     forward_route: Route,
@@ -49,6 +50,31 @@ impl Forwarder {
 
         Ok(())
     }
+
+    /// Stop this forwarder if the message was sent by the registered
+    /// worker to deregister itself
+    async fn handle_deregistration(
+        &self,
+        ctx: &mut Context,
+        transport_message: &TransportMessage,
+    ) -> Result<()> {
+        let mut sender_route = transport_message.return_route.clone();
+        sender_route.modify().pop_back();
+        let payload = String::decode(&transport_message.payload).ok();
+
+        if sender_route == self.forward_route && payload.as_deref() == Some(DEREGISTRATION_PAYLOAD)
+        {
+            info!("Deregistered alias {}", ctx.address());
+            ctx.stop_worker(ctx.address()).await
+        } else {
+            warn!(
+                "Dropping a message sent to the alias {} itself from {}",
+                ctx.address(),
+                transport_message.return_route
+            );
+            Ok(())
+        }
+    }
 }
 
 #[crate::worker]
@@ -82,6 +108,10 @@ impl Worker for Forwarder {
         // Remove my address from the onward_route
         transport_message.onward_route.step()?;
 
+        if transport_message.onward_route.is_empty() {
+            return self.handle_deregistration(ctx, transport_message).await;
+        }
+
         // Prepend forward route
         transport_message
             .onward_route
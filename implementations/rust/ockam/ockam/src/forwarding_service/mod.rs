@@ -5,3 +5,7 @@ mod options;
 
 pub use forwarding_service::*;
 pub use options::*;
+
+/// Payload sent by a [`RemoteForwarder`](crate::remote::RemoteForwarder) to
+/// its forwarder to delete it, when its node is drained
+pub(crate) const DEREGISTRATION_PAYLOAD: &str = "deregister";
@@ -63,6 +63,9 @@ pub use ockam_node::{
     debugger, Context, DelayedEvent, Executor, MessageReceiveOptions, MessageSendReceiveOptions,
     NodeBuilder, WorkerBuilder,
 };
+
+#[cfg(feature = "simulation")]
+pub use ockam_node::simulation;
 // ---
 
 mod delay;
@@ -11,6 +11,8 @@ pub(super) struct Addresses {
     pub(super) heartbeat: Address,
     // Used to receive completion callback
     pub(super) completion_callback: Address,
+    // Used to be notified when the node is drained
+    pub(super) drain: Address,
 }
 
 impl Addresses {
@@ -23,12 +25,14 @@ impl Addresses {
         let heartbeat = Address::random_tagged(&format!("RemoteForwarder.{}.heartbeat", type_str));
         let completion_callback =
             Address::random_tagged(&format!("RemoteForwarder.{}.child", type_str));
+        let drain = Address::random_tagged(&format!("RemoteForwarder.{}.drain", type_str));
 
         Self {
             main_remote,
             main_internal,
             heartbeat,
             completion_callback,
+            drain,
         }
     }
 }
@@ -0,0 +1,54 @@
+use crate::Context;
+use core::sync::atomic::{AtomicBool, Ordering};
+use ockam_core::compat::{boxed::Box, sync::Arc, vec::Vec};
+use ockam_core::{async_trait, Address, AllowOnwardAddress, DenyAll, Mailboxes, Result};
+use ockam_node::Drain;
+
+/// Drain of a [`RemoteForwarder`](crate::remote::RemoteForwarder)
+///
+/// When the node is drained, the forwarder is notified on its drain address
+/// and deregisters itself from the forwarding service.
+pub(super) struct RemoteForwarderDrain {
+    ctx: Context,
+    drain_address: Address,
+    deregistered: Arc<AtomicBool>,
+}
+
+impl RemoteForwarderDrain {
+    pub(super) async fn create(
+        ctx: &Context,
+        drain_address: Address,
+        deregistered: Arc<AtomicBool>,
+    ) -> Result<Self> {
+        let mailboxes = Mailboxes::main(
+            Address::random_tagged("RemoteForwarderDrain"),
+            Arc::new(DenyAll),
+            Arc::new(AllowOnwardAddress(drain_address.clone())),
+        );
+        let ctx = ctx.new_detached_with_mailboxes(mailboxes).await?;
+
+        Ok(Self {
+            ctx,
+            drain_address,
+            deregistered,
+        })
+    }
+
+    /// Address used to notify the forwarder
+    pub(super) fn address(&self) -> Address {
+        self.ctx.address()
+    }
+}
+
+#[async_trait]
+impl Drain for RemoteForwarderDrain {
+    async fn start_drain(&self, _ctx: &Context) -> Result<()> {
+        self.ctx
+            .send(self.drain_address.clone(), Vec::<u8>::new())
+            .await
+    }
+
+    fn is_drained(&self) -> bool {
+        self.deregistered.load(Ordering::Relaxed)
+    }
+}
@@ -1,5 +1,7 @@
+use crate::remote::drain::RemoteForwarderDrain;
 use crate::remote::{Addresses, RemoteForwarder, RemoteForwarderInfo, RemoteForwarderOptions};
 use crate::Context;
+use core::sync::atomic::AtomicBool;
 use core::time::Duration;
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::{
@@ -35,6 +37,7 @@ impl RemoteForwarder {
     fn mailboxes(
         addresses: Addresses,
         heartbeat_source_address: Option<Address>,
+        drain_source_address: Address,
         outgoing_access_control: Arc<dyn OutgoingAccessControl>,
     ) -> Mailboxes {
         let main_internal = Mailbox::new(
@@ -49,7 +52,13 @@ impl RemoteForwarder {
             Arc::new(AllowAll),
         );
 
-        let mut additional_mailboxes = vec![main_remote];
+        let drain = Mailbox::new(
+            addresses.drain,
+            Arc::new(AllowSourceAddress(drain_source_address)),
+            Arc::new(DenyAll),
+        );
+
+        let mut additional_mailboxes = vec![main_remote, drain];
 
         if let Some(heartbeat_source_address) = heartbeat_source_address {
             let heartbeat = Mailbox::new(
@@ -62,6 +71,21 @@ impl RemoteForwarder {
 
         Mailboxes::new(main_internal, additional_mailboxes)
     }
+
+    /// Register the drain of this forwarder in the node, and return
+    /// the address used to notify it when the node is drained
+    async fn register_drain(&self, ctx: &Context) -> Result<Address> {
+        let drain = RemoteForwarderDrain::create(
+            ctx,
+            self.addresses.drain.clone(),
+            self.deregistered.clone(),
+        )
+        .await?;
+        let drain_source_address = drain.address();
+        ctx.drains()
+            .register(self.addresses.main_remote.to_string(), Arc::new(drain));
+        Ok(drain_source_address)
+    }
 }
 
 impl RemoteForwarder {
@@ -81,6 +105,8 @@ impl RemoteForwarder {
             flow_control_id,
             heartbeat,
             heartbeat_interval,
+            forwarder_route: None,
+            deregistered: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -124,9 +150,11 @@ impl RemoteForwarder {
             "Starting static RemoteForwarder at {}",
             &addresses.heartbeat
         );
+        let drain_source_address = forwarder.register_drain(ctx).await?;
         let mailboxes = Self::mailboxes(
             addresses,
             Some(heartbeat_source_address),
+            drain_source_address,
             outgoing_access_control,
         );
         WorkerBuilder::new(forwarder)
@@ -175,7 +203,13 @@ impl RemoteForwarder {
             "Starting ephemeral RemoteForwarder at {}",
             &addresses.main_internal
         );
-        let mailboxes = Self::mailboxes(addresses, None, outgoing_access_control);
+        let drain_source_address = forwarder.register_drain(ctx).await?;
+        let mailboxes = Self::mailboxes(
+            addresses,
+            None,
+            drain_source_address,
+            outgoing_access_control,
+        );
         WorkerBuilder::new(forwarder)
             .with_mailboxes(mailboxes)
             .start(ctx)
@@ -226,7 +260,13 @@ impl RemoteForwarder {
             "Starting static RemoteForwarder without heartbeats at {}",
             &addresses.main_internal
         );
-        let mailboxes = Self::mailboxes(addresses, None, outgoing_access_control);
+        let drain_source_address = forwarder.register_drain(ctx).await?;
+        let mailboxes = Self::mailboxes(
+            addresses,
+            None,
+            drain_source_address,
+            outgoing_access_control,
+        );
         WorkerBuilder::new(forwarder)
             .with_mailboxes(mailboxes)
             .start(ctx)
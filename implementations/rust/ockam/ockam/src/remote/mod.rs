@@ -2,6 +2,7 @@
 //! which allows other nodes forward messages to local workers on this node using that alias.
 
 mod addresses;
+mod drain;
 mod info;
 mod lifecycle;
 mod options;
@@ -11,8 +12,9 @@ pub use info::*;
 pub use options::*;
 
 use crate::remote::addresses::Addresses;
+use core::sync::atomic::AtomicBool;
 use core::time::Duration;
-use ockam_core::compat::{string::String, vec::Vec};
+use ockam_core::compat::{string::String, sync::Arc, vec::Vec};
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::Route;
 use ockam_node::DelayedEvent;
@@ -28,4 +30,8 @@ pub struct RemoteForwarder {
     // We only use Heartbeat for static RemoteForwarder
     heartbeat: Option<DelayedEvent<Vec<u8>>>,
     heartbeat_interval: Duration,
+    // Route to the forwarder created by the forwarding service, once registered
+    forwarder_route: Option<Route>,
+    // Set once the forwarder was deregistered because the node is drained
+    deregistered: Arc<AtomicBool>,
 }
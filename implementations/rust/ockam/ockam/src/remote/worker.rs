@@ -1,13 +1,57 @@
+use crate::forwarding_service::DEREGISTRATION_PAYLOAD;
 use crate::remote::{RemoteForwarder, RemoteForwarderInfo};
 use crate::{Context, OckamError};
+use core::sync::atomic::Ordering;
 use ockam_core::compat::{
     boxed::Box,
     string::{String, ToString},
     vec::Vec,
 };
-use ockam_core::{Any, Decodable, Result, Routed, Worker};
+use ockam_core::{Any, Decodable, Priority, Result, Routed, Worker};
 use tracing::{debug, info};
 
+impl RemoteForwarder {
+    /// Send the registration message, also used as a heartbeat.
+    /// It is sent with a high priority, to not be delayed by the forwarded payloads
+    async fn send_registration(&self, ctx: &mut Context) -> Result<()> {
+        ctx.set_message_priority(Priority::High);
+        let result = ctx
+            .send_from_address(
+                self.registration_route.clone(),
+                self.registration_payload.clone(),
+                self.addresses.main_remote.clone(),
+            )
+            .await;
+        ctx.clear_message_priority();
+        result
+    }
+
+    /// Delete the forwarder created by the forwarding service, and stop
+    /// sending heartbeats, since the node is drained
+    async fn deregister(&mut self, ctx: &mut Context) -> Result<()> {
+        self.heartbeat = None;
+        self.deregistered.store(true, Ordering::Relaxed);
+        if let Some(forwarder_route) = self.forwarder_route.take() {
+            info!("RemoteForwarder deregistering from {}", forwarder_route);
+            ctx.set_message_priority(Priority::High);
+            let result = ctx
+                .send_from_address(
+                    forwarder_route,
+                    DEREGISTRATION_PAYLOAD.to_string(),
+                    self.addresses.main_remote.clone(),
+                )
+                .await;
+            ctx.clear_message_priority();
+            result?;
+        }
+        Ok(())
+    }
+
+    fn is_deregistered(&self) -> bool {
+        self.deregistered.load(Ordering::Relaxed)
+    }
+}
+
 #[crate::worker]
 impl Worker for RemoteForwarder {
     type Context = Context;
@@ -16,12 +60,14 @@ impl Worker for RemoteForwarder {
     async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
         debug!("RemoteForwarder registration...");
 
-        ctx.send_from_address(
-            self.registration_route.clone(),
-            self.registration_payload.clone(),
-            self.addresses.main_remote.clone(),
-        )
-        .await?;
+        self.send_registration(ctx).await?;
+
+        Ok(())
+    }
+
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        ctx.drains()
+            .unregister(&self.addresses.main_remote.to_string());
 
         Ok(())
     }
@@ -31,14 +77,16 @@ impl Worker for RemoteForwarder {
         ctx: &mut Context,
         msg: Routed<Self::Message>,
     ) -> Result<()> {
-        if msg.msg_addr() == self.addresses.heartbeat {
+        if msg.msg_addr() == self.addresses.drain {
+            // The node is drained
+            self.deregister(ctx).await
+        } else if msg.msg_addr() == self.addresses.heartbeat {
+            if self.is_deregistered() {
+                return Ok(());
+            }
+
             // Heartbeat message, send registration message
-            ctx.send_from_address(
-                self.registration_route.clone(),
-                self.registration_payload.clone(),
-                self.addresses.main_remote.clone(),
-            )
-            .await?;
+            self.send_registration(ctx).await?;
 
             if let Some(heartbeat) = &mut self.heartbeat {
                 heartbeat.schedule(self.heartbeat_interval).await?;
@@ -57,6 +105,10 @@ impl Worker for RemoteForwarder {
                 Err(_) => {
                     debug!("RemoteForwarder received service message");
 
+                    if self.is_deregistered() {
+                        return Ok(());
+                    }
+
                     let payload = Vec::<u8>::decode(&transport_message.payload)
                         .map_err(|_| OckamError::InvalidHubResponse)?;
                     let payload =
@@ -68,6 +120,7 @@ impl Worker for RemoteForwarder {
 
                     if !self.completion_msg_sent {
                         info!("RemoteForwarder registered with route: {}", return_route);
+                        self.forwarder_route = Some(return_route.clone());
                         let address = match return_route.recipient()?.to_string().strip_prefix("0#")
                         {
                             Some(addr) => addr.to_string(),
@@ -1,7 +1,7 @@
 use ockam::remote::{RemoteForwarder, RemoteForwarderOptions};
 use ockam::workers::Echoer;
 use ockam::{ForwardingService, ForwardingServiceOptions};
-use ockam_core::{route, AllowAll, Result};
+use ockam_core::{route, Address, AllowAll, Result};
 use ockam_identity::{secure_channels, SecureChannelListenerOptions, SecureChannelOptions};
 use ockam_node::{Context, MessageReceiveOptions};
 use ockam_transport_tcp::{TcpConnectionOptions, TcpListenerOptions, TcpTransport};
@@ -259,3 +259,41 @@ async fn test4(ctx: &mut Context) -> Result<()> {
 
     ctx.stop().await
 }
+
+// Cloud: Hosts a Forwarding service and listens on a tcp port. No flow control
+// Server: Connects to a Cloud using tcp and creates a dynamic Forwarder, then is drained
+#[ockam_macros::test]
+async fn test5(ctx: &mut Context) -> Result<()> {
+    let tcp_listener_options = TcpListenerOptions::new();
+    let options = ForwardingServiceOptions::new()
+        .service_as_consumer(&tcp_listener_options.spawner_flow_control_id())
+        .forwarder_as_consumer(&tcp_listener_options.spawner_flow_control_id());
+    ForwardingService::create(ctx, "forwarding_service", options).await?;
+    let cloud_tcp = TcpTransport::create(ctx).await?;
+
+    let cloud_listener = cloud_tcp
+        .listen("127.0.0.1:0", tcp_listener_options)
+        .await?;
+
+    let server_tcp = TcpTransport::create(ctx).await?;
+    let cloud_connection = server_tcp
+        .connect(cloud_listener.socket_string(), TcpConnectionOptions::new())
+        .await?;
+
+    let remote_info =
+        RemoteForwarder::create(ctx, cloud_connection, RemoteForwarderOptions::new()).await?;
+    let alias = Address::from_string(remote_info.remote_address());
+    assert!(ctx.list_workers().await?.contains(&alias));
+
+    let pending = ctx.drain(Duration::from_secs(5)).await?;
+    assert!(pending.is_empty());
+
+    let mut attempts = 0;
+    while ctx.list_workers().await?.contains(&alias) && attempts < 50 {
+        ctx.sleep(Duration::from_millis(100)).await;
+        attempts += 1;
+    }
+    assert!(!ctx.list_workers().await?.contains(&alias));
+
+    ctx.stop().await
+}
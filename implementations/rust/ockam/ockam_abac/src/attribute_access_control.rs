@@ -11,7 +11,7 @@ use tracing as log;
 
 use crate::expr::str;
 use crate::Expr::*;
-use crate::{eval, Env, Expr};
+use crate::{eval, Action, Decision, DecisionSink, Env, Expr, Resource};
 use ockam_core::compat::format;
 use ockam_core::compat::string::ToString;
 use ockam_core::compat::sync::Arc;
@@ -25,6 +25,9 @@ pub struct AbacAccessControl {
     repository: Arc<dyn IdentitiesRepository>,
     expression: Expr,
     environment: Env,
+    resource: Option<Resource>,
+    action: Option<Action>,
+    decisions: Option<Arc<dyn DecisionSink>>,
 }
 
 /// Debug implementation printing out the policy expression only
@@ -46,9 +49,25 @@ impl AbacAccessControl {
             repository,
             expression,
             environment,
+            resource: None,
+            action: None,
+            decisions: None,
         }
     }
 
+    /// Send a [`Decision`] record for every evaluation to the given sink
+    pub fn with_decision_sink(mut self, sink: Arc<dyn DecisionSink>) -> Self {
+        self.decisions = Some(sink);
+        self
+    }
+
+    /// Tag the recorded decisions with the resource and action being protected
+    pub(crate) fn with_resource_action(mut self, r: Resource, a: Action) -> Self {
+        self.resource = Some(r);
+        self.action = Some(a);
+        self
+    }
+
     /// Create an AccessControl which will verify that the sender of
     /// a message has an authenticated attribute with the correct name and value
     pub fn create(
@@ -69,10 +88,54 @@ where {
 impl AbacAccessControl {
     /// Returns true if the identity is authorized
     pub async fn is_identity_authorized(&self, id: IdentityIdentifier) -> Result<bool> {
+        let environment = self.subject_environment(&id).await?;
+
+        // Finally, evaluate the expression and return the result:
+        let verdict = match eval(&self.expression, &environment) {
+            Ok(Expr::Bool(b)) => {
+                log::debug! {
+                    policy        = %self.expression,
+                    id            = %id,
+                    is_authorized = %b,
+                    "policy evaluated"
+                }
+                b
+            }
+            Ok(x) => {
+                log::warn! {
+                    policy = %self.expression,
+                    id     = %id,
+                    expr   = %x,
+                    "evaluation did not yield a boolean result"
+                }
+                false
+            }
+            Err(e) => {
+                log::warn! {
+                    policy = %self.expression,
+                    id     = %id,
+                    err    = %e,
+                    "policy evaluation failed"
+                }
+                false
+            }
+        };
+
+        self.record(verdict, || self.decision(&environment).with_subject(id))
+            .await;
+
+        Ok(verdict)
+    }
+
+    /// Build the evaluation environment for the given identity
+    ///
+    /// The environment of this access control is extended with the
+    /// attributes of the identity, prefixed with `subject.`
+    async fn subject_environment(&self, id: &IdentityIdentifier) -> Result<Env> {
         let mut environment = self.environment.clone();
 
         // Get identity attributes and populate the environment:
-        if let Some(attrs) = self.repository.get_attributes(&id).await? {
+        if let Some(attrs) = self.repository.get_attributes(id).await? {
             for (key, value) in attrs.attrs() {
                 if key.find(|c: char| c.is_whitespace()).is_some() {
                     log::warn! {
@@ -111,34 +174,38 @@ impl AbacAccessControl {
         // add the identifier itself as a subject parameter
         environment.put("subject.identifier", str(id.to_string()));
 
-        // Finally, evaluate the expression and return the result:
-        match eval(&self.expression, &environment) {
-            Ok(Expr::Bool(b)) => {
-                log::debug! {
-                    policy        = %self.expression,
-                    id            = %id,
-                    is_authorized = %b,
-                    "policy evaluated"
-                }
-                Ok(b)
-            }
-            Ok(x) => {
-                log::warn! {
-                    policy = %self.expression,
-                    id     = %id,
-                    expr   = %x,
-                    "evaluation did not yield a boolean result"
-                }
-                Ok(false)
-            }
-            Err(e) => {
-                log::warn! {
-                    policy = %self.expression,
-                    id     = %id,
-                    err    = %e,
-                    "policy evaluation failed"
-                }
-                Ok(false)
+        Ok(environment)
+    }
+
+    /// Evaluate the policy for the given identity without enforcing it.
+    ///
+    /// The returned [`Decision`] contains the environment the expression was
+    /// evaluated in and the result of each sub-expression.
+    pub async fn explain_identity(&self, id: IdentityIdentifier) -> Result<Decision> {
+        let environment = self.subject_environment(&id).await?;
+        Ok(self.decision(&environment).with_subject(id))
+    }
+
+    fn decision(&self, environment: &Env) -> Decision {
+        self.tag(Decision::evaluate(&self.expression, environment))
+    }
+
+    /// Add the resource and action, if known, to a decision
+    fn tag(&self, mut d: Decision) -> Decision {
+        if let Some(r) = &self.resource {
+            d = d.with_resource(r.clone())
+        }
+        if let Some(a) = &self.action {
+            d = d.with_action(a.clone())
+        }
+        d
+    }
+
+    /// Record a decision with the given verdict, if the sink records it
+    async fn record(&self, verdict: bool, d: impl FnOnce() -> Decision) {
+        if let Some(sink) = &self.decisions {
+            if sink.is_recording(verdict) {
+                sink.record(&d()).await
             }
         }
     }
@@ -156,6 +223,11 @@ impl IncomingAccessControl for AbacAccessControl {
                 policy = %self.expression,
                 "identity identifier not found; access denied"
             }
+            self.record(false, || {
+                let d = Decision::denied("identity identifier not found");
+                self.tag(d.with_environment(self.environment.clone()))
+            })
+            .await;
             return Ok(false);
         };
 
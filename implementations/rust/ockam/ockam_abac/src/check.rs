@@ -0,0 +1,455 @@
+use crate::error::CheckError;
+use crate::expr::Expr;
+#[cfg(feature = "std")]
+use crate::parser::parse_with_spans;
+use core::fmt;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::format;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::vec::Vec;
+
+/// The static type of a policy expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Type {
+    Str,
+    Int,
+    Float,
+    Bool,
+    Unit,
+    Seq(Box<Type>),
+    /// The type is only known at evaluation time.
+    Any,
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Str => f.write_str("str"),
+            Type::Int => f.write_str("int"),
+            Type::Float => f.write_str("float"),
+            Type::Bool => f.write_str("bool"),
+            Type::Unit => f.write_str("unit"),
+            Type::Seq(t) => write!(f, "[{t}]"),
+            Type::Any => f.write_str("any"),
+        }
+    }
+}
+
+impl Type {
+    /// The type compatible with both `self` and `other`, if any.
+    fn unify(&self, other: &Type) -> Option<Type> {
+        match (self, other) {
+            (Type::Any, t) | (t, Type::Any) => Some(t.clone()),
+            (Type::Seq(a), Type::Seq(b)) => a.unify(b).map(|t| Type::Seq(Box::new(t))),
+            (a, b) if a == b => Some(a.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Checks policy expressions before they are stored.
+///
+/// The checker validates that
+///
+/// - every function is known and applied to the right number of arguments,
+/// - every identifier uses one of the known namespaces (`subject.`,
+///   `resource.` and `action.` by default),
+/// - the argument types of every function application are consistent,
+/// - the whole expression evaluates to a boolean.
+///
+/// Subject attributes come from credentials and are always strings.
+/// Resource and action attributes are provided by the node and are only
+/// known at evaluation time, unless declared with [`Checker::with_ident`].
+#[derive(Debug, Clone)]
+pub struct Checker {
+    namespaces: Vec<(String, Type)>,
+    idents: BTreeMap<String, Type>,
+}
+
+impl Default for Checker {
+    fn default() -> Self {
+        Checker {
+            namespaces: Vec::from([
+                ("subject.".to_string(), Type::Str),
+                ("resource.".to_string(), Type::Any),
+                ("action.".to_string(), Type::Any),
+            ]),
+            idents: BTreeMap::new(),
+        }
+    }
+}
+
+/// Check a policy expression with the default [`Checker`].
+pub fn check(expr: &Expr) -> Result<(), CheckError> {
+    Checker::default().check(expr)
+}
+
+/// Parse and check a policy with the default [`Checker`].
+#[cfg(feature = "std")]
+pub fn parse_policy(s: &str) -> Result<Expr, CheckError> {
+    Checker::default().parse(s)
+}
+
+impl Checker {
+    pub fn new() -> Self {
+        Checker::default()
+    }
+
+    /// Declare the type of a specific identifier.
+    pub fn with_ident<S: Into<String>>(mut self, id: S, t: Type) -> Self {
+        self.idents.insert(id.into(), t);
+        self
+    }
+
+    /// Declare a namespace of identifiers (e.g. `"env."`) and their type.
+    pub fn with_namespace<S: Into<String>>(mut self, prefix: S, t: Type) -> Self {
+        self.namespaces.push((prefix.into(), t));
+        self
+    }
+
+    /// Check that the expression is a well-typed boolean policy.
+    ///
+    /// Errors point into the `Display` representation of the expression.
+    pub fn check(&self, expr: &Expr) -> Result<(), CheckError> {
+        self.verify(expr).map_err(|(x, msg)| {
+            let span = spans(expr)
+                .get(&(x as *const Expr))
+                .copied()
+                .unwrap_or((0, 0));
+            CheckError::new(expr.to_string(), span, msg)
+        })
+    }
+
+    /// Parse a policy and check that it is a well-typed boolean policy.
+    ///
+    /// Errors point into `s`, as written.
+    #[cfg(feature = "std")]
+    pub fn parse(&self, s: &str) -> Result<Expr, CheckError> {
+        let (expr, spans) = match parse_with_spans(s) {
+            Ok(Some(x)) => x,
+            Ok(None) => {
+                let msg = "empty policy".to_string();
+                return Err(CheckError::new(s.to_string(), (0, s.len()), msg));
+            }
+            Err(e) => {
+                let msg = format!("invalid syntax: {e}");
+                return Err(CheckError::new(s.to_string(), (0, s.len()), msg));
+            }
+        };
+        match self.verify(&expr) {
+            Ok(()) => Ok(expr),
+            Err((x, msg)) => {
+                let span = position(&expr, x)
+                    .and_then(|i| spans.get(i))
+                    .copied()
+                    .unwrap_or((0, s.len()));
+                Err(CheckError::new(s.to_string(), span, msg))
+            }
+        }
+    }
+
+    /// Check an expression and return the offending sub-expression on error.
+    fn verify<'a>(&self, expr: &'a Expr) -> Result<(), (&'a Expr, String)> {
+        match self.infer(expr)? {
+            Type::Bool | Type::Any => Ok(()),
+            t => Err((expr, format!("policy must evaluate to bool, not {t}"))),
+        }
+    }
+
+    fn ident_type(&self, id: &str) -> Option<Type> {
+        if let Some(t) = self.idents.get(id) {
+            return Some(t.clone());
+        }
+        self.namespaces
+            .iter()
+            .find(|(p, _)| id.len() > p.len() && id.starts_with(p.as_str()))
+            .map(|(_, t)| t.clone())
+    }
+
+    /// Infer the type of an expression.
+    ///
+    /// On error the offending sub-expression is returned with a message.
+    #[rustfmt::skip]
+    fn infer<'a>(&self, expr: &'a Expr) -> Result<Type, (&'a Expr, String)> {
+        /// A stack operation.
+        enum Op<'a> {
+            Infer(&'a Expr),
+            Apply(&'a Expr, &'a str, usize),
+            Seq(&'a Expr, usize),
+        }
+
+        // Control stack.
+        let mut ctrl: Vec<Op> = Vec::new();
+        // Types of already checked sub-expressions.
+        let mut types: Vec<(&'a Expr, Type)> = Vec::new();
+
+        ctrl.push(Op::Infer(expr));
+
+        while let Some(x) = ctrl.pop() {
+            match x {
+                Op::Infer(e @ Expr::Str(_))   => types.push((e, Type::Str)),
+                Op::Infer(e @ Expr::Int(_))   => types.push((e, Type::Int)),
+                Op::Infer(e @ Expr::Float(_)) => types.push((e, Type::Float)),
+                Op::Infer(e @ Expr::Bool(_))  => types.push((e, Type::Bool)),
+                Op::Infer(e @ Expr::Ident(id)) => match self.ident_type(id) {
+                    Some(t) => types.push((e, t)),
+                    None    => return Err((e, format!("unknown identifier: {id}")))
+                }
+                Op::Infer(e @ Expr::Seq(xs)) => {
+                    ctrl.push(Op::Seq(e, xs.len()));
+                    for x in xs.iter().rev() {
+                        ctrl.push(Op::Infer(x))
+                    }
+                }
+                Op::Infer(e @ Expr::List(xs)) => match &xs[..] {
+                    [] => types.push((e, Type::Unit)),
+                    [Expr::Ident(id), args @ ..] => {
+                        let nargs = args.len();
+                        let arity = match id.as_str() {
+                            "and" | "or"            => true,
+                            "permit-overrides"      => true,
+                            "deny-overrides"        => true,
+                            "not"                   => nargs == 1,
+                            "if"                    => nargs == 3,
+                            "<" | ">" | "=" | "!="  => nargs >= 2,
+                            "member?"               => nargs == 2,
+                            "exists?"               => nargs >= 1,
+                            _ => return Err((&xs[0], format!("unknown operator: {id}")))
+                        };
+                        if !arity {
+                            let msg = format!("wrong number of arguments for '{id}': {nargs}");
+                            return Err((e, msg))
+                        }
+                        if id == "exists?" {
+                            // 'exists?' does not evaluate its arguments.
+                            for a in args {
+                                match a {
+                                    Expr::Ident(v) if self.ident_type(v).is_some() => {}
+                                    Expr::Ident(v) => {
+                                        return Err((a, format!("unknown identifier: {v}")))
+                                    }
+                                    _ => {
+                                        let msg = "'exists?' expects identifiers as arguments";
+                                        return Err((a, msg.to_string()))
+                                    }
+                                }
+                            }
+                            types.push((e, Type::Bool));
+                            continue
+                        }
+                        ctrl.push(Op::Apply(e, id, nargs));
+                        for x in args.iter().rev() {
+                            ctrl.push(Op::Infer(x))
+                        }
+                    }
+                    [other, ..] => return Err((other, "expected (op ...)".to_string()))
+                }
+                Op::Seq(e, n) => {
+                    let mut t = Type::Any;
+                    for (x, u) in types.split_off(types.len() - n) {
+                        t = match t.unify(&u) {
+                            Some(t) => t,
+                            None    => {
+                                let msg = format!("sequence elements of type {t} and {u}");
+                                return Err((x, msg))
+                            }
+                        }
+                    }
+                    types.push((e, Type::Seq(Box::new(t))))
+                }
+                Op::Apply(e, id, n) => {
+                    let args = types.split_off(types.len() - n);
+                    let t = apply(id, &args)?;
+                    types.push((e, t))
+                }
+            }
+        }
+
+        debug_assert_eq!(1, types.len());
+        Ok(types.pop().expect("stack is not empty").1)
+    }
+}
+
+/// The result type of applying function `id` to arguments of the given types.
+fn apply<'a>(id: &str, args: &[(&'a Expr, Type)]) -> Result<Type, (&'a Expr, String)> {
+    let expect = |(x, t): &(&'a Expr, Type), u: &Type| {
+        t.unify(u)
+            .ok_or_else(|| (*x, format!("'{id}' expects {u}, found {t}")))
+    };
+    match id {
+        "and" | "or" | "not" | "permit-overrides" | "deny-overrides" => {
+            for a in args {
+                expect(a, &Type::Bool)?;
+            }
+            Ok(Type::Bool)
+        }
+        "if" => {
+            expect(&args[0], &Type::Bool)?;
+            expect(&args[2], &args[1].1)
+        }
+        "member?" => {
+            let s = expect(&args[1], &Type::Seq(Box::new(Type::Any)))?;
+            if let Type::Seq(t) = s {
+                expect(&args[0], &t)?;
+            }
+            Ok(Type::Bool)
+        }
+        // "<", ">", "=", "!="
+        _ => {
+            let mut t = Type::Any;
+            for a in args {
+                t = expect(a, &t)?;
+            }
+            Ok(Type::Bool)
+        }
+    }
+}
+
+/// The index of the sub-expression `at` of `expr`, in pre-order.
+#[cfg(feature = "std")]
+fn position(expr: &Expr, at: &Expr) -> Option<usize> {
+    let mut ctrl = Vec::from([expr]);
+    let mut i = 0;
+    while let Some(x) = ctrl.pop() {
+        if core::ptr::eq(x, at) {
+            return Some(i);
+        }
+        if let Expr::List(es) | Expr::Seq(es) = x {
+            ctrl.extend(es.iter().rev())
+        }
+        i += 1
+    }
+    None
+}
+
+/// Compute the position of every sub-expression in the `Display`
+/// representation of `expr`.
+#[rustfmt::skip]
+fn spans(expr: &Expr) -> BTreeMap<*const Expr, (usize, usize)> {
+    enum Op<'a> {
+        Show(&'a Expr),
+        End(&'a Expr, usize),
+        Whitespace,
+    }
+
+    let mut ctrl = Vec::from([Op::Show(expr)]);
+    let mut spans = BTreeMap::new();
+    let mut pos = 0;
+
+    while let Some(x) = ctrl.pop() {
+        match x {
+            Op::Show(e @ (Expr::List(es) | Expr::Seq(es))) => {
+                ctrl.push(Op::End(e, pos));
+                pos += 1;
+                let mut n = es.len();
+                for e in es.iter().rev() {
+                    ctrl.push(Op::Show(e));
+                    if n > 1 {
+                        ctrl.push(Op::Whitespace)
+                    }
+                    n -= 1
+                }
+            }
+            Op::Show(e) => {
+                let len = e.to_string().len();
+                spans.insert(e as *const Expr, (pos, pos + len));
+                pos += len
+            }
+            Op::End(e, start) => {
+                pos += 1;
+                spans.insert(e as *const Expr, (start, pos));
+            }
+            Op::Whitespace => pos += 1,
+        }
+    }
+
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check, parse_policy, Checker, Type};
+    use crate::parser::parse;
+
+    fn check_str(s: &str) -> Result<(), crate::error::CheckError> {
+        parse_policy(s).map(|_| ())
+    }
+
+    #[test]
+    fn valid_policies() {
+        check_str("true").unwrap();
+        check_str(r#"(= subject.role "admin")"#).unwrap();
+        check_str(r#"(and (= resource.project_id subject.project_id) (exists? subject.env))"#)
+            .unwrap();
+        check_str(r#"(member? subject.role ["admin" "dev"])"#).unwrap();
+        check_str(r#"(if (= subject.a "x") true (!= subject.b "y"))"#).unwrap();
+    }
+
+    #[test]
+    fn unknown_operator() {
+        let e = check_str(r#"(and (eqq subject.role "admin"))"#).unwrap_err();
+        assert_eq!((6, 9), e.span());
+        assert!(e.message().contains("eqq"))
+    }
+
+    #[test]
+    fn unknown_identifier() {
+        let e = check_str(r#"(= role "admin")"#).unwrap_err();
+        assert_eq!((3, 7), e.span());
+    }
+
+    #[test]
+    fn type_mismatch() {
+        let e = check_str(r#"(or false (= subject.age 25))"#).unwrap_err();
+        assert_eq!((25, 27), e.span());
+        let e = check_str(r#"(member? 1 ["a" "b"])"#).unwrap_err();
+        assert_eq!((9, 10), e.span());
+        let e = check_str(r#"(= subject.a "x" "y" [1 2])"#).unwrap_err();
+        assert_eq!((21, 26), e.span());
+    }
+
+    #[test]
+    fn arity() {
+        let e = check_str(r#"(not true false)"#).unwrap_err();
+        assert_eq!((0, 16), e.span());
+        assert!(check_str(r#"(= subject.a)"#).is_err())
+    }
+
+    #[test]
+    fn non_boolean_policy() {
+        assert!(check_str(r#""admin""#).is_err());
+        let c = Checker::new().with_ident("resource.max_age", Type::Int);
+        let e = parse("(< resource.max_age 3.0)").unwrap().unwrap();
+        assert!(c.check(&e).is_err())
+    }
+
+    #[test]
+    fn spans_point_into_the_policy_as_written() {
+        let policy =
+            "(and\n  (= subject.role   \"admin\")  ;; admins only\n  (eqq subject.env \"prod\"))";
+        let e = parse_policy(policy).unwrap_err();
+        assert_eq!("eqq", &policy[e.span().0..e.span().1]);
+        assert_eq!("  (eqq subject.env \"prod\"))\n   ^^^", e.annotate());
+
+        // Displayed expressions are used when there is no source
+        let e = check(&parse(policy).unwrap().unwrap()).unwrap_err();
+        assert_eq!("eqq", &e.source()[e.span().0..e.span().1]);
+    }
+
+    #[test]
+    fn annotations_count_characters() {
+        let e = parse_policy(r#"(= subject.name "Zoë" 3)"#).unwrap_err();
+        assert_eq!((23, 24), e.span());
+        assert_eq!(
+            "(= subject.name \"Zoë\" 3)\n                      ^",
+            e.annotate()
+        );
+    }
+
+    #[test]
+    fn syntax_errors() {
+        assert!(parse_policy("(= subject.role").is_err());
+        assert!(parse_policy("").is_err())
+    }
+}
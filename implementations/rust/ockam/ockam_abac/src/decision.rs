@@ -0,0 +1,355 @@
+use crate::env::Env;
+use crate::eval::eval;
+use crate::expr::Expr;
+use crate::types::{Action, Resource};
+use core::fmt;
+use minicbor::{Decode, Encode};
+use ockam_core::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::format;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::vec::Vec;
+use ockam_identity::IdentityIdentifier;
+use tracing as log;
+
+/// The record of a single access control decision.
+///
+/// A decision captures everything that went into evaluating a policy:
+/// the resource and action it applies to, the subject that was checked,
+/// the environment the expression was evaluated in, the result of every
+/// sub-expression and the final verdict.
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct Decision {
+    #[n(0)] resource: Option<Resource>,
+    #[n(1)] action: Option<Action>,
+    #[n(2)] subject: Option<IdentityIdentifier>,
+    #[n(3)] environment: Env,
+    #[n(4)] expression: Option<Expr>,
+    #[n(5)] steps: Vec<Step>,
+    #[n(6)] verdict: bool,
+    #[n(7)] reason: Option<String>,
+}
+
+/// The evaluation result of one sub-expression of a policy.
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct Step {
+    #[n(0)] expr: Expr,
+    #[n(1)] value: Option<Expr>,
+    #[n(2)] error: Option<String>,
+}
+
+impl Step {
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+
+    /// The value the sub-expression evaluated to, if evaluation succeeded.
+    pub fn value(&self) -> Option<&Expr> {
+        self.value.as_ref()
+    }
+
+    /// The evaluation error, if evaluation failed.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+}
+
+impl Decision {
+    /// Evaluate `expression` in `environment` and record the result of
+    /// every sub-expression together with the final verdict.
+    ///
+    /// Sub-expressions are evaluated independently of each other, so the
+    /// steps also contain results for branches which a lazy `and`, `or`
+    /// or `if` would not have evaluated.
+    pub fn evaluate(expression: &Expr, environment: &Env) -> Self {
+        let (verdict, reason) = match eval(expression, environment) {
+            Ok(Expr::Bool(b)) => (b, None),
+            Ok(x) => (
+                false,
+                Some(format!("evaluation did not yield a boolean result: {x}")),
+            ),
+            Err(e) => (false, Some(format!("policy evaluation failed: {e}"))),
+        };
+        Decision {
+            resource: None,
+            action: None,
+            subject: None,
+            environment: environment.clone(),
+            expression: Some(expression.clone()),
+            steps: steps(expression, environment),
+            verdict,
+            reason,
+        }
+    }
+
+    /// A decision which denied access without evaluating any expression.
+    pub fn denied<S: Into<String>>(reason: S) -> Self {
+        Decision {
+            resource: None,
+            action: None,
+            subject: None,
+            environment: Env::new(),
+            expression: None,
+            steps: Vec::new(),
+            verdict: false,
+            reason: Some(reason.into()),
+        }
+    }
+
+    pub fn with_resource(mut self, r: Resource) -> Self {
+        self.resource = Some(r);
+        self
+    }
+
+    pub fn with_action(mut self, a: Action) -> Self {
+        self.action = Some(a);
+        self
+    }
+
+    pub fn with_subject(mut self, id: IdentityIdentifier) -> Self {
+        self.subject = Some(id);
+        self
+    }
+
+    pub fn with_environment(mut self, env: Env) -> Self {
+        self.environment = env;
+        self
+    }
+
+    pub fn resource(&self) -> Option<&Resource> {
+        self.resource.as_ref()
+    }
+
+    pub fn action(&self) -> Option<&Action> {
+        self.action.as_ref()
+    }
+
+    pub fn subject(&self) -> Option<&IdentityIdentifier> {
+        self.subject.as_ref()
+    }
+
+    pub fn environment(&self) -> &Env {
+        &self.environment
+    }
+
+    pub fn expression(&self) -> Option<&Expr> {
+        self.expression.as_ref()
+    }
+
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Returns true if access was granted.
+    pub fn verdict(&self) -> bool {
+        self.verdict
+    }
+
+    /// Why access was denied, if the denial was not the plain result of the
+    /// expression evaluating to `false`.
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = if self.verdict { "allowed" } else { "denied" };
+        write!(f, "{verdict}")?;
+        if let Some(r) = &self.resource {
+            write!(f, " resource={r}")?
+        }
+        if let Some(a) = &self.action {
+            write!(f, " action={a}")?
+        }
+        if let Some(s) = &self.subject {
+            write!(f, " subject={s}")?
+        }
+        if let Some(e) = &self.expression {
+            write!(f, " policy={e}")?
+        }
+        if let Some(r) = &self.reason {
+            write!(f, " reason=\"{r}\"")?
+        }
+        Ok(())
+    }
+}
+
+/// Collect the evaluation results of all compound sub-expressions of
+/// `expr`, in pre-order.
+fn steps(expr: &Expr, env: &Env) -> Vec<Step> {
+    let mut ctrl = Vec::new();
+    let mut steps = Vec::new();
+    ctrl.push(expr);
+    while let Some(x) = ctrl.pop() {
+        let xs = match x {
+            Expr::List(xs) if !xs.is_empty() => &xs[1..],
+            Expr::Seq(xs) => &xs[..],
+            _ => continue,
+        };
+        if let Expr::List(_) = x {
+            let step = match eval(x, env) {
+                Ok(v) => Step {
+                    expr: x.clone(),
+                    value: Some(v),
+                    error: None,
+                },
+                Err(e) => Step {
+                    expr: x.clone(),
+                    value: None,
+                    error: Some(e.to_string()),
+                },
+            };
+            steps.push(step)
+        }
+        for x in xs.iter().rev() {
+            ctrl.push(x)
+        }
+    }
+    steps
+}
+
+/// A destination for access control decisions, e.g. an audit log.
+#[async_trait]
+pub trait DecisionSink: Send + Sync + 'static {
+    /// Returns true if decisions with this verdict are recorded.
+    ///
+    /// Building a [`Decision`] evaluates every sub-expression of the policy
+    /// again, so access controls only build one for the sinks recording it.
+    fn is_recording(&self, _verdict: bool) -> bool {
+        true
+    }
+
+    async fn record(&self, decision: &Decision);
+}
+
+/// A [`DecisionSink`] emitting every decision as a `tracing` event.
+///
+/// Denials are logged at `info` level, grants at `debug` level. Decisions
+/// are only recorded if events of their level are enabled.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TracingDecisionSink;
+
+#[async_trait]
+impl DecisionSink for TracingDecisionSink {
+    fn is_recording(&self, verdict: bool) -> bool {
+        if verdict {
+            log::enabled!(log::Level::DEBUG)
+        } else {
+            log::enabled!(log::Level::INFO)
+        }
+    }
+
+    async fn record(&self, d: &Decision) {
+        let resource = d.resource.as_ref().map(|r| r.to_string());
+        let action = d.action.as_ref().map(|a| a.to_string());
+        let subject = d.subject.as_ref().map(|s| s.to_string());
+        let policy = d.expression.as_ref().map(|e| e.to_string());
+        if d.verdict {
+            log::debug! {
+                resource = ?resource,
+                action   = ?action,
+                subject  = ?subject,
+                policy   = ?policy,
+                "access granted"
+            }
+        } else {
+            log::info! {
+                resource = ?resource,
+                action   = ?action,
+                subject  = ?subject,
+                policy   = ?policy,
+                reason   = ?d.reason,
+                "access denied"
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Decision, DecisionSink};
+    use crate::env::Env;
+    use crate::expr::{eq, ident, str};
+    use crate::mem::Memory;
+    use crate::parser::parse;
+    use crate::{Action, PolicyAccessControl, PolicyStorage, Resource};
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use ockam_core::compat::sync::Arc;
+    use ockam_core::{async_trait, Result};
+    use ockam_identity::{IdentitiesStorage, IdentityAttributesWriter, IdentityIdentifier};
+
+    #[test]
+    fn records_subexpression_results() {
+        let policy = r#"(and (= subject.role "admin") (= subject.env "prod"))"#;
+        let expr = parse(policy).unwrap().unwrap();
+        let mut env = Env::new();
+        env.put("subject.role", str("admin"));
+        env.put("subject.env", str("dev"));
+
+        let d = Decision::evaluate(&expr, &env);
+        assert!(!d.verdict());
+        assert!(d.reason().is_none());
+        assert_eq!(3, d.steps().len());
+        assert!(d.steps()[0].value().unwrap().is_false());
+        assert!(d.steps()[1].value().unwrap().is_true());
+        assert!(d.steps()[2].value().unwrap().is_false());
+    }
+
+    #[test]
+    fn records_evaluation_errors() {
+        let expr = crate::expr::eq([ident("subject.role"), str("admin")]);
+        let d = Decision::evaluate(&expr, &Env::new());
+        assert!(!d.verdict());
+        assert!(d.reason().is_some());
+        assert_eq!(1, d.steps().len());
+        assert!(d.steps()[0].error().is_some())
+    }
+
+    /// A sink recording the denials only
+    #[derive(Default)]
+    struct Denials(AtomicUsize);
+
+    #[async_trait]
+    impl DecisionSink for Denials {
+        fn is_recording(&self, verdict: bool) -> bool {
+            !verdict
+        }
+
+        async fn record(&self, d: &Decision) {
+            assert!(!d.verdict());
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[tokio::test]
+    async fn decisions_are_only_built_for_the_recorded_verdicts() -> Result<()> {
+        let resource = Resource::new("tcp-outlet/db");
+        let action = Action::new("handle_message");
+        let policies = Arc::new(Memory::new());
+        policies
+            .set_policy(&resource, &action, &eq([ident("subject.role"), str("db")]))
+            .await?;
+
+        let repository = IdentitiesStorage::create();
+        let member = IdentityIdentifier::from_hex("1111");
+        let stranger = IdentityIdentifier::from_hex("2222");
+        repository
+            .put_attribute_value(&member, "role", "db")
+            .await?;
+
+        let sink = Arc::new(Denials::default());
+        let ac = PolicyAccessControl::new(policies, repository, resource, action, Env::new())
+            .with_decision_sink(sink.clone());
+
+        assert!(ac.is_identity_authorized(member).await?);
+        assert_eq!(0, sink.0.load(Ordering::Relaxed));
+        assert!(!ac.is_identity_authorized(stranger).await?);
+        assert_eq!(1, sink.0.load(Ordering::Relaxed));
+        Ok(())
+    }
+}
@@ -1,10 +1,12 @@
 use crate::error::{EvalError, MergeError};
 use crate::expr::Expr;
+use minicbor::{Decode, Encode};
 use ockam_core::compat::collections::BTreeMap;
 use ockam_core::compat::string::{String, ToString};
 
-#[derive(Debug, Clone, Default)]
-pub struct Env(BTreeMap<String, Expr>);
+#[derive(Debug, Clone, Default, Encode, Decode)]
+#[cbor(transparent)]
+pub struct Env(#[n(0)] BTreeMap<String, Expr>);
 
 impl Env {
     pub fn new() -> Self {
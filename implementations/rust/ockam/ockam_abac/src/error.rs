@@ -24,6 +24,18 @@ pub enum EvalError {
     Malformed(String),
 }
 
+/// A policy expression failed static checking.
+///
+/// The span is a byte range into the source of the checked policy, as
+/// written when it was parsed with [`crate::Checker::parse`], and points
+/// at the offending sub-expression.
+#[derive(Debug)]
+pub struct CheckError {
+    source: String,
+    span: (usize, usize),
+    message: String,
+}
+
 #[derive(Debug)]
 pub enum MergeError {
     BindingExists(String),
@@ -45,6 +57,46 @@ impl EvalError {
     }
 }
 
+impl CheckError {
+    pub(crate) fn new(source: String, span: (usize, usize), message: String) -> Self {
+        CheckError {
+            source,
+            span,
+            message,
+        }
+    }
+
+    /// The checked policy, as written or displayed.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The byte range of the offending sub-expression in [`CheckError::source`].
+    pub fn span(&self) -> (usize, usize) {
+        self.span
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Render the line of the offending sub-expression, underlined.
+    pub fn annotate(&self) -> String {
+        let (start, end) = self.span;
+        let line_start = self.source[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = self.source[start..]
+            .find('\n')
+            .map_or(self.source.len(), |i| start + i);
+        let column = self.source[line_start..start].chars().count();
+        let width = self.source[start..end.min(line_end)].chars().count();
+        let mut s = self.source[line_start..line_end].to_string();
+        s.push('\n');
+        s.extend(core::iter::repeat(' ').take(column));
+        s.extend(core::iter::repeat('^').take(width.max(1)));
+        s
+    }
+}
+
 impl From<Utf8Error> for ParseError {
     fn from(e: Utf8Error) -> Self {
         Self::Utf8(e)
@@ -95,6 +147,13 @@ impl fmt::Display for EvalError {
     }
 }
 
+impl fmt::Display for CheckError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (start, end) = self.span;
+        write!(f, "{} at {start}..{end}", self.message)
+    }
+}
+
 #[cfg(feature = "std")]
 impl std::error::Error for ParseError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
@@ -122,6 +181,19 @@ impl From<ParseError> for ockam_core::Error {
     }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for CheckError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+impl From<CheckError> for ockam_core::Error {
+    fn from(e: CheckError) -> Self {
+        ockam_core::Error::new(Origin::Application, Kind::Invalid, e.to_string())
+    }
+}
+
 impl From<EvalError> for ockam_core::Error {
     fn from(e: EvalError) -> Self {
         ockam_core::Error::new(Origin::Application, Kind::Invalid, e.to_string())
@@ -82,6 +82,21 @@ pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
                             }
                             continue
                         }
+                        "permit-overrides" | "deny-overrides" => {
+                            // The arguments are policies combined by a policy
+                            // set. Each policy is evaluated on its own and a
+                            // policy failing to evaluate to a boolean counts as
+                            // denying access, without affecting the verdict of
+                            // the other policies.
+                            let allows = |x| matches!(eval(x, env), Ok(Expr::Bool(true)));
+                            let b = if id == "permit-overrides" {
+                                xs[1 ..].iter().any(allows)
+                            } else {
+                                xs[1 ..].iter().all(allows)
+                            };
+                            args.push(Expr::Bool(b));
+                            continue
+                        }
                         "not" => {
                             if nargs != 1 {
                                 return Err(EvalError::malformed("'not' requires one argument"))
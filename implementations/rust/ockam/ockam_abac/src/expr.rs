@@ -211,6 +211,27 @@ where
     with_op(ident("or"), exprs)
 }
 
+/// Combine policies so that access is granted if any of them grants it.
+///
+/// Unlike with [`or`], a policy failing to evaluate only denies access
+/// for itself.
+pub fn permit_overrides<I>(exprs: I) -> Expr
+where
+    I: IntoIterator<Item = Expr>,
+{
+    with_op(ident("permit-overrides"), exprs)
+}
+
+/// Combine policies so that access is granted if all of them grant it.
+///
+/// A policy failing to evaluate denies access.
+pub fn deny_overrides<I>(exprs: I) -> Expr
+where
+    I: IntoIterator<Item = Expr>,
+{
+    with_op(ident("deny-overrides"), exprs)
+}
+
 pub fn exists<I>(exprs: I) -> Expr
 where
     I: IntoIterator<Item = Expr>,
@@ -6,10 +6,15 @@
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+mod check;
+mod decision;
 mod env;
 mod error;
 mod eval;
+mod outgoing;
 mod policy;
+mod policy_set;
+mod sync;
 mod traits;
 mod types;
 
@@ -22,14 +27,21 @@ pub mod mem;
 mod storage;
 
 pub use attribute_access_control::AbacAccessControl;
+pub use check::{check, Checker, Type};
+pub use decision::{Decision, DecisionSink, Step, TracingDecisionSink};
 pub use env::Env;
-pub use error::{EvalError, ParseError};
+pub use error::{CheckError, EvalError, ParseError};
 pub use eval::eval;
 pub use expr::Expr;
+pub use outgoing::{OutgoingAbacAccessControl, OutgoingPolicyAccessControl};
 pub use policy::PolicyAccessControl;
+pub use policy_set::{migrate_policies, Combine, PolicySet, RESOURCE_SEPARATOR, RESOURCE_WILDCARD};
+pub use sync::{PolicyBundle, PolicyEntry, PolicySource, SyncedPolicyStorage};
 pub use traits::PolicyStorage;
 pub use types::{Action, Resource, Subject};
 
+#[cfg(feature = "std")]
+pub use check::parse_policy;
 #[cfg(feature = "std")]
 pub use parser::parse;
 
@@ -67,6 +67,10 @@ impl Inner {
             Vec::new()
         }
     }
+
+    fn resources(&self) -> Vec<Resource> {
+        self.policies.keys().cloned().collect()
+    }
 }
 
 #[async_trait]
@@ -88,6 +92,10 @@ impl PolicyStorage for Memory {
     async fn policies(&self, r: &Resource) -> Result<Vec<(Action, Expr)>> {
         Ok(self.inner.write().unwrap().policies(r))
     }
+
+    async fn resources(&self) -> Result<Vec<Resource>> {
+        Ok(self.inner.read().unwrap().resources())
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,214 @@
+use crate::{AbacAccessControl, PolicyAccessControl};
+use core::fmt;
+use core::fmt::{Debug, Formatter};
+use ockam_core::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::{OutgoingAccessControl, RelayMessage, Result};
+use ockam_identity::{IdentityIdentifier, SecureChannelRegistry};
+use tracing as log;
+
+/// Find the identity at the other end of the first secure channel on the
+/// onward route of a message.
+///
+/// Secure channels are found by the address of their encryptor in the
+/// given registry.
+fn destination_identity(
+    registry: &SecureChannelRegistry,
+    msg: &RelayMessage,
+) -> Option<IdentityIdentifier> {
+    msg.onward_route()
+        .iter()
+        .find_map(|addr| registry.get_channel_by_encryptor_address(addr))
+        .map(|entry| entry.their_id())
+}
+
+/// An [`OutgoingAccessControl`] evaluating a policy expression against the
+/// attributes of the *destination* identity of a message.
+///
+/// The destination identity is the identity at the other end of the secure
+/// channel the message is sent through. Messages which are not sent through
+/// a secure channel are denied.
+pub struct OutgoingAbacAccessControl {
+    registry: SecureChannelRegistry,
+    abac: AbacAccessControl,
+}
+
+/// Debug implementation printing out the policy expression only
+impl Debug for OutgoingAbacAccessControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.abac, f)
+    }
+}
+
+impl OutgoingAbacAccessControl {
+    pub fn new(registry: SecureChannelRegistry, abac: AbacAccessControl) -> Self {
+        Self { registry, abac }
+    }
+}
+
+#[async_trait]
+impl OutgoingAccessControl for OutgoingAbacAccessControl {
+    /// Returns true if the recipient of the message is validated by the policy expression
+    async fn is_authorized(&self, msg: &RelayMessage) -> Result<bool> {
+        if let Some(id) = destination_identity(&self.registry, msg) {
+            self.abac.is_identity_authorized(id).await
+        } else {
+            log::debug! {
+                onward = %msg.onward_route(),
+                "destination identity not found; access denied"
+            }
+            Ok(false)
+        }
+    }
+}
+
+/// An [`OutgoingAccessControl`] evaluating the stored policy of a resource
+/// and action against the attributes of the *destination* identity of a
+/// message.
+///
+/// See [`OutgoingAbacAccessControl`] for how the destination identity is
+/// determined.
+pub struct OutgoingPolicyAccessControl {
+    registry: SecureChannelRegistry,
+    policy: PolicyAccessControl,
+}
+
+/// Debug implementation writing out the resource, action and initial environment
+impl Debug for OutgoingPolicyAccessControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.policy, f)
+    }
+}
+
+impl OutgoingPolicyAccessControl {
+    pub fn new(registry: SecureChannelRegistry, policy: PolicyAccessControl) -> Self {
+        Self { registry, policy }
+    }
+}
+
+#[async_trait]
+impl OutgoingAccessControl for OutgoingPolicyAccessControl {
+    async fn is_authorized(&self, msg: &RelayMessage) -> Result<bool> {
+        if let Some(id) = destination_identity(&self.registry, msg) {
+            self.policy.is_identity_authorized(id).await
+        } else {
+            log::debug! {
+                onward = %msg.onward_route(),
+                "destination identity not found; access denied"
+            }
+            Ok(false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OutgoingPolicyAccessControl;
+    use crate::expr::{eq, ident, str};
+    use crate::mem::Memory;
+    use crate::{Action, Env, PolicyAccessControl, PolicyStorage, Resource};
+    use ockam_core::compat::sync::Arc;
+    use ockam_core::{
+        route, Address, LocalMessage, OutgoingAccessControl, RelayMessage, Result, Route,
+        TransportMessage,
+    };
+    use ockam_identity::{
+        IdentitiesStorage, IdentityAttributesWriter, IdentityIdentifier, SecureChannelRegistry,
+        SecureChannelRegistryEntry,
+    };
+
+    fn message(onward_route: Route) -> RelayMessage {
+        let destination = onward_route.next().unwrap().clone();
+        let local_message =
+            LocalMessage::new(TransportMessage::v1(onward_route, route![], vec![]), vec![]);
+        RelayMessage::new(Address::random_local(), destination, local_message)
+    }
+
+    fn channel(registry: &SecureChannelRegistry, their_id: &IdentityIdentifier) -> Address {
+        let encryptor = Address::random_local();
+        registry
+            .register_channel(SecureChannelRegistryEntry::new(
+                encryptor.clone(),
+                Address::random_local(),
+                Address::random_local(),
+                Address::random_local(),
+                true,
+                IdentityIdentifier::from_hex("0000"),
+                their_id.clone(),
+                Address::random_local(),
+            ))
+            .unwrap();
+        encryptor
+    }
+
+    #[tokio::test]
+    async fn destination_identity_is_checked_against_the_policy() -> Result<()> {
+        let resource = Resource::new("tcp-outlet/db");
+        let action = Action::new("send_message");
+        let policies = Arc::new(Memory::new());
+        policies
+            .set_policy(
+                &resource,
+                &action,
+                &eq([ident("subject.role"), str("db-client")]),
+            )
+            .await?;
+
+        let repository = IdentitiesStorage::create();
+        let client = IdentityIdentifier::from_hex("1111");
+        let stranger = IdentityIdentifier::from_hex("2222");
+        repository
+            .put_attribute_value(&client, "role", "db-client")
+            .await?;
+
+        let registry = SecureChannelRegistry::new();
+        let to_client = channel(&registry, &client);
+        let to_stranger = channel(&registry, &stranger);
+
+        let policy = PolicyAccessControl::new(policies, repository, resource, action, Env::new());
+        let ac = OutgoingPolicyAccessControl::new(registry, policy);
+
+        // Messages are checked against the identity at the other end of
+        // the first secure channel of the onward route
+        assert!(
+            ac.is_authorized(&message(route![to_client.clone(), "inlet"]))
+                .await?
+        );
+        assert!(
+            ac.is_authorized(&message(route!["tcp", to_client, "inlet"]))
+                .await?
+        );
+        assert!(
+            !ac.is_authorized(&message(route![to_stranger, "inlet"]))
+                .await?
+        );
+
+        // Messages which are not sent through a secure channel are denied
+        assert!(!ac.is_authorized(&message(route!["tcp", "inlet"])).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn messages_are_denied_without_a_policy() -> Result<()> {
+        let client = IdentityIdentifier::from_hex("1111");
+        let registry = SecureChannelRegistry::new();
+        let to_client = channel(&registry, &client);
+
+        let policy = PolicyAccessControl::new(
+            Arc::new(Memory::new()),
+            IdentitiesStorage::create(),
+            Resource::new("tcp-outlet/db"),
+            Action::new("send_message"),
+            Env::new(),
+        );
+        let ac = OutgoingPolicyAccessControl::new(registry, policy);
+
+        assert!(
+            !ac.is_authorized(&message(route![to_client, "inlet"]))
+                .await?
+        );
+
+        Ok(())
+    }
+}
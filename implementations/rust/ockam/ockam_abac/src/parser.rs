@@ -22,8 +22,21 @@ fn ident_pattern() -> &'static Regex {
 }
 
 #[cfg(feature = "std")]
-#[rustfmt::skip]
 pub fn parse(s: &str) -> Result<Option<Expr>, ParseError> {
+    Ok(parse_with_spans(s)?.map(|(expr, _)| expr))
+}
+
+/// Byte range of an expression in the string it was parsed from.
+#[cfg(feature = "std")]
+pub(crate) type Span = (usize, usize);
+
+/// Parse an expression and return the byte range in `s` of every
+/// sub-expression, in pre-order.
+///
+/// Several expressions in `s` are parsed as a list, spanning all of `s`.
+#[cfg(feature = "std")]
+#[rustfmt::skip]
+pub(crate) fn parse_with_spans(s: &str) -> Result<Option<(Expr, Vec<Span>)>, ParseError> {
     /// A stack operation.
     enum Op {
         Next,
@@ -42,6 +55,12 @@ pub fn parse(s: &str) -> Result<Option<Expr>, ParseError> {
     // Result values.
     let mut vals: Vec<Expr> = Vec::new();
 
+    // Spans of the expressions, in the order they start.
+    let mut spans: Vec<Span> = Vec::new();
+
+    // Indices in `spans` of the open lists and sequences.
+    let mut open: Vec<usize> = Vec::new();
+
     // Start by parsing the next expression.
     ctrl.push(Op::Next);
 
@@ -51,6 +70,28 @@ pub fn parse(s: &str) -> Result<Option<Expr>, ParseError> {
             Op::Next => match lx.parse(&mut parse_position)? {
                 None => continue,
                 Some(token) => {
+                    let end = parse_position;
+                    let start = end - token.src(s).len();
+                    match token.kind {
+                        TokenKind::Whitespace | TokenKind::LineComment | TokenKind::BlockComment => {}
+                        TokenKind::RParen => {
+                            let i = open.pop().ok_or_else(|| ParseError::message("')' without matching '('"))?;
+                            spans[i].1 = end
+                        }
+                        TokenKind::Reserved if token.reserved(s) == "]" => {
+                            let i = open.pop().ok_or_else(|| ParseError::message("']' without matching '['"))?;
+                            spans[i].1 = end
+                        }
+                        TokenKind::LParen => {
+                            open.push(spans.len());
+                            spans.push((start, end))
+                        }
+                        TokenKind::Reserved if token.reserved(s) == "[" => {
+                            open.push(spans.len());
+                            spans.push((start, end))
+                        }
+                        _ => spans.push((start, end))
+                    }
                     match token.kind {
                         TokenKind::Whitespace | TokenKind::LineComment | TokenKind::BlockComment =>
                             ctrl.push(Op::Next),
@@ -174,10 +215,11 @@ pub fn parse(s: &str) -> Result<Option<Expr>, ParseError> {
 
     match vals.len() {
         0 => Ok(None),
-        1 => Ok(Some(vals.remove(0))),
+        1 => Ok(Some((vals.remove(0), spans))),
         _ => {
             vals.reverse();
-            Ok(Some(Expr::List(vals)))
+            spans.insert(0, (0, s.len()));
+            Ok(Some((Expr::List(vals), spans)))
         }
     }
 }
@@ -1,7 +1,7 @@
 use crate::traits::PolicyStorage;
 use crate::types::{Action, Resource};
 use crate::AbacAccessControl;
-use crate::{Env, Expr};
+use crate::{Decision, DecisionSink, Env, Expr};
 use core::fmt;
 use core::fmt::{Debug, Formatter};
 use ockam_core::compat::boxed::Box;
@@ -9,7 +9,7 @@ use ockam_core::compat::format;
 use ockam_core::compat::sync::Arc;
 use ockam_core::{async_trait, RelayMessage};
 use ockam_core::{IncomingAccessControl, Result};
-use ockam_identity::IdentitiesRepository;
+use ockam_identity::{IdentitiesRepository, IdentityIdentifier, IdentitySecureChannelLocalInfo};
 use tracing as log;
 
 /// Evaluates a policy expression against an environment of attributes.
@@ -22,6 +22,8 @@ pub struct PolicyAccessControl {
     policies: Arc<dyn PolicyStorage>,
     repository: Arc<dyn IdentitiesRepository>,
     environment: Env,
+    fallback: Option<Expr>,
+    decisions: Option<Arc<dyn DecisionSink>>,
 }
 
 /// Debug implementation writing out the resource, action and initial environment
@@ -55,22 +57,123 @@ impl PolicyAccessControl {
             policies,
             repository,
             environment: env,
+            fallback: None,
+            decisions: None,
         }
     }
+
+    /// Evaluate the given policy if no policy is stored for this resource and action.
+    ///
+    /// The fallback policy is only kept by this access control, it is not stored.
+    pub fn with_fallback(mut self, policy: Expr) -> Self {
+        self.fallback = Some(policy);
+        self
+    }
+
+    /// Send a [`Decision`] record for every evaluation to the given sink
+    pub fn with_decision_sink(mut self, sink: Arc<dyn DecisionSink>) -> Self {
+        self.decisions = Some(sink);
+        self
+    }
+
+    /// The stored policy for this resource and action, or the fallback policy
+    async fn policy(&self) -> Result<Option<Expr>> {
+        match self
+            .policies
+            .get_policy(&self.resource, &self.action)
+            .await?
+        {
+            Some(expr) => Ok(Some(expr)),
+            None => Ok(self.fallback.clone()),
+        }
+    }
+
+    /// Evaluate the policy for the given identity without enforcing it.
+    pub async fn explain(&self, id: IdentityIdentifier) -> Result<Decision> {
+        if let Some(expr) = self.policy().await? {
+            self.abac(expr).explain_identity(id).await
+        } else {
+            Ok(self
+                .tag(Decision::denied("no policy found"))
+                .with_subject(id)
+                .with_environment(self.environment.clone()))
+        }
+    }
+
+    fn abac(&self, expr: Expr) -> AbacAccessControl {
+        let abac = AbacAccessControl::new(self.repository.clone(), expr, self.environment.clone())
+            .with_resource_action(self.resource.clone(), self.action.clone());
+        if let Some(sink) = &self.decisions {
+            abac.with_decision_sink(sink.clone())
+        } else {
+            abac
+        }
+    }
+
+    fn tag(&self, d: Decision) -> Decision {
+        d.with_resource(self.resource.clone())
+            .with_action(self.action.clone())
+    }
+
+    /// Record a decision with the given verdict, if the sink records it
+    async fn record(
+        &self,
+        verdict: bool,
+        subject: Option<IdentityIdentifier>,
+        d: impl FnOnce() -> Decision,
+    ) {
+        if let Some(sink) = &self.decisions {
+            if !sink.is_recording(verdict) {
+                return;
+            }
+            let mut d = self.tag(d());
+            if let Some(id) = subject {
+                d = d.with_subject(id)
+            }
+            sink.record(&d).await
+        }
+    }
+
+    /// Returns true if the identity is authorized by the policy
+    /// for this resource and action.
+    pub async fn is_identity_authorized(&self, id: IdentityIdentifier) -> Result<bool> {
+        if let Some(expr) = self.policy().await? {
+            self.abac(expr).is_identity_authorized(id).await
+        } else {
+            log::debug! {
+                resource = %self.resource,
+                action   = %self.action,
+                id       = %id,
+                "no policy found; access denied"
+            }
+            self.record(false, Some(id), || {
+                Decision::denied("no policy found").with_environment(self.environment.clone())
+            })
+            .await;
+            Ok(false)
+        }
+    }
+}
+
+/// The identity of the sender of a message, if it was received through a secure channel.
+fn their_identity(msg: &RelayMessage) -> Option<IdentityIdentifier> {
+    IdentitySecureChannelLocalInfo::find_info(msg.local_message())
+        .ok()
+        .map(|info| info.their_identity_id())
 }
 
 #[async_trait]
 impl IncomingAccessControl for PolicyAccessControl {
     async fn is_authorized(&self, msg: &RelayMessage) -> Result<bool> {
         // Load the policy expression for resource and action:
-        let expr = if let Some(expr) = self
-            .policies
-            .get_policy(&self.resource, &self.action)
-            .await?
-        {
+        let expr = if let Some(expr) = self.policy().await? {
             if let Expr::Bool(b) = expr {
                 // If the policy is a constant there is no need to populate
                 // the environment or look for message metadata.
+                self.record(b, their_identity(msg), || {
+                    Decision::evaluate(&expr, &Env::new())
+                })
+                .await;
                 return Ok(b);
             } else {
                 expr
@@ -82,11 +185,13 @@ impl IncomingAccessControl for PolicyAccessControl {
                 action   = %self.action,
                 "no policy found; access denied"
             }
+            self.record(false, their_identity(msg), || {
+                Decision::denied("no policy found").with_environment(self.environment.clone())
+            })
+            .await;
             return Ok(false);
         };
 
-        AbacAccessControl::new(self.repository.clone(), expr, self.environment.clone())
-            .is_authorized(msg)
-            .await
+        self.abac(expr).is_authorized(msg).await
     }
 }
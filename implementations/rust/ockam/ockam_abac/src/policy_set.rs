@@ -0,0 +1,500 @@
+use crate::expr::{deny_overrides, permit_overrides};
+use crate::traits::PolicyStorage;
+use crate::types::{Action, Resource};
+use crate::Expr;
+use core::fmt;
+use core::str::FromStr;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use ockam_core::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::format;
+use ockam_core::compat::sync::{Arc, RwLock};
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+
+/// Separator between the segments of a hierarchical resource name.
+pub const RESOURCE_SEPARATOR: char = '/';
+
+/// Wildcard character of resource patterns.
+pub const RESOURCE_WILDCARD: char = '*';
+
+/// How the policies of several resources which apply to the same
+/// resource are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Combine {
+    /// Only the policy of the most specific resource is used.
+    #[default]
+    MostSpecific,
+    /// Access is granted if all applicable policies grant it.
+    DenyOverrides,
+    /// Access is granted if any applicable policy grants it.
+    PermitOverrides,
+}
+
+impl fmt::Display for Combine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Combine::MostSpecific => f.write_str("most-specific"),
+            Combine::DenyOverrides => f.write_str("deny-overrides"),
+            Combine::PermitOverrides => f.write_str("permit-overrides"),
+        }
+    }
+}
+
+impl FromStr for Combine {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "most-specific" => Ok(Combine::MostSpecific),
+            "deny-overrides" => Ok(Combine::DenyOverrides),
+            "permit-overrides" => Ok(Combine::PermitOverrides),
+            other => Err(Error::new(
+                Origin::Application,
+                Kind::Invalid,
+                format!("unknown policy combining rule: {other}"),
+            )),
+        }
+    }
+}
+
+impl Resource {
+    /// Returns true if this resource name contains wildcards.
+    pub fn is_pattern(&self) -> bool {
+        self.as_str().contains(RESOURCE_WILDCARD)
+    }
+
+    /// Returns true if this resource, taken as a pattern, matches `other`.
+    ///
+    /// A `*` matches any sequence of characters, including the empty one.
+    /// A resource without wildcards only matches itself.
+    pub fn matches(&self, other: &Resource) -> bool {
+        glob(self.as_str(), other.as_str())
+    }
+
+    /// The parent resources of a hierarchical resource, closest first.
+    ///
+    /// For example the parents of `tcp-outlet/db/main` are `tcp-outlet/db`
+    /// and `tcp-outlet`.
+    pub fn parents(&self) -> impl Iterator<Item = Resource> + '_ {
+        let s = self.as_str();
+        s.char_indices()
+            .rev()
+            .filter(|(_, c)| *c == RESOURCE_SEPARATOR)
+            .map(move |(i, _)| Resource::new(&s[..i]))
+    }
+
+    /// The child resource with the given name, e.g. `tcp-outlet/db-main`
+    /// for the child `db-main` of `tcp-outlet`.
+    pub fn child(&self, name: &str) -> Resource {
+        Resource::from(format!("{self}{RESOURCE_SEPARATOR}{name}"))
+    }
+
+    /// How specific this resource is when used as a pattern: the number
+    /// of literal characters it matches.
+    fn specificity(&self) -> usize {
+        self.as_str()
+            .chars()
+            .filter(|c| *c != RESOURCE_WILDCARD)
+            .count()
+    }
+}
+
+/// Match `s` against a glob pattern where `*` matches any sequence of characters.
+fn glob(pattern: &str, s: &str) -> bool {
+    let p = pattern.as_bytes();
+    let s = s.as_bytes();
+    let (mut i, mut j) = (0, 0);
+    // Position of the last `*` in the pattern and the input position it matched at.
+    let mut star: Option<(usize, usize)> = None;
+    while j < s.len() {
+        if i < p.len() && p[i] == RESOURCE_WILDCARD as u8 {
+            star = Some((i, j));
+            i += 1
+        } else if i < p.len() && p[i] == s[j] {
+            i += 1;
+            j += 1
+        } else if let Some((si, sj)) = star {
+            i = si + 1;
+            j = sj + 1;
+            star = Some((si, sj + 1))
+        } else {
+            return false;
+        }
+    }
+    p[i..].iter().all(|c| *c == RESOURCE_WILDCARD as u8)
+}
+
+/// Copy the policies stored for a resource under a previous name to the resource.
+///
+/// Nothing is copied if the resource already has policies, so that policies set
+/// for the new name are kept. The policies of the previous name are kept as well,
+/// since another resource may still use that name.
+/// Returns true if policies were copied.
+pub async fn migrate_policies(
+    storage: &dyn PolicyStorage,
+    from: &Resource,
+    to: &Resource,
+) -> Result<bool> {
+    if from == to || !storage.policies(to).await?.is_empty() {
+        return Ok(false);
+    }
+    let policies = storage.policies(from).await?;
+    for (a, e) in &policies {
+        storage.set_policy(to, a, e).await?
+    }
+    Ok(!policies.is_empty())
+}
+
+/// A [`PolicyStorage`] resolving policies through a resource hierarchy.
+///
+/// The policy for a resource is looked up in this order:
+///
+/// 1. the policy stored for the resource itself,
+/// 2. the policies stored for resource patterns matching the resource,
+///    e.g. `tcp-outlet/db-*` or `tcp-outlet/*`,
+/// 3. the policies stored for the parents of the resource, e.g. `tcp-outlet`
+///    for `tcp-outlet/db-main`.
+///
+/// Patterns and parents are ranked by specificity, so that `tcp-outlet/db-*`
+/// takes precedence over `tcp-outlet/*`, which takes precedence over
+/// `tcp-outlet`. The applicable policies are then combined according to the
+/// [`Combine`] rule of the set: each policy is evaluated on its own, a policy
+/// failing to evaluate counting as denying access, and their verdicts are
+/// combined. If no policy applies, no policy is returned and access is denied.
+///
+/// Parents are found by walking the resource path. The resource patterns
+/// having policies are listed once and kept in memory, and this list is
+/// refreshed when a pattern policy is stored or deleted through the set.
+/// Call [`PolicySet::reload_patterns`] after changing pattern policies
+/// directly in the underlying storage.
+///
+/// Storing and deleting policies is delegated to the underlying storage.
+pub struct PolicySet {
+    storage: Arc<dyn PolicyStorage>,
+    combine: Combine,
+    patterns: RwLock<Option<Vec<Resource>>>,
+    generation: AtomicUsize,
+}
+
+impl fmt::Debug for PolicySet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "PolicySet({})", self.combine)
+    }
+}
+
+impl PolicySet {
+    pub fn new(storage: Arc<dyn PolicyStorage>, combine: Combine) -> Self {
+        Self {
+            storage,
+            combine,
+            patterns: RwLock::new(None),
+            generation: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn combine(&self) -> Combine {
+        self.combine
+    }
+
+    /// List the resource patterns of the underlying storage again on next use.
+    pub fn reload_patterns(&self) {
+        let mut patterns = self.patterns.write().unwrap();
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        *patterns = None
+    }
+
+    /// The resource patterns which have policies.
+    async fn patterns(&self) -> Result<Vec<Resource>> {
+        if let Some(ps) = self.patterns.read().unwrap().as_ref() {
+            return Ok(ps.clone());
+        }
+        let generation = self.generation.load(Ordering::SeqCst);
+        let mut ps = self.storage.resources().await?;
+        ps.retain(Resource::is_pattern);
+        let mut patterns = self.patterns.write().unwrap();
+        // Do not keep the list if patterns changed while it was read.
+        if self.generation.load(Ordering::SeqCst) == generation {
+            *patterns = Some(ps.clone())
+        }
+        Ok(ps)
+    }
+
+    /// All policies applying to the resource and action, most specific first.
+    pub async fn applicable(&self, r: &Resource, a: &Action) -> Result<Vec<(Resource, Expr)>> {
+        let mut candidates: Vec<Resource> = self
+            .patterns()
+            .await?
+            .into_iter()
+            .filter(|p| p.matches(r))
+            .collect();
+        candidates.extend(r.parents());
+        candidates.sort_by_key(|p| core::cmp::Reverse(p.specificity()));
+
+        let mut applicable = Vec::new();
+        if let Some(e) = self.storage.get_policy(r, a).await? {
+            applicable.push((r.clone(), e))
+        }
+        for p in candidates {
+            if let Some(e) = self.storage.get_policy(&p, a).await? {
+                applicable.push((p, e))
+            }
+        }
+        Ok(applicable)
+    }
+}
+
+#[async_trait]
+impl PolicyStorage for PolicySet {
+    async fn get_policy(&self, r: &Resource, a: &Action) -> Result<Option<Expr>> {
+        // Fast path: a policy for the resource itself is the most specific one.
+        if let Some(e) = self.storage.get_policy(r, a).await? {
+            if self.combine == Combine::MostSpecific {
+                return Ok(Some(e));
+            }
+        }
+        let applicable = self.applicable(r, a).await?;
+        if applicable.len() <= 1 || self.combine == Combine::MostSpecific {
+            return Ok(applicable.into_iter().next().map(|(_, e)| e));
+        }
+        let exprs = applicable.into_iter().map(|(_, e)| e);
+        if self.combine == Combine::DenyOverrides {
+            Ok(Some(deny_overrides(exprs)))
+        } else {
+            Ok(Some(permit_overrides(exprs)))
+        }
+    }
+
+    async fn set_policy(&self, r: &Resource, a: &Action, c: &Expr) -> Result<()> {
+        self.storage.set_policy(r, a, c).await?;
+        if r.is_pattern() {
+            self.reload_patterns()
+        }
+        Ok(())
+    }
+
+    async fn del_policy(&self, r: &Resource, a: &Action) -> Result<()> {
+        self.storage.del_policy(r, a).await?;
+        if r.is_pattern() {
+            self.reload_patterns()
+        }
+        Ok(())
+    }
+
+    async fn policies(&self, r: &Resource) -> Result<Vec<(Action, Expr)>> {
+        self.storage.policies(r).await
+    }
+
+    async fn resources(&self) -> Result<Vec<Resource>> {
+        self.storage.resources().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{glob, migrate_policies, Combine, PolicySet};
+    use crate::expr::{eq, ident, str};
+    use crate::mem::Memory;
+    use crate::{eval, Action, Env, PolicyStorage, Resource};
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use ockam_core::async_trait;
+    use ockam_core::compat::boxed::Box;
+    use ockam_core::compat::sync::Arc;
+    use ockam_core::compat::vec::Vec;
+    use ockam_core::Result;
+
+    #[test]
+    fn glob_matching() {
+        assert!(glob("tcp-outlet/*", "tcp-outlet/db"));
+        assert!(glob("tcp-outlet/*", "tcp-outlet/"));
+        assert!(glob("tcp-outlet/db-*", "tcp-outlet/db-main"));
+        assert!(glob("*-main", "tcp-outlet/db-main"));
+        assert!(glob("tcp-*/db-*", "tcp-outlet/db-main"));
+        assert!(glob("*", ""));
+        assert!(!glob("tcp-outlet/db-*", "tcp-outlet/web"));
+        assert!(!glob("tcp-outlet/*", "tcp-outlet"));
+        assert!(!glob("tcp-outlet", "tcp-outlet/db"));
+    }
+
+    #[test]
+    fn parents() {
+        let r = Resource::new("tcp-outlet/db/main");
+        let ps: Vec<Resource> = r.parents().collect();
+        assert_eq!(
+            ps,
+            [Resource::new("tcp-outlet/db"), Resource::new("tcp-outlet")]
+        );
+        assert_eq!(Resource::new("tcp-outlet").child("db"), ps[0]);
+        assert_eq!(Resource::new("echo").parents().count(), 0)
+    }
+
+    fn allows(e: &crate::Expr, role: &str) -> bool {
+        let mut env = Env::new();
+        env.put("subject.role", str(role));
+        eval(e, &env).unwrap().is_true()
+    }
+
+    async fn set(s: &PolicySet, r: &str, role: &str) -> Result<()> {
+        let e = eq([ident("subject.role"), str(role)]);
+        s.set_policy(&Resource::new(r), &Action::new("handle_message"), &e)
+            .await
+    }
+
+    #[tokio::test]
+    async fn most_specific_wins() -> Result<()> {
+        let s = PolicySet::new(Arc::new(Memory::new()), Combine::MostSpecific);
+        let a = Action::new("handle_message");
+        set(&s, "tcp-outlet", "any").await?;
+        set(&s, "tcp-outlet/*", "outlet").await?;
+        set(&s, "tcp-outlet/db-*", "dba").await?;
+
+        let e = s
+            .get_policy(&Resource::new("tcp-outlet/db-main"), &a)
+            .await?;
+        assert!(allows(&e.unwrap(), "dba"));
+        let e = s.get_policy(&Resource::new("tcp-outlet/web"), &a).await?;
+        assert!(allows(&e.unwrap(), "outlet"));
+        let e = s.get_policy(&Resource::new("tcp-outlet"), &a).await?;
+        assert!(allows(&e.unwrap(), "any"));
+
+        set(&s, "tcp-outlet/db-main", "owner").await?;
+        let e = s
+            .get_policy(&Resource::new("tcp-outlet/db-main"), &a)
+            .await?;
+        assert!(allows(&e.unwrap(), "owner"));
+
+        // default deny
+        assert!(s
+            .get_policy(&Resource::new("tcp-inlet/x"), &a)
+            .await?
+            .is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn combining_rules() -> Result<()> {
+        let a = Action::new("handle_message");
+        let r = Resource::new("tcp-outlet/db-main");
+
+        let storage = Arc::new(Memory::new());
+        let deny = PolicySet::new(storage.clone(), Combine::DenyOverrides);
+        set(&deny, "tcp-outlet/*", "dba").await?;
+        set(&deny, "tcp-outlet/db-*", "dba").await?;
+        assert!(allows(&deny.get_policy(&r, &a).await?.unwrap(), "dba"));
+        set(&deny, "tcp-outlet", "admin").await?;
+        assert!(!allows(&deny.get_policy(&r, &a).await?.unwrap(), "dba"));
+
+        let permit = PolicySet::new(storage, Combine::PermitOverrides);
+        let e = permit.get_policy(&r, &a).await?.unwrap();
+        assert!(allows(&e, "dba"));
+        assert!(allows(&e, "admin"));
+        assert!(!allows(&e, "guest"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn a_failing_policy_only_denies_for_itself() -> Result<()> {
+        let a = Action::new("handle_message");
+        let r = Resource::new("tcp-outlet/db-main");
+
+        // `subject.team` is not in the environment, so this policy fails to evaluate
+        let storage = Arc::new(Memory::new());
+        let team = eq([ident("subject.team"), str("db")]);
+        storage
+            .set_policy(&Resource::new("tcp-outlet"), &a, &team)
+            .await?;
+        let permit = PolicySet::new(storage.clone(), Combine::PermitOverrides);
+        set(&permit, "tcp-outlet/db-*", "dba").await?;
+
+        let e = permit.get_policy(&r, &a).await?.unwrap();
+        assert!(allows(&e, "dba"));
+        assert!(!allows(&e, "guest"));
+
+        let deny = PolicySet::new(storage, Combine::DenyOverrides);
+        assert!(!allows(&deny.get_policy(&r, &a).await?.unwrap(), "dba"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn policies_are_migrated_from_a_previous_name() -> Result<()> {
+        let a = Action::new("handle_message");
+        let s = PolicySet::new(Arc::new(Memory::new()), Combine::MostSpecific);
+        let legacy = Resource::new("db");
+        let r = Resource::new("tcp-outlet").child("db");
+        set(&s, "db", "dba").await?;
+
+        assert!(migrate_policies(&s, &legacy, &r).await?);
+        assert!(allows(&s.get_policy(&r, &a).await?.unwrap(), "dba"));
+        assert!(s.get_policy(&legacy, &a).await?.is_some());
+
+        // the policies of the new name are not overwritten
+        set(&s, "tcp-outlet/db", "owner").await?;
+        assert!(!migrate_policies(&s, &legacy, &r).await?);
+        assert!(allows(&s.get_policy(&r, &a).await?.unwrap(), "owner"));
+
+        // nothing to migrate
+        let other = Resource::new("tcp-outlet").child("web");
+        assert!(!migrate_policies(&s, &Resource::new("web"), &other).await?);
+        assert!(s.get_policy(&other, &a).await?.is_none());
+        Ok(())
+    }
+
+    /// A storage counting how many times its resources are listed
+    #[derive(Default)]
+    struct Listing {
+        storage: Memory,
+        listed: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl PolicyStorage for Listing {
+        async fn get_policy(&self, r: &Resource, a: &Action) -> Result<Option<crate::Expr>> {
+            self.storage.get_policy(r, a).await
+        }
+
+        async fn set_policy(&self, r: &Resource, a: &Action, c: &crate::Expr) -> Result<()> {
+            self.storage.set_policy(r, a, c).await
+        }
+
+        async fn del_policy(&self, r: &Resource, a: &Action) -> Result<()> {
+            self.storage.del_policy(r, a).await
+        }
+
+        async fn policies(&self, r: &Resource) -> Result<Vec<(Action, crate::Expr)>> {
+            self.storage.policies(r).await
+        }
+
+        async fn resources(&self) -> Result<Vec<Resource>> {
+            self.listed.fetch_add(1, Ordering::Relaxed);
+            self.storage.resources().await
+        }
+    }
+
+    #[tokio::test]
+    async fn resources_are_not_listed_on_every_lookup() -> Result<()> {
+        let a = Action::new("handle_message");
+        let r = Resource::new("tcp-outlet/db-main");
+        let storage = Arc::new(Listing::default());
+        let s = PolicySet::new(storage.clone(), Combine::MostSpecific);
+        set(&s, "tcp-outlet", "any").await?;
+
+        for _ in 0..3 {
+            assert!(allows(&s.get_policy(&r, &a).await?.unwrap(), "any"));
+        }
+        assert_eq!(storage.listed.load(Ordering::Relaxed), 1);
+
+        // storing a pattern refreshes the list of patterns
+        set(&s, "tcp-outlet/db-*", "dba").await?;
+        for _ in 0..3 {
+            assert!(allows(&s.get_policy(&r, &a).await?.unwrap(), "dba"));
+        }
+        assert_eq!(storage.listed.load(Ordering::Relaxed), 2);
+
+        // and so does deleting it
+        s.del_policy(&Resource::new("tcp-outlet/db-*"), &a).await?;
+        assert!(allows(&s.get_policy(&r, &a).await?.unwrap(), "any"));
+        assert_eq!(storage.listed.load(Ordering::Relaxed), 3);
+        Ok(())
+    }
+}
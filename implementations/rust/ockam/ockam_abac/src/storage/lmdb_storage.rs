@@ -67,6 +67,28 @@ impl PolicyStorage for LmdbStorage {
         };
         spawn_blocking(t).await.map_err(map_join_err)?
     }
+
+    async fn resources(&self) -> Result<Vec<Resource>> {
+        let d = self.clone();
+        let t = move || {
+            let tx = d.env.begin_ro_txn().map_err(map_lmdb_err)?;
+            let mut c = tx.open_ro_cursor(d.map).map_err(map_lmdb_err)?;
+            let mut xs: Vec<Resource> = Vec::new();
+            for entry in c.iter_start() {
+                let (k, _) = entry.map_err(map_lmdb_err)?;
+                let ks = str::from_utf8(k).map_err(from_utf8_err)?;
+                if let Some((r, _)) = ks.split_once(':') {
+                    if xs.last().map(|x| x.as_str()) != Some(r) {
+                        xs.push(Resource::new(r))
+                    }
+                } else {
+                    log::warn!(key = %ks, "malformed key in policy database")
+                }
+            }
+            Ok(xs)
+        };
+        spawn_blocking(t).await.map_err(map_join_err)?
+    }
 }
 
 fn map_join_err(err: JoinError) -> Error {
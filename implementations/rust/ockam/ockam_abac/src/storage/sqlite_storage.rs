@@ -115,6 +115,23 @@ impl PolicyStorage for SqliteStorage {
         };
         spawn_blocking(t).await.map_err(map_join_err)?
     }
+
+    async fn resources(&self) -> Result<Vec<Resource>> {
+        let conn = self.conn();
+        let t = move || {
+            let conn = conn.lock().unwrap();
+            let mut stmt = conn
+                .prepare("SELECT DISTINCT resource FROM policy;")
+                .map_err(map_sqlite_err)?;
+            let result = stmt
+                .query_map([], |row| Ok(Resource::from(row.get::<_, String>(0)?)))
+                .map_err(map_sqlite_err)?
+                .map(|r| r.map_err(map_sqlite_err))
+                .collect::<Result<Vec<Resource>, Error>>();
+            result
+        };
+        spawn_blocking(t).await.map_err(map_join_err)?
+    }
 }
 
 fn map_join_err(err: JoinError) -> Error {
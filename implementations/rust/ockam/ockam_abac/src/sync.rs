@@ -0,0 +1,379 @@
+use crate::expr::{seq, str, Expr};
+use crate::traits::PolicyStorage;
+use crate::types::{Action, Resource};
+use core::fmt;
+use minicbor::{Decode, Encode};
+use ockam_core::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::format;
+use ockam_core::compat::sync::{Arc, RwLock};
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use tracing as log;
+
+/// Resource under which the version of the last applied bundle is cached.
+///
+/// The name contains a character which is not valid in resource names
+/// created by nodes, so it can not clash with an actual resource.
+const BUNDLE_RESOURCE: Resource = Resource::assert_inline("#policy-bundle");
+const BUNDLE_VERSION: Action = Action::assert_inline("version");
+const BUNDLE_POLICIES: Action = Action::assert_inline("policies");
+
+/// A versioned set of policies distributed to nodes.
+#[derive(Debug, Clone, Default, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PolicyBundle {
+    #[n(0)] version: u64,
+    #[n(1)] policies: Vec<PolicyEntry>,
+}
+
+/// The policy of one resource and action in a [`PolicyBundle`].
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PolicyEntry {
+    #[n(0)] resource: Resource,
+    #[n(1)] action: Action,
+    #[n(2)] expression: Expr,
+}
+
+impl PolicyBundle {
+    pub fn new(version: u64) -> Self {
+        PolicyBundle {
+            version,
+            policies: Vec::new(),
+        }
+    }
+
+    pub fn with_policy(mut self, r: Resource, a: Action, e: Expr) -> Self {
+        self.policies.push(PolicyEntry::new(r, a, e));
+        self
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub fn policies(&self) -> &[PolicyEntry] {
+        &self.policies
+    }
+}
+
+impl PolicyEntry {
+    pub fn new(resource: Resource, action: Action, expression: Expr) -> Self {
+        PolicyEntry {
+            resource,
+            action,
+            expression,
+        }
+    }
+
+    pub fn resource(&self) -> &Resource {
+        &self.resource
+    }
+
+    pub fn action(&self) -> &Action {
+        &self.action
+    }
+
+    pub fn expression(&self) -> &Expr {
+        &self.expression
+    }
+}
+
+/// A remote source of truth for policies, e.g. a project authority.
+///
+/// Implementations are responsible for authenticating the bundles they
+/// return, e.g. by verifying a signature of the bundle issuer.
+#[async_trait]
+pub trait PolicySource: Send + Sync + 'static {
+    /// Fetch the current policy bundle if it is newer than `version`.
+    async fn fetch(&self, version: Option<u64>) -> Result<Option<PolicyBundle>>;
+}
+
+/// A [`PolicyStorage`] whose policies are managed by a [`PolicySource`].
+///
+/// Policies are read from a local cache, so that access control keeps
+/// working while the source is unreachable. When a newer bundle is
+/// applied the cache is updated in place, which changes the decisions of
+/// any running access control sharing this storage without a restart.
+///
+/// Policies set or deleted locally are written to the cache. They are
+/// replaced by the next bundle if it has a policy for the same resource and
+/// action, and are kept otherwise: only policies of a previous bundle which
+/// are not in the next one are removed.
+pub struct SyncedPolicyStorage {
+    cache: Arc<dyn PolicyStorage>,
+    version: RwLock<Option<u64>>,
+}
+
+impl fmt::Debug for SyncedPolicyStorage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SyncedPolicyStorage({:?})", self.version())
+    }
+}
+
+impl SyncedPolicyStorage {
+    /// Create a synced storage on top of a local cache, restoring the
+    /// version of the last bundle applied to it.
+    pub async fn new(cache: Arc<dyn PolicyStorage>) -> Result<Self> {
+        let version = match cache.get_policy(&BUNDLE_RESOURCE, &BUNDLE_VERSION).await? {
+            Some(Expr::Int(v)) => Some(v as u64),
+            _ => None,
+        };
+        Ok(SyncedPolicyStorage {
+            cache,
+            version: RwLock::new(version),
+        })
+    }
+
+    /// The version of the last applied bundle, if any.
+    pub fn version(&self) -> Option<u64> {
+        *self.version.read().unwrap()
+    }
+
+    /// Replace the cached policies with those of the bundle.
+    ///
+    /// Bundles which are not newer than the current version are ignored.
+    /// Returns true if the bundle was applied.
+    pub async fn apply(&self, bundle: PolicyBundle) -> Result<bool> {
+        if let Some(v) = self.version() {
+            if bundle.version <= v {
+                log::debug! {
+                    current = %v,
+                    version = %bundle.version,
+                    "ignoring outdated policy bundle"
+                }
+                return Ok(false);
+            }
+        }
+        if i64::try_from(bundle.version).is_err() {
+            let msg = format!("invalid policy bundle version: {}", bundle.version);
+            return Err(Error::new(Origin::Application, Kind::Invalid, msg));
+        }
+
+        // Write the new policies before deleting stale ones, so that
+        // policies present in both bundles are never missing.
+        let stale: Vec<(Resource, Action)> = self
+            .bundled()
+            .await?
+            .into_iter()
+            .filter(|(r, a)| {
+                !bundle
+                    .policies
+                    .iter()
+                    .any(|p| &p.resource == r && &p.action == a)
+            })
+            .collect();
+        for p in &bundle.policies {
+            self.cache
+                .set_policy(&p.resource, &p.action, &p.expression)
+                .await?
+        }
+        for (r, a) in &stale {
+            self.cache.del_policy(r, a).await?
+        }
+        let bundled = seq(bundle
+            .policies
+            .iter()
+            .map(|p| seq([str(p.resource.as_str()), str(p.action.as_str())])));
+        self.cache
+            .set_policy(&BUNDLE_RESOURCE, &BUNDLE_POLICIES, &bundled)
+            .await?;
+
+        // The version is stored last, so that an interrupted update is
+        // applied again.
+        let version = Expr::Int(bundle.version as i64);
+        self.cache
+            .set_policy(&BUNDLE_RESOURCE, &BUNDLE_VERSION, &version)
+            .await?;
+        *self.version.write().unwrap() = Some(bundle.version);
+
+        log::info! {
+            version  = %bundle.version,
+            policies = %bundle.policies.len(),
+            removed  = %stale.len(),
+            "applied policy bundle"
+        }
+        Ok(true)
+    }
+
+    /// The resources and actions of the policies of the last applied bundle.
+    async fn bundled(&self) -> Result<Vec<(Resource, Action)>> {
+        let mut bundled = Vec::new();
+        if let Some(Expr::Seq(entries)) = self
+            .cache
+            .get_policy(&BUNDLE_RESOURCE, &BUNDLE_POLICIES)
+            .await?
+        {
+            for entry in entries {
+                if let Expr::Seq(ra) = entry {
+                    if let [Expr::Str(r), Expr::Str(a)] = &ra[..] {
+                        bundled.push((Resource::new(r), Action::new(a)))
+                    }
+                }
+            }
+        }
+        Ok(bundled)
+    }
+
+    /// Fetch the current bundle from the source and apply it if it is newer.
+    ///
+    /// Returns true if the cached policies changed.
+    pub async fn sync(&self, source: &dyn PolicySource) -> Result<bool> {
+        match source.fetch(self.version()).await? {
+            Some(bundle) => self.apply(bundle).await,
+            None => Ok(false),
+        }
+    }
+}
+
+#[async_trait]
+impl PolicyStorage for SyncedPolicyStorage {
+    async fn get_policy(&self, r: &Resource, a: &Action) -> Result<Option<Expr>> {
+        if r == &BUNDLE_RESOURCE {
+            return Ok(None);
+        }
+        self.cache.get_policy(r, a).await
+    }
+
+    async fn set_policy(&self, r: &Resource, a: &Action, c: &Expr) -> Result<()> {
+        not_reserved(r)?;
+        self.cache.set_policy(r, a, c).await
+    }
+
+    async fn del_policy(&self, r: &Resource, a: &Action) -> Result<()> {
+        not_reserved(r)?;
+        self.cache.del_policy(r, a).await
+    }
+
+    async fn policies(&self, r: &Resource) -> Result<Vec<(Action, Expr)>> {
+        if r == &BUNDLE_RESOURCE {
+            return Ok(Vec::new());
+        }
+        self.cache.policies(r).await
+    }
+
+    async fn resources(&self) -> Result<Vec<Resource>> {
+        let mut resources = self.cache.resources().await?;
+        resources.retain(|r| r != &BUNDLE_RESOURCE);
+        Ok(resources)
+    }
+}
+
+/// The bundle resource is only written when a bundle is applied.
+fn not_reserved(r: &Resource) -> Result<()> {
+    if r == &BUNDLE_RESOURCE {
+        let msg = format!("resource {} is reserved for policy bundles", r);
+        return Err(Error::new(Origin::Application, Kind::Invalid, msg));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PolicyBundle, PolicySource, SyncedPolicyStorage, BUNDLE_RESOURCE, BUNDLE_VERSION};
+    use crate::expr::{eq, ident, str};
+    use crate::mem::Memory;
+    use crate::{Action, PolicyStorage, Resource};
+    use ockam_core::async_trait;
+    use ockam_core::compat::boxed::Box;
+    use ockam_core::compat::sync::Arc;
+    use ockam_core::errcode::Kind;
+    use ockam_core::Result;
+
+    struct Source(PolicyBundle);
+
+    #[async_trait]
+    impl PolicySource for Source {
+        async fn fetch(&self, version: Option<u64>) -> Result<Option<PolicyBundle>> {
+            if version < Some(self.0.version()) {
+                Ok(Some(self.0.clone()))
+            } else {
+                Ok(None)
+            }
+        }
+    }
+
+    fn bundle(version: u64, rs: &[&str]) -> PolicyBundle {
+        let e = eq([ident("subject.env"), str("prod")]);
+        rs.iter().fold(PolicyBundle::new(version), |b, r| {
+            b.with_policy(Resource::new(r), Action::new("handle_message"), e.clone())
+        })
+    }
+
+    #[tokio::test]
+    async fn applies_newer_bundles_only() -> Result<()> {
+        let a = Action::new("handle_message");
+        let cache = Arc::new(Memory::new());
+        let s = SyncedPolicyStorage::new(cache.clone()).await?;
+        assert_eq!(None, s.version());
+
+        assert!(s.sync(&Source(bundle(2, &["tcp-outlet", "echo"]))).await?);
+        assert_eq!(Some(2), s.version());
+        assert!(s.get_policy(&Resource::new("echo"), &a).await?.is_some());
+        assert_eq!(2, s.resources().await?.len());
+
+        assert!(!s.sync(&Source(bundle(2, &["tcp-inlet"]))).await?);
+        assert!(!s.apply(bundle(1, &["tcp-inlet"])).await?);
+
+        // stale policies are removed
+        assert!(s.apply(bundle(3, &["tcp-outlet"])).await?);
+        assert!(s.get_policy(&Resource::new("echo"), &a).await?.is_none());
+        assert!(s
+            .get_policy(&Resource::new("tcp-outlet"), &a)
+            .await?
+            .is_some());
+
+        // the version survives a restart
+        let s = SyncedPolicyStorage::new(cache).await?;
+        assert_eq!(Some(3), s.version());
+        assert_eq!(1, s.resources().await?.len());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn keeps_local_policies_which_are_not_bundled() -> Result<()> {
+        let a = Action::new("handle_message");
+        let local = Resource::new("tcp-outlet/db");
+        let s = SyncedPolicyStorage::new(Arc::new(Memory::new())).await?;
+        s.set_policy(&local, &a, &eq([ident("subject.role"), str("db")]))
+            .await?;
+
+        assert!(s.apply(bundle(1, &["tcp-outlet", "echo"])).await?);
+        assert!(s.apply(bundle(2, &["tcp-outlet"])).await?);
+        assert!(s.get_policy(&local, &a).await?.is_some());
+        assert!(s.get_policy(&Resource::new("echo"), &a).await?.is_none());
+
+        // a bundled policy replaces the local one and is removed with its bundle
+        assert!(s.apply(bundle(3, &["tcp-outlet/db"])).await?);
+        assert!(s.apply(bundle(4, &["tcp-outlet"])).await?);
+        assert!(s.get_policy(&local, &a).await?.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rejects_changes_to_the_bundle_resource() -> Result<()> {
+        let s = SyncedPolicyStorage::new(Arc::new(Memory::new())).await?;
+        assert!(s.apply(bundle(1, &["tcp-outlet"])).await?);
+
+        let e = eq([ident("subject.env"), str("prod")]);
+        let err = s
+            .set_policy(&BUNDLE_RESOURCE, &BUNDLE_VERSION, &e)
+            .await
+            .unwrap_err();
+        assert_eq!(Kind::Invalid, err.code().kind);
+        let err = s
+            .del_policy(&BUNDLE_RESOURCE, &BUNDLE_VERSION)
+            .await
+            .unwrap_err();
+        assert_eq!(Kind::Invalid, err.code().kind);
+
+        // the applied bundle is left untouched
+        let s = SyncedPolicyStorage::new(s.cache.clone()).await?;
+        assert_eq!(Some(1), s.version());
+        Ok(())
+    }
+}
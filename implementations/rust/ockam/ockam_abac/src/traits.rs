@@ -10,4 +10,11 @@ pub trait PolicyStorage: Send + Sync + 'static {
     async fn set_policy(&self, r: &Resource, a: &Action, c: &Expr) -> Result<()>;
     async fn del_policy(&self, r: &Resource, a: &Action) -> Result<()>;
     async fn policies(&self, r: &Resource) -> Result<Vec<(Action, Expr)>>;
+    /// All resources which have at least one policy.
+    ///
+    /// The default implementation returns no resources, so resource
+    /// patterns are not used with storages which do not list their resources.
+    async fn resources(&self) -> Result<Vec<Resource>> {
+        Ok(Vec::new())
+    }
 }
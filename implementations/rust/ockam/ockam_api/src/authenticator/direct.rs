@@ -1,3 +1,4 @@
+pub mod endpoints;
 pub mod types;
 
 use core::str;
@@ -6,19 +7,23 @@ use minicbor::Decoder;
 use ockam::identity::{AttributesEntry, IdentityAttributesReader, IdentityAttributesWriter};
 use ockam::identity::{IdentityIdentifier, IdentitySecureChannelLocalInfo};
 use ockam::identity::{OneTimeCode, Timestamp};
-use ockam_core::api::{self, Method, Request, Response, Status};
+use ockam_core::api::{Method, Request, RequestError, Response, Status};
 use ockam_core::compat::sync::{Arc, RwLock};
 use ockam_core::errcode::{Kind, Origin};
-use ockam_core::{self, CowStr, Result, Routed, Worker};
-use ockam_identity::{secure_channel_required, LEGACY_ID, TRUST_CONTEXT_ID};
+use ockam_core::{self, Result, Routed, Worker};
+use ockam_identity::{LEGACY_ID, TRUST_CONTEXT_ID};
+use ockam_node::api::{ApiService, Handle, RequestInfo};
 use ockam_node::{Context, RpcClient};
 use std::collections::HashMap;
 use std::num::NonZeroUsize;
 use std::time::{Duration, Instant};
-use tracing::{trace, warn};
-use types::AddMember;
+use tracing::warn;
 
-use crate::authenticator::direct::types::CreateToken;
+use crate::authenticator::direct::endpoints::{
+    AddMemberEndpoint, CreateTokenEndpoint, DeleteMemberEndpoint, ListMemberIdsEndpoint,
+    ListMembersEndpoint, PresentTokenEndpoint,
+};
+use crate::authenticator::direct::types::{AddMember, CreateToken};
 
 const MAX_TOKEN_DURATION: Duration = Duration::from_secs(600);
 
@@ -165,11 +170,24 @@ impl DirectAuthenticator {
         })
     }
 
-    async fn add_member<'a>(
+    /// Service serving the endpoints of the authenticator, at their paths and at
+    /// the paths used by previous clients
+    pub fn into_service(self) -> ApiService<Self> {
+        ApiService::new(self)
+            .route::<AddMemberEndpoint>()
+            .route_at::<AddMemberEndpoint>("/")
+            .route::<ListMemberIdsEndpoint>()
+            .route::<ListMembersEndpoint>()
+            .route_at::<ListMembersEndpoint>("/")
+            .route::<DeleteMemberEndpoint>()
+            .route_at::<DeleteMemberEndpoint>("/:id")
+    }
+
+    async fn add_member(
         &self,
         enroller: &IdentityIdentifier,
         id: &IdentityIdentifier,
-        attrs: &HashMap<CowStr<'a>, CowStr<'a>>,
+        attrs: &HashMap<String, String>,
     ) -> Result<()> {
         let auth_attrs = attrs
             .iter()
@@ -210,65 +228,74 @@ impl DirectAuthenticator {
     }
 }
 
-#[ockam_core::worker]
-impl Worker for DirectAuthenticator {
-    type Context = Context;
-    type Message = Vec<u8>;
+/// Identity of the sender of a request received on a secure channel
+fn caller(request: &RequestInfo<'_>) -> Result<IdentityIdentifier, RequestError> {
+    Ok(request
+        .extract::<IdentitySecureChannelLocalInfo>()?
+        .their_identity_id())
+}
 
-    async fn handle_message(&mut self, c: &mut Context, m: Routed<Self::Message>) -> Result<()> {
-        if let Ok(i) = IdentitySecureChannelLocalInfo::find_info(m.local_message()) {
-            let from = i.their_identity_id();
-            let mut dec = Decoder::new(m.as_body());
-            let req: Request = dec.decode()?;
-            trace! {
-                target: "ockam_api::authenticator::direct::direct_authenticator",
-                from   = %from,
-                id     = %req.id(),
-                method = ?req.method(),
-                path   = %req.path(),
-                body   = %req.has_body(),
-                "request"
-            }
-            let path_segments = req.path_segments::<5>();
-            let res = match (req.method(), path_segments.as_slice()) {
-                (Some(Method::Post), [""]) | (Some(Method::Post), ["members"]) => {
-                    let add: AddMember = dec.decode()?;
-                    self.add_member(&from, add.member(), add.attributes())
-                        .await?;
-                    Response::ok(req.id()).to_vec()?
-                }
-                (Some(Method::Get), ["member_ids"]) => {
-                    let entries = self.list_members(&from).await?;
-                    let ids: Vec<IdentityIdentifier> = entries.into_keys().collect();
-                    Response::ok(req.id()).body(ids).to_vec()?
-                }
-                // List members attested by our identity (enroller)
-                (Some(Method::Get), [""]) | (Some(Method::Get), ["members"]) => {
-                    let entries = self.list_members(&from).await?;
+#[ockam_core::async_trait]
+impl Handle<AddMemberEndpoint> for DirectAuthenticator {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context,
+        request: RequestInfo<'_>,
+        body: AddMember,
+    ) -> Result<(), RequestError> {
+        let from = caller(&request)?;
+        Ok(self
+            .add_member(&from, body.member(), body.attributes())
+            .await?)
+    }
+}
 
-                    Response::ok(req.id()).body(entries).to_vec()?
-                }
-                // Delete member if they were attested by our identity (enroller)
-                (Some(Method::Delete), [id]) | (Some(Method::Delete), ["members", id]) => {
-                    let identifier = IdentityIdentifier::try_from(id.to_string())?;
-                    if let Some(entry) = self.attributes_reader.get_attributes(&identifier).await? {
-                        if entry.attested_by() == Some(from) {
-                            self.attributes_writer.delete(&identifier).await?;
-                            Response::ok(req.id()).to_vec()?
-                        } else {
-                            api::forbidden(&req, "not attested by current enroller").to_vec()?
-                        }
-                    } else {
-                        Response::ok(req.id()).to_vec()?
-                    }
-                }
+#[ockam_core::async_trait]
+impl Handle<ListMemberIdsEndpoint> for DirectAuthenticator {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context,
+        request: RequestInfo<'_>,
+        _body: (),
+    ) -> Result<Vec<IdentityIdentifier>, RequestError> {
+        let from = caller(&request)?;
+        let entries = self.list_members(&from).await?;
+        Ok(entries.into_keys().collect())
+    }
+}
 
-                _ => api::unknown_path(&req).to_vec()?,
-            };
-            c.send(m.return_route(), res).await
-        } else {
-            secure_channel_required(c, m).await
+/// List members attested by our identity (enroller)
+#[ockam_core::async_trait]
+impl Handle<ListMembersEndpoint> for DirectAuthenticator {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context,
+        request: RequestInfo<'_>,
+        _body: (),
+    ) -> Result<HashMap<IdentityIdentifier, AttributesEntry>, RequestError> {
+        let from = caller(&request)?;
+        Ok(self.list_members(&from).await?)
+    }
+}
+
+/// Delete member if they were attested by our identity (enroller)
+#[ockam_core::async_trait]
+impl Handle<DeleteMemberEndpoint> for DirectAuthenticator {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context,
+        request: RequestInfo<'_>,
+        _body: (),
+    ) -> Result<(), RequestError> {
+        let from = caller(&request)?;
+        let identifier = IdentityIdentifier::try_from(request.param("id")?.to_string())?;
+        if let Some(entry) = self.attributes_reader.get_attributes(&identifier).await? {
+            if entry.attested_by() != Some(from) {
+                return Err(RequestError::forbidden("not attested by current enroller"));
+            }
+            self.attributes_writer.delete(&identifier).await?;
         }
+        Ok(())
     }
 }
 
@@ -289,17 +316,23 @@ impl EnrollmentTokenAuthenticator {
     pub fn new_worker_pair(
         trust_context: String,
         attributes_writer: Arc<dyn IdentityAttributesWriter>,
-    ) -> (EnrollmentTokenIssuer, EnrollmentTokenAcceptor) {
+    ) -> (
+        ApiService<EnrollmentTokenIssuer>,
+        ApiService<EnrollmentTokenAcceptor>,
+    ) {
         let base = Self {
             trust_context,
             tokens: Arc::new(RwLock::new(LruCache::new(
                 NonZeroUsize::new(128).expect("0 < 128"),
             ))),
         };
-        (
-            EnrollmentTokenIssuer(base.clone()),
-            EnrollmentTokenAcceptor(base, attributes_writer),
-        )
+        let issuer = ApiService::new(EnrollmentTokenIssuer(base.clone()))
+            .route::<CreateTokenEndpoint>()
+            .route_at::<CreateTokenEndpoint>("/");
+        let acceptor = ApiService::new(EnrollmentTokenAcceptor(base, attributes_writer))
+            .route::<PresentTokenEndpoint>()
+            .route_at::<PresentTokenEndpoint>("/");
+        (issuer, acceptor)
     }
 }
 
@@ -335,120 +368,67 @@ impl EnrollmentTokenIssuer {
     }
 }
 
-#[ockam_core::worker]
-impl Worker for EnrollmentTokenIssuer {
-    type Context = Context;
-    type Message = Vec<u8>;
-
-    async fn handle_message(&mut self, c: &mut Context, m: Routed<Self::Message>) -> Result<()> {
-        if let Ok(i) = IdentitySecureChannelLocalInfo::find_info(m.local_message()) {
-            let from = i.their_identity_id();
-            let mut dec = Decoder::new(m.as_body());
-            let req: Request = dec.decode()?;
-            trace! {
-                target: "ockam_api::authenticator::direct::enrollment_token_issuer",
-                from   = %from,
-                id     = %req.id(),
-                method = ?req.method(),
-                path   = %req.path(),
-                body   = %req.has_body(),
-                "request"
-            }
-            let res = match (req.method(), req.path()) {
-                (Some(Method::Post), "/") | (Some(Method::Post), "/tokens") => {
-                    let att: CreateToken = dec.decode()?;
-                    let duration = att.token_duration();
-                    match self
-                        .issue_token(&from, att.into_owned_attributes(), duration)
-                        .await
-                    {
-                        Ok(otc) => Response::ok(req.id()).body(&otc).to_vec()?,
-                        Err(error) => api::internal_error(&req, &error.to_string()).to_vec()?,
-                    }
-                }
-                _ => api::unknown_path(&req).to_vec()?,
-            };
-            c.send(m.return_route(), res).await
-        } else {
-            secure_channel_required(c, m).await
-        }
+#[ockam_core::async_trait]
+impl Handle<CreateTokenEndpoint> for EnrollmentTokenIssuer {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context,
+        request: RequestInfo<'_>,
+        body: CreateToken,
+    ) -> Result<OneTimeCode, RequestError> {
+        let from = caller(&request)?;
+        let duration = body.token_duration();
+        Ok(self
+            .issue_token(&from, body.into_owned_attributes(), duration)
+            .await?)
     }
 }
 
-#[ockam_core::worker]
-impl Worker for EnrollmentTokenAcceptor {
-    type Context = Context;
-    type Message = Vec<u8>;
-
-    async fn handle_message(&mut self, c: &mut Context, m: Routed<Self::Message>) -> Result<()> {
-        if let Ok(i) = IdentitySecureChannelLocalInfo::find_info(m.local_message()) {
-            let from = i.their_identity_id();
-            let mut dec = Decoder::new(m.as_body());
-            let req: Request = dec.decode()?;
-            trace! {
-                target: "ockam_api::authenticator::direct::enrollment_token_acceptor",
-                from   = %from,
-                id     = %req.id(),
-                method = ?req.method(),
-                path   = %req.path(),
-                body   = %req.has_body(),
-                "request"
-            }
-            let res = match (req.method(), req.path()) {
-                (Some(Method::Post), "/") | (Some(Method::Post), "/credential") => {
-                    //TODO: move out of the worker handle_message implementation
-                    let otc: OneTimeCode = dec.decode()?;
-                    let token = match self.0.tokens.write() {
-                        Ok(mut r) => {
-                            if let Some(tkn) = r.pop(otc.code()) {
-                                if tkn.time.elapsed() > tkn.max_token_duration {
-                                    Err(api::forbidden(&req, "expired token"))
-                                } else {
-                                    Ok(tkn)
-                                }
-                            } else {
-                                Err(api::forbidden(&req, "unknown token"))
-                            }
-                        }
-                        Err(_) => Err(api::internal_error(
-                            &req,
-                            "Failed to get read lock on tokens table",
-                        )),
-                    };
-                    match token {
-                        Ok(tkn) => {
-                            //TODO: fixme:  unify use of hashmap vs btreemap
-                            let trust_context = self.0.trust_context.as_bytes().to_vec();
-                            let attrs = tkn
-                                .attrs
-                                .iter()
-                                .map(|(k, v)| (k.to_string(), v.as_bytes().to_vec()))
-                                .chain(
-                                    [
-                                        (LEGACY_ID.to_owned(), trust_context.clone()),
-                                        (TRUST_CONTEXT_ID.to_owned(), trust_context),
-                                    ]
-                                    .into_iter(),
-                                )
-                                .collect();
-                            let entry = AttributesEntry::new(
-                                attrs,
-                                Timestamp::now().unwrap(),
-                                None,
-                                Some(tkn.generated_by),
-                            );
-                            self.1.put_attributes(&from, entry).await?;
-                            Response::ok(req.id()).to_vec()?
-                        }
-                        Err(err) => err.to_vec()?,
-                    }
+#[ockam_core::async_trait]
+impl Handle<PresentTokenEndpoint> for EnrollmentTokenAcceptor {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context,
+        request: RequestInfo<'_>,
+        otc: OneTimeCode,
+    ) -> Result<(), RequestError> {
+        let from = caller(&request)?;
+        let tkn = match self.0.tokens.write() {
+            Ok(mut r) => match r.pop(otc.code()) {
+                Some(tkn) if tkn.time.elapsed() > tkn.max_token_duration => {
+                    return Err(RequestError::forbidden("expired token"))
                 }
-                _ => api::unknown_path(&req).to_vec()?,
-            };
-            c.send(m.return_route(), res).await
-        } else {
-            secure_channel_required(c, m).await
-        }
+                Some(tkn) => tkn,
+                None => return Err(RequestError::forbidden("unknown token")),
+            },
+            Err(_) => {
+                return Err(RequestError::internal_error(
+                    "Failed to get read lock on tokens table",
+                ))
+            }
+        };
+
+        //TODO: fixme:  unify use of hashmap vs btreemap
+        let trust_context = self.0.trust_context.as_bytes().to_vec();
+        let attrs = tkn
+            .attrs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.as_bytes().to_vec()))
+            .chain(
+                [
+                    (LEGACY_ID.to_owned(), trust_context.clone()),
+                    (TRUST_CONTEXT_ID.to_owned(), trust_context),
+                ]
+                .into_iter(),
+            )
+            .collect();
+        let entry = AttributesEntry::new(
+            attrs,
+            Timestamp::now().unwrap(),
+            None,
+            Some(tkn.generated_by),
+        );
+        Ok(self.1.put_attributes(&from, entry).await?)
     }
 }
 
@@ -472,23 +452,21 @@ impl DirectAuthenticatorClient {
         attributes: HashMap<&str, &str>,
     ) -> Result<()> {
         self.0
-            .request_no_resp_body(
-                &Request::post("/").body(AddMember::new(id).with_attributes(attributes)),
-            )
+            .call::<AddMemberEndpoint>(&[], AddMember::new(id).with_attributes(attributes))
             .await
     }
 
     pub async fn list_member_ids(&self) -> Result<Vec<IdentityIdentifier>> {
-        self.0.request(&Request::get("/member_ids")).await
+        self.0.call::<ListMemberIdsEndpoint>(&[], ()).await
     }
 
     pub async fn list_members(&self) -> Result<HashMap<IdentityIdentifier, AttributesEntry>> {
-        self.0.request(&Request::get("/")).await
+        self.0.call::<ListMembersEndpoint>(&[], ()).await
     }
 
     pub async fn delete_member(&self, id: IdentityIdentifier) -> Result<()> {
         self.0
-            .request_no_resp_body(&Request::delete(format!("/{id}")))
+            .call::<DeleteMemberEndpoint>(&[&id.to_string()], ())
             .await
     }
 }
@@ -505,15 +483,10 @@ impl TokenIssuerClient {
         attributes: HashMap<&str, &str>,
         duration: Option<Duration>,
     ) -> Result<OneTimeCode> {
-        self.0
-            .request(
-                &Request::post("/").body(
-                    CreateToken::new()
-                        .with_attributes(attributes)
-                        .with_duration(duration),
-                ),
-            )
-            .await
+        let body = CreateToken::new()
+            .with_attributes(attributes)
+            .with_duration(duration);
+        self.0.call::<CreateTokenEndpoint>(&[], body).await
     }
 }
 
@@ -525,8 +498,6 @@ impl TokenAcceptorClient {
     }
 
     pub async fn present_token(&self, c: &OneTimeCode) -> Result<()> {
-        self.0
-            .request_no_resp_body(&Request::post("/").body(c))
-            .await
+        self.0.call::<PresentTokenEndpoint>(&[], c.clone()).await
     }
 }
@@ -0,0 +1,65 @@
+use ockam::identity::{AttributesEntry, IdentityIdentifier, OneTimeCode};
+use ockam_core::api::{Endpoint, Method};
+use std::collections::HashMap;
+
+use crate::authenticator::direct::types::{AddMember, CreateToken};
+
+/// Add a member attested by the enroller sending the request
+pub struct AddMemberEndpoint;
+
+impl Endpoint for AddMemberEndpoint {
+    const METHOD: Method = Method::Post;
+    const PATH: &'static str = "/members";
+    type Request = AddMember;
+    type Response = ();
+}
+
+/// List the identifiers of the members attested by the enroller sending the request
+pub struct ListMemberIdsEndpoint;
+
+impl Endpoint for ListMemberIdsEndpoint {
+    const METHOD: Method = Method::Get;
+    const PATH: &'static str = "/member_ids";
+    type Request = ();
+    type Response = Vec<IdentityIdentifier>;
+}
+
+/// List the members attested by the enroller sending the request, with their attributes
+pub struct ListMembersEndpoint;
+
+impl Endpoint for ListMembersEndpoint {
+    const METHOD: Method = Method::Get;
+    const PATH: &'static str = "/members";
+    type Request = ();
+    type Response = HashMap<IdentityIdentifier, AttributesEntry>;
+}
+
+/// Delete a member attested by the enroller sending the request
+pub struct DeleteMemberEndpoint;
+
+impl Endpoint for DeleteMemberEndpoint {
+    const METHOD: Method = Method::Delete;
+    const PATH: &'static str = "/members/:id";
+    type Request = ();
+    type Response = ();
+}
+
+/// Create an enrollment token for the attributes of a future member
+pub struct CreateTokenEndpoint;
+
+impl Endpoint for CreateTokenEndpoint {
+    const METHOD: Method = Method::Post;
+    const PATH: &'static str = "/tokens";
+    type Request = CreateToken;
+    type Response = OneTimeCode;
+}
+
+/// Present an enrollment token to become a member
+pub struct PresentTokenEndpoint;
+
+impl Endpoint for PresentTokenEndpoint {
+    const METHOD: Method = Method::Post;
+    const PATH: &'static str = "/credential";
+    type Request = OneTimeCode;
+    type Response = ();
+}
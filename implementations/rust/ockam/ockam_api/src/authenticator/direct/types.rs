@@ -1,6 +1,5 @@
 use minicbor::{Decode, Encode};
 use ockam::identity::IdentityIdentifier;
-use ockam_core::CowStr;
 use std::collections::HashMap;
 use std::time::Duration;
 
@@ -10,14 +9,14 @@ use ockam_core::TypeTag;
 #[derive(Debug, Decode, Encode)]
 #[rustfmt::skip]
 #[cbor(map)]
-pub struct AddMember<'a> {
+pub struct AddMember {
     #[cfg(feature = "tag")]
     #[n(0)] tag: TypeTag<2820828>,
     #[n(1)] member: IdentityIdentifier,
-    #[b(2)] attributes: HashMap<CowStr<'a>, CowStr<'a>>,
+    #[n(2)] attributes: HashMap<String, String>,
 }
 
-impl<'a> AddMember<'a> {
+impl AddMember {
     pub fn new(member: IdentityIdentifier) -> Self {
         AddMember {
             #[cfg(feature = "tag")]
@@ -27,7 +26,7 @@ impl<'a> AddMember<'a> {
         }
     }
 
-    pub fn with_attributes<S: Into<CowStr<'a>>>(mut self, attributes: HashMap<S, S>) -> Self {
+    pub fn with_attributes<S: Into<String>>(mut self, attributes: HashMap<S, S>) -> Self {
         self.attributes = attributes
             .into_iter()
             .map(|(k, v)| (k.into(), v.into()))
@@ -39,7 +38,7 @@ impl<'a> AddMember<'a> {
         &self.member
     }
 
-    pub fn attributes(&self) -> &HashMap<CowStr, CowStr> {
+    pub fn attributes(&self) -> &HashMap<String, String> {
         &self.attributes
     }
 }
@@ -47,14 +46,14 @@ impl<'a> AddMember<'a> {
 #[derive(Debug, Decode, Encode)]
 #[rustfmt::skip]
 #[cbor(map)]
-pub struct CreateToken<'a> {
+pub struct CreateToken {
     #[cfg(feature = "tag")]
     #[n(0)] tag: TypeTag<2502742>,
-    #[b(1)] attributes: HashMap<CowStr<'a>, CowStr<'a>>,
-    #[b(2)] token_duration_secs: Option<u64>
+    #[n(1)] attributes: HashMap<String, String>,
+    #[n(2)] token_duration_secs: Option<u64>
 }
 
-impl<'a> CreateToken<'a> {
+impl CreateToken {
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         CreateToken {
@@ -65,7 +64,7 @@ impl<'a> CreateToken<'a> {
         }
     }
 
-    pub fn with_attributes<S: Into<CowStr<'a>>>(mut self, attributes: HashMap<S, S>) -> Self {
+    pub fn with_attributes<S: Into<String>>(mut self, attributes: HashMap<S, S>) -> Self {
         self.attributes = attributes
             .into_iter()
             .map(|(k, v)| (k.into(), v.into()))
@@ -80,9 +79,6 @@ impl<'a> CreateToken<'a> {
 
     pub fn into_owned_attributes(self) -> HashMap<String, String> {
         self.attributes
-            .into_iter()
-            .map(|(k, v)| (k.into_owned(), v.into_owned()))
-            .collect()
     }
 
     pub fn token_duration(&self) -> Option<Duration> {
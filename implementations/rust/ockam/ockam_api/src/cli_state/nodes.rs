@@ -8,6 +8,7 @@ use crate::nodes::models::transport::CreateTransportJson;
 use backwards_compatibility::*;
 use miette::{IntoDiagnostic, WrapErr};
 use nix::errno::Errno;
+use nix::sys::signal::Signal;
 use ockam_core::compat::collections::HashSet;
 use ockam_core::compat::sync::Arc;
 use ockam_identity::{IdentityIdentifier, LmdbStorage};
@@ -16,6 +17,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::{Duration, Instant};
 use sysinfo::{Pid, ProcessExt, ProcessStatus, System, SystemExt};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -80,14 +82,44 @@ impl NodeState {
 
     pub fn kill_process(&self, sigkill: bool) -> Result<()> {
         if let Some(pid) = self.pid()? {
-            nix::sys::signal::kill(
-                nix::unistd::Pid::from_raw(pid),
+            self.send_signal(
+                pid,
                 if sigkill {
-                    nix::sys::signal::Signal::SIGKILL
+                    Signal::SIGKILL
                 } else {
-                    nix::sys::signal::Signal::SIGTERM
+                    Signal::SIGTERM
                 },
-            )
+            )?;
+            std::fs::remove_file(self.paths.pid())?;
+        }
+        info!(name = %self.name(), "node process killed");
+        Ok(())
+    }
+
+    /// Send a SIGTERM signal to the node process, which drains the node before
+    /// stopping it, and wait at most `timeout` for the process to exit
+    pub fn drain_process(&self, timeout: Duration) -> Result<()> {
+        if let Some(pid) = self.pid()? {
+            let started = Instant::now();
+            self.send_signal(pid, Signal::SIGTERM)?;
+            while self.is_running() {
+                if started.elapsed() >= timeout {
+                    return Err(CliStateError::Io(std::io::Error::new(
+                        std::io::ErrorKind::TimedOut,
+                        format!("PID `{pid}` was still draining after {timeout:?}"),
+                    )));
+                }
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            // The node removes its PID file once drained, unless it was killed
+            let _ = std::fs::remove_file(self.paths.pid());
+        }
+        info!(name = %self.name(), "node process drained");
+        Ok(())
+    }
+
+    fn send_signal(&self, pid: i32, signal: Signal) -> Result<()> {
+        nix::sys::signal::kill(nix::unistd::Pid::from_raw(pid), signal)
             .or_else(|e| {
                 if e == Errno::ESRCH {
                     tracing::warn!(node = %self.name(), %pid, "No such process");
@@ -101,11 +133,7 @@ impl NodeState {
                     std::io::ErrorKind::Other,
                     format!("failed to stop PID `{pid}` with error `{e}`"),
                 ))
-            })?;
-            std::fs::remove_file(self.paths.pid())?;
-        }
-        info!(name = %self.name(), "node process killed");
-        Ok(())
+            })
     }
 
     pub fn set_setup(&self, setup: &NodeSetupConfig) -> Result<()> {
@@ -309,6 +337,14 @@ pub struct NodeSetupConfig {
     pub authority_node: Option<bool>,
     pub project: Option<ProjectLookup>,
     pub api_transport: Option<CreateTransportJson>,
+    /// How the policies of a resource and of its parents are combined, if not the default
+    pub policy_combining: Option<String>,
+    /// This flag is used to restart the node with the same policy synchronization.
+    /// The field might be missing in previous configuration files, hence it is an Option
+    pub sync_policies: Option<bool>,
+    /// The address where the runtime metrics are served, if any.
+    /// The field might be missing in previous configuration files, hence it is an Option
+    pub metrics_addr: Option<String>,
 }
 
 impl NodeSetupConfig {
@@ -327,6 +363,21 @@ impl NodeSetupConfig {
         self
     }
 
+    pub fn set_policy_combining(mut self, policy_combining: Option<String>) -> Self {
+        self.policy_combining = policy_combining;
+        self
+    }
+
+    pub fn set_sync_policies(mut self, sync_policies: bool) -> Self {
+        self.sync_policies = Some(sync_policies);
+        self
+    }
+
+    pub fn set_metrics_addr(mut self, metrics_addr: Option<String>) -> Self {
+        self.metrics_addr = metrics_addr;
+        self
+    }
+
     pub fn set_api_transport(mut self, transport: CreateTransportJson) -> Self {
         self.api_transport = Some(transport);
         self
@@ -490,6 +541,9 @@ mod traits {
                         authority_node: setup.authority_node,
                         project: setup.project,
                         api_transport: None,
+                        policy_combining: None,
+                        sync_policies: None,
+                        metrics_addr: None,
                     };
                     if let Some(t) = setup
                         .transports
@@ -256,6 +256,18 @@ impl TrustAuthorityConfig {
             .as_ref()
             .ok_or_else(|| ApiError::core("Missing own credential on trust authority config"))
     }
+
+    /// Return the identity of the authority and the route to its node, if
+    /// credentials are retrieved from a remote credential issuer
+    pub async fn remote_authority(&self) -> Result<Option<(Identity, Route)>> {
+        match &self.own_credential {
+            Some(CredentialRetrieverConfig::FromCredentialIssuer(issuer)) => Ok(Some((
+                issuer.resolve_identity().await?,
+                issuer.resolve_route().await?,
+            ))),
+            _ => Ok(None),
+        }
+    }
 }
 
 /// Type of credential retriever
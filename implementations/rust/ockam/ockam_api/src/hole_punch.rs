@@ -0,0 +1,427 @@
+//! Direct connections between nodes through UDP hole punching
+//!
+//! Two nodes which can already talk to each other, typically over a relay,
+//! use a rendezvous service to punch a hole through their NATs. The node
+//! asking for a direct path sends a [`HolePunchRequest`] to the
+//! [`HolePunchService`] of its peer, and both nodes then start a
+//! [`UdpHolePuncher`] towards each other.
+//!
+//! Hole punched paths run over a reliable UDP transport, so the rendezvous
+//! service must use a reliable UDP transport too.
+
+use core::time::Duration;
+use minicbor::{Decode, Decoder, Encode};
+use ockam_core::api::{self, Method, Request, RequestBuilder, Response};
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::Arc;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::flow_control::FlowControlId;
+use ockam_core::{
+    route, Address, AllowAll, AllowOnwardAddress, Error, Result, Route, Routed, Worker,
+};
+use ockam_node::compat::asynchronous::Mutex;
+use ockam_node::tokio;
+use ockam_node::{Context, MessageReceiveOptions};
+use ockam_transport_udp::{UdpHolePuncher, UdpReliabilityOptions, UdpTransport, UDP};
+use tracing::{debug, trace, warn};
+
+#[cfg(feature = "tag")]
+use ockam_core::TypeTag;
+
+use crate::DefaultAddress;
+
+/// Maximum duration to wait for a hole to open.
+pub const HOLE_PUNCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximum duration to wait for a peer to close its side of a hole.
+pub const HOLE_CLOSE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Request body asking a node to punch a hole towards the sender.
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct HolePunchRequest {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<4751830>,
+    /// UDP address (`host:port`) of the rendezvous service.
+    #[n(1)] rendezvous_addr: String,
+    /// Name of the puncher to create on the receiving node.
+    #[n(2)] puncher_name: String,
+    /// Name of the puncher of the sending node.
+    #[n(3)] peer_puncher_name: String,
+}
+
+impl HolePunchRequest {
+    pub fn new(
+        rendezvous_addr: impl Into<String>,
+        puncher_name: impl Into<String>,
+        peer_puncher_name: impl Into<String>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            rendezvous_addr: rendezvous_addr.into(),
+            puncher_name: puncher_name.into(),
+            peer_puncher_name: peer_puncher_name.into(),
+        }
+    }
+
+    pub fn rendezvous_addr(&self) -> &str {
+        &self.rendezvous_addr
+    }
+
+    pub fn puncher_name(&self) -> &str {
+        &self.puncher_name
+    }
+
+    pub fn peer_puncher_name(&self) -> &str {
+        &self.peer_puncher_name
+    }
+}
+
+/// The reliable UDP transport of a node, created when it punches its first hole.
+///
+/// A node can only run one UDP transport, so this handle is shared by the
+/// node manager and the [`HolePunchService`]. It also keeps the punchers of
+/// the node, by name, until their hole is closed.
+#[derive(Clone, Default)]
+pub struct HolePunchTransport {
+    udp: Arc<Mutex<Option<UdpTransport>>>,
+    punchers: Arc<Mutex<BTreeMap<String, UdpHolePuncher>>>,
+}
+
+impl HolePunchTransport {
+    /// Create the UDP transport of the node if it does not exist yet
+    async fn ensure_created(&self, ctx: &Context) -> Result<()> {
+        let mut udp = self.udp.lock().await;
+        if udp.is_none() {
+            debug!("Creating the UDP transport used for hole punching");
+            *udp = Some(UdpTransport::create_reliable(ctx, UdpReliabilityOptions::new()).await?);
+        }
+        Ok(())
+    }
+
+    /// Punch a hole to the puncher named `peer_puncher_name`.
+    ///
+    /// Once the hole is open, messages sent to the returned address are
+    /// delivered to the peer node. The hole stays open until it is closed
+    /// with [`HolePunchTransport::close_hole`].
+    pub async fn punch_hole(
+        &self,
+        ctx: &Context,
+        rendezvous_addr: &str,
+        puncher_name: &str,
+        peer_puncher_name: &str,
+    ) -> Result<Address> {
+        if self.punchers.lock().await.contains_key(puncher_name) {
+            return Err(Error::new(
+                Origin::Transport,
+                Kind::AlreadyExists,
+                format!("a hole is already punched by {puncher_name}"),
+            ));
+        }
+        self.ensure_created(ctx).await?;
+
+        let rendezvous_route = route![(UDP, rendezvous_addr), DefaultAddress::RENDEZVOUS_SERVICE];
+        let mut puncher_ctx = ctx
+            .new_detached(
+                Address::random_tagged("HolePunchTransport.puncher"),
+                AllowAll,
+                AllowAll,
+            )
+            .await?;
+        let mut puncher = UdpHolePuncher::create(
+            &mut puncher_ctx,
+            puncher_name,
+            peer_puncher_name,
+            rendezvous_route,
+        )
+        .await?;
+
+        match tokio::time::timeout(HOLE_PUNCH_TIMEOUT, puncher.wait_for_hole_open()).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                puncher.stop().await?;
+                return Err(e);
+            }
+            Err(_) => {
+                puncher.stop().await?;
+                return Err(Error::new(
+                    Origin::Transport,
+                    Kind::Timeout,
+                    format!("the hole to {peer_puncher_name} did not open in time"),
+                ));
+            }
+        }
+        debug!(%puncher_name, %peer_puncher_name, "Hole punched");
+        let address = puncher.address();
+        self.punchers
+            .lock()
+            .await
+            .insert(puncher_name.to_string(), puncher);
+        Ok(address)
+    }
+
+    /// Stop the puncher named `puncher_name`, if it exists.
+    ///
+    /// Return `true` if a puncher was stopped.
+    pub async fn close_hole(&self, puncher_name: &str) -> Result<bool> {
+        let puncher = self.punchers.lock().await.remove(puncher_name);
+        match puncher {
+            Some(puncher) => {
+                debug!(%puncher_name, "Closing hole");
+                puncher.stop().await?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+/// Send a [`HolePunchRequest`] to the [`HolePunchService`] of a peer node.
+///
+/// `ctx` must be able to receive the reply coming back over `route`.
+/// The call returns once the peer has opened its side of the hole.
+pub async fn request_hole_punch(
+    ctx: &mut Context,
+    route: Route,
+    request: RequestBuilder<HolePunchRequest>,
+) -> Result<()> {
+    send_request(ctx, route, request.to_vec()?, HOLE_PUNCH_TIMEOUT * 2).await
+}
+
+/// Ask the [`HolePunchService`] of a peer node to close the hole of its
+/// puncher named `puncher_name`.
+pub async fn request_hole_close(ctx: &mut Context, route: Route, puncher_name: &str) -> Result<()> {
+    let request = Request::delete(format!("/{puncher_name}")).to_vec()?;
+    send_request(ctx, route, request, HOLE_CLOSE_TIMEOUT).await
+}
+
+async fn send_request(
+    ctx: &mut Context,
+    route: Route,
+    request: Vec<u8>,
+    timeout: Duration,
+) -> Result<()> {
+    ctx.send(route, request).await?;
+    let options = MessageReceiveOptions::new().with_timeout(timeout);
+    let reply = ctx.receive_extended::<Vec<u8>>(options).await?.body();
+    let (response, decoder) = Response::parse_response_header(&reply)?;
+    if response.is_ok() {
+        Ok(())
+    } else {
+        Err(Error::new(
+            Origin::Api,
+            Kind::Invalid,
+            Response::parse_err_msg(response, decoder),
+        ))
+    }
+}
+
+/// Create a client context sending hole punch requests through `next`.
+pub async fn hole_punch_client(ctx: &Context, next: &Address) -> Result<Context> {
+    ctx.new_detached(
+        Address::random_tagged("HolePunchService.client"),
+        AllowAll,
+        AllowOnwardAddress(next.clone()),
+    )
+    .await
+}
+
+/// A direct path of an inlet, over a hole punched towards the outlet node
+#[derive(Clone, Debug)]
+pub(crate) struct DirectPath {
+    /// Name of the puncher of the inlet node
+    pub(crate) puncher_name: String,
+    /// Name of the puncher of the outlet node
+    pub(crate) peer_puncher_name: String,
+    /// Encryptor of the secure channel to the outlet node on the relay path
+    pub(crate) relay_encryptor: Address,
+    /// Flow control of the secure channel to the outlet node on the relay path
+    pub(crate) relay_flow_control_id: Option<FlowControlId>,
+    /// Encryptor of the secure channel to the outlet node over the hole, once created
+    pub(crate) encryptor: Option<Address>,
+}
+
+impl DirectPath {
+    /// Close both sides of the hole.
+    ///
+    /// The outlet node is asked to close its side over the relay path, which
+    /// may not be reachable anymore.
+    pub(crate) async fn close(&self, ctx: &Context, transport: &HolePunchTransport) -> Result<()> {
+        transport.close_hole(&self.puncher_name).await?;
+
+        let mut client = hole_punch_client(ctx, &self.relay_encryptor).await?;
+        if let Some(flow_control_id) = &self.relay_flow_control_id {
+            ctx.flow_controls()
+                .add_consumer(client.address(), flow_control_id);
+        }
+        request_hole_close(
+            &mut client,
+            route![
+                self.relay_encryptor.clone(),
+                DefaultAddress::HOLE_PUNCH_SERVICE
+            ],
+            &self.peer_puncher_name,
+        )
+        .await
+    }
+}
+
+/// Service punching holes towards the nodes sending it a [`HolePunchRequest`].
+///
+/// A hole is only punched through a rendezvous service running on one of the
+/// `rendezvous_hosts`, which are the hosts of the projects of the node, so that
+/// a peer can't make the node send UDP datagrams to arbitrary addresses.
+///
+/// Requests are handled concurrently, each one in its own task, since they
+/// wait for the hole to open before getting a reply. A hole stays open until
+/// the peer asks to close it, with a `DELETE /{puncher_name}` request.
+pub struct HolePunchService {
+    transport: HolePunchTransport,
+    rendezvous_hosts: Vec<String>,
+}
+
+impl HolePunchService {
+    pub fn new(transport: HolePunchTransport, rendezvous_hosts: Vec<String>) -> Self {
+        Self {
+            transport,
+            rendezvous_hosts,
+        }
+    }
+
+    /// Check that a rendezvous address runs on one of the allowed hosts
+    fn check_rendezvous_addr(&self, rendezvous_addr: &str) -> Result<()> {
+        match rendezvous_host(rendezvous_addr) {
+            Some(host) if self.rendezvous_hosts.iter().any(|h| h == host) => Ok(()),
+            _ => Err(Error::new(
+                Origin::Api,
+                Kind::Invalid,
+                format!("{rendezvous_addr} is not the address of a project rendezvous service"),
+            )),
+        }
+    }
+}
+
+/// The host of a `host:port` address
+pub(crate) fn rendezvous_host(addr: &str) -> Option<&str> {
+    match addr.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => Some(host),
+        _ => None,
+    }
+}
+
+#[ockam_core::worker]
+impl Worker for HolePunchService {
+    type Context = Context;
+    type Message = Vec<u8>;
+
+    async fn handle_message(&mut self, c: &mut Context, m: Routed<Self::Message>) -> Result<()> {
+        let return_route = m.return_route();
+        let mut dec = Decoder::new(m.as_body());
+        let req: Request = dec.decode()?;
+        trace! {
+            target: "ockam_api::hole_punch",
+            id     = %req.id(),
+            method = ?req.method(),
+            path   = %req.path(),
+            body   = %req.has_body(),
+            "request"
+        }
+        let body: HolePunchRequest = match (req.method(), req.path_segments::<2>().as_slice()) {
+            (Some(Method::Post), [""]) => dec.decode()?,
+            (Some(Method::Delete), [puncher_name]) => {
+                let res = match self.transport.close_hole(puncher_name).await {
+                    Ok(true) => Response::ok(req.id()).to_vec()?,
+                    Ok(false) => Response::not_found(req.id())
+                        .body(
+                            api::Error::new(req.path())
+                                .with_message(format!("Unknown puncher {puncher_name}")),
+                        )
+                        .to_vec()?,
+                    Err(e) => api::internal_error(&req, &e.to_string()).to_vec()?,
+                };
+                return c.send(return_route, res).await;
+            }
+            _ => {
+                let res = api::unknown_path(&req).to_vec()?;
+                return c.send(return_route, res).await;
+            }
+        };
+
+        if let Err(e) = self.check_rendezvous_addr(body.rendezvous_addr()) {
+            warn!(peer = %body.peer_puncher_name(), err = %e, "Rejected a hole punch request");
+            let res = api::forbidden(&req, &e.to_string()).to_vec()?;
+            return c.send(return_route, res).await;
+        }
+
+        let transport = self.transport.clone();
+        let ctx = c
+            .new_detached(
+                Address::random_tagged("HolePunchService.reply"),
+                AllowAll,
+                AllowAll,
+            )
+            .await?;
+        tokio::spawn(async move {
+            let res = transport
+                .punch_hole(
+                    &ctx,
+                    body.rendezvous_addr(),
+                    body.puncher_name(),
+                    body.peer_puncher_name(),
+                )
+                .await;
+            let reply = match res {
+                Ok(_) => Response::ok(req.id()).to_vec(),
+                Err(e) => {
+                    warn!(peer = %body.peer_puncher_name(), err = %e, "Failed to punch a hole");
+                    api::bad_request(&req, &e.to_string()).to_vec()
+                }
+            };
+            match reply {
+                Ok(reply) => {
+                    if let Err(e) = ctx.send(return_route, reply).await {
+                        debug!(err = %e, "Failed to reply to a hole punch request")
+                    }
+                }
+                Err(e) => debug!(err = %e, "Failed to encode a hole punch reply"),
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_roundtrip() {
+        let request = HolePunchRequest::new("127.0.0.1:4000", "outlet", "inlet");
+        let bytes = minicbor::to_vec(request).unwrap();
+        let decoded: HolePunchRequest = minicbor::decode(&bytes).unwrap();
+        assert_eq!(decoded.rendezvous_addr(), "127.0.0.1:4000");
+        assert_eq!(decoded.puncher_name(), "outlet");
+        assert_eq!(decoded.peer_puncher_name(), "inlet");
+    }
+
+    #[test]
+    fn only_project_rendezvous_addresses_are_allowed() {
+        let service = HolePunchService::new(
+            HolePunchTransport::default(),
+            vec!["k8s-hub.ockam.network".to_string()],
+        );
+        assert!(service
+            .check_rendezvous_addr("k8s-hub.ockam.network:4000")
+            .is_ok());
+        assert!(service.check_rendezvous_addr("127.0.0.1:4000").is_err());
+        assert!(service
+            .check_rendezvous_addr("k8s-hub.ockam.network")
+            .is_err());
+
+        // A node without projects punches no holes
+        let service = HolePunchService::new(HolePunchTransport::default(), vec![]);
+        assert!(service.check_rendezvous_addr("127.0.0.1:4000").is_err());
+    }
+}
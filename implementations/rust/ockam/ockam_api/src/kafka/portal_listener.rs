@@ -1,6 +1,6 @@
 use ockam_core::compat::sync::Arc;
 use ockam_core::{route, Address, Any, Routed, Worker};
-use ockam_node::Context;
+use ockam_node::{Context, RestartPolicy, Supervisor};
 use tracing::trace;
 
 use crate::kafka::inlet_controller::KafkaInletController;
@@ -80,14 +80,19 @@ impl KafkaPortalListener {
         secure_channel_controller: Arc<dyn KafkaSecureChannelController>,
         listener_address: Address,
     ) -> ockam_core::Result<()> {
+        let uuid_to_name = TopicUuidMap::default();
+
+        // The listener is restarted if it panics, instead of leaving the kafka inlet unusable.
+        // An error for one connection doesn't restart it, so that it can't make it give up.
+        let supervisor = Supervisor::new(RestartPolicy::new().without_restart_on_errors());
         context
             .start_worker(
                 listener_address,
-                Self {
-                    inlet_controller,
-                    secure_channel_controller,
-                    uuid_to_name: Default::default(),
-                },
+                supervisor.supervise(move || Self {
+                    inlet_controller: inlet_controller.clone(),
+                    secure_channel_controller: secure_channel_controller.clone(),
+                    uuid_to_name: uuid_to_name.clone(),
+                }),
             )
             .await
     }
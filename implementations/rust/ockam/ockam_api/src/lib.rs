@@ -133,12 +133,15 @@ pub mod config;
 pub mod echoer;
 pub mod enroll;
 pub mod error;
+pub mod hole_punch;
 pub mod hop;
 pub mod identity;
 pub mod kafka;
+pub mod metrics;
 pub mod minicbor_url;
 pub mod nodes;
 pub mod okta;
+pub mod policies;
 pub mod port_range;
 pub mod rpc_proxy_service;
 pub mod trust_context;
@@ -177,6 +180,9 @@ impl DefaultAddress {
     pub const KAFKA_PRODUCER: &'static str = "kafka_producer";
     pub const KAFKA_DIRECT: &'static str = "kafka_direct";
     pub const RPC_PROXY: &'static str = "rpc_proxy_service";
+    pub const POLICY_SERVICE: &'static str = "policies";
+    pub const HOLE_PUNCH_SERVICE: &'static str = "hole_punch";
+    pub const RENDEZVOUS_SERVICE: &'static str = "rendezvous";
 
     pub fn is_valid(name: &str) -> bool {
         matches!(
@@ -200,6 +206,9 @@ impl DefaultAddress {
                 | Self::KAFKA_OUTLET
                 | Self::KAFKA_DIRECT
                 | Self::RPC_PROXY
+                | Self::POLICY_SERVICE
+                | Self::HOLE_PUNCH_SERVICE
+                | Self::RENDEZVOUS_SERVICE
         )
     }
 
@@ -224,6 +233,9 @@ impl DefaultAddress {
             Self::KAFKA_OUTLET,
             Self::KAFKA_DIRECT,
             Self::RPC_PROXY,
+            Self::POLICY_SERVICE,
+            Self::HOLE_PUNCH_SERVICE,
+            Self::RENDEZVOUS_SERVICE,
         ]
         .iter()
         .copied()
@@ -234,6 +246,7 @@ pub mod actions {
     use ockam_abac::Action;
 
     pub const HANDLE_MESSAGE: Action = Action::assert_inline("handle_message");
+    pub const SEND_MESSAGE: Action = Action::assert_inline("send_message");
 }
 
 pub mod resources {
@@ -336,5 +349,8 @@ mod test {
         assert!(DefaultAddress::is_valid(DefaultAddress::KAFKA_CONSUMER));
         assert!(DefaultAddress::is_valid(DefaultAddress::KAFKA_PRODUCER));
         assert!(DefaultAddress::is_valid(DefaultAddress::RPC_PROXY));
+        assert!(DefaultAddress::is_valid(DefaultAddress::POLICY_SERVICE));
+        assert!(DefaultAddress::is_valid(DefaultAddress::HOLE_PUNCH_SERVICE));
+        assert!(DefaultAddress::is_valid(DefaultAddress::RENDEZVOUS_SERVICE));
     }
 }
@@ -0,0 +1,115 @@
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::thread;
+
+use tiny_http::{Header, Method, Response, Server};
+use tracing::{debug, info, warn};
+
+use crate::error::ApiError;
+use ockam_core::Result;
+use ockam_node::MetricsRegistry;
+
+/// Content type of the OpenMetrics text format
+const OPENMETRICS_CONTENT_TYPE: &str =
+    "Content-Type: application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Local HTTP server exposing the runtime metrics of a node
+/// in the OpenMetrics text format, on the `/metrics` path
+///
+/// The server runs on a dedicated thread until it is stopped or dropped.
+pub struct MetricsServer {
+    server: Arc<Server>,
+    address: SocketAddr,
+}
+
+impl MetricsServer {
+    /// Start serving the metrics of the given registry on the given address
+    pub fn start(registry: MetricsRegistry, address: &str) -> Result<MetricsServer> {
+        let server = Server::http(address).map_err(|e| {
+            ApiError::core(format!(
+                "failed to start the metrics server on {address}: {e}"
+            ))
+        })?;
+        let address = server.server_addr().to_ip().ok_or_else(|| {
+            ApiError::core(format!(
+                "the metrics server address {address} is not an IP address"
+            ))
+        })?;
+        let server = Arc::new(server);
+        info!("Serving the node metrics on http://{address}/metrics");
+
+        let incoming = server.clone();
+        thread::spawn(move || {
+            for request in incoming.incoming_requests() {
+                let result = if request.method() == &Method::Get && request.url() == "/metrics" {
+                    let content_type = Header::from_str(OPENMETRICS_CONTENT_TYPE).unwrap();
+                    request
+                        .respond(Response::from_string(registry.render()).with_header(content_type))
+                } else {
+                    request.respond(Response::empty(404))
+                };
+                if let Err(e) = result {
+                    warn!("Failed to respond to a metrics request: {e}");
+                }
+            }
+            debug!("Metrics server on {address} stopped");
+        });
+
+        Ok(MetricsServer { server, address })
+    }
+
+    /// Address the server is listening on
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Stop the server
+    pub fn stop(&self) {
+        self.server.unblock();
+    }
+}
+
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    fn get(address: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "GET {path} HTTP/1.1\r\nHost: {address}\r\nConnection: close\r\n\r\n"
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn serve_metrics() -> Result<()> {
+        let registry = MetricsRegistry::new();
+        registry
+            .counter("ockam_test_messages", "Messages", &[("address", "app")])
+            .add(2);
+        let server = MetricsServer::start(registry, "127.0.0.1:0")?;
+
+        let response = get(server.address(), "/metrics");
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.contains("application/openmetrics-text"));
+        assert!(response.contains("ockam_test_messages_total{address=\"app\"} 2\n"));
+        assert!(response.ends_with("# EOF\n"));
+
+        let response = get(server.address(), "/other");
+        assert!(response.starts_with("HTTP/1.1 404"));
+        Ok(())
+    }
+}
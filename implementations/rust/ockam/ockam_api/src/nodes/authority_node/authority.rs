@@ -22,6 +22,7 @@ use crate::bootstrapped_identities_store::BootstrapedIdentityStore;
 use crate::echoer::Echoer;
 use crate::nodes::authority_node::authority::EnrollerCheck::{AnyMember, EnrollerOnly};
 use crate::nodes::authority_node::Configuration;
+use crate::policies::PolicyService;
 use crate::{actions, DefaultAddress};
 
 /// This struct represents an Authority, which is an
@@ -31,6 +32,7 @@ use crate::{actions, DefaultAddress};
 //   - a credential issuer
 //   - an enrollment token issuer
 //   - an enrollment token acceptor
+//   - a policy service
 pub struct Authority {
     identifier: IdentityIdentifier,
     secure_channels: Arc<SecureChannels>,
@@ -117,7 +119,8 @@ impl Authority {
             self.attributes_writer(),
             self.attributes_reader(),
         )
-        .await?;
+        .await?
+        .into_service();
 
         let name = configuration.clone().authenticator_name();
         ctx.flow_controls()
@@ -203,6 +206,34 @@ impl Authority {
         Ok(())
     }
 
+    /// Start the policy service distributing signed policy bundles to the project nodes
+    pub async fn start_policy_service(
+        &self,
+        ctx: &Context,
+        secure_channel_flow_control_id: &FlowControlId,
+        configuration: &Configuration,
+    ) -> Result<()> {
+        let storage_path = configuration.policies_storage_path();
+        Self::create_ockam_directory_if_necessary(&storage_path)?;
+        let storage = Arc::new(LmdbStorage::new(&storage_path).await?);
+        let service = PolicyService::new(
+            self.identities(),
+            self.identifier(),
+            self.attributes_reader(),
+            storage,
+        );
+
+        let address = DefaultAddress::POLICY_SERVICE.to_string();
+        ctx.flow_controls()
+            .add_consumer(address.clone(), secure_channel_flow_control_id);
+
+        self.start(ctx, configuration, address.clone(), AnyMember, service)
+            .await?;
+
+        info!("started a policy service at '{address}'");
+        Ok(())
+    }
+
     /// Start the Okta service to retrieve attributes authenticated by Okta
     pub async fn start_okta(
         &self,
@@ -86,6 +86,14 @@ impl Configuration {
             .clone()
             .unwrap_or(DefaultAddress::DIRECT_AUTHENTICATOR.to_string())
     }
+
+    /// Return the path where published policies are persisted, next to the
+    /// storage for identity attributes
+    pub(crate) fn policies_storage_path(&self) -> PathBuf {
+        let mut name = self.storage_path.file_name().unwrap_or_default().to_owned();
+        name.push(".policies");
+        self.storage_path.with_file_name(name)
+    }
 }
 
 /// Configuration for the Okta service
@@ -3,11 +3,11 @@ use crate::nodes::connection::{Changes, ConnectionInstanceBuilder, Instantiator}
 use crate::{multiaddr_to_route, route_to_multiaddr};
 
 use ockam_core::{async_trait, Error};
-use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Tcp};
+use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Tcp, Tls};
 use ockam_multiaddr::{Match, Protocol};
 use ockam_transport_tcp::TcpTransport;
 
-/// Creates the tcp connection.
+/// Creates the tcp connection, over TLS if the tcp protocol is followed by a tls protocol.
 pub(crate) struct PlainTcpInstantiator {
     tcp_transport: TcpTransport,
 }
@@ -33,8 +33,16 @@ impl Instantiator for PlainTcpInstantiator {
         builder: &ConnectionInstanceBuilder,
         match_start: usize,
     ) -> Result<Changes, Error> {
+        let length = if builder
+            .current_multiaddr
+            .matches(match_start + 2, &[Tls::CODE.into()])
+        {
+            3
+        } else {
+            2
+        };
         let (before, tcp_piece, after) =
-            ConnectionInstanceBuilder::extract(&builder.current_multiaddr, match_start, 2);
+            ConnectionInstanceBuilder::extract(&builder.current_multiaddr, match_start, length);
 
         let mut tcp = multiaddr_to_route(&tcp_piece, &self.tcp_transport)
             .await
@@ -1,5 +1,6 @@
 use minicbor::{Decode, Encode};
 use ockam_abac::{Action, Expr};
+use ockam_identity::IdentityIdentifier;
 
 #[cfg(feature = "tag")]
 use ockam_core::TypeTag;
@@ -79,3 +80,27 @@ impl Expression {
         &self.expr
     }
 }
+
+/// Request body to evaluate a policy for an identity without enforcing it
+#[derive(Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ExplainRequest {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6184232>,
+    #[n(1)] identity: IdentityIdentifier,
+}
+
+impl ExplainRequest {
+    pub fn new(identity: IdentityIdentifier) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity,
+        }
+    }
+
+    pub fn identity(&self) -> &IdentityIdentifier {
+        &self.identity
+    }
+}
@@ -1,10 +1,12 @@
 //! Inlets and outlet request/response types
 
+use std::fmt::{Display, Formatter};
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::time::Duration;
 
 use minicbor::{Decode, Encode};
 use ockam::route;
+use ockam_core::api::{Endpoint, Method, StreamEndpoint};
 use ockam_core::compat::borrow::Cow;
 #[cfg(feature = "tag")]
 use ockam_core::TypeTag;
@@ -43,6 +45,12 @@ pub struct CreateInlet<'a> {
     #[n(6)] suffix_route: Route,
     /// The maximum duration to wait for an outlet to be available
     #[n(7)] wait_for_outlet_duration: Option<Duration>,
+    /// Try to reach the outlet over a hole punched UDP path
+    #[n(8)] direct: Option<bool>,
+    /// UDP address (`host:port`) of the rendezvous service used to punch the hole
+    #[n(9)] rendezvous_addr: Option<String>,
+    /// Bandwidth and connection limits of the inlet
+    #[n(10)] limits: Option<PortalLimits>,
 }
 
 impl<'a> CreateInlet<'a> {
@@ -62,6 +70,9 @@ impl<'a> CreateInlet<'a> {
             prefix_route,
             suffix_route,
             wait_for_outlet_duration: None,
+            direct: None,
+            rendezvous_addr: None,
+            limits: None,
         }
     }
 
@@ -82,6 +93,9 @@ impl<'a> CreateInlet<'a> {
             prefix_route,
             suffix_route,
             wait_for_outlet_duration: None,
+            direct: None,
+            rendezvous_addr: None,
+            limits: None,
         }
     }
 
@@ -93,6 +107,15 @@ impl<'a> CreateInlet<'a> {
         self.wait_for_outlet_duration = Some(Duration::from_millis(ms))
     }
 
+    pub fn set_direct(&mut self, rendezvous_addr: String) {
+        self.direct = Some(true);
+        self.rendezvous_addr = Some(rendezvous_addr)
+    }
+
+    pub fn set_limits(&mut self, limits: PortalLimits) {
+        self.limits = Some(limits)
+    }
+
     pub fn listen_addr(&self) -> String {
         self.listen_addr.clone()
     }
@@ -120,6 +143,18 @@ impl<'a> CreateInlet<'a> {
     pub fn wait_for_outlet_duration(&self) -> Option<Duration> {
         self.wait_for_outlet_duration
     }
+
+    pub fn direct(&self) -> bool {
+        self.direct.unwrap_or(false)
+    }
+
+    pub fn rendezvous_addr(&self) -> Option<&str> {
+        self.rendezvous_addr.as_deref()
+    }
+
+    pub fn limits(&self) -> PortalLimits {
+        self.limits.clone().unwrap_or_default()
+    }
 }
 
 /// Request body to create an outlet
@@ -138,6 +173,10 @@ pub struct CreateOutlet {
     /// Allow the outlet to be reachable from the default secure channel, useful when we want to
     /// tighten the flow control
     #[n(4)] pub reachable_from_default_secure_channel: bool,
+    /// Bandwidth and connection limits of the outlet
+    #[n(5)] pub limits: Option<PortalLimits>,
+    /// Accept direct, hole punched paths from the inlets of this outlet
+    #[n(6)] pub direct: Option<bool>,
 }
 
 impl CreateOutlet {
@@ -154,6 +193,87 @@ impl CreateOutlet {
             worker_addr,
             alias: alias.into(),
             reachable_from_default_secure_channel,
+            limits: None,
+            direct: None,
+        }
+    }
+
+    pub fn with_limits(mut self, limits: PortalLimits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    pub fn with_direct(mut self, direct: bool) -> Self {
+        self.direct = Some(direct);
+        self
+    }
+}
+
+/// Bandwidth and connection limits of a portal
+#[derive(Clone, Debug, Default, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PortalLimits {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<3954211>,
+    /// Bandwidth limit of all the connections of the portal together, in bytes per second
+    #[n(1)] rate_limit: Option<u64>,
+    /// Bandwidth limit of each connection of the portal, in bytes per second
+    #[n(2)] connection_rate_limit: Option<u64>,
+    /// Maximum number of concurrent connections of the portal
+    #[n(3)] max_connections: Option<u32>,
+}
+
+impl PortalLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_rate_limit(mut self, bytes_per_second: impl Into<Option<u64>>) -> Self {
+        self.rate_limit = bytes_per_second.into();
+        self
+    }
+
+    pub fn with_connection_rate_limit(mut self, bytes_per_second: impl Into<Option<u64>>) -> Self {
+        self.connection_rate_limit = bytes_per_second.into();
+        self
+    }
+
+    pub fn with_max_connections(mut self, max_connections: impl Into<Option<u32>>) -> Self {
+        self.max_connections = max_connections.into();
+        self
+    }
+
+    pub fn rate_limit(&self) -> Option<u64> {
+        self.rate_limit
+    }
+
+    pub fn connection_rate_limit(&self) -> Option<u64> {
+        self.connection_rate_limit
+    }
+
+    pub fn max_connections(&self) -> Option<u32> {
+        self.max_connections
+    }
+}
+
+/// The path taken by the traffic of an inlet to reach its outlet
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Decode, Encode, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum InletPath {
+    /// Through the route given when creating the inlet, usually a relay
+    #[n(0)] Relay,
+    /// Over a hole punched UDP path between the two nodes
+    #[n(1)] Direct,
+}
+
+impl Display for InletPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InletPath::Relay => write!(f, "relay"),
+            InletPath::Direct => write!(f, "direct"),
         }
     }
 }
@@ -172,6 +292,7 @@ pub struct InletStatus {
     /// An optional status payload
     #[n(4)] pub payload: Option<String>,
     #[n(5)] pub outlet_route: String,
+    #[n(6)] pub path: Option<InletPath>,
 }
 
 impl InletStatus {
@@ -184,6 +305,7 @@ impl InletStatus {
             alias: "".into(),
             payload: Some(reason.into()),
             outlet_route: "".into(),
+            path: None,
         }
     }
 
@@ -202,10 +324,62 @@ impl InletStatus {
             alias: alias.into(),
             payload: payload.into(),
             outlet_route: outlet_route.into(),
+            path: None,
+        }
+    }
+
+    pub fn with_path(mut self, path: InletPath) -> Self {
+        self.path = Some(path);
+        self
+    }
+}
+
+/// Change of the inlets of a node
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Decode, Encode, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum InletEventKind {
+    #[n(0)] Created,
+    #[n(1)] Deleted,
+}
+
+/// Chunk of the streaming response to `GET /node/inlet/events`, sent
+/// each time an inlet is created or deleted
+#[derive(Clone, Debug, Decode, Encode, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct InletEvent {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<6180397>,
+    #[n(1)] pub kind: InletEventKind,
+    #[n(2)] pub inlet: InletStatus,
+}
+
+impl InletEvent {
+    pub fn new(kind: InletEventKind, inlet: InletStatus) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            kind,
+            inlet,
         }
     }
 }
 
+/// Subscription to the changes of the inlets of a node
+pub struct InletEvents;
+
+impl Endpoint for InletEvents {
+    const METHOD: Method = Method::Get;
+    const PATH: &'static str = "/node/inlet/events";
+    type Request = ();
+    type Response = InletEvent;
+}
+
+impl StreamEndpoint for InletEvents {}
+
 /// Response body when interacting with a portal endpoint
 #[derive(Clone, Debug, Decode, Encode, Serialize, Deserialize)]
 #[rustfmt::skip]
@@ -1,9 +1,12 @@
+use crate::hole_punch::DirectPath;
+use crate::nodes::models::portal::{InletEvent, InletPath};
 use crate::nodes::service::Alias;
 use ockam::identity::IdentityIdentifier;
 use ockam::remote::RemoteForwarderInfo;
 use ockam_core::compat::collections::BTreeMap;
 use ockam_core::{Address, Route};
 use ockam_identity::{SecureChannel, SecureChannelListener};
+use ockam_node::api::ResponseStreamSender;
 use std::fmt::Display;
 use std::net::SocketAddr;
 
@@ -105,6 +108,9 @@ pub(crate) struct EchoerServiceInfo {}
 #[derive(Default)]
 pub(crate) struct HopServiceInfo {}
 
+#[derive(Default)]
+pub(crate) struct HolePunchServiceInfo {}
+
 #[derive(Default)]
 pub(crate) struct VerifierServiceInfo {}
 
@@ -152,6 +158,7 @@ pub(crate) struct InletInfo {
     pub(crate) bind_addr: String,
     pub(crate) worker_addr: Address,
     pub(crate) outlet_route: Route,
+    pub(crate) direct: Option<DirectPath>,
 }
 
 impl InletInfo {
@@ -168,6 +175,19 @@ impl InletInfo {
             bind_addr: bind_addr.to_owned(),
             worker_addr,
             outlet_route: outlet_route.to_owned(),
+            direct: None,
+        }
+    }
+
+    pub(crate) fn with_direct(mut self, direct: Option<DirectPath>) -> Self {
+        self.direct = direct;
+        self
+    }
+
+    pub(crate) fn path(&self) -> InletPath {
+        match self.direct {
+            Some(_) => InletPath::Direct,
+            None => InletPath::Relay,
         }
     }
 }
@@ -202,6 +222,7 @@ pub(crate) struct Registry {
     pub(crate) echoer_services: BTreeMap<Address, EchoerServiceInfo>,
     pub(crate) kafka_services: BTreeMap<Address, KafkaServiceInfo>,
     pub(crate) hop_services: BTreeMap<Address, HopServiceInfo>,
+    pub(crate) hole_punch_services: BTreeMap<Address, HolePunchServiceInfo>,
     pub(crate) verifier_services: BTreeMap<Address, VerifierServiceInfo>,
     pub(crate) credentials_services: BTreeMap<Address, CredentialsServiceInfo>,
     #[cfg(feature = "direct-authenticator")]
@@ -211,4 +232,5 @@ pub(crate) struct Registry {
     pub(crate) forwarders: BTreeMap<String, RemoteForwarderInfo>,
     pub(crate) inlets: BTreeMap<Alias, InletInfo>,
     pub(crate) outlets: BTreeMap<Alias, OutletInfo>,
+    pub(crate) inlet_subscribers: Vec<ResponseStreamSender<InletEvent>>,
 }
@@ -4,6 +4,7 @@ use std::collections::BTreeMap;
 use std::error::Error as _;
 use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use minicbor::{Decoder, Encode};
 
@@ -18,15 +19,20 @@ use ockam::{
     Worker,
 };
 use ockam_abac::expr::{and, eq, ident, str};
-use ockam_abac::{Action, Env, Expr, PolicyAccessControl, PolicyStorage, Resource};
-use ockam_core::api::{Error, Method, Request, Response, ResponseBuilder, Status};
+use ockam_abac::{
+    Action, Combine, Env, Expr, OutgoingPolicyAccessControl, PolicyAccessControl, PolicySet,
+    PolicyStorage, Resource, SyncedPolicyStorage, TracingDecisionSink,
+};
+use ockam_core::api::{Error, Method, Request, RequestError, Response, ResponseBuilder, Status};
 use ockam_core::compat::{string::String, sync::Arc};
 use ockam_core::flow_control::FlowControlId;
-use ockam_core::IncomingAccessControl;
-use ockam_core::{AllowAll, AsyncTryClone};
+use ockam_core::{async_trait, AllowAll, AsyncTryClone, Priority};
+use ockam_core::{IncomingAccessControl, OutgoingAccessControl};
 use ockam_identity::TrustContext;
 use ockam_multiaddr::MultiAddr;
+use ockam_node::api::{ApiRoutes, Handle, RequestInfo};
 use ockam_node::compat::asynchronous::RwLock;
+use ockam_node::tokio::task::JoinHandle;
 
 use crate::bootstrapped_identities_store::BootstrapedIdentityStore;
 use crate::bootstrapped_identities_store::PreTrustedIdentities;
@@ -34,6 +40,7 @@ use crate::cli_state::{CliState, StateDirTrait, StateItemTrait};
 use crate::config::cli::TrustContextConfig;
 use crate::config::lookup::ProjectLookup;
 use crate::error::ApiError;
+use crate::hole_punch::HolePunchTransport;
 use crate::nodes::connection::{
     Connection, ConnectionInstance, ConnectionInstanceBuilder, PlainTcpInstantiator,
     ProjectInstantiator, SecureChannelInstantiator,
@@ -43,7 +50,14 @@ use crate::nodes::models::portal::{OutletList, OutletStatus};
 use crate::nodes::models::transport::{TransportMode, TransportType};
 use crate::nodes::models::workers::{WorkerList, WorkerStatus};
 use crate::nodes::registry::KafkaServiceKind;
+use crate::nodes::service::endpoints::{
+    CreateTcpConnectionEndpoint, CreateTcpListenerEndpoint, DeleteTcpConnectionEndpoint,
+    DeleteTcpListenerEndpoint, GetNodeStatusEndpoint, GetTcpConnectionEndpoint,
+    GetTcpListenerEndpoint, ListTcpConnectionsEndpoint, ListTcpListenersEndpoint,
+    ListWorkersEndpoint,
+};
 use crate::nodes::NODEMANAGER_ADDR;
+use crate::policies::{start_policy_sync, RemotePolicySource};
 use crate::session::sessions::{Key, Session};
 use crate::session::MedicHandle;
 use crate::DefaultAddress;
@@ -52,6 +66,7 @@ use crate::RpcProxyService;
 use super::registry::Registry;
 
 mod credentials;
+pub mod endpoints;
 mod flow_controls;
 mod forwarder;
 pub mod message;
@@ -89,6 +104,7 @@ pub struct NodeManager {
     node_name: String,
     api_transport_flow_control_id: FlowControlId,
     pub(crate) tcp_transport: TcpTransport,
+    pub(crate) hole_punch_transport: HolePunchTransport,
     pub(crate) controller_identity_id: IdentityIdentifier,
     skip_defaults: bool,
     enable_credential_checks: bool,
@@ -98,6 +114,7 @@ pub struct NodeManager {
     pub(crate) registry: Registry,
     medic_handle: MedicHandle,
     policies: Arc<dyn PolicyStorage>,
+    policy_sync: Option<JoinHandle<()>>,
 }
 
 impl NodeManager {
@@ -149,21 +166,44 @@ impl NodeManager {
 #[derive(Clone)]
 pub struct NodeManagerWorker {
     node_manager: Arc<RwLock<NodeManager>>,
+    routes: Arc<ApiRoutes<NodeManagerWorker>>,
 }
 
 impl NodeManagerWorker {
     pub fn new(node_manager: NodeManager) -> Self {
+        Self::from_shared(Arc::new(RwLock::new(node_manager)))
+    }
+
+    pub(crate) fn from_shared(node_manager: Arc<RwLock<NodeManager>>) -> Self {
         NodeManagerWorker {
-            node_manager: Arc::new(RwLock::new(node_manager)),
+            node_manager,
+            routes: Arc::new(Self::routes()),
         }
     }
 
+    /// Routes served with typed endpoints. The other requests are still
+    /// matched in [`NodeManagerWorker::handle_request`]
+    fn routes() -> ApiRoutes<NodeManagerWorker> {
+        ApiRoutes::new()
+            .route::<GetNodeStatusEndpoint>()
+            .route::<ListWorkersEndpoint>()
+            .route::<ListTcpConnectionsEndpoint>()
+            .route::<GetTcpConnectionEndpoint>()
+            .route::<CreateTcpConnectionEndpoint>()
+            .route::<DeleteTcpConnectionEndpoint>()
+            .route::<ListTcpListenersEndpoint>()
+            .route::<GetTcpListenerEndpoint>()
+            .route::<CreateTcpListenerEndpoint>()
+            .route::<DeleteTcpListenerEndpoint>()
+    }
+
     pub fn inner(&self) -> &Arc<RwLock<NodeManager>> {
         &self.node_manager
     }
 
     pub async fn stop(&self, ctx: &Context) -> Result<()> {
         let nm = self.node_manager.read().await;
+        nm.stop_policy_sync();
         nm.medic_handle.stop_medic(ctx).await?;
         for addr in DefaultAddress::iter() {
             ctx.stop_worker(addr).await?;
@@ -187,46 +227,80 @@ impl NodeManager {
         custom_default: Option<&Expr>,
     ) -> Result<Arc<dyn IncomingAccessControl>> {
         if let Some(tcid) = trust_context_id {
-            // Populate environment with known attributes:
-            let mut env = Env::new();
-            env.put("resource.id", str(r.as_str()));
-            env.put("action.id", str(a.as_str()));
-            env.put("resource.project_id", str(tcid.to_string()));
-            env.put("resource.trust_context_id", str(tcid));
-
-            // Check if a policy exists for (resource, action) and if not, then
-            // create or use a default entry:
-            if self.policies.get_policy(r, a).await?.is_none() {
-                let fallback = match custom_default {
-                    Some(e) => e.clone(),
-                    None => and([
-                        eq([ident("resource.project_id"), ident("subject.project_id")]), // TODO: DEPRECATE - Removing PROJECT_ID attribute in favor of TRUST_CONTEXT_ID
-                                                                                         /*
-                                                                                         * TODO: replace the project_id check for trust_context_id.  For now the
-                                                                                         * existing authority deployed doesn't know about trust_context so this is to
-                                                                                         * be done after updating deployed authorities.
-                                                                                         eq([
-                                                                                             ident("resource.trust_context_id"),
-                                                                                             ident("subject.trust_context_id"),
-                                                                                         ]),
-                                                                                         */
-                    ]),
-                };
-                self.policies.set_policy(r, a, &fallback).await?
-            }
+            let env = policy_environment(r, a, Some(tcid));
+
+            // The fallback policy applies when no policy exists for (resource, action)
+            // or one of its parents at evaluation time. It is not stored, so that it
+            // does not apply to the other resources of its kind or outlive the resource:
+            let fallback = match custom_default {
+                Some(e) => e.clone(),
+                None => and([
+                    eq([ident("resource.project_id"), ident("subject.project_id")]), // TODO: DEPRECATE - Removing PROJECT_ID attribute in favor of TRUST_CONTEXT_ID
+                                                                                     /*
+                                                                                     * TODO: replace the project_id check for trust_context_id.  For now the
+                                                                                     * existing authority deployed doesn't know about trust_context so this is to
+                                                                                     * be done after updating deployed authorities.
+                                                                                     eq([
+                                                                                         ident("resource.trust_context_id"),
+                                                                                         ident("subject.trust_context_id"),
+                                                                                     ]),
+                                                                                     */
+                ]),
+            };
             let policies = self.policies.clone();
-            Ok(Arc::new(PolicyAccessControl::new(
-                policies,
-                self.identities_repository(),
-                r.clone(),
-                a.clone(),
-                env,
-            )))
+            Ok(Arc::new(
+                PolicyAccessControl::new(
+                    policies,
+                    self.identities_repository(),
+                    r.clone(),
+                    a.clone(),
+                    env,
+                )
+                .with_fallback(fallback)
+                .with_decision_sink(Arc::new(TracingDecisionSink)),
+            ))
         } else {
             Ok(Arc::new(AllowAll))
         }
     }
 
+    /// Access control for messages sent to the identity at the other end of
+    /// a secure channel.
+    ///
+    /// Unlike incoming access control there is no default policy: messages
+    /// are only checked if a policy for (resource, action) or one of its
+    /// parents exists when the access control is created.
+    async fn outgoing_access_control(
+        &self,
+        r: &Resource,
+        a: &Action,
+        trust_context_id: Option<&str>,
+    ) -> Result<Arc<dyn OutgoingAccessControl>> {
+        if let Some(tcid) = trust_context_id {
+            if self.policies.get_policy(r, a).await?.is_some() {
+                let env = policy_environment(r, a, Some(tcid));
+                let policy = PolicyAccessControl::new(
+                    self.policies.clone(),
+                    self.identities_repository(),
+                    r.clone(),
+                    a.clone(),
+                    env,
+                )
+                .with_decision_sink(Arc::new(TracingDecisionSink));
+                let registry = self.secure_channels.secure_channel_registry();
+                return Ok(Arc::new(OutgoingPolicyAccessControl::new(registry, policy)));
+            }
+        }
+        Ok(Arc::new(AllowAll))
+    }
+
+    /// Stop syncing the node policies, if they are synced
+    fn stop_policy_sync(&self) {
+        if let Some(handle) = &self.policy_sync {
+            handle.abort()
+        }
+    }
+
     pub(crate) fn trust_context(&self) -> Result<&TrustContext> {
         self.trust_context
             .as_ref()
@@ -234,11 +308,25 @@ impl NodeManager {
     }
 }
 
+/// Populate a policy evaluation environment with the known resource and action attributes
+pub(crate) fn policy_environment(r: &Resource, a: &Action, trust_context_id: Option<&str>) -> Env {
+    let mut env = Env::new();
+    env.put("resource.id", str(r.as_str()));
+    env.put("action.id", str(a.as_str()));
+    if let Some(tcid) = trust_context_id {
+        env.put("resource.project_id", str(tcid.to_string()));
+        env.put("resource.trust_context_id", str(tcid));
+    }
+    env
+}
+
 pub struct NodeManagerGeneralOptions {
     cli_state: CliState,
     node_name: String,
     skip_defaults: bool,
     pre_trusted_identities: Option<PreTrustedIdentities>,
+    policy_combining: Combine,
+    policy_sync: Option<Duration>,
 }
 
 impl NodeManagerGeneralOptions {
@@ -253,8 +341,23 @@ impl NodeManagerGeneralOptions {
             node_name,
             skip_defaults,
             pre_trusted_identities,
+            policy_combining: Combine::default(),
+            policy_sync: None,
         }
     }
+
+    /// Set how the policies of a resource and of its parents are combined
+    pub fn with_policy_combining(mut self, combine: Combine) -> Self {
+        self.policy_combining = combine;
+        self
+    }
+
+    /// Sync the node policies with the policy service of the trust context
+    /// authority at the given interval
+    pub fn with_policy_sync(mut self, interval: Duration) -> Self {
+        self.policy_sync = Some(interval);
+        self
+    }
 }
 
 #[derive(Clone)]
@@ -341,7 +444,44 @@ impl NodeManager {
             .with_identities_repository(identities_repository.clone())
             .build();
 
-        let policies: Arc<dyn PolicyStorage> = Arc::new(node_state.policies_storage().await?);
+        // The synced storage, if any, writes through the policy set, so that
+        // the policy set sees the resource patterns of the policy bundles
+        let mut policy_sync = None;
+        let mut policies: Arc<dyn PolicyStorage> = Arc::new(PolicySet::new(
+            Arc::new(node_state.policies_storage().await?),
+            general_options.policy_combining,
+        ));
+        if let Some(interval) = general_options.policy_sync {
+            let authority = match trust_options
+                .trust_context_config
+                .as_ref()
+                .and_then(|tc| tc.authority().ok())
+            {
+                Some(authority) => authority.remote_authority().await?,
+                None => None,
+            };
+            if let Some((authority, route)) = authority {
+                debug!("sync policies with the authority policy service");
+                let synced = Arc::new(SyncedPolicyStorage::new(policies).await?);
+                let source = RemotePolicySource::new(
+                    ctx,
+                    secure_channels.clone(),
+                    node_state.config().identifier()?,
+                    authority,
+                    route,
+                    DefaultAddress::POLICY_SERVICE.into(),
+                )
+                .await?;
+                policy_sync = Some(start_policy_sync(
+                    synced.clone(),
+                    Arc::new(source),
+                    interval,
+                ));
+                policies = synced
+            } else {
+                warn!("policy sync requires a trust context with a remote authority");
+            }
+        }
 
         debug!("start the Medic");
         let medic_handle = MedicHandle::start_medic(ctx).await?;
@@ -351,6 +491,7 @@ impl NodeManager {
             node_name: general_options.node_name,
             api_transport_flow_control_id: transport_options.api_transport_flow_control_id,
             tcp_transport: transport_options.tcp_transport,
+            hole_punch_transport: Default::default(),
             controller_identity_id: Self::load_controller_identifier()?,
             skip_defaults: general_options.skip_defaults,
             enable_credential_checks: trust_options.trust_context_config.is_some()
@@ -366,6 +507,7 @@ impl NodeManager {
             registry: Default::default(),
             medic_handle,
             policies,
+            policy_sync,
         };
 
         if !general_options.skip_defaults {
@@ -542,44 +684,6 @@ impl NodeManagerWorker {
         };
 
         let r = match (method, path_segments.as_slice()) {
-            // ==*== Basic node information ==*==
-            // TODO: create, delete, destroy remote nodes
-            (Get, ["node"]) => {
-                let node_name = &self.node_manager.read().await.node_name;
-                Response::ok(req.id())
-                    .body(NodeStatus::new(
-                        node_name,
-                        "Running",
-                        ctx.list_workers().await?.len() as u32,
-                        std::process::id() as i32,
-                    ))
-                    .to_vec()?
-            }
-
-            // ==*== Tcp Connection ==*==
-            (Get, ["node", "tcp", "connection"]) => self.get_tcp_connections(req).await.to_vec()?,
-            (Get, ["node", "tcp", "connection", address]) => {
-                encode_request_result(self.get_tcp_connection(req, address.to_string()).await)?
-            }
-            (Post, ["node", "tcp", "connection"]) => {
-                encode_request_result(self.create_tcp_connection(req, dec, ctx).await)?
-            }
-            (Delete, ["node", "tcp", "connection"]) => {
-                encode_request_result(self.delete_tcp_connection(req, dec).await)?
-            }
-
-            // ==*== Tcp Listeners ==*==
-            (Get, ["node", "tcp", "listener"]) => self.get_tcp_listeners(req).await.to_vec()?,
-            (Get, ["node", "tcp", "listener", address]) => {
-                encode_request_result(self.get_tcp_listener(req, address.to_string()).await)?
-            }
-            (Post, ["node", "tcp", "listener"]) => {
-                encode_request_result(self.create_tcp_listener(req, dec).await)?
-            }
-            (Delete, ["node", "tcp", "listener"]) => {
-                encode_request_result(self.delete_tcp_listener(req, dec).await)?
-            }
-
             // ==*== Credential ==*==
             (Post, ["node", "credentials", "actions", "get"]) => self
                 .get_credential(req, dec, ctx)
@@ -711,7 +815,7 @@ impl NodeManagerWorker {
                 encode_request_result(self.delete_outlet(req, alias).await)?
             }
             (Delete, ["node", "inlet", alias]) => {
-                encode_request_result(self.delete_inlet(req, alias).await)?
+                encode_request_result(self.delete_inlet(ctx, req, alias).await)?
             }
             (Delete, ["node", "portal"]) => todo!(),
 
@@ -720,19 +824,6 @@ impl NodeManagerWorker {
                 encode_request_result(self.add_consumer(ctx, req, dec))?
             }
 
-            // ==*== Workers ==*==
-            (Get, ["node", "workers"]) => {
-                let workers = ctx.list_workers().await?;
-
-                let mut list = Vec::new();
-                workers
-                    .iter()
-                    .for_each(|addr| list.push(WorkerStatus::new(addr.address())));
-
-                Response::ok(req.id())
-                    .body(WorkerList::new(list))
-                    .to_vec()?
-            }
             (Post, ["policy", resource, action]) => encode_request_result(
                 self.node_manager
                     .read()
@@ -754,6 +845,13 @@ impl NodeManagerWorker {
                 .get_policy(req, resource, action)
                 .await?
                 .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?,
+            (Get, ["policy", resource, action, "explain"]) => encode_request_result(
+                self.node_manager
+                    .read()
+                    .await
+                    .explain_policy(req, resource, action, dec)
+                    .await,
+            )?,
             (Delete, ["policy", resource, action]) => encode_request_result(
                 self.node_manager
                     .read()
@@ -856,6 +954,42 @@ impl NodeManagerWorker {
     }
 }
 
+#[async_trait]
+impl Handle<GetNodeStatusEndpoint> for NodeManagerWorker {
+    async fn handle(
+        &mut self,
+        ctx: &mut Context,
+        _request: RequestInfo<'_>,
+        _body: (),
+    ) -> Result<NodeStatus, RequestError> {
+        let node_name = &self.node_manager.read().await.node_name;
+        Ok(NodeStatus::new(
+            node_name,
+            "Running",
+            ctx.list_workers().await?.len() as u32,
+            std::process::id() as i32,
+        ))
+    }
+}
+
+#[async_trait]
+impl Handle<ListWorkersEndpoint> for NodeManagerWorker {
+    async fn handle(
+        &mut self,
+        ctx: &mut Context,
+        _request: RequestInfo<'_>,
+        _body: (),
+    ) -> Result<WorkerList, RequestError> {
+        let workers = ctx.list_workers().await?;
+        Ok(WorkerList::new(
+            workers
+                .iter()
+                .map(|addr| WorkerStatus::new(addr.address()))
+                .collect(),
+        ))
+    }
+}
+
 #[ockam::worker]
 impl Worker for NodeManagerWorker {
     type Message = Vec<u8>;
@@ -884,11 +1018,15 @@ impl Worker for NodeManagerWorker {
         ctx.start_worker(DefaultAddress::RPC_PROXY, RpcProxyService::new())
             .await?;
 
+        // Node API requests and responses must not wait behind portal payloads
+        ctx.set_message_priority(Priority::High);
+
         Ok(())
     }
 
     async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
         let node_manager = self.node_manager.read().await;
+        node_manager.stop_policy_sync();
         node_manager.medic_handle.stop_medic(ctx).await
     }
 
@@ -902,6 +1040,35 @@ impl Worker for NodeManagerWorker {
             }
         };
 
+        // Subscriptions are answered by streaming responses, which are sent
+        // by the node manager when the subscribed events happen
+        if let (Some(Method::Get), ["node", "inlet", "events"]) =
+            (req.method(), req.path_segments::<5>().as_slice())
+        {
+            if let Err(err) = self
+                .subscribe_inlet_events(ctx, &req, msg.return_route())
+                .await
+            {
+                let err = Error::new(req.path()).with_message(err.to_string());
+                let response = Response::internal_error(req.id()).body(err).to_vec()?;
+                ctx.send(msg.return_route(), response).await?;
+            }
+            return Ok(());
+        }
+
+        if self.routes.serves(req.path()) {
+            let routes = self.routes.clone();
+            let return_route = msg.return_route();
+            let local_info = msg.local_message().local_info().to_vec();
+            return match routes
+                .respond(self, ctx, &return_route, &local_info, msg.as_body())
+                .await?
+            {
+                Some(response) => ctx.send(return_route, response).await,
+                None => Ok(()),
+            };
+        }
+
         let r = match self.handle_request(ctx, &req, &mut dec).await {
             Ok(r) => r,
             Err(err) => {
@@ -931,3 +1098,220 @@ impl Worker for NodeManagerWorker {
         ctx.send(msg.return_route(), r).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::actions;
+    use crate::nodes::models::policy::ExplainRequest;
+    use crate::nodes::models::transport::{CreateTcpListener, DeleteTransport};
+    use crate::nodes::service::endpoints::{
+        CreateTcpListenerEndpoint, DeleteTcpListenerEndpoint, GetNodeStatusEndpoint,
+        GetTcpConnectionEndpoint, GetTcpListenerEndpoint, ListTcpListenersEndpoint,
+    };
+    use crate::nodes::NODEMANAGER_ADDR;
+    use ockam::Context;
+    use ockam_abac::expr::{eq, ident, str};
+    use ockam_abac::{Decision, Expr, Resource};
+    use ockam_core::api::Request;
+    use ockam_core::{route, Address, LocalMessage, RelayMessage, Result, TransportMessage};
+    use ockam_node::RpcClient;
+    use std::time::Duration;
+
+    fn message_to(destination: &str) -> RelayMessage {
+        let destination = Address::from_string(destination);
+        let local_message = LocalMessage::new(
+            TransportMessage::v1(route![destination.clone()], route![], vec![]),
+            vec![],
+        );
+        RelayMessage::new(Address::random_local(), destination, local_message)
+    }
+
+    #[ockam_macros::test]
+    async fn outgoing_access_control_is_only_enforced_with_a_policy(
+        context: &mut Context,
+    ) -> Result<()> {
+        let handler = crate::test_utils::start_manager_for_tests(context).await?;
+        let node_manager = handler.node_manager.read().await;
+        let resource = Resource::new("tcp-inlet/db");
+
+        // Without a policy, messages are not checked
+        let ac = node_manager
+            .outgoing_access_control(&resource, &actions::SEND_MESSAGE, Some("tc"))
+            .await?;
+        assert!(ac.is_authorized(&message_to("outlet")).await?);
+
+        // With a policy, messages need a destination identity and are
+        // denied when they are not sent through a secure channel
+        node_manager
+            .policies
+            .set_policy(
+                &resource,
+                &actions::SEND_MESSAGE,
+                &eq([ident("subject.role"), str("db")]),
+            )
+            .await?;
+        let ac = node_manager
+            .outgoing_access_control(&resource, &actions::SEND_MESSAGE, Some("tc"))
+            .await?;
+        assert!(!ac.is_authorized(&message_to("outlet")).await?);
+
+        // Without a trust context, there is no identity to check
+        let ac = node_manager
+            .outgoing_access_control(&resource, &actions::SEND_MESSAGE, None)
+            .await?;
+        assert!(ac.is_authorized(&message_to("outlet")).await?);
+
+        drop(node_manager);
+        context.stop().await
+    }
+
+    #[allow(non_snake_case)]
+    #[ockam_macros::test]
+    async fn access_control__with_a_fallback_policy__should_not_store_it(
+        context: &mut Context,
+    ) -> Result<()> {
+        let handler = crate::test_utils::start_manager_for_tests(context).await?;
+        let node_manager = handler.node_manager.read().await;
+        let resource = Resource::new("tcp-outlet/db");
+        let action = &actions::HANDLE_MESSAGE;
+
+        // Without a stored policy, the fallback policy is evaluated
+        let ac = node_manager
+            .access_control(&resource, action, Some("tc"), Some(&Expr::Bool(true)))
+            .await?;
+        assert!(ac.is_authorized(&message_to("outlet")).await?);
+        assert!(node_manager
+            .policies
+            .get_policy(&resource, action)
+            .await?
+            .is_none());
+
+        // A policy stored later takes precedence over the fallback policy
+        node_manager
+            .policies
+            .set_policy(&resource, action, &Expr::Bool(false))
+            .await?;
+        assert!(!ac.is_authorized(&message_to("outlet")).await?);
+
+        drop(node_manager);
+        context.stop().await
+    }
+
+    /// Wait until a listener is registered, or unregistered, since its
+    /// processor registers itself once initialized and unregisters on shutdown
+    async fn wait_for_listener(
+        context: &Context,
+        client: &RpcClient,
+        socket_addr: &str,
+        registered: bool,
+    ) -> Result<bool> {
+        for _ in 0..50 {
+            let found = client
+                .call::<GetTcpListenerEndpoint>(&[socket_addr], ())
+                .await
+                .is_ok();
+            if found == registered {
+                return Ok(true);
+            }
+            context.sleep(Duration::from_millis(100)).await;
+        }
+        Ok(false)
+    }
+
+    #[allow(non_snake_case)]
+    #[ockam_macros::test]
+    async fn node_manager__typed_and_legacy_routes__should_both_be_served(
+        context: &mut Context,
+    ) -> Result<()> {
+        let handle = crate::test_utils::start_manager_for_tests(context).await?;
+        let client = RpcClient::new(route![NODEMANAGER_ADDR], context).await?;
+
+        let status = client.call::<GetNodeStatusEndpoint>(&[], ()).await?;
+        assert_eq!(status.status, "Running");
+
+        let listener = client
+            .call::<CreateTcpListenerEndpoint>(
+                &[],
+                CreateTcpListener::new("127.0.0.1:0".to_string()),
+            )
+            .await?;
+        let socket_addr = listener.socket_addr.as_str();
+        assert!(wait_for_listener(context, &client, socket_addr, true).await?);
+        let listeners = client.call::<ListTcpListenersEndpoint>(&[], ()).await?;
+        assert!(listeners
+            .list
+            .iter()
+            .any(|l| l.processor_address == listener.processor_address));
+        let found = client
+            .call::<GetTcpListenerEndpoint>(&[socket_addr], ())
+            .await?;
+        assert_eq!(found.processor_address, listener.processor_address);
+
+        client
+            .call::<DeleteTcpListenerEndpoint>(
+                &[],
+                DeleteTransport::new(listener.processor_address.clone()),
+            )
+            .await?;
+        assert!(wait_for_listener(context, &client, socket_addr, false).await?);
+        let missing = client
+            .call::<GetTcpListenerEndpoint>(&[socket_addr], ())
+            .await;
+        assert!(missing.unwrap_err().to_string().contains("was not found"));
+
+        let missing = client
+            .call::<GetTcpConnectionEndpoint>(&["127.0.0.1:1"], ())
+            .await;
+        assert!(missing.unwrap_err().to_string().contains("was not found"));
+
+        // The routes which are not migrated yet are still served
+        let channels: Vec<String> = client
+            .request(&Request::get("/node/secure_channel"))
+            .await?;
+        assert!(channels.is_empty());
+
+        drop(handle);
+        context.stop().await
+    }
+
+    #[allow(non_snake_case)]
+    #[ockam_macros::test]
+    async fn explain_policy__with_and_without_policy__should_return_the_decision(
+        context: &mut Context,
+    ) -> Result<()> {
+        let handle = crate::test_utils::start_manager_for_tests(context).await?;
+        let client = RpcClient::new(route![NODEMANAGER_ADDR], context).await?;
+        let explain = || {
+            Request::get("/policy/echo/handle_message/explain")
+                .body(ExplainRequest::new(handle.identifier.clone()))
+        };
+
+        // Without a policy, access is denied without evaluating anything
+        let decision: Decision = client.request(&explain()).await?;
+        assert!(!decision.verdict());
+        assert_eq!(decision.reason(), Some("no policy found"));
+        assert!(decision.expression().is_none());
+        assert_eq!(decision.subject(), Some(&handle.identifier));
+
+        // With a policy, every sub-expression is evaluated
+        handle
+            .node_manager
+            .read()
+            .await
+            .policies
+            .set_policy(
+                &Resource::new("echo"),
+                &actions::HANDLE_MESSAGE,
+                &eq([ident("subject.role"), str("db")]),
+            )
+            .await?;
+        let decision: Decision = client.request(&explain()).await?;
+        assert!(!decision.verdict());
+        assert!(decision.expression().is_some());
+        assert_eq!(decision.steps().len(), 1);
+        assert!(decision.environment().contains("subject.identifier"));
+
+        drop(handle);
+        context.stop().await
+    }
+}
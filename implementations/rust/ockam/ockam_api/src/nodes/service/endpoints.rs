@@ -0,0 +1,107 @@
+use ockam_core::api::{Endpoint, Method};
+
+use crate::nodes::models::base::NodeStatus;
+use crate::nodes::models::transport::{
+    CreateTcpConnection, CreateTcpListener, DeleteTransport, TransportList, TransportStatus,
+};
+use crate::nodes::models::workers::WorkerList;
+
+/// Status of the node
+pub struct GetNodeStatusEndpoint;
+
+impl Endpoint for GetNodeStatusEndpoint {
+    const METHOD: Method = Method::Get;
+    const PATH: &'static str = "/node";
+    type Request = ();
+    type Response = NodeStatus;
+}
+
+/// List the workers of the node
+pub struct ListWorkersEndpoint;
+
+impl Endpoint for ListWorkersEndpoint {
+    const METHOD: Method = Method::Get;
+    const PATH: &'static str = "/node/workers";
+    type Request = ();
+    type Response = WorkerList;
+}
+
+/// List the TCP connections of the node
+pub struct ListTcpConnectionsEndpoint;
+
+impl Endpoint for ListTcpConnectionsEndpoint {
+    const METHOD: Method = Method::Get;
+    const PATH: &'static str = "/node/tcp/connection";
+    type Request = ();
+    type Response = TransportList;
+}
+
+/// Get a TCP connection by its socket address or worker address
+pub struct GetTcpConnectionEndpoint;
+
+impl Endpoint for GetTcpConnectionEndpoint {
+    const METHOD: Method = Method::Get;
+    const PATH: &'static str = "/node/tcp/connection/:address";
+    type Request = ();
+    type Response = TransportStatus;
+}
+
+/// Create a TCP connection
+pub struct CreateTcpConnectionEndpoint;
+
+impl Endpoint for CreateTcpConnectionEndpoint {
+    const METHOD: Method = Method::Post;
+    const PATH: &'static str = "/node/tcp/connection";
+    type Request = CreateTcpConnection;
+    type Response = TransportStatus;
+}
+
+/// Delete a TCP connection
+pub struct DeleteTcpConnectionEndpoint;
+
+impl Endpoint for DeleteTcpConnectionEndpoint {
+    const METHOD: Method = Method::Delete;
+    const PATH: &'static str = "/node/tcp/connection";
+    type Request = DeleteTransport;
+    type Response = ();
+}
+
+/// List the TCP listeners of the node
+pub struct ListTcpListenersEndpoint;
+
+impl Endpoint for ListTcpListenersEndpoint {
+    const METHOD: Method = Method::Get;
+    const PATH: &'static str = "/node/tcp/listener";
+    type Request = ();
+    type Response = TransportList;
+}
+
+/// Get a TCP listener by its socket address or processor address
+pub struct GetTcpListenerEndpoint;
+
+impl Endpoint for GetTcpListenerEndpoint {
+    const METHOD: Method = Method::Get;
+    const PATH: &'static str = "/node/tcp/listener/:address";
+    type Request = ();
+    type Response = TransportStatus;
+}
+
+/// Create a TCP listener
+pub struct CreateTcpListenerEndpoint;
+
+impl Endpoint for CreateTcpListenerEndpoint {
+    const METHOD: Method = Method::Post;
+    const PATH: &'static str = "/node/tcp/listener";
+    type Request = CreateTcpListener;
+    type Response = TransportStatus;
+}
+
+/// Stop a TCP listener
+pub struct DeleteTcpListenerEndpoint;
+
+impl Endpoint for DeleteTcpListenerEndpoint {
+    const METHOD: Method = Method::Delete;
+    const PATH: &'static str = "/node/tcp/listener";
+    type Request = DeleteTransport;
+    type Response = ();
+}
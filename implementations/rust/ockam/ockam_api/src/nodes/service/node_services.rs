@@ -15,8 +15,11 @@ use ockam_node::WorkerBuilder;
 
 use crate::auth::Server;
 use crate::authenticator::direct::EnrollmentTokenAuthenticator;
+use crate::cli_state::StateDirTrait;
+use crate::config::lookup::ProjectLookup;
 use crate::echoer::Echoer;
 use crate::error::ApiError;
+use crate::hole_punch::{rendezvous_host, HolePunchService};
 use crate::hop::Hop;
 use crate::identity::IdentityService;
 use crate::kafka::{
@@ -24,7 +27,7 @@ use crate::kafka::{
     KAFKA_OUTLET_BOOTSTRAP_ADDRESS, KAFKA_OUTLET_INTERCEPTOR_ADDRESS,
 };
 use crate::kafka::{OutletManagerService, PrefixForwarderService};
-use crate::nodes::models::portal::CreateInlet;
+use crate::nodes::models::portal::{CreateInlet, PortalLimits};
 use crate::nodes::models::services::{
     DeleteServiceRequest, ServiceList, ServiceStatus, StartAuthenticatedServiceRequest,
     StartAuthenticatorRequest, StartCredentialsService, StartEchoerServiceRequest,
@@ -40,8 +43,7 @@ use crate::nodes::registry::{
 use crate::nodes::NodeManager;
 use crate::port_range::PortRange;
 use crate::uppercase::Uppercase;
-use crate::DefaultAddress;
-use crate::{actions, resources};
+use crate::{actions, multiaddr_to_transport_route, resources, DefaultAddress};
 
 use super::NodeManagerWorker;
 
@@ -182,6 +184,63 @@ impl NodeManager {
         Ok(())
     }
 
+    /// Start the hole punch service, used by inlets to reach the outlets of
+    /// this node over a direct path, unless it is already started
+    pub(super) async fn start_hole_punch_service_impl(&mut self, ctx: &Context) -> Result<()> {
+        let addr: Address = DefaultAddress::HOLE_PUNCH_SERVICE.into();
+        if self.registry.hole_punch_services.contains_key(&addr) {
+            return Ok(());
+        }
+
+        let maybe_trust_context_id = self.trust_context.as_ref().map(|c| c.id());
+        let resource = Resource::assert_inline(addr.address());
+        let ac = self
+            .access_control(
+                &resource,
+                &actions::HANDLE_MESSAGE,
+                maybe_trust_context_id,
+                None,
+            )
+            .await?;
+
+        // Holes are only punched through the rendezvous service of the project of the node
+        let projects = ProjectLookup::from_state(self.cli_state.projects.list()?)
+            .await
+            .map_err(|e| ApiError::core(format!("Cannot load projects: {e:?}")))?;
+        let rendezvous_hosts = projects
+            .values()
+            .filter(|p| Some(p.id.as_str()) == maybe_trust_context_id)
+            .filter_map(|p| p.node_route.as_ref())
+            .filter_map(multiaddr_to_transport_route)
+            .filter_map(|r| r.next().ok().map(|a| a.address().to_string()))
+            .filter_map(|a| rendezvous_host(&a).map(|h| h.to_string()))
+            .collect();
+
+        // Inlets reach the hole punch service through the default secure channel
+        if let Some(flow_control_id) = ctx
+            .flow_controls()
+            .get_flow_control_with_spawner(&DefaultAddress::SECURE_CHANNEL_LISTENER.into())
+        {
+            ctx.flow_controls()
+                .add_consumer(addr.clone(), &flow_control_id);
+        }
+
+        WorkerBuilder::new(HolePunchService::new(
+            self.hole_punch_transport.clone(),
+            rendezvous_hosts,
+        ))
+        .with_address(addr.clone())
+        .with_incoming_access_control_arc(ac)
+        .start(ctx)
+        .await?;
+
+        self.registry
+            .hole_punch_services
+            .insert(addr, Default::default());
+
+        Ok(())
+    }
+
     async fn build_access_control(
         &self,
         r: &Resource,
@@ -259,7 +318,8 @@ impl NodeManager {
             self.attributes_writer(),
             self.attributes_reader(),
         )
-        .await?;
+        .await?
+        .into_service();
 
         WorkerBuilder::new(direct)
             .with_address(addr.clone())
@@ -598,6 +658,7 @@ impl NodeManagerWorker {
                 KAFKA_OUTLET_BOOTSTRAP_ADDRESS.into(),
                 Some(KAFKA_OUTLET_BOOTSTRAP_ADDRESS.to_string()),
                 false,
+                PortalLimits::default(),
             )
             .await
         {
@@ -688,6 +749,7 @@ impl NodeManagerWorker {
             KAFKA_OUTLET_BOOTSTRAP_ADDRESS.into(),
             Some(KAFKA_OUTLET_BOOTSTRAP_ADDRESS.to_string()),
             false,
+            PortalLimits::default(),
         )
         .await?;
 
@@ -1007,6 +1069,12 @@ impl NodeManagerWorker {
                 DefaultAddress::HOP_SERVICE,
             ))
         });
+        registry.hole_punch_services.keys().for_each(|addr| {
+            list.push(ServiceStatus::new(
+                addr.address(),
+                DefaultAddress::HOLE_PUNCH_SERVICE,
+            ))
+        });
         registry.verifier_services.keys().for_each(|addr| {
             list.push(ServiceStatus::new(addr.address(), DefaultAddress::VERIFIER))
         });
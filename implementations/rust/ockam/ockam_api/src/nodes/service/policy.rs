@@ -1,13 +1,13 @@
 use either::Either;
 use minicbor::Decoder;
 
-use ockam_abac::{Action, Resource};
+use ockam_abac::{check, Action, Decision, PolicyAccessControl, Resource};
 use ockam_core::api::{Error, Request, Response, ResponseBuilder};
 use ockam_core::Result;
 
-use crate::nodes::models::policy::{Expression, Policy, PolicyList};
+use crate::nodes::models::policy::{ExplainRequest, Expression, Policy, PolicyList};
 
-use super::NodeManager;
+use super::{policy_environment, NodeManager};
 
 impl NodeManager {
     pub(super) async fn add_policy(
@@ -18,6 +18,14 @@ impl NodeManager {
         dec: &mut Decoder<'_>,
     ) -> Result<ResponseBuilder<()>, ResponseBuilder<Error>> {
         let p: Policy = dec.decode()?;
+        if let Err(e) = check(p.expression()) {
+            let mut err = Error::new(req.path())
+                .with_message(format!("invalid policy: {e}\n{}", e.annotate()));
+            if let Some(m) = req.method() {
+                err.set_method(m)
+            }
+            return Err(Response::bad_request(req.id()).body(err));
+        }
         let r = Resource::new(resource);
         let a = Action::new(action);
         self.policies.set_policy(&r, &a, p.expression()).await?;
@@ -65,4 +73,27 @@ impl NodeManager {
         self.policies.del_policy(&r, &a).await?;
         Ok(Response::ok(req.id()))
     }
+
+    pub(super) async fn explain_policy(
+        &self,
+        req: &Request,
+        res: &str,
+        act: &str,
+        dec: &mut Decoder<'_>,
+    ) -> Result<ResponseBuilder<Decision>, ResponseBuilder<Error>> {
+        let body: ExplainRequest = dec.decode()?;
+        let r = Resource::new(res);
+        let a = Action::new(act);
+        let env = policy_environment(&r, &a, self.trust_context.as_ref().map(|c| c.id()));
+        let decision = PolicyAccessControl::new(
+            self.policies.clone(),
+            self.identities_repository(),
+            r,
+            a,
+            env,
+        )
+        .explain(body.identity().clone())
+        .await?;
+        Ok(Response::ok(req.id()).body(decision))
+    }
 }
@@ -7,25 +7,31 @@ use minicbor::Decoder;
 use ockam::compat::tokio::time::timeout;
 use ockam::identity::IdentityIdentifier;
 use ockam::{Address, AsyncTryClone, Result};
-use ockam_abac::Resource;
+use ockam_abac::{migrate_policies, Resource};
 use ockam_core::api::{Error, Id, Request, Response, ResponseBuilder};
 use ockam_core::compat::sync::Arc;
 use ockam_core::errcode::{Kind, Origin};
-use ockam_core::{route, IncomingAccessControl, Route};
+use ockam_core::{route, IncomingAccessControl, OutgoingAccessControl, Route};
 use ockam_multiaddr::proto::Project;
 use ockam_multiaddr::MultiAddr;
+use ockam_node::api::ResponseStreamSender;
 use ockam_node::compat::asynchronous::RwLock;
-use ockam_node::Context;
+use ockam_node::{tokio, Context};
 use ockam_transport_tcp::{TcpInletOptions, TcpOutletOptions};
 
 use crate::cli_state::StateDirTrait;
 use crate::config::lookup::ProjectLookup;
 use crate::error::ApiError;
+use crate::hole_punch::{
+    hole_punch_client, request_hole_punch, DirectPath, HolePunchRequest, HOLE_PUNCH_TIMEOUT,
+};
 use crate::local_multiaddr_to_route;
 use crate::nodes::connection::{Connection, ConnectionInstance};
 use crate::nodes::models::portal::{
-    CreateInlet, CreateOutlet, InletList, InletStatus, OutletList, OutletStatus,
+    CreateInlet, CreateOutlet, InletEvent, InletEventKind, InletList, InletStatus, OutletList,
+    OutletStatus, PortalLimits,
 };
+use crate::nodes::models::secure_channel::CredentialExchangeMode;
 use crate::nodes::registry::{InletInfo, OutletInfo};
 use crate::nodes::service::random_alias;
 use crate::session::sessions::{Replacer, Session, MAX_CONNECT_TIME, MAX_RECOVERY_TIME};
@@ -34,6 +40,22 @@ use crate::{actions, resources, DefaultAddress};
 use super::{NodeManager, NodeManagerWorker};
 
 impl NodeManager {
+    /// Resource of a portal of the given kind, e.g. `tcp-outlet/db` for the outlet `db`
+    ///
+    /// The policies of a portal used to be stored under its bare alias.
+    /// They are copied to the portal resource when the portal is created.
+    async fn portal_resource(&self, kind: Resource, alias: Option<&str>) -> Result<Resource> {
+        let alias = match alias {
+            Some(alias) => alias,
+            None => return Ok(kind),
+        };
+        let resource = kind.child(alias);
+        if migrate_policies(self.policies.as_ref(), &Resource::new(alias), &resource).await? {
+            info!(%resource, "Migrated the policies stored for the alias {alias}");
+        }
+        Ok(resource)
+    }
+
     pub async fn create_outlet(
         &mut self,
         ctx: &Context,
@@ -41,15 +63,15 @@ impl NodeManager {
         worker_addr: Address,
         alias: Option<String>,
         reachable_from_default_secure_channel: bool,
+        limits: PortalLimits,
     ) -> Result<OutletStatus> {
         info!(
             "Handling request to create outlet portal at {:?}",
             socket_addr
         );
-        let resource = alias
-            .as_deref()
-            .map(Resource::new)
-            .unwrap_or(resources::OUTLET);
+        let resource = self
+            .portal_resource(resources::OUTLET, alias.as_deref())
+            .await?;
 
         let alias = alias.unwrap_or_else(random_alias);
 
@@ -74,7 +96,8 @@ impl NodeManager {
             .access_control(&resource, &actions::HANDLE_MESSAGE, trust_context_id, None)
             .await?;
 
-        let options = TcpOutletOptions::new().with_incoming_access_control(access_control);
+        let options = limits
+            .outlet_options(TcpOutletOptions::new().with_incoming_access_control(access_control));
         let options = if !check_credential {
             options.as_consumer(&self.api_transport_flow_control_id)
         } else {
@@ -140,6 +163,28 @@ impl NodeManager {
             Ok(None)
         }
     }
+
+    /// Push an inlet event to the subscribers, dropping the ones which cancelled
+    /// their subscription or can't be reached anymore
+    ///
+    /// A subscriber which is slow to read its events misses some of them, and
+    /// is told so once it catches up.
+    pub(super) async fn publish_inlet_event(&mut self, event: InletEvent) {
+        let mut subscribers = Vec::new();
+        for mut subscriber in self.registry.inlet_subscribers.drain(..) {
+            match subscriber.try_send(event.clone()).await {
+                Ok(()) => subscribers.push(subscriber),
+                Err(e) if e.code().kind == Kind::ResourceExhausted => {
+                    trace!(id = %subscriber.request_id(), "Inlet events subscriber is lagging");
+                    subscribers.push(subscriber)
+                }
+                Err(e) => {
+                    debug!(id = %subscriber.request_id(), %e, "Removing inlet events subscriber")
+                }
+            }
+        }
+        self.registry.inlet_subscribers = subscribers;
+    }
 }
 
 impl NodeManagerWorker {
@@ -156,11 +201,31 @@ impl NodeManagerWorker {
                         None,
                         info.outlet_route.to_string(),
                     )
+                    .with_path(info.path())
                 })
                 .collect(),
         ))
     }
 
+    /// Subscribe to the inlet events. They are sent as a streaming response
+    /// to the request, until the client cancels it
+    pub(super) async fn subscribe_inlet_events(
+        &mut self,
+        ctx: &Context,
+        req: &Request,
+        return_route: Route,
+    ) -> Result<()> {
+        let subscriber = ResponseStreamSender::new(ctx, req, return_route).await?;
+        debug!(id = %req.id(), "Adding inlet events subscriber");
+        self.node_manager
+            .write()
+            .await
+            .registry
+            .inlet_subscribers
+            .push(subscriber);
+        Ok(())
+    }
+
     pub(super) async fn get_outlets(&self, req: &Request) -> ResponseBuilder<OutletList> {
         Response::ok(req.id()).body(self.list_outlets().await)
     }
@@ -246,15 +311,36 @@ impl NodeManagerWorker {
             }
         };
 
-        let outlet_route = route![
+        let relay_route = route![
             req.prefix_route().clone(),
-            outlet_route,
+            outlet_route.clone(),
             req.suffix_route().clone()
         ];
 
-        let resource = req.alias().map(Resource::new).unwrap_or(resources::INLET);
+        // A direct path bypasses the prefix route. The session of the inlet keeps
+        // monitoring the relay path, and recreates the inlet on that path
+        let (outlet_route, direct) = if req.direct() {
+            match self
+                .direct_outlet_route(ctx, &req, &connection_instance, &outlet_route)
+                .await
+            {
+                Ok((direct_route, direct)) => (
+                    route![direct_route, req.suffix_route().clone()],
+                    Some(direct),
+                ),
+                Err(e) => {
+                    warn!(to = %req.outlet_addr(), err = %e, "Failed to open a direct path to the outlet, using the relay path");
+                    (relay_route, None)
+                }
+            }
+        } else {
+            (relay_route, None)
+        };
 
         let mut node_manager = self.node_manager.write().await;
+        let resource = node_manager
+            .portal_resource(resources::INLET, req.alias())
+            .await?;
         let projects = node_manager.cli_state.projects.list().map_err(|e| {
             Response::bad_request(req_id)
                 .body(Error::new_without_path().with_message(e.to_string()))
@@ -290,7 +376,16 @@ impl NodeManagerWorker {
             .access_control(&resource, &actions::HANDLE_MESSAGE, project_id, None)
             .await?;
 
-        let options = TcpInletOptions::new().with_incoming_access_control(access_control.clone());
+        let outgoing_access_control = node_manager
+            .outgoing_access_control(&resource, &actions::SEND_MESSAGE, project_id)
+            .await?;
+
+        let limits = req.limits();
+        let options = limits.inlet_options(
+            TcpInletOptions::new()
+                .with_incoming_access_control(access_control.clone())
+                .with_outgoing_access_control(outgoing_access_control.clone()),
+        );
 
         let res = node_manager
             .tcp_transport
@@ -304,10 +399,10 @@ impl NodeManagerWorker {
                 let listen_addr = socket_address.to_string();
 
                 // TODO: Use better way to store inlets?
-                node_manager.registry.inlets.insert(
-                    alias.clone(),
-                    InletInfo::new(&listen_addr, Some(&worker_addr), &outlet_route),
-                );
+                let inlet = InletInfo::new(&listen_addr, Some(&worker_addr), &outlet_route)
+                    .with_direct(direct);
+                let path = inlet.path();
+                node_manager.registry.inlets.insert(alias.clone(), inlet);
                 if !connection_instance.normalized_addr.is_empty() {
                     debug! {
                         %alias,
@@ -321,6 +416,7 @@ impl NodeManagerWorker {
                     let ctx = Arc::new(ctx.async_try_clone().await?);
                     let repl = replacer(
                         self.node_manager.clone(),
+                        alias.clone(),
                         connection_instance,
                         worker_addr.clone(),
                         listen_addr.clone(),
@@ -329,22 +425,33 @@ impl NodeManagerWorker {
                         req.suffix_route().clone(),
                         req.authorized(),
                         access_control.clone(),
+                        outgoing_access_control.clone(),
+                        limits,
                         ctx,
                     );
                     session.set_replacer(repl);
                     node_manager.add_session(session);
                 }
 
-                Response::ok(req_id).body(InletStatus::new(
+                let status = InletStatus::new(
                     listen_addr,
                     worker_addr.to_string(),
                     alias,
                     None,
                     outlet_route.to_string(),
-                ))
+                )
+                .with_path(path);
+                node_manager
+                    .publish_inlet_event(InletEvent::new(InletEventKind::Created, status.clone()))
+                    .await;
+                Response::ok(req_id).body(status)
             }
             Err(e) => {
                 warn!(to = %req.outlet_addr(), err = %e, "Failed to create TCP inlet");
+                if let Some(direct) = direct {
+                    drop(node_manager);
+                    close_direct_path(ctx, &self.node_manager, &direct).await;
+                }
                 let err_body = Error::new_without_path()
                     .with_message(format!("Failed to create TCP inlet: {}", e));
                 return Err(Response::bad_request(req_id).body(err_body));
@@ -352,8 +459,120 @@ impl NodeManagerWorker {
         })
     }
 
+    /// Try to reach the outlet over a hole punched UDP path
+    ///
+    /// The inlet and outlet nodes both punch a hole through a rendezvous service,
+    /// then a secure channel is created over that hole, trusting the identity of
+    /// the outlet node on the relay path. Return the route to the outlet through
+    /// this secure channel, and the direct path to close when the inlet is deleted.
+    async fn direct_outlet_route(
+        &self,
+        ctx: &Context,
+        req: &CreateInlet<'_>,
+        connection_instance: &ConnectionInstance,
+        outlet_route: &Route,
+    ) -> Result<(Route, DirectPath)> {
+        let rendezvous_addr = req
+            .rendezvous_addr()
+            .ok_or_else(|| ApiError::core("A rendezvous address is required for a direct path"))?;
+
+        // The services of the outlet node come after the secure channel to that node
+        let relay_encryptor = connection_instance
+            .secure_channel_encryptors
+            .last()
+            .cloned()
+            .ok_or_else(|| ApiError::core("The outlet is not reached through a secure channel"))?;
+        let services: Vec<Address> = outlet_route
+            .iter()
+            .skip_while(|a| **a != relay_encryptor)
+            .skip(1)
+            .cloned()
+            .collect();
+        if services.is_empty() {
+            return Err(ApiError::core(
+                "The outlet route does not go through a secure channel",
+            ));
+        }
+
+        let (their_identity, hole_punch_transport) = {
+            let node_manager = self.node_manager.read().await;
+            let channel = node_manager
+                .secure_channels
+                .secure_channel_registry()
+                .get_channel_by_encryptor_address(&relay_encryptor)
+                .ok_or_else(|| ApiError::core("Unknown secure channel to the outlet node"))?;
+            (
+                channel.their_id(),
+                node_manager.hole_punch_transport.clone(),
+            )
+        };
+
+        // Both nodes punch a hole at the same time. If the outlet node refuses
+        // to punch its side, there is no need to wait for ours to open
+        let puncher_name = random_alias();
+        let peer_puncher_name = random_alias();
+        let mut client = hole_punch_client(ctx, &relay_encryptor).await?;
+        connection_instance.add_consumer(ctx, &client.address());
+        let request = Request::post("/").body(HolePunchRequest::new(
+            rendezvous_addr,
+            &peer_puncher_name,
+            &puncher_name,
+        ));
+        let (_, puncher) = tokio::try_join!(
+            request_hole_punch(
+                &mut client,
+                route![relay_encryptor.clone(), DefaultAddress::HOLE_PUNCH_SERVICE],
+                request
+            ),
+            hole_punch_transport.punch_hole(
+                ctx,
+                rendezvous_addr,
+                &puncher_name,
+                &peer_puncher_name
+            )
+        )?;
+
+        let mut direct = DirectPath {
+            puncher_name,
+            peer_puncher_name,
+            relay_encryptor,
+            relay_flow_control_id: connection_instance.flow_control_id.clone(),
+            encryptor: None,
+        };
+        let sc = self
+            .node_manager
+            .write()
+            .await
+            .create_secure_channel_impl(
+                route![puncher, DefaultAddress::SECURE_CHANNEL_LISTENER],
+                Some(vec![their_identity]),
+                CredentialExchangeMode::Mutual,
+                Some(HOLE_PUNCH_TIMEOUT),
+                None,
+                ctx,
+                None,
+            )
+            .await;
+        let sc = match sc {
+            Ok(sc) => sc,
+            Err(e) => {
+                if let Err(e) = direct.close(ctx, &hole_punch_transport).await {
+                    debug!(err = %e, "Failed to close the hole to the outlet node");
+                }
+                return Err(e);
+            }
+        };
+        direct.encryptor = Some(sc.encryptor_address().clone());
+
+        Ok((
+            route![sc.encryptor_address().clone(), Route::create(services)],
+            direct,
+        ))
+    }
+
     pub(super) async fn delete_inlet<'a>(
         &mut self,
+        ctx: &Context,
         req: &Request,
         alias: &'a str,
     ) -> Result<ResponseBuilder<InletStatus>, ResponseBuilder<Error>> {
@@ -369,13 +588,25 @@ impl NodeManagerWorker {
             {
                 Ok(_) => {
                     debug!(%alias, "Successfully stopped inlet");
-                    Ok(Response::ok(req.id()).body(InletStatus::new(
-                        inlet_to_delete.bind_addr,
+                    let status = InletStatus::new(
+                        &inlet_to_delete.bind_addr,
                         inlet_to_delete.worker_addr.to_string(),
                         alias,
                         None,
                         inlet_to_delete.outlet_route.to_string(),
-                    )))
+                    )
+                    .with_path(inlet_to_delete.path());
+                    node_manager
+                        .publish_inlet_event(InletEvent::new(
+                            InletEventKind::Deleted,
+                            status.clone(),
+                        ))
+                        .await;
+                    drop(node_manager);
+                    if let Some(direct) = &inlet_to_delete.direct {
+                        close_direct_path(ctx, &self.node_manager, direct).await;
+                    }
+                    Ok(Response::ok(req.id()).body(status))
                 }
                 Err(e) => {
                     error!(%alias, "Failed to remove inlet from node registry");
@@ -402,13 +633,16 @@ impl NodeManagerWorker {
         info!(%alias, "Handling request to show inlet portal");
         if let Some(inlet_to_show) = node_manager.registry.inlets.get(alias) {
             debug!(%alias, "Inlet not found in node registry");
-            Ok(Response::ok(req.id()).body(InletStatus::new(
-                inlet_to_show.bind_addr.to_string(),
-                inlet_to_show.worker_addr.to_string(),
-                alias,
-                None,
-                inlet_to_show.outlet_route.to_string(),
-            )))
+            Ok(Response::ok(req.id()).body(
+                InletStatus::new(
+                    inlet_to_show.bind_addr.to_string(),
+                    inlet_to_show.worker_addr.to_string(),
+                    alias,
+                    None,
+                    inlet_to_show.outlet_route.to_string(),
+                )
+                .with_path(inlet_to_show.path()),
+            ))
         } else {
             error!(%alias, "Inlet not found in the node registry");
             let err_body =
@@ -428,9 +662,20 @@ impl NodeManagerWorker {
             worker_addr,
             alias,
             reachable_from_default_secure_channel,
+            limits,
+            direct,
             ..
         } = create_outlet;
 
+        if direct.unwrap_or(false) {
+            let mut node_manager = self.node_manager.write().await;
+            if let Err(e) = node_manager.start_hole_punch_service_impl(ctx).await {
+                let err_body = Error::new_without_path()
+                    .with_message(format!("Failed to start the hole punch service: {e}"));
+                return Err(Response::bad_request(req.id()).body(err_body));
+            }
+        }
+
         self.create_outlet_impl(
             ctx,
             req.id(),
@@ -438,10 +683,12 @@ impl NodeManagerWorker {
             worker_addr,
             alias,
             reachable_from_default_secure_channel,
+            limits.unwrap_or_default(),
         )
         .await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_outlet_impl(
         &self,
         ctx: &Context,
@@ -450,6 +697,7 @@ impl NodeManagerWorker {
         worker_addr: Address,
         alias: Option<String>,
         reachable_from_default_secure_channel: bool,
+        limits: PortalLimits,
     ) -> Result<ResponseBuilder<OutletStatus>, ResponseBuilder<Error>> {
         let mut node_manager = self.inner().write().await;
         match node_manager
@@ -459,6 +707,7 @@ impl NodeManagerWorker {
                 worker_addr,
                 alias,
                 reachable_from_default_secure_channel,
+                limits,
             )
             .await
         {
@@ -527,6 +776,58 @@ impl NodeManagerWorker {
     }
 }
 
+impl PortalLimits {
+    /// Apply these limits to the options of a TCP inlet
+    fn inlet_options(&self, mut options: TcpInletOptions) -> TcpInletOptions {
+        if let Some(rate_limit) = self.rate_limit() {
+            options = options.with_rate_limit(rate_limit)
+        }
+        if let Some(rate_limit) = self.connection_rate_limit() {
+            options = options.with_connection_rate_limit(rate_limit)
+        }
+        if let Some(max_connections) = self.max_connections() {
+            options = options.with_max_connections(max_connections as usize)
+        }
+        options
+    }
+
+    /// Apply these limits to the options of a TCP outlet
+    fn outlet_options(&self, mut options: TcpOutletOptions) -> TcpOutletOptions {
+        if let Some(rate_limit) = self.rate_limit() {
+            options = options.with_rate_limit(rate_limit)
+        }
+        if let Some(rate_limit) = self.connection_rate_limit() {
+            options = options.with_connection_rate_limit(rate_limit)
+        }
+        if let Some(max_connections) = self.max_connections() {
+            options = options.with_max_connections(max_connections as usize)
+        }
+        options
+    }
+}
+
+/// Delete the secure channel of a direct path and close its hole.
+///
+/// Failures are only logged, since the outlet node may not be reachable anymore.
+async fn close_direct_path(
+    ctx: &Context,
+    node_manager: &Arc<RwLock<NodeManager>>,
+    direct: &DirectPath,
+) {
+    let transport = {
+        let mut node_manager = node_manager.write().await;
+        if let Some(encryptor) = &direct.encryptor {
+            if let Err(e) = node_manager.delete_secure_channel(ctx, encryptor).await {
+                debug!(%encryptor, err = %e, "Failed to delete the secure channel of a direct path");
+            }
+        }
+        node_manager.hole_punch_transport.clone()
+    };
+    if let Err(e) = direct.close(ctx, &transport).await {
+        debug!(puncher = %direct.puncher_name, err = %e, "Failed to close a direct path");
+    }
+}
+
 /// Create a session replacer.
 ///
 /// This returns a function that accepts the previous ping address (e.g.
@@ -535,6 +836,7 @@ impl NodeManagerWorker {
 #[allow(clippy::too_many_arguments)]
 fn replacer(
     manager: Arc<RwLock<NodeManager>>,
+    alias: String,
     connection_instance: ConnectionInstance,
     inlet_address: Address,
     bind: String,
@@ -543,17 +845,22 @@ fn replacer(
     suffix_route: Route,
     auth: Option<IdentityIdentifier>,
     access: Arc<dyn IncomingAccessControl>,
+    outgoing_access: Arc<dyn OutgoingAccessControl>,
+    limits: PortalLimits,
     ctx: Arc<Context>,
 ) -> Replacer {
     let connection_instance_arc = Arc::new(Mutex::new(connection_instance));
     let inlet_address_arc = Arc::new(Mutex::new(inlet_address));
 
     Box::new(move |previous_addr| {
+        let alias = alias.clone();
         let addr = addr.clone();
         let auth = auth.clone();
         let bind = bind.clone();
         let node_manager_arc = manager.clone();
         let access = access.clone();
+        let outgoing_access = outgoing_access.clone();
+        let limits = limits.clone();
         let ctx = ctx.clone();
         let connection_instance_arc = connection_instance_arc.clone();
         let inlet_address_arc = inlet_address_arc.clone();
@@ -566,6 +873,18 @@ fn replacer(
             debug!(%previous_addr, %addr, "creating new tcp inlet");
             // The future that recreates the inlet:
             let f = async {
+                // A direct path is not restored, the inlet falls back to the relay path
+                let direct = node_manager_arc
+                    .write()
+                    .await
+                    .registry
+                    .inlets
+                    .get_mut(&alias)
+                    .and_then(|inlet| inlet.direct.take());
+                if let Some(direct) = direct {
+                    close_direct_path(&ctx, &node_manager_arc, &direct).await;
+                }
+
                 let mut node_manager = node_manager_arc.write().await;
                 //stop/delete previous secure channels
                 for encryptor in &previous_connection_instance.secure_channel_encryptors {
@@ -613,16 +932,24 @@ fn replacer(
                     suffix_route
                 ];
 
-                let node_manager = node_manager_arc.write().await;
+                let mut node_manager = node_manager_arc.write().await;
 
-                let options = TcpInletOptions::new().with_incoming_access_control(access);
+                let options = limits.inlet_options(
+                    TcpInletOptions::new()
+                        .with_incoming_access_control(access)
+                        .with_outgoing_access_control(outgoing_access),
+                );
 
                 // Finally attempt to create a new inlet using the new route:
                 let new_inlet_address = node_manager
                     .tcp_transport
-                    .create_inlet(bind, normalized_route, options)
+                    .create_inlet(bind, normalized_route.clone(), options)
                     .await?
                     .1;
+                if let Some(inlet) = node_manager.registry.inlets.get_mut(&alias) {
+                    inlet.worker_addr = new_inlet_address.clone();
+                    inlet.outlet_route = normalized_route;
+                }
                 *inlet_address_arc.lock().unwrap() = new_inlet_address;
 
                 Ok(new_connection_instance.transport_route.clone())
@@ -643,3 +970,92 @@ fn replacer(
         })
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::models::portal::InletPath;
+    use ockam_abac::Expr;
+    use ockam_core::api::Method;
+    use std::str::FromStr;
+
+    #[allow(non_snake_case)]
+    #[ockam_macros::test(timeout = 30000)]
+    async fn inlet__direct_path_refused__should_use_the_relay_path(
+        context: &mut Context,
+    ) -> Result<()> {
+        let handle = crate::test_utils::start_manager_for_tests(context).await?;
+        let mut worker = NodeManagerWorker::from_shared(handle.node_manager.clone());
+
+        // The hole punch service is only started for outlets accepting direct paths
+        {
+            let node_manager = handle.node_manager.read().await;
+            assert!(node_manager.registry.hole_punch_services.is_empty());
+            node_manager
+                .policies
+                .set_policy(
+                    &Resource::new(DefaultAddress::HOLE_PUNCH_SERVICE),
+                    &actions::HANDLE_MESSAGE,
+                    &Expr::Bool(true),
+                )
+                .await?;
+        }
+        let outlet = CreateOutlet::new(
+            "127.0.0.1:5000".parse().unwrap(),
+            "outlet".into(),
+            None,
+            true,
+        )
+        .with_direct(true);
+        let req = Request::new(Method::Post, "/node/outlet", true);
+        assert!(worker.create_outlet(context, &req, outlet).await.is_ok());
+        assert!(!handle
+            .node_manager
+            .read()
+            .await
+            .registry
+            .hole_punch_services
+            .is_empty());
+
+        // The node is not part of a project, so the outlet node refuses to
+        // punch a hole through any rendezvous service
+        let mut inlet = CreateInlet::to_node(
+            "127.0.0.1:0".into(),
+            MultiAddr::from_str("/secure/api/service/outlet").unwrap(),
+            route![],
+            route![],
+            None,
+        );
+        inlet.set_alias("db");
+        inlet.set_direct("127.0.0.1:4000".into());
+        let (_, status) = worker
+            .create_inlet_impl(Id::fresh(), inlet, context)
+            .await
+            .map_err(|_| ApiError::core("cannot create the inlet"))?
+            .into_parts();
+        assert_eq!(Some(InletPath::Relay), status.unwrap().path);
+
+        // The path is reported when listing, showing and deleting the inlet
+        let req = Request::new(Method::Get, "/node/inlet", false);
+        let (_, inlets) = worker.get_inlets(&req).await.into_parts();
+        assert_eq!(Some(InletPath::Relay), inlets.unwrap().list[0].path);
+
+        let req = Request::new(Method::Get, "/node/inlet/db", false);
+        let (_, status) = worker
+            .show_inlet(&req, "db")
+            .await
+            .map_err(|_| ApiError::core("cannot show the inlet"))?
+            .into_parts();
+        assert_eq!(Some(InletPath::Relay), status.unwrap().path);
+
+        let req = Request::new(Method::Delete, "/node/inlet/db", false);
+        let (_, status) = worker
+            .delete_inlet(context, &req, "db")
+            .await
+            .map_err(|_| ApiError::core("cannot delete the inlet"))?
+            .into_parts();
+        assert_eq!(Some(InletPath::Relay), status.unwrap().path);
+
+        context.stop().await
+    }
+}
@@ -1,10 +1,8 @@
 use std::net::SocketAddr;
 
-use minicbor::Decoder;
-
-use ockam::Result;
-use ockam_core::api::{Error, Request, Response, ResponseBuilder};
-use ockam_core::Address;
+use ockam_core::api::RequestError;
+use ockam_core::{async_trait, Address};
+use ockam_node::api::{Handle, RequestInfo};
 use ockam_node::Context;
 use ockam_transport_tcp::{
     TcpConnectionOptions, TcpListenerInfo, TcpListenerOptions, TcpSenderInfo, TcpTransport,
@@ -14,6 +12,11 @@ use crate::nodes::models::transport::{
     CreateTcpConnection, CreateTcpListener, DeleteTransport, TransportList, TransportMode,
     TransportStatus, TransportType,
 };
+use crate::nodes::service::endpoints::{
+    CreateTcpConnectionEndpoint, CreateTcpListenerEndpoint, DeleteTcpConnectionEndpoint,
+    DeleteTcpListenerEndpoint, GetTcpConnectionEndpoint, GetTcpListenerEndpoint,
+    ListTcpConnectionsEndpoint, ListTcpListenersEndpoint,
+};
 use crate::nodes::service::ApiTransport;
 
 use super::NodeManagerWorker;
@@ -70,11 +73,16 @@ impl NodeManagerWorker {
             }
         }
     }
+}
 
-    pub(super) async fn get_tcp_connections(
-        &self,
-        req: &Request,
-    ) -> ResponseBuilder<TransportList> {
+#[async_trait]
+impl Handle<ListTcpConnectionsEndpoint> for NodeManagerWorker {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context,
+        _request: RequestInfo<'_>,
+        _body: (),
+    ) -> Result<TransportList, RequestError> {
         let tcp_transport = &self.node_manager.read().await.tcp_transport;
         let map = |info: &TcpSenderInfo| {
             TransportStatus::new(ApiTransport {
@@ -87,7 +95,7 @@ impl NodeManagerWorker {
             })
         };
 
-        Response::ok(req.id()).body(TransportList::new(
+        Ok(TransportList::new(
             tcp_transport
                 .registry()
                 .get_all_sender_workers()
@@ -96,36 +104,44 @@ impl NodeManagerWorker {
                 .collect(),
         ))
     }
+}
 
-    pub(super) async fn get_tcp_connection(
-        &self,
-        req: &Request,
-        address: String,
-    ) -> Result<ResponseBuilder<TransportStatus>, ResponseBuilder<Error>> {
+#[async_trait]
+impl Handle<GetTcpConnectionEndpoint> for NodeManagerWorker {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context,
+        request: RequestInfo<'_>,
+        _body: (),
+    ) -> Result<TransportStatus, RequestError> {
+        let address = request.param("address")?;
         let tcp_transport = &self.node_manager.read().await.tcp_transport;
-        let sender = match Self::find_connection(tcp_transport, address.to_string()) {
-            None => {
-                let error = Error::new(req.path()).with_message(format!(
+        let sender =
+            Self::find_connection(tcp_transport, address.to_string()).ok_or_else(|| {
+                RequestError::not_found(format!(
                     "Connection {address} was not found in the registry."
-                ));
-                return Err(Response::not_found(req.id()).body(error));
-            }
-            Some(sender) => sender,
-        };
+                ))
+            })?;
 
-        let status = TransportStatus::new(ApiTransport {
+        Ok(TransportStatus::new(ApiTransport {
             tt: TransportType::Tcp,
             tm: (*sender.mode()).into(),
             socket_address: sender.socket_address(),
             worker_address: sender.address().to_string(),
             processor_address: sender.receiver_address().to_string(),
             flow_control_id: sender.flow_control_id().clone(),
-        });
-
-        Ok(Response::ok(req.id()).body(status))
+        }))
     }
+}
 
-    pub(super) async fn get_tcp_listeners(&self, req: &Request) -> ResponseBuilder<TransportList> {
+#[async_trait]
+impl Handle<ListTcpListenersEndpoint> for NodeManagerWorker {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context,
+        _request: RequestInfo<'_>,
+        _body: (),
+    ) -> Result<TransportList, RequestError> {
         let tcp_transport = &self.node_manager.read().await.tcp_transport;
 
         let map = |info: &TcpListenerInfo| {
@@ -139,7 +155,7 @@ impl NodeManagerWorker {
             })
         };
 
-        Response::ok(req.id()).body(TransportList::new(
+        Ok(TransportList::new(
             tcp_transport
                 .registry()
                 .get_all_listeners()
@@ -148,48 +164,57 @@ impl NodeManagerWorker {
                 .collect(),
         ))
     }
+}
 
-    pub(super) async fn get_tcp_listener(
-        &self,
-        req: &Request,
-        address: String,
-    ) -> Result<ResponseBuilder<TransportStatus>, ResponseBuilder<Error>> {
+#[async_trait]
+impl Handle<GetTcpListenerEndpoint> for NodeManagerWorker {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context,
+        request: RequestInfo<'_>,
+        _body: (),
+    ) -> Result<TransportStatus, RequestError> {
+        let address = request.param("address")?;
         let tcp_transport = &self.node_manager.read().await.tcp_transport;
-
-        let listener = match Self::find_listener(tcp_transport, address.to_string()) {
-            None => {
-                let err_body = Error::new(req.path())
-                    .with_message(format!("Listener {address} was not found in the registry."));
-                return Err(Response::bad_request(req.id()).body(err_body));
-            }
-            Some(listener) => listener,
-        };
-
-        let status = TransportStatus::new(ApiTransport {
+        let listener =
+            Self::find_listener(tcp_transport, address.to_string()).ok_or_else(|| {
+                RequestError::bad_request(format!(
+                    "Listener {address} was not found in the registry."
+                ))
+            })?;
+
+        Ok(TransportStatus::new(ApiTransport {
             tt: TransportType::Tcp,
             tm: TransportMode::Listen,
             socket_address: listener.socket_address(),
             worker_address: "<none>".into(),
             processor_address: listener.address().to_string(),
             flow_control_id: listener.flow_control_id().clone(),
-        });
-
-        Ok(Response::ok(req.id()).body(status))
+        }))
     }
+}
 
-    pub(super) async fn create_tcp_connection<'a>(
-        &self,
-        req: &Request,
-        dec: &mut Decoder<'_>,
-        ctx: &Context,
-    ) -> Result<ResponseBuilder<TransportStatus>, ResponseBuilder<Error>> {
+#[async_trait]
+impl Handle<CreateTcpConnectionEndpoint> for NodeManagerWorker {
+    async fn handle(
+        &mut self,
+        ctx: &mut Context,
+        _request: RequestInfo<'_>,
+        body: CreateTcpConnection,
+    ) -> Result<TransportStatus, RequestError> {
         let node_manager = self.node_manager.read().await;
-        let CreateTcpConnection { addr, .. } = dec.decode()?;
+        let CreateTcpConnection { addr, .. } = body;
 
         info!("Handling request to create a new TCP connection: {}", addr);
         let socket_addr = addr.to_string();
 
-        let options = TcpConnectionOptions::new();
+        // Honour the proxy of the environment, if any
+        let options = TcpConnectionOptions::new()
+            .with_proxy_from_env()
+            .map_err(|msg| {
+                error!("{}", msg.to_string());
+                RequestError::bad_request(format!("Unable to connect to {addr}: {msg}"))
+            })?;
 
         // Add all Hop workers as consumers for Demo purposes
         // Production nodes should not run any Hop workers
@@ -198,167 +223,136 @@ impl NodeManagerWorker {
                 .add_consumer(hop.clone(), &options.flow_control_id());
         }
 
-        let res = node_manager
+        let connection = node_manager
             .tcp_transport
             .connect(&socket_addr, options)
-            .await;
-
-        use {super::TransportType::*, TransportMode::*};
-
-        let response = match res {
-            Ok(connection) => {
-                let api_transport = ApiTransport {
-                    tt: Tcp,
-                    tm: Outgoing,
-                    socket_address: *connection.socket_address(),
-                    worker_address: connection.sender_address().to_string(),
-                    processor_address: connection.receiver_address().to_string(),
-                    flow_control_id: connection.flow_control_id().clone(),
-                };
-                Response::ok(req.id()).body(TransportStatus::new(api_transport))
-            }
-            Err(msg) => {
+            .await
+            .map_err(|msg| {
                 error!("{}", msg.to_string());
-                let err_body = Error::new(req.path())
-                    .with_message(format!("Unable to connect to {}.", addr))
-                    .with_cause(Error::new(req.path()).with_message(msg.to_string()));
-                return Err(Response::bad_request(req.id()).body(err_body));
-            }
-        };
+                RequestError::bad_request(format!("Unable to connect to {addr}: {msg}"))
+            })?;
 
-        Ok(response)
+        Ok(TransportStatus::new(ApiTransport {
+            tt: TransportType::Tcp,
+            tm: TransportMode::Outgoing,
+            socket_address: *connection.socket_address(),
+            worker_address: connection.sender_address().to_string(),
+            processor_address: connection.receiver_address().to_string(),
+            flow_control_id: connection.flow_control_id().clone(),
+        }))
     }
+}
 
-    pub(super) async fn create_tcp_listener<'a>(
-        &self,
-        req: &Request,
-        dec: &mut Decoder<'_>,
-    ) -> Result<ResponseBuilder<TransportStatus>, ResponseBuilder<Error>> {
+#[async_trait]
+impl Handle<CreateTcpListenerEndpoint> for NodeManagerWorker {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context,
+        _request: RequestInfo<'_>,
+        body: CreateTcpListener,
+    ) -> Result<TransportStatus, RequestError> {
         let node_manager = self.node_manager.read().await;
-        let CreateTcpListener { addr, .. } = dec.decode()?;
-
-        use {super::TransportType::*, TransportMode::*};
+        let CreateTcpListener { addr, .. } = body;
 
         info!("Handling request to create a new tcp listener: {}", addr);
 
         let options = TcpListenerOptions::new();
-        let res = node_manager.tcp_transport.listen(&addr, options).await;
-
-        let response = match res {
-            Ok(listener) => {
-                let api_transport = ApiTransport {
-                    tt: Tcp,
-                    tm: Listen,
-                    socket_address: *listener.socket_address(),
-                    worker_address: "<none>".into(),
-                    processor_address: listener.processor_address().to_string(),
-                    flow_control_id: listener.flow_control_id().clone(),
-                };
-                Response::ok(req.id()).body(TransportStatus::new(api_transport))
-            }
-            Err(msg) => {
+        let listener = node_manager
+            .tcp_transport
+            .listen(&addr, options)
+            .await
+            .map_err(|msg| {
                 error!("{}", msg.to_string());
-                let err_body = Error::new(req.path())
-                    .with_message(format!("Unable to listen on {}.", addr))
-                    .with_cause(Error::new(req.path()).with_message(msg.to_string()));
-                return Err(Response::bad_request(req.id()).body(err_body));
-            }
-        };
+                RequestError::bad_request(format!("Unable to listen on {addr}: {msg}"))
+            })?;
 
-        Ok(response)
+        Ok(TransportStatus::new(ApiTransport {
+            tt: TransportType::Tcp,
+            tm: TransportMode::Listen,
+            socket_address: *listener.socket_address(),
+            worker_address: "<none>".into(),
+            processor_address: listener.processor_address().to_string(),
+            flow_control_id: listener.flow_control_id().clone(),
+        }))
     }
+}
 
-    pub(super) async fn delete_tcp_connection(
-        &self,
-        req: &Request,
-        dec: &mut Decoder<'_>,
-    ) -> Result<ResponseBuilder<()>, ResponseBuilder<Error>> {
+#[async_trait]
+impl Handle<DeleteTcpConnectionEndpoint> for NodeManagerWorker {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context,
+        _request: RequestInfo<'_>,
+        body: DeleteTransport,
+    ) -> Result<(), RequestError> {
         let node_manager = self.node_manager.read().await;
-        let body: DeleteTransport = dec.decode()?;
 
         info!("Handling request to stop listener: {}", body.address);
 
         let sender_address = match body.address.parse::<SocketAddr>() {
-            Ok(socket_address) => {
-                match node_manager
-                    .tcp_transport
-                    .registry()
-                    .get_all_sender_workers()
-                    .iter()
-                    .find(|x| x.socket_address() == socket_address)
-                    .map(|x| x.address().clone())
-                {
-                    None => {
-                        let err_body = Error::new(req.path()).with_message(format!(
-                            "Connection {socket_address} was not found in the registry."
-                        ));
-                        return Err(Response::bad_request(req.id()).body(err_body));
-                    }
-                    Some(addr) => addr,
-                }
-            }
+            Ok(socket_address) => node_manager
+                .tcp_transport
+                .registry()
+                .get_all_sender_workers()
+                .iter()
+                .find(|x| x.socket_address() == socket_address)
+                .map(|x| x.address().clone())
+                .ok_or_else(|| {
+                    RequestError::bad_request(format!(
+                        "Connection {socket_address} was not found in the registry."
+                    ))
+                })?,
             Err(_err) => body.address.into(),
         };
 
-        match node_manager
+        node_manager
             .tcp_transport
             .disconnect(sender_address.clone())
             .await
-        {
-            Ok(_) => Ok(Response::ok(req.id())),
-            Err(err) => {
-                let err_body = Error::new(req.path())
-                    .with_message(format!("Unable to disconnect from {}.", sender_address))
-                    .with_cause(Error::new(req.path()).with_message(err.to_string()));
-                Err(Response::bad_request(req.id()).body(err_body))
-            }
-        }
+            .map_err(|err| {
+                RequestError::bad_request(format!(
+                    "Unable to disconnect from {sender_address}: {err}"
+                ))
+            })
     }
+}
 
-    pub(super) async fn delete_tcp_listener(
-        &self,
-        req: &Request,
-        dec: &mut Decoder<'_>,
-    ) -> Result<ResponseBuilder<()>, ResponseBuilder<Error>> {
+#[async_trait]
+impl Handle<DeleteTcpListenerEndpoint> for NodeManagerWorker {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context,
+        _request: RequestInfo<'_>,
+        body: DeleteTransport,
+    ) -> Result<(), RequestError> {
         let node_manager = self.node_manager.read().await;
-        let body: DeleteTransport = dec.decode()?;
 
         info!("Handling request to stop listener: {}", body.address);
 
         let listener_address = match body.address.parse::<SocketAddr>() {
-            Ok(socket_address) => {
-                match node_manager
-                    .tcp_transport
-                    .registry()
-                    .get_all_listeners()
-                    .iter()
-                    .find(|x| x.socket_address() == socket_address)
-                    .map(|x| x.address().clone())
-                {
-                    None => {
-                        let err_body = Error::new(req.path()).with_message(format!(
-                            "Listener {socket_address} was not found in the registry."
-                        ));
-                        return Err(Response::bad_request(req.id()).body(err_body));
-                    }
-                    Some(addr) => addr,
-                }
-            }
+            Ok(socket_address) => node_manager
+                .tcp_transport
+                .registry()
+                .get_all_listeners()
+                .iter()
+                .find(|x| x.socket_address() == socket_address)
+                .map(|x| x.address().clone())
+                .ok_or_else(|| {
+                    RequestError::bad_request(format!(
+                        "Listener {socket_address} was not found in the registry."
+                    ))
+                })?,
             Err(_err) => body.address.into(),
         };
 
-        match node_manager
+        node_manager
             .tcp_transport
             .stop_listener(&listener_address)
             .await
-        {
-            Ok(_) => Ok(Response::ok(req.id())),
-            Err(err) => {
-                let err_body = Error::new(req.path())
-                    .with_message(format!("Unable to stop listener {}.", listener_address))
-                    .with_cause(Error::new(req.path()).with_message(err.to_string()));
-                Err(Response::bad_request(req.id()).body(err_body))
-            }
-        }
+            .map_err(|err| {
+                RequestError::bad_request(format!(
+                    "Unable to stop listener {listener_address}: {err}"
+                ))
+            })
     }
 }
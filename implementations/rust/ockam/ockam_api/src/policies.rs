@@ -0,0 +1,320 @@
+pub mod types;
+
+use core::time::Duration;
+use minicbor::Decoder;
+use ockam::identity::{
+    Identities, IdentityAttributesReader, IdentityIdentifier, IdentitySecureChannelLocalInfo,
+    SecureChannelOptions, SecureChannels, TrustMultiIdentifiersPolicy,
+};
+use ockam::identity::{Identity, Storage};
+use ockam_abac::{check, PolicyBundle, PolicySource, SyncedPolicyStorage};
+use ockam_core::api::{self, Method, Request, Response};
+use ockam_core::compat::sync::Arc;
+use ockam_core::{self, async_trait, route, Address, DenyAll, Result, Route, Routed, Worker};
+use ockam_identity::secure_channel_required;
+use ockam_node::tokio;
+use ockam_node::tokio::sync::Mutex;
+use ockam_node::tokio::task::JoinHandle;
+use ockam_node::{Context, RpcClient};
+use tracing::{debug, trace, warn};
+
+use self::types::SignedPolicyBundle;
+
+/// Default interval between two syncs of the node policies.
+pub const POLICY_SYNC_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Storage namespace and key of the current signed bundle.
+const BUNDLE_ID: &str = "policy_bundle";
+const BUNDLE_KEY: &str = "current";
+
+/// Policy service distributing signed policy bundles to the nodes of a project.
+///
+/// Any identity connected through a secure channel can fetch the current
+/// bundle. Only enrollers can publish a new set of policies, which is then
+/// versioned, signed with the identity of the service and persisted.
+pub struct PolicyService {
+    identities: Arc<Identities>,
+    issuer: IdentityIdentifier,
+    attributes: Arc<dyn IdentityAttributesReader>,
+    storage: Arc<dyn Storage>,
+}
+
+#[ockam_core::worker]
+impl Worker for PolicyService {
+    type Context = Context;
+    type Message = Vec<u8>;
+
+    async fn handle_message(&mut self, c: &mut Context, m: Routed<Self::Message>) -> Result<()> {
+        if let Ok(i) = IdentitySecureChannelLocalInfo::find_info(m.local_message()) {
+            let from = i.their_identity_id();
+            let mut dec = Decoder::new(m.as_body());
+            let req: Request = dec.decode()?;
+            trace! {
+                target: "ockam_api::policies::service",
+                from   = %from,
+                id     = %req.id(),
+                method = ?req.method(),
+                path   = %req.path(),
+                body   = %req.has_body(),
+                "request"
+            }
+            let res = match (req.method(), req.path_segments::<2>().as_slice()) {
+                (Some(Method::Get), [""]) => {
+                    let bundle = self.current().await?.map(|(_, b)| b);
+                    Response::ok(req.id()).body(bundle).to_vec()?
+                }
+                // Only return the current bundle if it is newer than the given version
+                (Some(Method::Get), [version]) => match version.parse::<u64>() {
+                    Ok(v) => {
+                        let bundle = self
+                            .current()
+                            .await?
+                            .filter(|(current, _)| *current > v)
+                            .map(|(_, b)| b);
+                        Response::ok(req.id()).body(bundle).to_vec()?
+                    }
+                    Err(_) => api::bad_request(&req, "invalid policy bundle version").to_vec()?,
+                },
+                (Some(Method::Put), [""]) => {
+                    if !self.is_enroller(&from).await? {
+                        api::forbidden(&req, "only enrollers can publish policies").to_vec()?
+                    } else {
+                        let bundle: PolicyBundle = dec.decode()?;
+                        match bundle
+                            .policies()
+                            .iter()
+                            .try_for_each(|p| check(p.expression()))
+                        {
+                            Ok(()) => {
+                                let version = self.publish(bundle).await?;
+                                Response::ok(req.id()).body(version).to_vec()?
+                            }
+                            Err(e) => {
+                                let msg = format!("invalid policy: {e}\n{}", e.annotate());
+                                api::bad_request(&req, &msg).to_vec()?
+                            }
+                        }
+                    }
+                }
+                (Some(Method::Get), _) | (Some(Method::Put), _) => {
+                    api::unknown_path(&req).to_vec()?
+                }
+                _ => api::invalid_method(&req).to_vec()?,
+            };
+            c.send(m.return_route(), res).await
+        } else {
+            secure_channel_required(c, m).await
+        }
+    }
+}
+
+impl PolicyService {
+    pub fn new(
+        identities: Arc<Identities>,
+        issuer: IdentityIdentifier,
+        attributes: Arc<dyn IdentityAttributesReader>,
+        storage: Arc<dyn Storage>,
+    ) -> Self {
+        Self {
+            identities,
+            issuer,
+            attributes,
+            storage,
+        }
+    }
+
+    /// The version and signed data of the current bundle, if any.
+    async fn current(&self) -> Result<Option<(u64, SignedPolicyBundle)>> {
+        match self.storage.get(BUNDLE_ID, BUNDLE_KEY).await? {
+            Some(data) => {
+                let signed: SignedPolicyBundle = minicbor::decode(&data)?;
+                let identity = self
+                    .identities
+                    .identities_reader()
+                    .get_identity(&self.issuer)
+                    .await?;
+                let bundle = signed.verify(&self.identities, &identity).await?;
+                Ok(Some((bundle.version(), signed)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Sign and store the policies of a bundle as the next version.
+    async fn publish(&self, policies: PolicyBundle) -> Result<u64> {
+        let version = self.current().await?.map(|(v, _)| v).unwrap_or(0) + 1;
+        let bundle = policies
+            .policies()
+            .iter()
+            .fold(PolicyBundle::new(version), |b, p| {
+                b.with_policy(
+                    p.resource().clone(),
+                    p.action().clone(),
+                    p.expression().clone(),
+                )
+            });
+        let signed = SignedPolicyBundle::sign(&self.identities, &self.issuer, &bundle).await?;
+        self.storage
+            .set(
+                BUNDLE_ID,
+                BUNDLE_KEY.to_string(),
+                minicbor::to_vec(&signed)?,
+            )
+            .await?;
+        debug!(%version, policies = %bundle.policies().len(), "published policy bundle");
+        Ok(version)
+    }
+
+    async fn is_enroller(&self, id: &IdentityIdentifier) -> Result<bool> {
+        let role = self
+            .attributes
+            .get_attributes(id)
+            .await?
+            .and_then(|e| e.attrs().get("ockam-role").cloned());
+        Ok(role.as_deref() == Some(b"enroller".as_slice()))
+    }
+}
+
+pub struct PolicyServiceClient(RpcClient);
+
+impl PolicyServiceClient {
+    pub fn new(client: RpcClient) -> Self {
+        PolicyServiceClient(client)
+    }
+
+    /// Publish a new set of policies and return the version of the bundle.
+    ///
+    /// The version of the given bundle is ignored.
+    pub async fn publish(&self, policies: &PolicyBundle) -> Result<u64> {
+        self.0.request(&Request::put("/").body(policies)).await
+    }
+
+    /// Fetch the current bundle if it is newer than `version`.
+    pub async fn fetch(&self, version: Option<u64>) -> Result<Option<SignedPolicyBundle>> {
+        let path = match version {
+            Some(v) => format!("/{v}"),
+            None => "/".to_string(),
+        };
+        self.0.request(&Request::get(path)).await
+    }
+}
+
+/// A [`PolicySource`] fetching signed bundles from a policy service over a
+/// secure channel, e.g. from the project authority.
+///
+/// The secure channel is created on the first fetch and reused by the next
+/// ones. It is created again after a failed fetch.
+pub struct RemotePolicySource {
+    ctx: Context,
+    secure_channels: Arc<SecureChannels>,
+    identifier: IdentityIdentifier,
+    authority: Identity,
+    route: Route,
+    service: Address,
+    channel: Mutex<Option<Address>>,
+}
+
+impl RemotePolicySource {
+    pub async fn new(
+        ctx: &Context,
+        secure_channels: Arc<SecureChannels>,
+        identifier: IdentityIdentifier,
+        authority: Identity,
+        route: Route,
+        service: Address,
+    ) -> Result<Self> {
+        let ctx = ctx
+            .new_detached(
+                Address::random_tagged("RemotePolicySource.detached"),
+                DenyAll,
+                DenyAll,
+            )
+            .await?;
+        Ok(Self {
+            ctx,
+            secure_channels,
+            identifier,
+            authority,
+            route,
+            service,
+            channel: Mutex::new(None),
+        })
+    }
+
+    /// The encryptor address of the secure channel to the policy service,
+    /// creating the channel if needed.
+    async fn secure_channel(&self, channel: &mut Option<Address>) -> Result<Address> {
+        if let Some(address) = channel {
+            return Ok(address.clone());
+        }
+        let resolved_route = self.ctx.resolve_transport_route(self.route.clone()).await?;
+        let options =
+            SecureChannelOptions::new().with_trust_policy(TrustMultiIdentifiersPolicy::new(vec![
+                self.authority.identifier(),
+            ]));
+        let sc = self
+            .secure_channels
+            .create_secure_channel(&self.ctx, &self.identifier, resolved_route, options)
+            .await?;
+        let address = sc.encryptor_address().clone();
+        *channel = Some(address.clone());
+        Ok(address)
+    }
+}
+
+#[async_trait]
+impl PolicySource for RemotePolicySource {
+    async fn fetch(&self, version: Option<u64>) -> Result<Option<PolicyBundle>> {
+        let mut channel = self.channel.lock().await;
+        let address = self.secure_channel(&mut channel).await?;
+
+        let client = RpcClient::new(route![address.clone(), self.service.clone()], &self.ctx)
+            .await
+            .map(PolicyServiceClient::new);
+        let signed = match client {
+            Ok(client) => client.fetch(version).await,
+            Err(e) => Err(e),
+        };
+        if signed.is_err() {
+            // The channel may be broken, create a new one on the next fetch
+            *channel = None;
+            if let Err(e) = self
+                .secure_channels
+                .stop_secure_channel(&self.ctx, &address)
+                .await
+            {
+                debug!(%address, "failed to stop the policy service secure channel: {e}")
+            }
+        }
+
+        match signed? {
+            Some(signed) => {
+                let identities = self.secure_channels.identities();
+                Ok(Some(signed.verify(&identities, &self.authority).await?))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// Periodically sync the policies of a storage with a source.
+///
+/// The sync runs until the returned handle is aborted. Failures are logged and the cached policies stay in effect until the
+/// next successful sync.
+pub fn start_policy_sync(
+    storage: Arc<SyncedPolicyStorage>,
+    source: Arc<dyn PolicySource>,
+    interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = storage.sync(source.as_ref()).await {
+                warn! {
+                    version = ?storage.version(),
+                    "failed to sync policies, using cached policies: {e}"
+                }
+            }
+            tokio::time::sleep(interval).await
+        }
+    })
+}
@@ -0,0 +1,76 @@
+use minicbor::{Decode, Encode};
+use ockam::identity::{Identities, Identity, IdentityIdentifier};
+use ockam_abac::PolicyBundle;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use ockam_vault::{Signature, SignatureVec};
+
+#[cfg(feature = "tag")]
+use ockam_core::TypeTag;
+
+/// A [`PolicyBundle`] signed by the identity which issued it.
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SignedPolicyBundle {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<5193274>,
+    #[n(1)] issuer: IdentityIdentifier,
+    /// CBOR-encoded [`PolicyBundle`].
+    #[cbor(with = "minicbor::bytes")]
+    #[n(2)] data: Vec<u8>,
+    #[cbor(with = "minicbor::bytes")]
+    #[n(3)] signature: Vec<u8>,
+}
+
+impl SignedPolicyBundle {
+    /// Sign a bundle with the key of the issuer identity.
+    pub async fn sign(
+        identities: &Identities,
+        issuer: &IdentityIdentifier,
+        bundle: &PolicyBundle,
+    ) -> Result<Self> {
+        let data = minicbor::to_vec(bundle)?;
+        let identity = identities.identities_reader().get_identity(issuer).await?;
+        let signature = identities
+            .identities_keys()
+            .create_signature(&identity, &data, None)
+            .await?;
+        Ok(SignedPolicyBundle {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            issuer: issuer.clone(),
+            data,
+            signature: SignatureVec::from(signature),
+        })
+    }
+
+    pub fn issuer(&self) -> &IdentityIdentifier {
+        &self.issuer
+    }
+
+    /// Check that the bundle was signed by the given authority and return it.
+    pub async fn verify(
+        &self,
+        identities: &Identities,
+        authority: &Identity,
+    ) -> Result<PolicyBundle> {
+        if self.issuer != authority.identifier() {
+            let msg = format!("policy bundle issued by unknown identity {}", self.issuer);
+            return Err(Error::new(Origin::Application, Kind::Invalid, msg));
+        }
+        let signature = Signature::new(self.signature.clone());
+        if !identities
+            .identities_keys()
+            .verify_signature(authority, &signature, &self.data, None)
+            .await?
+        {
+            return Err(Error::new(
+                Origin::Application,
+                Kind::Invalid,
+                "invalid policy bundle signature",
+            ));
+        }
+        Ok(minicbor::decode(&self.data)?)
+    }
+}
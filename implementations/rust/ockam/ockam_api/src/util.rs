@@ -1,13 +1,16 @@
 use miette::miette;
+use std::iter::Peekable;
 use std::net::{SocketAddrV4, SocketAddrV6};
 
 use ockam::TcpTransport;
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::{Address, Error, Result, Route, TransportType, LOCAL};
 use ockam_multiaddr::proto::{
-    DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp, Worker,
+    DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp, Tls, Worker,
 };
-use ockam_multiaddr::{Code, MultiAddr, Protocol};
+use ockam_multiaddr::{Code, MultiAddr, ProtoIter, Protocol};
+#[cfg(feature = "tls")]
+use ockam_transport_tcp::TlsClientOptions;
 use ockam_transport_tcp::{TcpConnection, TcpConnectionOptions, TCP};
 
 use crate::error::ApiError;
@@ -74,7 +77,7 @@ pub async fn multiaddr_to_route(
                 let port = it.next()?.cast::<Tcp>()?;
                 let socket_addr = SocketAddrV4::new(*ip4, *port);
 
-                let options = TcpConnectionOptions::new();
+                let options = tcp_connection_options(&mut it)?;
                 flow_control_id = Some(options.flow_control_id().clone());
 
                 let connection = match tcp.connect(socket_addr.to_string(), options).await {
@@ -99,7 +102,7 @@ pub async fn multiaddr_to_route(
                 let port = it.next()?.cast::<Tcp>()?;
                 let socket_addr = SocketAddrV6::new(*ip6, *port, 0, 0);
 
-                let options = TcpConnectionOptions::new();
+                let options = tcp_connection_options(&mut it)?;
                 flow_control_id = Some(options.flow_control_id().clone());
 
                 let connection = match tcp.connect(socket_addr.to_string(), options).await {
@@ -123,11 +126,11 @@ pub async fn multiaddr_to_route(
                 let host = p.cast::<DnsAddr>()?;
                 if let Some(p) = it.peek() {
                     if p.code() == Tcp::CODE {
-                        let port = p.cast::<Tcp>()?;
+                        let peer = format!("{}:{}", &*host, *p.cast::<Tcp>()?);
+                        let _ = it.next();
 
-                        let options = TcpConnectionOptions::new();
+                        let options = tcp_connection_options(&mut it)?;
                         flow_control_id = Some(options.flow_control_id().clone());
-                        let peer = format!("{}:{}", &*host, *port);
 
                         let connection = match tcp.connect(&peer, options).await {
                             Ok(c) => c,
@@ -142,8 +145,6 @@ pub async fn multiaddr_to_route(
 
                         tcp_connection = Some(connection);
 
-                        let _ = it.next();
-
                         continue;
                     }
                 }
@@ -174,6 +175,46 @@ pub async fn multiaddr_to_route(
     })
 }
 
+/// Create the options of a TCP connection. If the next protocol is
+/// `/tls/<server name>` it is consumed and the connection uses TLS, which
+/// requires the `tls` feature.
+///
+/// The connection goes through the proxy set in the `HTTPS_PROXY` or `ALL_PROXY`
+/// environment variables, if any.
+fn tcp_connection_options(it: &mut Peekable<ProtoIter>) -> Option<TcpConnectionOptions> {
+    let options = match TcpConnectionOptions::new().with_proxy_from_env() {
+        Ok(options) => options,
+        Err(error) => {
+            error!(%error, "Couldn't read the proxy from the environment");
+            return None;
+        }
+    };
+    let server_name = match it.peek() {
+        Some(p) if p.code() == Tls::CODE => p.cast::<Tls>()?.to_string(),
+        _ => return Some(options),
+    };
+    let _ = it.next();
+
+    with_tls(options, server_name)
+}
+
+#[cfg(feature = "tls")]
+fn with_tls(options: TcpConnectionOptions, server_name: String) -> Option<TcpConnectionOptions> {
+    match TlsClientOptions::new().with_native_roots() {
+        Ok(tls) => Some(options.with_tls(tls.with_server_name(server_name))),
+        Err(error) => {
+            error!(%error, "Couldn't load the TLS root certificates");
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "tls"))]
+fn with_tls(_options: TcpConnectionOptions, server_name: String) -> Option<TcpConnectionOptions> {
+    error!(%server_name, "TLS connections require the tls feature");
+    None
+}
+
 /// Resolve all the multiaddresses which represent transport addresses
 /// For example /tcp/127.0.0.1/port/4000 is transformed to the Address (TCP, "127.0.0.1:4000")
 /// The creation of a TCP worker and the substitution of that transport address to a worker address
@@ -336,6 +377,7 @@ pub fn local_worker(code: &Code) -> Result<bool> {
         | Ip4::CODE
         | Ip6::CODE
         | Tcp::CODE
+        | Tls::CODE
         | Secure::CODE => Ok(false),
         Worker::CODE | Service::CODE => Ok(true),
 
@@ -0,0 +1,179 @@
+use ockam::identity::{identities, Identity, InMemoryStorage};
+use ockam::route;
+use ockam_abac::expr::{eq, ident, str};
+use ockam_abac::mem::Memory;
+use ockam_abac::{
+    Action, PolicyBundle, PolicySource, PolicyStorage, Resource, SyncedPolicyStorage,
+};
+use ockam_api::policies::types::SignedPolicyBundle;
+use ockam_api::policies::{PolicyService, PolicyServiceClient, RemotePolicySource};
+use ockam_core::compat::sync::Arc;
+use ockam_core::{Address, Result};
+use ockam_identity::{SecureChannelListenerOptions, SecureChannelOptions, SecureChannels};
+use ockam_node::{Context, RpcClient};
+
+fn bundle(resources: &[&str]) -> PolicyBundle {
+    let e = eq([ident("subject.role"), str("db")]);
+    resources.iter().fold(PolicyBundle::new(0), |b, r| {
+        b.with_policy(Resource::new(r), Action::new("handle_message"), e.clone())
+    })
+}
+
+#[ockam_macros::test]
+async fn signed_bundles_are_verified(ctx: &mut Context) -> Result<()> {
+    let identities = identities();
+    let authority = identities.identities_creation().create_identity().await?;
+    let other = identities.identities_creation().create_identity().await?;
+
+    let signed =
+        SignedPolicyBundle::sign(&identities, &authority.identifier(), &bundle(&["echo"])).await?;
+    assert_eq!(&authority.identifier(), signed.issuer());
+
+    let verified = signed.verify(&identities, &authority).await?;
+    assert_eq!(1, verified.policies().len());
+
+    // A bundle is only valid for the identity which issued it
+    assert!(signed.verify(&identities, &other).await.is_err());
+
+    // A bundle signed by another identity claiming to be the authority is rejected
+    let forged =
+        SignedPolicyBundle::sign(&identities, &other.identifier(), &bundle(&["echo"])).await?;
+    assert!(forged.verify(&identities, &authority).await.is_err());
+
+    ctx.stop().await
+}
+
+/// Start a policy service for an authority behind a secure channel listener
+async fn policy_service(
+    ctx: &Context,
+    secure_channels: &Arc<SecureChannels>,
+    authority: &Identity,
+) -> Result<(Address, Address)> {
+    let listener = Address::random_local();
+    let service = Address::random_local();
+
+    let options = SecureChannelListenerOptions::new();
+    let sc_flow_control_id = options.spawner_flow_control_id();
+    secure_channels
+        .create_secure_channel_listener(ctx, &authority.identifier(), listener.clone(), options)
+        .await?;
+    ctx.flow_controls()
+        .add_consumer(service.clone(), &sc_flow_control_id);
+
+    let identities = secure_channels.identities();
+    let policies = PolicyService::new(
+        identities.clone(),
+        authority.identifier(),
+        identities.repository().as_attributes_reader(),
+        InMemoryStorage::create(),
+    );
+    ctx.start_worker(service.clone(), policies).await?;
+
+    Ok((listener, service))
+}
+
+#[ockam_macros::test]
+async fn only_enrollers_can_publish_policies(ctx: &mut Context) -> Result<()> {
+    let secure_channels = SecureChannels::builder().build();
+    let identities = secure_channels.identities();
+    let authority = identities.identities_creation().create_identity().await?;
+    let enroller = identities.identities_creation().create_identity().await?;
+    let member = identities.identities_creation().create_identity().await?;
+    identities
+        .repository()
+        .as_attributes_writer()
+        .put_attribute_value(&enroller.identifier(), "ockam-role", "enroller")
+        .await?;
+
+    let (listener, service) = policy_service(ctx, &secure_channels, &authority).await?;
+
+    let publish = |id: Identity| {
+        let secure_channels = secure_channels.clone();
+        let listener = listener.clone();
+        let service = service.clone();
+        let ctx = &*ctx;
+        async move {
+            let channel = secure_channels
+                .create_secure_channel(ctx, &id.identifier(), listener, SecureChannelOptions::new())
+                .await?;
+            let client = RpcClient::new(route![channel, service], ctx).await?;
+            PolicyServiceClient::new(client)
+                .publish(&bundle(&["echo"]))
+                .await
+        }
+    };
+
+    assert!(publish(member).await.is_err());
+    assert_eq!(1, publish(enroller.clone()).await?);
+    assert_eq!(2, publish(enroller).await?);
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn remote_policy_source_fetches_newer_bundles(ctx: &mut Context) -> Result<()> {
+    let secure_channels = SecureChannels::builder().build();
+    let identities = secure_channels.identities();
+    let authority = identities.identities_creation().create_identity().await?;
+    let enroller = identities.identities_creation().create_identity().await?;
+    let node = identities.identities_creation().create_identity().await?;
+    identities
+        .repository()
+        .as_attributes_writer()
+        .put_attribute_value(&enroller.identifier(), "ockam-role", "enroller")
+        .await?;
+
+    let (listener, service) = policy_service(ctx, &secure_channels, &authority).await?;
+
+    let source = RemotePolicySource::new(
+        ctx,
+        secure_channels.clone(),
+        node.identifier(),
+        authority.clone(),
+        route![listener.clone()],
+        service.clone(),
+    )
+    .await?;
+
+    // Nothing is published yet
+    assert!(source.fetch(None).await?.is_none());
+    let channels = secure_channels
+        .secure_channel_registry()
+        .get_channel_list()
+        .len();
+
+    let channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &enroller.identifier(),
+            listener,
+            SecureChannelOptions::new(),
+        )
+        .await?;
+    let client = PolicyServiceClient::new(RpcClient::new(route![channel, service], ctx).await?);
+    client.publish(&bundle(&["echo", "tcp-outlet"])).await?;
+    let channels = channels + 2;
+
+    let storage = SyncedPolicyStorage::new(Arc::new(Memory::new())).await?;
+    assert!(storage.sync(&source).await?);
+    assert_eq!(Some(1), storage.version());
+    let policy = storage
+        .get_policy(&Resource::new("echo"), &Action::new("handle_message"))
+        .await?;
+    assert!(policy.is_some());
+
+    // The bundle is only returned when it is newer than the given version
+    assert!(source.fetch(Some(1)).await?.is_none());
+    assert!(!storage.sync(&source).await?);
+
+    // The secure channel of the first fetch is reused
+    assert_eq!(
+        channels,
+        secure_channels
+            .secure_channel_registry()
+            .get_channel_list()
+            .len()
+    );
+
+    ctx.stop().await
+}
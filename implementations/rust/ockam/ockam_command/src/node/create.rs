@@ -12,11 +12,14 @@ use tokio::try_join;
 
 use ockam::{Address, AsyncTryClone, TcpListenerOptions};
 use ockam::{Context, TcpTransport};
+use ockam_abac::Combine;
 use ockam_api::cli_state::traits::{StateDirTrait, StateItemTrait};
 use ockam_api::cli_state::{add_project_info_to_node_state, init_node_state};
+use ockam_api::metrics::MetricsServer;
 use ockam_api::nodes::authority_node;
 use ockam_api::nodes::models::transport::CreateTransportJson;
 use ockam_api::nodes::service::NodeManagerTrustOptions;
+use ockam_api::policies::POLICY_SYNC_INTERVAL;
 use ockam_api::{
     bootstrapped_identities_store::PreTrustedIdentities,
     nodes::models::transport::{TransportMode, TransportType},
@@ -33,6 +36,7 @@ use crate::secure_channel::listener::create as secure_channel_listener;
 use crate::service::config::Config;
 use crate::terminal::OckamColor;
 use crate::util::api::TrustContextOpts;
+use crate::util::parsers::policy_combining_parser;
 use crate::util::{api, parse_node_name, Rpc};
 use crate::util::{embedded_node_that_is_not_stopped, exitcode};
 use crate::util::{local_cmd, node_rpc};
@@ -44,6 +48,10 @@ use super::show::is_node_up;
 const LONG_ABOUT: &str = include_str!("./static/create/long_about.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/create/after_long_help.txt");
 
+/// Time given to a node to finish its work in progress
+/// when it receives a signal to stop
+pub const NODE_DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Create a new node
 #[derive(Clone, Debug, Args)]
 #[command(
@@ -104,6 +112,20 @@ pub struct CreateCommand {
     #[arg(long = "credential", value_name = "CREDENTIAL_NAME")]
     pub credential: Option<String>,
 
+    /// Keep the node policies in sync with the policy service of the trust context authority
+    #[arg(long)]
+    pub sync_policies: bool,
+
+    /// How the policies of a resource and of its parents are combined:
+    /// `most-specific` (default), `deny-overrides` or `permit-overrides`
+    #[arg(long, value_name = "RULE", value_parser = policy_combining_parser)]
+    pub policy_combining: Option<Combine>,
+
+    /// Serve the runtime metrics of the node in the OpenMetrics format
+    /// on http://<METRICS_ADDR>/metrics
+    #[arg(long, value_name = "METRICS_ADDR")]
+    pub metrics_addr: Option<String>,
+
     #[command(flatten)]
     pub trust_context_opts: TrustContextOpts,
 }
@@ -124,6 +146,9 @@ impl Default for CreateCommand {
             reload_from_trusted_identities_file: None,
             authority_identity: None,
             credential: None,
+            sync_policies: false,
+            policy_combining: None,
+            metrics_addr: None,
             trust_context_opts: TrustContextOpts::default(),
         }
     }
@@ -297,6 +322,9 @@ async fn run_foreground_node(
             .config()
             .setup_mut()
             .set_verbose(opts.global_args.verbose)
+            .set_policy_combining(cmd.policy_combining.map(|c| c.to_string()))
+            .set_sync_policies(cmd.sync_policies)
+            .set_metrics_addr(cmd.metrics_addr.clone())
             .set_api_transport(
                 CreateTransportJson::new(
                     TransportType::Tcp,
@@ -309,14 +337,22 @@ async fn run_foreground_node(
 
     let pre_trusted_identities = load_pre_trusted_identities(&cmd)?;
 
+    let mut general_options = NodeManagerGeneralOptions::new(
+        opts.state.clone(),
+        cmd.node_name.clone(),
+        cmd.launch_config.is_some(),
+        pre_trusted_identities,
+    );
+    if cmd.sync_policies {
+        general_options = general_options.with_policy_sync(POLICY_SYNC_INTERVAL);
+    }
+    if let Some(combine) = cmd.policy_combining {
+        general_options = general_options.with_policy_combining(combine);
+    }
+
     let node_man = NodeManager::create(
         &ctx,
-        NodeManagerGeneralOptions::new(
-            opts.state.clone(),
-            cmd.node_name.clone(),
-            cmd.launch_config.is_some(),
-            pre_trusted_identities,
-        ),
+        general_options,
         NodeManagerTransportOptions::new(
             listener.flow_control_id().clone(),
             tcp.async_try_clone().await.into_diagnostic()?,
@@ -333,6 +369,14 @@ async fn run_foreground_node(
         .await
         .into_diagnostic()?;
 
+    // The metrics are served until the node is stopped
+    let _metrics_server = match &cmd.metrics_addr {
+        Some(address) => {
+            Some(MetricsServer::start(ctx.metrics().clone(), address).into_diagnostic()?)
+        }
+        None => None,
+    };
+
     if let Some(config) = &cmd.launch_config {
         if start_services(&ctx, config).await.is_err() {
             //TODO: Process should terminate on any error during its setup phase,
@@ -360,6 +404,16 @@ async fn run_foreground_node(
     )
     .await?;
 
+    // Refuse new connections and notify the peers, then let the open
+    // portal connections finish before stopping the node
+    let pending = ctx.drain(NODE_DRAIN_TIMEOUT).await.into_diagnostic()?;
+    if !pending.is_empty() {
+        opts.terminal.write_line(&fmt_log!(
+            "Node drain timed out, still busy: {}",
+            pending.join(", ")
+        ))?;
+    }
+
     // Try to stop node; it might have already been stopped or deleted (e.g. when running `node delete --all`)
     if let Ok(state) = opts.state.nodes.get(&node_name) {
         let _ = state.kill_process(false);
@@ -491,6 +545,9 @@ pub async fn spawn_background_node(
         cmd.credential.as_ref(),
         trust_context_path.as_ref(),
         cmd.trust_context_opts.project.as_ref(),
+        cmd.sync_policies,
+        cmd.policy_combining,
+        cmd.metrics_addr.as_ref(),
         cmd.logging_to_file(),
     )?;
 
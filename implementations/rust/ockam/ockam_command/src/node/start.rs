@@ -1,6 +1,10 @@
+use std::str::FromStr;
+
 use clap::Args;
 use colorful::Colorful;
+use miette::IntoDiagnostic;
 
+use ockam_abac::Combine;
 use ockam_api::cli_state::{StateDirTrait, StateItemTrait};
 
 use crate::node::show::print_query_status;
@@ -57,6 +61,12 @@ async fn run_impl(
     node_state.kill_process(false)?;
     let node_setup = node_state.config().setup();
     opts.global_args.verbose = node_setup.verbose;
+    let policy_combining = node_setup
+        .policy_combining
+        .as_deref()
+        .map(Combine::from_str)
+        .transpose()
+        .into_diagnostic()?;
 
     // Restart node
     spawn_node(
@@ -72,6 +82,9 @@ async fn run_impl(
         None,                                          // Credential
         None,                                          // Trust Context
         None,                                          // Project Name
+        node_setup.sync_policies.unwrap_or(false),     // Policy sync
+        policy_combining,                              // Policy combining rule
+        node_setup.metrics_addr.as_ref(),              // Metrics endpoint
         true,                                          // Restarted nodes will log to files
     )?;
 
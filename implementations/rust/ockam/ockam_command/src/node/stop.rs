@@ -1,9 +1,10 @@
-use crate::node::get_node_name;
+use crate::node::{get_node_name, NODE_DRAIN_TIMEOUT};
 use crate::util::local_cmd;
 use crate::{docs, fmt_ok, CommandGlobalOpts};
 use clap::Args;
 use colorful::Colorful;
 use ockam_api::cli_state::StateDirTrait;
+use std::time::Duration;
 
 const LONG_ABOUT: &str = include_str!("./static/stop/long_about.txt");
 const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
@@ -23,6 +24,11 @@ pub struct StopCommand {
     /// Whether to use the SIGTERM or SIGKILL signal to stop the node
     #[arg(short, long)]
     force: bool,
+    /// Wait for the node to be drained before returning: it stops accepting new
+    /// connections and secure channels, deregisters its relays, and lets its open
+    /// portal connections finish
+    #[arg(long, conflicts_with = "force")]
+    drain: bool,
 }
 
 impl StopCommand {
@@ -34,7 +40,12 @@ impl StopCommand {
 fn run_impl(opts: CommandGlobalOpts, cmd: StopCommand) -> miette::Result<()> {
     let node_name = get_node_name(&opts.state, &cmd.node_name);
     let node_state = opts.state.nodes.get(&node_name)?;
-    node_state.kill_process(cmd.force)?;
+    if cmd.drain {
+        // Leave a few seconds to the node to stop once drained
+        node_state.drain_process(NODE_DRAIN_TIMEOUT + Duration::from_secs(5))?;
+    } else {
+        node_state.kill_process(cmd.force)?;
+    }
     opts.terminal
         .stdout()
         .plain(fmt_ok!("Stopped node '{}'", &node_name))
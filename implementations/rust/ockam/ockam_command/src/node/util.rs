@@ -2,6 +2,7 @@ use miette::Context as _;
 use miette::{miette, IntoDiagnostic};
 
 use ockam::{Context, TcpListenerOptions, TcpTransport};
+use ockam_abac::Combine;
 
 use ockam_api::cli_state::{
     add_project_info_to_node_state, init_node_state, CliState, StateDirTrait,
@@ -143,6 +144,9 @@ pub fn spawn_node(
     credential: Option<&String>,
     trust_context: Option<&PathBuf>,
     project_name: Option<&String>,
+    sync_policies: bool,
+    policy_combining: Option<Combine>,
+    metrics_addr: Option<&String>,
     logging_to_file: bool,
 ) -> miette::Result<()> {
     let mut args = vec![
@@ -219,6 +223,20 @@ pub fn spawn_node(
         args.push(project_name.to_string());
     }
 
+    if sync_policies {
+        args.push("--sync-policies".to_string());
+    }
+
+    if let Some(combine) = policy_combining {
+        args.push("--policy-combining".to_string());
+        args.push(combine.to_string());
+    }
+
+    if let Some(metrics_addr) = metrics_addr {
+        args.push("--metrics-addr".to_string());
+        args.push(metrics_addr.to_string());
+    }
+
     args.push(name.to_owned());
 
     run_ockam(opts, name, args, logging_to_file)
@@ -7,6 +7,7 @@ use ockam_core::api::Request;
 
 use crate::node::get_node_name;
 use crate::policy::policy_path;
+use crate::util::parsers::policy_expression_parser;
 use crate::util::{node_rpc, parse_node_name, Rpc};
 use crate::CommandGlobalOpts;
 
@@ -21,7 +22,7 @@ pub struct CreateCommand {
     #[arg(short, long, default_value = "handle_message")]
     action: Action,
 
-    #[arg(short, long)]
+    #[arg(short, long, value_parser = policy_expression_parser)]
     expression: Expr,
 }
 
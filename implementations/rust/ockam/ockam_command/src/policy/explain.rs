@@ -0,0 +1,100 @@
+use std::fmt::Write;
+
+use clap::Args;
+use colorful::Colorful;
+
+use ockam::identity::IdentityIdentifier;
+use ockam::Context;
+use ockam_abac::{Action, Decision, Resource};
+use ockam_api::nodes::models::policy::ExplainRequest;
+use ockam_core::api::Request;
+
+use crate::node::get_node_name;
+use crate::policy::policy_path;
+use crate::terminal::OckamColor;
+use crate::util::{node_rpc, parse_node_name, Rpc};
+use crate::CommandGlobalOpts;
+
+/// Evaluate a policy for an identity without sending any traffic
+#[derive(Clone, Debug, Args)]
+pub struct ExplainCommand {
+    #[arg(long, display_order = 900, id = "NODE_NAME")]
+    at: Option<String>,
+
+    #[arg(short, long)]
+    resource: Resource,
+
+    #[arg(short, long, default_value = "handle_message")]
+    action: Action,
+
+    /// The identifier of the identity to evaluate the policy for
+    #[arg(short, long)]
+    identity: IdentityIdentifier,
+}
+
+impl ExplainCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self));
+    }
+}
+
+async fn rpc(
+    mut ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, ExplainCommand),
+) -> miette::Result<()> {
+    run_impl(&mut ctx, opts, cmd).await
+}
+
+async fn run_impl(
+    ctx: &mut Context,
+    opts: CommandGlobalOpts,
+    cmd: ExplainCommand,
+) -> miette::Result<()> {
+    let at = get_node_name(&opts.state, &cmd.at);
+    let node_name = parse_node_name(&at)?;
+    let path = format!("{}/explain", policy_path(&cmd.resource, &cmd.action));
+    let req = Request::get(path).body(ExplainRequest::new(cmd.identity));
+    let mut rpc = Rpc::background(ctx, &opts, &node_name).await?;
+    let decision: Decision = rpc.ask(req).await?;
+    opts.terminal
+        .stdout()
+        .plain(explanation(&decision)?)
+        .write_line()?;
+    Ok(())
+}
+
+fn explanation(d: &Decision) -> crate::Result<String> {
+    let mut output = String::new();
+    let verdict = if d.verdict() {
+        format!("Access {}", "allowed".color(OckamColor::Success.color()))
+    } else {
+        format!("Access {}", "denied".color(OckamColor::Failure.color()))
+    };
+    writeln!(output, "{verdict}")?;
+    if let Some(reason) = d.reason() {
+        writeln!(output, "Reason: {reason}")?;
+    }
+    match d.expression() {
+        Some(e) => writeln!(
+            output,
+            "Policy: {}",
+            e.to_string().color(OckamColor::PrimaryResource.color())
+        )?,
+        None => writeln!(output, "Policy: none")?,
+    }
+    writeln!(output, "Environment:")?;
+    for (k, v) in d.environment().entries() {
+        writeln!(output, "  {k} = {v}")?;
+    }
+    if !d.steps().is_empty() {
+        writeln!(output, "Evaluation:")?;
+        for step in d.steps() {
+            match (step.value(), step.error()) {
+                (Some(v), _) => writeln!(output, "  {} => {v}", step.expr())?,
+                (None, Some(e)) => writeln!(output, "  {} => error: {e}", step.expr())?,
+                (None, None) => writeln!(output, "  {}", step.expr())?,
+            }
+        }
+    }
+    Ok(output)
+}
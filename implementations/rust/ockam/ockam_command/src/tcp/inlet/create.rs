@@ -14,8 +14,7 @@ use ockam::identity::IdentityIdentifier;
 use ockam::Context;
 use ockam_abac::Resource;
 use ockam_api::cli_state::{StateDirTrait, StateItemTrait};
-use ockam_api::nodes::models::portal::CreateInlet;
-use ockam_api::nodes::models::portal::InletStatus;
+use ockam_api::nodes::models::portal::{CreateInlet, InletStatus, PortalLimits};
 use ockam_core::api::{Reply, Request, Status};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{route, Error};
@@ -27,7 +26,7 @@ use crate::policy::{add_default_project_policy, has_policy};
 use crate::tcp::util::alias_parser;
 use crate::terminal::OckamColor;
 use crate::util::duration::duration_parser;
-use crate::util::parsers::socket_addr_parser;
+use crate::util::parsers::{bandwidth_parser, socket_addr_parser};
 use crate::util::{
     find_available_port, node_rpc, parse_node_name, port_is_free_guard, process_nodes_multiaddr,
     Rpc,
@@ -67,6 +66,24 @@ pub struct CreateCommand {
     /// Time to wait before retrying to connect to outlet.
     #[arg(long, display_order = 900, id = "RETRY", default_value = "20s", value_parser = duration_parser)]
     retry_wait: Duration,
+
+    /// Try to reach the outlet over a UDP path hole punched through the rendezvous service
+    /// of the project at the given `host:port`, and use the relay path if that fails.
+    /// The outlet must be created with --direct.
+    #[arg(long, display_order = 900, id = "RENDEZVOUS_ADDRESS")]
+    direct: Option<String>,
+
+    /// Bandwidth limit of all the connections of the inlet together, for example 10MB/s.
+    #[arg(long, display_order = 900, id = "RATE_LIMIT", value_parser = bandwidth_parser)]
+    rate_limit: Option<u64>,
+
+    /// Bandwidth limit of each connection of the inlet, for example 1MB/s.
+    #[arg(long, display_order = 900, id = "CONNECTION_RATE_LIMIT", value_parser = bandwidth_parser)]
+    connection_rate_limit: Option<u64>,
+
+    /// Maximum number of concurrent connections of the inlet.
+    #[arg(long, display_order = 900, id = "MAX_CONNECTIONS")]
+    max_connections: Option<u32>,
 }
 
 pub(crate) fn default_from_addr() -> SocketAddr {
@@ -155,6 +172,15 @@ async fn rpc(
                     payload.set_alias(a)
                 }
                 payload.set_wait_ms(cmd.connection_wait.as_millis() as u64);
+                if let Some(rendezvous_addr) = cmd.direct.as_ref() {
+                    payload.set_direct(rendezvous_addr.clone())
+                }
+                payload.set_limits(
+                    PortalLimits::new()
+                        .with_rate_limit(cmd.rate_limit)
+                        .with_connection_rate_limit(cmd.connection_rate_limit)
+                        .with_max_connections(cmd.max_connections),
+                );
 
                 Request::post("/node/inlet").body(payload)
             };
@@ -220,6 +246,10 @@ async fn rpc(
     let (inlet, _) = try_join!(create_inlet, progress_output)?;
 
     let machine_output = inlet.bind_addr.to_string();
+    let path = inlet
+        .path
+        .map(|path| format!(" ({} path)", path))
+        .unwrap_or_default();
 
     let json_output = serde_json::to_string_pretty(&inlet).into_diagnostic()?;
 
@@ -233,10 +263,11 @@ async fn rpc(
                     .color(OckamColor::PrimaryResource.color()),
                 &node.to_string().color(OckamColor::PrimaryResource.color())
             ) + &fmt_log!(
-                "to the outlet at {}",
+                "to the outlet at {}{}",
                 &cmd.to
                     .to_string()
-                    .color(OckamColor::PrimaryResource.color())
+                    .color(OckamColor::PrimaryResource.color()),
+                path
             ),
         )
         .machine(machine_output)
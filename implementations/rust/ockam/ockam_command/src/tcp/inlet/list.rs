@@ -6,13 +6,14 @@ use tokio::try_join;
 
 use ockam_api::address::extract_address_value;
 use ockam_api::cli_state::StateDirTrait;
-use ockam_api::nodes::models::portal::InletList;
+use ockam_api::nodes::models::portal::{InletEvents, InletList};
 use ockam_core::api::Request;
+use ockam_core::errcode::Kind;
 
 use crate::node::{get_node_name, initialize_node_if_default, NodeOpts};
 use crate::terminal::OckamColor;
 use crate::util::{node_rpc, Rpc};
-use crate::{docs, CommandGlobalOpts};
+use crate::{docs, fmt_warn, CommandGlobalOpts};
 
 const PREVIEW_TAG: &str = include_str!("../../static/preview_tag.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/list/after_long_help.txt");
@@ -25,6 +26,10 @@ after_long_help = docs::after_help(AFTER_LONG_HELP))]
 pub struct ListCommand {
     #[command(flatten)]
     node: NodeOpts,
+
+    /// Keep running and print the inlets created or deleted after the listing
+    #[arg(long)]
+    watch: bool,
 }
 
 impl ListCommand {
@@ -74,10 +79,44 @@ async fn run_impl(
     )?;
     let json = serde_json::to_string_pretty(&inlets.list).into_diagnostic()?;
     opts.terminal
+        .clone()
         .stdout()
         .plain(plain)
         .json(json)
         .write_line()?;
 
+    if cmd.watch {
+        let mut events = rpc.stream::<InletEvents>(&[], ()).await?;
+        loop {
+            let event = match events.next().await {
+                Ok(Some(event)) => event,
+                Ok(None) => break,
+                // The node dropped some events, the listing is no longer accurate
+                Err(e) if e.code().kind == Kind::ResourceExhausted => {
+                    opts.terminal
+                        .clone()
+                        .stdout()
+                        .plain(fmt_warn!(
+                            "Some inlet events were missed, run the command again to list the inlets"
+                        ))
+                        .write_line()?;
+                    continue;
+                }
+                Err(e) => return Err(e).into_diagnostic(),
+            };
+            let plain = format!(
+                "{:?} inlet {} at {} to {}",
+                event.kind, event.inlet.alias, event.inlet.bind_addr, event.inlet.outlet_route
+            );
+            let json = serde_json::to_string(&event).into_diagnostic()?;
+            opts.terminal
+                .clone()
+                .stdout()
+                .plain(plain)
+                .json(json)
+                .write_line()?;
+        }
+    }
+
     Ok(())
 }
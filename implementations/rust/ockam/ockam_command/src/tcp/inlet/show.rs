@@ -53,13 +53,18 @@ pub async fn run_impl(
         alias,
         bind_addr,
         outlet_route,
+        path,
         ..
     } = inlet_status;
+    let path = path
+        .map(|p| p.to_string())
+        .unwrap_or_else(|| "relay".into());
     let plain = formatdoc! {r#"
         Inlet:
           Alias: {alias}
           TCP Address: {bind_addr}
           To Outlet Address: {outlet_route}
+          Path: {path}
     "#};
     let machine = bind_addr;
     opts.terminal
@@ -10,14 +10,14 @@ use ockam::Context;
 use ockam_abac::Resource;
 use ockam_api::address::extract_address_value;
 use ockam_api::cli_state::{StateDirTrait, StateItemTrait};
-use ockam_api::nodes::models::portal::{CreateOutlet, OutletStatus};
+use ockam_api::nodes::models::portal::{CreateOutlet, OutletStatus, PortalLimits};
 use ockam_core::api::Request;
 
 use crate::node::{get_node_name, initialize_node_if_default};
 use crate::policy::{add_default_project_policy, has_policy};
 use crate::tcp::util::alias_parser;
 use crate::terminal::OckamColor;
-use crate::util::parsers::socket_addr_parser;
+use crate::util::parsers::{bandwidth_parser, socket_addr_parser};
 use crate::util::{node_rpc, Rpc};
 use crate::{display_parse_logs, fmt_log};
 use crate::{docs, fmt_ok, CommandGlobalOpts};
@@ -43,6 +43,23 @@ pub struct CreateCommand {
     /// Assign a name to this outlet.
     #[arg(long, display_order = 900, id = "ALIAS", value_parser = alias_parser)]
     alias: Option<String>,
+
+    /// Bandwidth limit of all the connections of the outlet together, for example 10MB/s.
+    #[arg(long, display_order = 903, id = "RATE_LIMIT", value_parser = bandwidth_parser)]
+    rate_limit: Option<u64>,
+
+    /// Bandwidth limit of each connection of the outlet, for example 1MB/s.
+    #[arg(long, display_order = 903, id = "CONNECTION_RATE_LIMIT", value_parser = bandwidth_parser)]
+    connection_rate_limit: Option<u64>,
+
+    /// Maximum number of concurrent connections of the outlet.
+    #[arg(long, display_order = 903, id = "MAX_CONNECTIONS")]
+    max_connections: Option<u32>,
+
+    /// Let inlets created with --direct reach the outlet over a hole punched UDP path.
+    /// Holes are only punched through the rendezvous service of the project of the node.
+    #[arg(long, display_order = 903)]
+    direct: bool,
 }
 
 impl CreateCommand {
@@ -93,7 +110,14 @@ pub async fn run_impl(
             extract_address_value(&cmd.from)?.into(),
             cmd.alias,
             true,
-        );
+        )
+        .with_limits(
+            PortalLimits::new()
+                .with_rate_limit(cmd.rate_limit)
+                .with_connection_rate_limit(cmd.connection_rate_limit)
+                .with_max_connections(cmd.max_connections),
+        )
+        .with_direct(cmd.direct);
         let res = send_request(&ctx, &opts, payload, node_name.clone()).await;
         *is_finished.lock().await = true;
         res
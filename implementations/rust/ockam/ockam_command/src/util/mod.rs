@@ -18,7 +18,7 @@ use ockam::{
 use ockam_api::cli_state::{CliState, StateDirTrait, StateItemTrait};
 use ockam_api::config::lookup::{InternetAddress, LookupMeta};
 use ockam_api::nodes::NODEMANAGER_ADDR;
-use ockam_core::api::{Reply, RequestBuilder, Response, Status};
+use ockam_core::api::{Reply, RequestBuilder, Response, Status, StreamEndpoint};
 use ockam_core::AsyncTryClone;
 use ockam_core::DenyAll;
 use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Project, Space, Tcp};
@@ -26,6 +26,8 @@ use ockam_multiaddr::{
     proto::{self, Node},
     MultiAddr, Protocol,
 };
+use ockam_node::api::ResponseStream;
+use ockam_node::RpcClient;
 
 use crate::node::util::{start_embedded_node, start_embedded_node_with_vault_and_identity};
 use crate::util::api::TrustContextOpts;
@@ -206,6 +208,19 @@ impl Rpc {
         self.parse_response_reply::<R>()
     }
 
+    /// Send a request to a streaming endpoint and return the stream of the response chunks
+    /// The chunks are awaited without a timeout, as subscriptions can wait for events
+    pub async fn stream<E: StreamEndpoint>(
+        &self,
+        params: &[&str],
+        body: E::Request,
+    ) -> Result<ResponseStream<E::Response>> {
+        let route = self.route_impl().await?;
+        let client = RpcClient::new(route, &self.ctx).await?;
+        let stream = client.call_stream::<E>(params, body).await?;
+        Ok(stream.without_timeout())
+    }
+
     /// Make a request and wait for a response
     /// This method _does not_ check the success of the request
     async fn send_request<T>(&mut self, req: RequestBuilder<T>) -> Result<()>
@@ -1,5 +1,6 @@
 use crate::Result;
 use miette::miette;
+use ockam_abac::{parse_policy, Combine, Expr};
 use ockam_identity::IdentityIdentifier;
 use ockam_transport_tcp::resolve_peer;
 use std::net::SocketAddr;
@@ -28,6 +29,54 @@ pub(crate) fn identity_identifier_parser(input: &str) -> Result<IdentityIdentifi
         .map_err(|_| miette!("Invalid identity identifier: {input}").into())
 }
 
+/// Helper fn for parsing how the policies of a resource and of its parents are combined:
+/// `most-specific`, `deny-overrides` or `permit-overrides`
+pub(crate) fn policy_combining_parser(input: &str) -> Result<Combine> {
+    Combine::from_str(input).map_err(|e| miette!("{e}").into())
+}
+
+/// Helper fn for parsing and checking a policy expression from user input.
+/// Errors underline the offending part of the expression
+pub(crate) fn policy_expression_parser(input: &str) -> Result<Expr> {
+    parse_policy(input).map_err(|e| miette!("Invalid policy: {e}\n{}", e.annotate()).into())
+}
+
+/// Helper fn for parsing a bandwidth in bytes per second from user input.
+/// The amount of bytes can use the `KB`, `MB`, `GB` (powers of 1000) or
+/// `KiB`, `MiB`, `GiB` (powers of 1024) units, and can be followed by `/s`
+pub(crate) fn bandwidth_parser(input: &str) -> Result<u64> {
+    let amount = input.trim();
+    let amount = amount.strip_suffix("/s").unwrap_or(amount);
+    let unit_start = amount
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(amount.len());
+    let (number, unit) = amount.split_at(unit_start);
+
+    let number: u64 = number
+        .parse()
+        .map_err(|_| miette!("Invalid bandwidth: {input}"))?;
+    let multiplier: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "kb" => 1_000,
+        "mb" => 1_000_000,
+        "gb" => 1_000_000_000,
+        "kib" => 1 << 10,
+        "mib" => 1 << 20,
+        "gib" => 1 << 30,
+        _ => {
+            return Err(miette!(
+                "Invalid bandwidth unit in {input}, expected B, KB, MB, GB, KiB, MiB or GiB"
+            )
+            .into())
+        }
+    };
+
+    match number.checked_mul(multiplier) {
+        Some(bytes) if bytes > 0 => Ok(bytes),
+        _ => Err(miette!("Invalid bandwidth: {input}").into()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -93,4 +142,32 @@ mod tests {
         let invalid_input = "192,166,0.1:9999";
         assert!(socket_addr_parser(invalid_input).is_err());
     }
+
+    #[test]
+    fn test_bandwidth() {
+        assert_eq!(bandwidth_parser("500").unwrap(), 500);
+        assert_eq!(bandwidth_parser("500B/s").unwrap(), 500);
+        assert_eq!(bandwidth_parser("10MB/s").unwrap(), 10_000_000);
+        assert_eq!(bandwidth_parser("10mb").unwrap(), 10_000_000);
+        assert_eq!(bandwidth_parser("64KiB/s").unwrap(), 65_536);
+        assert_eq!(bandwidth_parser("1GiB/s").unwrap(), 1 << 30);
+    }
+
+    #[test]
+    fn test_invalid_bandwidth() {
+        assert!(bandwidth_parser("").is_err());
+        assert!(bandwidth_parser("0MB/s").is_err());
+        assert!(bandwidth_parser("MB/s").is_err());
+        assert!(bandwidth_parser("10XB/s").is_err());
+        assert!(bandwidth_parser("1.5MB/s").is_err());
+    }
+
+    #[test]
+    fn test_policy_expression() {
+        assert!(policy_expression_parser(r#"(= subject.component "edge")"#).is_ok());
+
+        let err = policy_expression_parser(r#"(=  subject.component  edge)"#).unwrap_err();
+        assert!(err.to_string().contains("unknown identifier: edge"));
+        assert!(err.to_string().ends_with("                       ^^^^"));
+    }
 }
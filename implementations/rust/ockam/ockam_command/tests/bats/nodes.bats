@@ -93,6 +93,15 @@ force_kill_node() {
   run_success "$OCKAM" node create "$n"
 }
 
+@test "node - can recreate a background node after it was drained" {
+  n="$(random_str)"
+  run_success "$OCKAM" node create "$n"
+  run_success "$OCKAM" node stop "$n" --drain
+  run_failure "$OCKAM" node show "$n"
+  # Recreate node
+  run_success "$OCKAM" node create "$n"
+}
+
 @test "node - can recreate a background node after it was killed" {
   # This test emulates the situation where a node is killed by the OS
   # on a restart or a shutdown. The node should be able to restart without errors.
@@ -162,3 +171,19 @@ force_kill_node() {
     fail "Log file should be empty"
   fi
 }
+
+@test "node - explain a policy" {
+  n="$(random_str)"
+  run_success "$OCKAM" node create $n
+  identifier=$($OCKAM identity show)
+
+  run_success "$OCKAM" policy explain --at $n --resource echo --identity $identifier
+  assert_output --partial "Reason: no policy found"
+  assert_output --partial "resource.id = \"echo\""
+
+  run_success "$OCKAM" policy create --at $n --resource echo --expression '(= subject.role "db")'
+  run_success "$OCKAM" policy explain --at $n --resource echo --identity $identifier
+  assert_output --partial "subject.identifier = \"$identifier\""
+  assert_output --partial "Evaluation:"
+  assert_output --partial "(= subject.role \"db\") => error"
+}
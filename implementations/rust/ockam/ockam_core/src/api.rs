@@ -23,6 +23,12 @@ use crate::compat::vec::Vec;
 use crate::errcode::{Kind, Origin};
 use crate::Result;
 
+mod endpoint;
+mod stream;
+
+pub use endpoint::*;
+pub use stream::*;
+
 pub const SCHEMA: &str = core::include_str!("schema.cddl");
 
 #[cfg(feature = "tag")]
@@ -125,6 +131,13 @@ pub struct Response {
     #[n(3)] status: Option<Status>,
     /// Indicator if a response body is expected after this header.
     #[n(4)] has_body: bool,
+    /// The sequence number of this response if it is a chunk of a streaming response.
+    #[n(5)] seq: Option<u32>,
+    /// Indicator if this response is the last chunk of a streaming response.
+    #[n(6)] end: Option<bool>,
+    /// The number of chunks of a streaming response which were dropped
+    /// before this marker, because the client did not grant enough credit.
+    #[n(7)] dropped: Option<u32>,
 }
 
 impl Response {
@@ -320,7 +333,7 @@ pub fn internal_error(r: &Request, msg: &str) -> ResponseBuilder<Error> {
 pub struct Id(#[n(0)] u32);
 
 /// Request methods.
-#[derive(Debug, Copy, Clone, Encode, Decode)]
+#[derive(Debug, Copy, Clone, Encode, Decode, PartialEq, Eq)]
 #[rustfmt::skip]
 #[cbor(index_only)]
 pub enum Method {
@@ -464,6 +477,9 @@ impl Response {
             re,
             status: Some(status),
             has_body,
+            seq: None,
+            end: None,
+            dropped: None,
         }
     }
 
@@ -502,6 +518,28 @@ impl Response {
         Response::builder(re, Status::InternalServerError)
     }
 
+    /// Create a chunk of a streaming response.
+    pub fn chunk(re: Id, seq: u32) -> ResponseBuilder {
+        let mut b = Response::ok(re);
+        b.header.seq = Some(seq);
+        b
+    }
+
+    /// Create the end-of-stream marker of a streaming response.
+    pub fn end_of_stream(re: Id, seq: u32) -> ResponseBuilder {
+        let mut b = Response::chunk(re, seq);
+        b.header.end = Some(true);
+        b
+    }
+
+    /// Create the marker telling the client of a streaming response that
+    /// some chunks were dropped, so that it can resynchronize.
+    pub fn overflow(re: Id, seq: u32, dropped: u32) -> ResponseBuilder {
+        let mut b = Response::chunk(re, seq);
+        b.header.dropped = Some(dropped);
+        b
+    }
+
     pub fn id(&self) -> Id {
         self.id
     }
@@ -517,6 +555,26 @@ impl Response {
     pub fn has_body(&self) -> bool {
         self.has_body
     }
+
+    /// The sequence number of this response if it is a chunk of a streaming response.
+    pub fn seq(&self) -> Option<u32> {
+        self.seq
+    }
+
+    /// Return true if this response is a chunk of a streaming response.
+    pub fn is_chunk(&self) -> bool {
+        self.seq.is_some()
+    }
+
+    /// Return true if this response is the last chunk of a streaming response.
+    pub fn is_end_of_stream(&self) -> bool {
+        self.end == Some(true)
+    }
+
+    /// The number of chunks dropped before this overflow marker, if it is one.
+    pub fn dropped(&self) -> Option<u32> {
+        self.dropped
+    }
 }
 
 /// An error type used in response bodies.
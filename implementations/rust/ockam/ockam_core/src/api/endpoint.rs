@@ -0,0 +1,264 @@
+use minicbor::{Decode, Decoder, Encode};
+
+use crate::api::{Error, Method, Request, RequestBuilder, Response, ResponseBuilder, Status};
+use crate::compat::string::{String, ToString};
+use crate::compat::vec::Vec;
+use crate::errcode::{Kind, Origin};
+use crate::{LocalInfo, Result};
+
+/// An endpoint of a service: the method and path of its requests,
+/// and the types of their bodies.
+///
+/// The path can contain parameters, written `:name`, for example
+/// `/members/:id`. Endpoints without a request or a response body use `()`.
+pub trait Endpoint: Send + Sync + 'static {
+    /// Method of the requests
+    const METHOD: Method;
+
+    /// Path of the requests, with its parameters
+    const PATH: &'static str;
+
+    /// Body of the requests
+    type Request: Encode<()> + for<'a> Decode<'a, ()> + Send + 'static;
+
+    /// Body of the successful responses
+    type Response: Encode<()> + for<'a> Decode<'a, ()> + Send + 'static;
+
+    /// Path of a request, with the given values of the path parameters, in order
+    fn path(params: &[&str]) -> Result<String> {
+        fill_path(Self::PATH, params)
+    }
+
+    /// Create a request to this endpoint
+    fn request(params: &[&str], body: Self::Request) -> Result<RequestBuilder<Self::Request>> {
+        Ok(Request::builder(Self::METHOD, Self::path(params)?).body(body))
+    }
+}
+
+/// Parameters of a request path, matched against the path of an endpoint
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathParams(Vec<(String, String)>);
+
+impl PathParams {
+    /// Match a request path against a path with parameters.
+    /// Return `None` if they don't match
+    pub fn matching(pattern: &str, path: &str) -> Option<Self> {
+        let pattern = segments(pattern);
+        let path = segments(path);
+        if pattern.len() != path.len() {
+            return None;
+        }
+
+        let mut params = Vec::new();
+        for (expected, actual) in pattern.into_iter().zip(path) {
+            match expected.strip_prefix(':') {
+                Some(name) => params.push((name.to_string(), actual.to_string())),
+                None if expected == actual => {}
+                None => return None,
+            }
+        }
+        Some(Self(params))
+    }
+
+    /// Value of a path parameter
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Replace the parameters of a path with the given values, in order
+pub fn fill_path(pattern: &str, params: &[&str]) -> Result<String> {
+    let mut params = params.iter();
+    let mut path = String::new();
+    for segment in segments(pattern) {
+        path.push('/');
+        if segment.starts_with(':') {
+            let value = params.next().ok_or_else(|| {
+                crate::Error::new(
+                    Origin::Api,
+                    Kind::Invalid,
+                    format!("missing value for the parameter {} of {}", segment, pattern),
+                )
+            })?;
+            path.push_str(value);
+        } else {
+            path.push_str(segment);
+        }
+    }
+
+    if params.next().is_some() {
+        return Err(crate::Error::new(
+            Origin::Api,
+            Kind::Invalid,
+            format!("too many parameter values for {}", pattern),
+        ));
+    }
+    Ok(path)
+}
+
+fn segments(path: &str) -> Vec<&str> {
+    path.trim_start_matches('/').split('/').collect()
+}
+
+/// Decode the body of a request or a response.
+///
+/// A missing body is decoded as `()`, so that endpoints without a body can
+/// be called by clients which don't send one.
+pub fn decode_body<T>(dec: &mut Decoder<'_>, has_body: bool) -> Result<T>
+where
+    T: for<'a> Decode<'a, ()>,
+{
+    if has_body {
+        Ok(dec.decode()?)
+    } else {
+        let unit = minicbor::to_vec(())
+            .map_err(|e| crate::Error::new(Origin::Api, Kind::Serialization, e.to_string()))?;
+        minicbor::decode(&unit).map_err(|_| {
+            crate::Error::new(
+                Origin::Api,
+                Kind::Serialization,
+                "expected a message body, got nothing",
+            )
+        })
+    }
+}
+
+/// Types which can be extracted from the [`LocalInfo`] of a request,
+/// like the identity of the sender of a request received on a secure channel
+pub trait FromLocalInfo: Sized {
+    /// Find and decode a value in a list of [`LocalInfo`], or return
+    /// the error sent back to the client when it is missing
+    fn from_local_info(local_info: &[LocalInfo]) -> Result<Self, RequestError>;
+}
+
+/// Failure of a request, sent back to the client as an error response
+#[derive(Debug, Clone)]
+pub struct RequestError {
+    status: Status,
+    message: String,
+}
+
+impl RequestError {
+    /// Create a request error
+    pub fn new(status: Status, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    /// The request is invalid
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(Status::BadRequest, message)
+    }
+
+    /// The client is not allowed to make the request
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(Status::Forbidden, message)
+    }
+
+    /// The resource of the request does not exist
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(Status::NotFound, message)
+    }
+
+    /// The request failed because of the server
+    pub fn internal_error(message: impl Into<String>) -> Self {
+        Self::new(Status::InternalServerError, message)
+    }
+
+    /// Status of the response
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    /// Message of the error
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Error response to the given request
+    pub fn to_response(&self, r: &Request) -> ResponseBuilder<Error> {
+        let mut e = Error::new(r.path()).with_message(&self.message);
+        if let Some(m) = r.method() {
+            e = e.with_method(m)
+        }
+        Response::builder(r.id(), self.status).body(e)
+    }
+}
+
+impl From<crate::Error> for RequestError {
+    fn from(e: crate::Error) -> Self {
+        Self::new(Status::from_kind(e.code().kind), e.to_string())
+    }
+}
+
+impl From<minicbor::decode::Error> for RequestError {
+    fn from(e: minicbor::decode::Error) -> Self {
+        Self::bad_request(e.to_string())
+    }
+}
+
+impl Status {
+    /// Status of the response to a request which failed with an error of the given kind
+    pub fn from_kind(kind: Kind) -> Self {
+        match kind {
+            Kind::Invalid | Kind::Serialization | Kind::Misuse => Status::BadRequest,
+            Kind::NotFound => Status::NotFound,
+            Kind::AlreadyExists | Kind::Conflict => Status::Conflict,
+            Kind::Unsupported => Status::NotImplemented,
+            _ => Status::InternalServerError,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct DeleteMember;
+
+    impl Endpoint for DeleteMember {
+        const METHOD: Method = Method::Delete;
+        const PATH: &'static str = "/members/:id";
+        type Request = ();
+        type Response = ();
+    }
+
+    #[test]
+    fn path_parameters_are_matched() {
+        let params = PathParams::matching("/members/:id", "/members/I1234").unwrap();
+        assert_eq!(params.get("id"), Some("I1234"));
+        assert_eq!(params.get("name"), None);
+
+        assert!(PathParams::matching("/members/:id", "/members").is_none());
+        assert!(PathParams::matching("/members/:id", "/tokens/I1234").is_none());
+        assert!(PathParams::matching("/", "").is_some());
+    }
+
+    #[test]
+    fn path_parameters_are_filled() {
+        assert_eq!(DeleteMember::path(&["I1234"]).unwrap(), "/members/I1234");
+        assert!(DeleteMember::path(&[]).is_err());
+        assert!(DeleteMember::path(&["I1234", "I5678"]).is_err());
+    }
+
+    #[test]
+    fn missing_body_is_decoded_as_unit() {
+        let mut dec = Decoder::new(&[]);
+        assert!(decode_body::<()>(&mut dec, false).is_ok());
+        assert!(decode_body::<String>(&mut dec, false).is_err());
+    }
+
+    #[test]
+    fn errors_are_mapped_to_statuses() {
+        let e = crate::Error::new(Origin::Api, Kind::NotFound, "no such member");
+        assert_eq!(RequestError::from(e).status(), Status::NotFound);
+
+        let e = crate::Error::new(Origin::Api, Kind::Io, "disk full");
+        assert_eq!(RequestError::from(e).status(), Status::InternalServerError);
+    }
+}
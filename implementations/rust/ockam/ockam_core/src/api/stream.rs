@@ -0,0 +1,68 @@
+use minicbor::{Decode, Encode};
+
+use crate::api::Endpoint;
+
+/// Number of chunks the sender of a streaming response can send before it
+/// waits for more credit from the client.
+pub const STREAM_WINDOW: u32 = 16;
+
+/// Message sent by a client to the sender of a streaming response,
+/// at the return route of the chunks it received.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+pub enum StreamControl {
+    /// Allow the sender to send this many more chunks.
+    #[n(0)] Credit(#[n(0)] u32),
+    /// Stop the stream.
+    #[n(1)] Cancel,
+}
+
+/// An endpoint answering a request with a streaming response.
+///
+/// Each chunk of the response has a body of type [`Endpoint::Response`],
+/// and the stream ends with an end-of-stream marker or an error response.
+pub trait StreamEndpoint: Endpoint {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{Id, Response};
+    use minicbor::Decoder;
+
+    #[test]
+    fn chunks_are_numbered_until_the_end_of_stream() {
+        let re = Id::fresh();
+        let chunk = Response::chunk(re, 3).body(42u32).to_vec().unwrap();
+        let mut dec = Decoder::new(&chunk);
+        let header: Response = dec.decode().unwrap();
+        assert_eq!(header.re(), re);
+        assert_eq!(header.seq(), Some(3));
+        assert!(header.is_chunk());
+        assert!(!header.is_end_of_stream());
+        assert_eq!(dec.decode::<u32>().unwrap(), 42);
+
+        let end = Response::end_of_stream(re, 4).to_vec().unwrap();
+        let header: Response = minicbor::decode(&end).unwrap();
+        assert!(header.is_end_of_stream());
+        assert!(!header.has_body());
+
+        let overflow = Response::overflow(re, 4, 2).to_vec().unwrap();
+        let header: Response = minicbor::decode(&overflow).unwrap();
+        assert!(header.is_chunk());
+        assert!(!header.is_end_of_stream());
+        assert_eq!(header.dropped(), Some(2));
+        assert!(!header.has_body());
+
+        let single = Response::ok(re).to_vec().unwrap();
+        let header: Response = minicbor::decode(&single).unwrap();
+        assert!(!header.is_chunk());
+    }
+
+    #[test]
+    fn stream_control_messages_are_decoded() {
+        for control in [StreamControl::Credit(STREAM_WINDOW), StreamControl::Cancel] {
+            let bytes = minicbor::to_vec(control).unwrap();
+            assert_eq!(minicbor::decode::<StreamControl>(&bytes).unwrap(), control);
+        }
+    }
+}
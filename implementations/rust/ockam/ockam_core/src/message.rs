@@ -4,7 +4,7 @@ use crate::{
         vec::Vec,
     },
     errcode::{Kind, Origin},
-    Address, Error, LocalMessage, Result, Route, TransportMessage,
+    Address, Error, LocalMessage, Priority, Result, Route, TraceContext, TransportMessage,
 };
 use core::{
     fmt::{self, Debug, Display, Formatter},
@@ -261,6 +261,18 @@ impl<M: Message> Routed<M> {
         &self.local_msg
     }
 
+    /// Return the tracing context of the underlying transport message, if any.
+    #[inline]
+    pub fn tracing_context(&self) -> Option<&TraceContext> {
+        self.local_msg.transport().tracing_context.as_ref()
+    }
+
+    /// Return the priority of the underlying local message.
+    #[inline]
+    pub fn priority(&self) -> Priority {
+        self.local_msg.priority()
+    }
+
     /// Return a reference to the underlying transport message's binary payload.
     #[inline]
     pub fn payload(&self) -> &[u8] {
@@ -8,3 +8,9 @@ pub use relay_message::*;
 
 mod transport_message;
 pub use transport_message::*;
+
+mod trace_context;
+pub use trace_context::*;
+
+mod priority;
+pub use priority::*;
@@ -1,4 +1,4 @@
-use crate::{compat::string::String, compat::vec::Vec, Message, TransportMessage};
+use crate::{compat::string::String, compat::vec::Vec, Message, Priority, TransportMessage};
 use serde::{Deserialize, Serialize};
 
 /// Contains metadata that will only be routed locally within the
@@ -61,6 +61,9 @@ impl LocalInfo {
 pub struct LocalMessage {
     transport_message: TransportMessage,
     local_info: Vec<LocalInfo>,
+    // Only used by the mailboxes of this node, and not part of the encoding
+    #[serde(skip)]
+    priority: Priority,
 }
 
 impl LocalMessage {
@@ -80,6 +83,14 @@ impl LocalMessage {
     pub fn local_info(&self) -> &[LocalInfo] {
         &self.local_info
     }
+    /// Return the priority of the message in the mailboxes of this node.
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+    /// Set the priority of the message in the mailboxes of this node.
+    pub fn set_priority(&mut self, priority: Priority) {
+        self.priority = priority
+    }
     /// Dissolve
     pub fn dissolve(self) -> (TransportMessage, Vec<LocalInfo>) {
         (self.transport_message, self.local_info)
@@ -112,6 +123,42 @@ impl LocalMessage {
         LocalMessage {
             transport_message,
             local_info,
+            priority: Priority::default(),
         }
     }
+
+    /// Set the priority of the message in the mailboxes of this node.
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{route, Decodable, Encodable};
+
+    /// The layout of the messages before the introduction of the priority
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct LocalMessageV1 {
+        transport_message: TransportMessage,
+        local_info: Vec<LocalInfo>,
+    }
+
+    #[test]
+    fn layout_is_unchanged_by_the_priority() {
+        let transport_message = TransportMessage::v1(route!["a"], route!["b"], vec![1, 2, 3]);
+        let local_info = vec![LocalInfo::new("test".into(), vec![4])];
+        let msg = LocalMessage::new(transport_message.clone(), local_info.clone())
+            .with_priority(Priority::High);
+        let old = LocalMessageV1 {
+            transport_message,
+            local_info,
+        };
+
+        assert_eq!(msg.encode().unwrap(), old.encode().unwrap());
+        let decoded = LocalMessage::decode(&old.encode().unwrap()).unwrap();
+        assert_eq!(decoded.priority(), Priority::Normal);
+    }
 }
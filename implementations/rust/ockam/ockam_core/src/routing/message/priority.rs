@@ -0,0 +1,44 @@
+use core::fmt::{self, Display, Formatter};
+use serde::{Deserialize, Serialize};
+
+/// Quality of service class of a message routed within a node.
+///
+/// Mailboxes deliver the messages of a higher priority before the
+/// messages of a lower priority, so that control traffic, like secure
+/// channel handshakes, heartbeats, or node API requests, doesn't wait
+/// behind bulk payloads.
+///
+/// Priorities only reorder the messages of different senders: a message is
+/// never delivered before an older message sent by the same worker, e.g. the
+/// encrypted messages of a secure channel, whose nonces must stay within the
+/// window accepted by the decryptor.
+///
+/// The priority is part of the [`LocalMessage`](crate::LocalMessage), and
+/// is not transmitted to other nodes.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Hash, Ord, PartialOrd, Eq, PartialEq)]
+pub enum Priority {
+    /// Bulk traffic, delivered after all the other messages
+    Low,
+    /// Default priority of the messages
+    Normal,
+    /// Control traffic, delivered before all the other messages.
+    /// Messages of this priority can use a small headroom beyond the
+    /// capacity of a full mailbox
+    High,
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+impl Display for Priority {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Priority::Low => f.write_str("low"),
+            Priority::Normal => f.write_str("normal"),
+            Priority::High => f.write_str("high"),
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use crate::compat::format;
+use crate::compat::rand::random;
+use crate::compat::string::String;
+use core::fmt::{self, Display, Formatter};
+use serde::{Deserialize, Serialize};
+
+/// Identifier of a trace, shared by all the spans of that trace.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Hash, Ord, PartialOrd, Eq, PartialEq)]
+pub struct TraceId([u8; 16]);
+
+/// Identifier of a span within a trace.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Hash, Ord, PartialOrd, Eq, PartialEq)]
+pub struct SpanId([u8; 8]);
+
+impl TraceId {
+    /// Generate a random, valid, trace id.
+    pub fn random() -> Self {
+        loop {
+            let id = Self(random());
+            if id.is_valid() {
+                return id;
+            }
+        }
+    }
+
+    /// Return the bytes of this trace id.
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+
+    /// An all-zero trace id is invalid.
+    pub fn is_valid(&self) -> bool {
+        self.0 != [0; 16]
+    }
+}
+
+impl SpanId {
+    /// Generate a random, valid, span id.
+    pub fn random() -> Self {
+        loop {
+            let id = Self(random());
+            if id.is_valid() {
+                return id;
+            }
+        }
+    }
+
+    /// Return the bytes of this span id.
+    pub fn as_bytes(&self) -> &[u8; 8] {
+        &self.0
+    }
+
+    /// An all-zero span id is invalid.
+    pub fn is_valid(&self) -> bool {
+        self.0 != [0; 8]
+    }
+}
+
+impl Display for TraceId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.0))
+    }
+}
+
+impl Display for SpanId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.0))
+    }
+}
+
+/// The context of a distributed trace, carried by a [`TransportMessage`](crate::TransportMessage).
+///
+/// It holds the same information as a W3C `traceparent` header: the
+/// trace the message belongs to, the span that sent the message, and the
+/// trace flags. Every worker handling a message carrying a tracing context
+/// creates a child span of the span that sent it, so that the hops of a
+/// message across routes, secure channels and nodes end up in the same trace.
+#[derive(Serialize, Deserialize, Debug, Clone, Hash, Ord, PartialOrd, Eq, PartialEq)]
+pub struct TraceContext {
+    trace_id: TraceId,
+    parent_id: SpanId,
+    flags: u8,
+}
+
+impl TraceContext {
+    /// Version of the `traceparent` format supported by this type.
+    const TRACEPARENT_VERSION: u8 = 0;
+    /// Flag set when the trace is sampled.
+    const SAMPLED: u8 = 0x01;
+
+    /// Start a new, sampled, trace.
+    pub fn new_root() -> Self {
+        Self {
+            trace_id: TraceId::random(),
+            parent_id: SpanId::random(),
+            flags: Self::SAMPLED,
+        }
+    }
+
+    /// Create the context of a new span, child of the span of this context.
+    pub fn new_span(&self) -> Self {
+        Self {
+            trace_id: self.trace_id,
+            parent_id: SpanId::random(),
+            flags: self.flags,
+        }
+    }
+
+    /// Identifier of the trace.
+    pub fn trace_id(&self) -> &TraceId {
+        &self.trace_id
+    }
+
+    /// Identifier of the span which created this context.
+    pub fn parent_id(&self) -> &SpanId {
+        &self.parent_id
+    }
+
+    /// Trace flags.
+    pub fn flags(&self) -> u8 {
+        self.flags
+    }
+
+    /// Return true if the trace is sampled.
+    pub fn is_sampled(&self) -> bool {
+        self.flags & Self::SAMPLED != 0
+    }
+
+    /// Format this context as a W3C `traceparent` header value.
+    pub fn to_traceparent(&self) -> String {
+        format!(
+            "{:02x}-{}-{}-{:02x}",
+            Self::TRACEPARENT_VERSION,
+            self.trace_id,
+            self.parent_id,
+            self.flags
+        )
+    }
+
+    /// Parse a W3C `traceparent` header value.
+    ///
+    /// Return `None` if the value is malformed, or if the trace id or the parent id is invalid.
+    pub fn from_traceparent(value: &str) -> Option<Self> {
+        let mut parts = value.trim().split('-');
+        let version = parts.next()?;
+        let trace_id = parts.next()?;
+        let parent_id = parts.next()?;
+        let flags = parts.next()?;
+
+        // Future versions may append fields, the version 00 has exactly 4 of them
+        if version.len() != 2 || version == "ff" || (version == "00" && parts.next().is_some()) {
+            return None;
+        }
+        u8::from_str_radix(version, 16).ok()?;
+
+        let mut trace_id_bytes = [0u8; 16];
+        hex::decode_to_slice(trace_id, &mut trace_id_bytes).ok()?;
+        let mut parent_id_bytes = [0u8; 8];
+        hex::decode_to_slice(parent_id, &mut parent_id_bytes).ok()?;
+        if flags.len() != 2 {
+            return None;
+        }
+        let flags = u8::from_str_radix(flags, 16).ok()?;
+
+        let trace_id = TraceId(trace_id_bytes);
+        let parent_id = SpanId(parent_id_bytes);
+        if !trace_id.is_valid() || !parent_id.is_valid() {
+            return None;
+        }
+
+        Some(Self {
+            trace_id,
+            parent_id,
+            flags,
+        })
+    }
+}
+
+impl Display for TraceContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_traceparent())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn traceparent_roundtrip() {
+        let context = TraceContext::new_root();
+        let traceparent = context.to_traceparent();
+        assert_eq!(traceparent.len(), 55);
+        assert_eq!(TraceContext::from_traceparent(&traceparent), Some(context));
+    }
+
+    #[test]
+    fn parse_w3c_example() {
+        let context = TraceContext::from_traceparent(
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        )
+        .unwrap();
+        assert_eq!(
+            context.trace_id().to_string(),
+            "4bf92f3577b34da6a3ce929d0e0e4736"
+        );
+        assert_eq!(context.parent_id().to_string(), "00f067aa0ba902b7");
+        assert!(context.is_sampled());
+    }
+
+    #[test]
+    fn reject_invalid_traceparent() {
+        for value in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e473-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-00",
+        ] {
+            assert!(TraceContext::from_traceparent(value).is_none(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn new_span_stays_in_the_trace() {
+        let context = TraceContext::new_root();
+        let span = context.new_span();
+        assert_eq!(span.trace_id(), context.trace_id());
+        assert_ne!(span.parent_id(), context.parent_id());
+        assert_eq!(span.flags(), context.flags());
+    }
+}
@@ -1,6 +1,8 @@
-use crate::{compat::vec::Vec, Message, Route};
+use crate::{compat::vec::Vec, Message, Route, TraceContext};
 use core::fmt::{self, Display, Formatter};
-use serde::{Deserialize, Serialize};
+use serde::de::{Error, MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// A generic transport message type.
 ///
@@ -15,7 +17,7 @@ use serde::{Deserialize, Serialize};
 ///
 /// See `ockam_transport_tcp::workers::sender::TcpSendWorker` for a usage example.
 ///
-#[derive(Serialize, Deserialize, Debug, Clone, Hash, Ord, PartialOrd, Eq, PartialEq, Message)]
+#[derive(Debug, Clone, Hash, Ord, PartialOrd, Eq, PartialEq, Message)]
 pub struct TransportMessage {
     /// The transport protocol version.
     pub version: u8,
@@ -28,9 +30,16 @@ pub struct TransportMessage {
     pub return_route: Route,
     /// The message payload.
     pub payload: Vec<u8>,
+    /// The context of the distributed trace this message belongs to.
+    ///
+    /// This field is only transmitted by messages of version 2 and above.
+    pub tracing_context: Option<TraceContext>,
 }
 
 impl TransportMessage {
+    /// The first transport protocol version carrying a tracing context.
+    pub const TRACING_CONTEXT_VERSION: u8 = 2;
+
     /// Create a new v1 transport message with empty return route.
     pub fn v1(
         onward_route: impl Into<Route>,
@@ -42,8 +51,26 @@ impl TransportMessage {
             onward_route: onward_route.into(),
             return_route: return_route.into(),
             payload,
+            tracing_context: None,
         }
     }
+
+    /// Attach a tracing context to this message.
+    ///
+    /// The message is upgraded to the version 2 of the protocol when it carries
+    /// a tracing context. Nodes which only support the version 1 can't decode it.
+    pub fn with_tracing_context(mut self, tracing_context: Option<TraceContext>) -> Self {
+        self.set_tracing_context(tracing_context);
+        self
+    }
+
+    /// Replace the tracing context of this message.
+    pub fn set_tracing_context(&mut self, tracing_context: Option<TraceContext>) {
+        if tracing_context.is_some() && self.version < Self::TRACING_CONTEXT_VERSION {
+            self.version = Self::TRACING_CONTEXT_VERSION;
+        }
+        self.tracing_context = tracing_context;
+    }
 }
 
 impl Display for TransportMessage {
@@ -55,3 +82,163 @@ impl Display for TransportMessage {
         )
     }
 }
+
+const FIELDS: &[&str] = &[
+    "version",
+    "onward_route",
+    "return_route",
+    "payload",
+    "tracing_context",
+];
+
+// The version 1 layout is kept unchanged, the tracing context is only
+// appended to the messages of a later version
+impl Serialize for TransportMessage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let has_tracing_context = self.version >= Self::TRACING_CONTEXT_VERSION;
+        let len = if has_tracing_context { 5 } else { 4 };
+
+        let mut state = serializer.serialize_struct("TransportMessage", len)?;
+        state.serialize_field("version", &self.version)?;
+        state.serialize_field("onward_route", &self.onward_route)?;
+        state.serialize_field("return_route", &self.return_route)?;
+        state.serialize_field("payload", &self.payload)?;
+        if has_tracing_context {
+            state.serialize_field("tracing_context", &self.tracing_context)?;
+        }
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for TransportMessage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(field_identifier, rename_all = "snake_case")]
+        enum Field {
+            Version,
+            OnwardRoute,
+            ReturnRoute,
+            Payload,
+            TracingContext,
+        }
+
+        struct TransportMessageVisitor;
+
+        impl<'de> Visitor<'de> for TransportMessageVisitor {
+            type Value = TransportMessage;
+
+            fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+                formatter.write_str("struct TransportMessage")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let version: u8 = seq
+                    .next_element()?
+                    .ok_or_else(|| Error::invalid_length(0, &self))?;
+                let onward_route = seq
+                    .next_element()?
+                    .ok_or_else(|| Error::invalid_length(1, &self))?;
+                let return_route = seq
+                    .next_element()?
+                    .ok_or_else(|| Error::invalid_length(2, &self))?;
+                let payload = seq
+                    .next_element()?
+                    .ok_or_else(|| Error::invalid_length(3, &self))?;
+                let tracing_context = if version >= TransportMessage::TRACING_CONTEXT_VERSION {
+                    seq.next_element()?
+                        .ok_or_else(|| Error::invalid_length(4, &self))?
+                } else {
+                    None
+                };
+
+                Ok(TransportMessage {
+                    version,
+                    onward_route,
+                    return_route,
+                    payload,
+                    tracing_context,
+                })
+            }
+
+            // Self-describing formats, e.g. JSON, name the fields
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut version = None;
+                let mut onward_route = None;
+                let mut return_route = None;
+                let mut payload = None;
+                let mut tracing_context = None;
+                while let Some(field) = map.next_key()? {
+                    match field {
+                        Field::Version => version = Some(map.next_value()?),
+                        Field::OnwardRoute => onward_route = Some(map.next_value()?),
+                        Field::ReturnRoute => return_route = Some(map.next_value()?),
+                        Field::Payload => payload = Some(map.next_value()?),
+                        Field::TracingContext => tracing_context = map.next_value()?,
+                    }
+                }
+
+                Ok(TransportMessage {
+                    version: version.ok_or_else(|| Error::missing_field("version"))?,
+                    onward_route: onward_route
+                        .ok_or_else(|| Error::missing_field("onward_route"))?,
+                    return_route: return_route
+                        .ok_or_else(|| Error::missing_field("return_route"))?,
+                    payload: payload.ok_or_else(|| Error::missing_field("payload"))?,
+                    tracing_context,
+                })
+            }
+        }
+
+        deserializer.deserialize_struct("TransportMessage", FIELDS, TransportMessageVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{route, Decodable, Encodable};
+
+    /// The layout of the messages before the introduction of the tracing context
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct TransportMessageV1 {
+        version: u8,
+        onward_route: Route,
+        return_route: Route,
+        payload: Vec<u8>,
+    }
+
+    #[test]
+    fn v1_layout_is_unchanged() {
+        let msg = TransportMessage::v1(route!["a", "b"], route!["c"], vec![1, 2, 3]);
+        let old = TransportMessageV1 {
+            version: 1,
+            onward_route: route!["a", "b"],
+            return_route: route!["c"],
+            payload: vec![1, 2, 3],
+        };
+
+        assert_eq!(msg.encode().unwrap(), old.encode().unwrap());
+        assert_eq!(
+            TransportMessage::decode(&old.encode().unwrap()).unwrap(),
+            msg
+        );
+    }
+
+    #[test]
+    fn tracing_context_roundtrip() {
+        let tracing_context = TraceContext::new_root();
+        let msg = TransportMessage::v1(route!["a"], route!["b"], vec![1, 2, 3])
+            .with_tracing_context(Some(tracing_context.clone()));
+        assert_eq!(msg.version, TransportMessage::TRACING_CONTEXT_VERSION);
+
+        let decoded = TransportMessage::decode(&msg.encode().unwrap()).unwrap();
+        assert_eq!(decoded.tracing_context, Some(tracing_context));
+        assert_eq!(decoded, msg);
+    }
+
+    #[test]
+    fn no_tracing_context_keeps_v1() {
+        let msg = TransportMessage::v1(route!["a"], route!["b"], vec![]).with_tracing_context(None);
+        assert_eq!(msg.version, 1);
+    }
+}
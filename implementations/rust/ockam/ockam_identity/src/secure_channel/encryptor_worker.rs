@@ -81,7 +81,9 @@ impl EncryptorWorker {
             onward_route,
             return_route,
             msg.into_transport_message().payload,
-        );
+        )
+        // Keep the trace of the message on the other side of the channel
+        .with_tracing_context(ctx.tracing_context().cloned());
 
         // Encrypt the message
         let encrypted_payload = self.encryptor.encrypt(&msg.encode()?).await?;
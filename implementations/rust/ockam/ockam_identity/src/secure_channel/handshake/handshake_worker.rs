@@ -21,14 +21,19 @@ use core::time::Duration;
 use ockam_core::compat::{boxed::Box, vec::Vec};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{
-    AllowAll, Any, Decodable, DenyAll, Error, Mailbox, Mailboxes, OutgoingAccessControl, Route,
-    Routed,
+    AllowAll, Any, Decodable, DenyAll, Error, Mailbox, Mailboxes, OutgoingAccessControl, Priority,
+    Route, Routed,
 };
 use ockam_core::{AllowOnwardAddress, Result, Worker};
 use ockam_node::callback::CallbackSender;
-use ockam_node::{Context, WorkerBuilder};
+use ockam_node::{Context, Counter, Gauge, WorkerBuilder};
 use tracing::{debug, info};
 
+const SECURE_CHANNELS: &str = "ockam_secure_channels";
+const SECURE_CHANNELS_HELP: &str = "Number of established secure channels";
+const HANDSHAKE_FAILURES: &str = "ockam_secure_channel_handshake_failures";
+const HANDSHAKE_FAILURES_HELP: &str = "Number of failed secure channel handshakes";
+
 /// This struct implements a Worker receiving and sending messages
 /// on one side of the secure channel creation as specified with its role: INITIATOR or REPSONDER
 pub(crate) struct HandshakeWorker {
@@ -40,6 +45,8 @@ pub(crate) struct HandshakeWorker {
     role: Role,
     remote_route: Option<Route>,
     decryptor_handler: Option<DecryptorHandler>,
+    /// Established secure channels, set once the handshake is finished
+    secure_channels_gauge: Option<Gauge>,
 }
 
 #[ockam_core::worker]
@@ -50,23 +57,13 @@ impl Worker for HandshakeWorker {
     /// Initialize the state machine with an `Initialize` event
     /// Depending on the state machine role there might be a message to send to the other party
     async fn initialize(&mut self, context: &mut Self::Context) -> Result<()> {
-        match self.state_machine.on_event(Initialize).await? {
-            SendMessage(message) => {
-                info!(
-                    "remote route {:?}, decryptor remote {:?}",
-                    self.remote_route.clone(),
-                    self.addresses.decryptor_remote.clone()
-                );
-                context
-                    .send_from_address(
-                        self.remote_route()?,
-                        message,
-                        self.addresses.decryptor_remote.clone(),
-                    )
-                    .await
-            }
-            Action::NoAction => Ok(()),
+        // Handshake messages must not wait behind the payloads of other channels
+        context.set_message_priority(Priority::High);
+        let result = self.start_handshake(context).await;
+        if result.is_err() {
+            self.handshake_failures(context).inc();
         }
+        result
     }
 
     /// Handle a message coming from the other party
@@ -96,6 +93,64 @@ impl Worker for HandshakeWorker {
             return result;
         };
 
+        let result = self.handle_handshake_message(context, message).await;
+        if result.is_err() {
+            self.handshake_failures(context).inc();
+        }
+        // Once the channel is established, its payloads keep their own priority
+        if self.decryptor_handler.is_some() {
+            context.clear_message_priority();
+        }
+        result
+    }
+
+    async fn shutdown(&mut self, context: &mut Self::Context) -> Result<()> {
+        let _ = context.stop_worker(self.addresses.encryptor.clone()).await;
+        self.secure_channels
+            .secure_channel_registry
+            .unregister_channel(&self.addresses.encryptor);
+
+        if let Some(gauge) = self.secure_channels_gauge.take() {
+            gauge.dec();
+        }
+
+        if let Some(handler) = &self.decryptor_handler {
+            handler.shutdown().await?
+        }
+
+        Ok(())
+    }
+}
+
+impl HandshakeWorker {
+    /// Initialize the state machine with an `Initialize` event
+    async fn start_handshake(&mut self, context: &Context) -> Result<()> {
+        match self.state_machine.on_event(Initialize).await? {
+            SendMessage(message) => {
+                info!(
+                    "remote route {:?}, decryptor remote {:?}",
+                    self.remote_route.clone(),
+                    self.addresses.decryptor_remote.clone()
+                );
+                context
+                    .send_from_address(
+                        self.remote_route()?,
+                        message,
+                        self.addresses.decryptor_remote.clone(),
+                    )
+                    .await
+            }
+            Action::NoAction => Ok(()),
+        }
+    }
+
+    /// Unpack the payload of a handshake message and send it to the state machine
+    /// to trigger a transition
+    async fn handle_handshake_message(
+        &mut self,
+        context: &Context,
+        message: Routed<Any>,
+    ) -> Result<()> {
         let transport_message = message.into_transport_message();
         if let SendMessage(message) = self
             .state_machine
@@ -123,6 +178,15 @@ impl Worker for HandshakeWorker {
         if let Some(final_state) = self.state_machine.get_handshake_results() {
             // start the encryptor worker and return the decryptor
             self.decryptor_handler = Some(self.finalize(context, final_state).await?);
+
+            let gauge = context.metrics().gauge(
+                SECURE_CHANNELS,
+                SECURE_CHANNELS_HELP,
+                &[("role", self.role.str())],
+            );
+            gauge.inc();
+            self.secure_channels_gauge = Some(gauge);
+
             if let Some(callback_sender) = self.callback_sender.take() {
                 callback_sender.send(())?;
             }
@@ -131,21 +195,14 @@ impl Worker for HandshakeWorker {
         Ok(())
     }
 
-    async fn shutdown(&mut self, context: &mut Self::Context) -> Result<()> {
-        let _ = context.stop_worker(self.addresses.encryptor.clone()).await;
-        self.secure_channels
-            .secure_channel_registry
-            .unregister_channel(&self.addresses.encryptor);
-
-        if let Some(handler) = &self.decryptor_handler {
-            handler.shutdown().await?
-        }
-
-        Ok(())
+    fn handshake_failures(&self, context: &Context) -> Counter {
+        context.metrics().counter(
+            HANDSHAKE_FAILURES,
+            HANDSHAKE_FAILURES_HELP,
+            &[("role", self.role.str())],
+        )
     }
-}
 
-impl HandshakeWorker {
     /// Create a new HandshakeWorker with a role of either INITIATOR or RESPONDER
     #[allow(clippy::too_many_arguments)]
     pub(crate) async fn create(
@@ -205,6 +262,7 @@ impl HandshakeWorker {
             remote_route: remote_route.clone(),
             addresses: addresses.clone(),
             decryptor_handler: None,
+            secure_channels_gauge: None,
         };
 
         WorkerBuilder::new(worker)
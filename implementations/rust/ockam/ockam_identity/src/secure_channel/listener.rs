@@ -9,6 +9,7 @@ use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
 use ockam_core::{Address, Any, Result, Routed, Worker};
 use ockam_node::Context;
+use tracing::warn;
 
 pub(crate) struct IdentityChannelListener {
     secure_channels: Arc<SecureChannels>,
@@ -76,6 +77,14 @@ impl Worker for IdentityChannelListener {
         ctx: &mut Self::Context,
         message: Routed<Self::Message>,
     ) -> Result<()> {
+        if ctx.is_draining() {
+            warn!(
+                "The node is draining; refusing secure channel request from {}",
+                message.return_route()
+            );
+            return Ok(());
+        }
+
         let addresses = Addresses::generate(Role::Responder);
         let flow_control_id = self.options.setup_flow_control_for_channel(
             ctx.flow_controls(),
@@ -1,5 +1,6 @@
 use crate::identity::IdentityIdentifier;
 use crate::IdentityError;
+use ockam_core::api::{FromLocalInfo, RequestError};
 use ockam_core::compat::vec::Vec;
 use ockam_core::{Decodable, Encodable, LocalInfo, LocalMessage, Result};
 use serde::{Deserialize, Serialize};
@@ -53,6 +54,13 @@ impl IdentitySecureChannelLocalInfo {
     }
 }
 
+impl FromLocalInfo for IdentitySecureChannelLocalInfo {
+    fn from_local_info(local_info: &[LocalInfo]) -> Result<Self, RequestError> {
+        Self::find_info_from_list(local_info)
+            .map_err(|_| RequestError::forbidden("secure channel required"))
+    }
+}
+
 impl IdentitySecureChannelLocalInfo {
     /// Key exchange name
     pub fn their_identity_id(&self) -> IdentityIdentifier {
@@ -9,6 +9,8 @@ pub(crate) struct Symbol(&'static str);
 // Attributes
 pub(crate) const NO_MAIN: Symbol = Symbol("no_main");
 pub(crate) const OCKAM_CRATE: Symbol = Symbol("crate");
+pub(crate) const SEED: Symbol = Symbol("seed");
+pub(crate) const SIMULATE: Symbol = Symbol("simulate");
 pub(crate) const TIMEOUT_MS: Symbol = Symbol("timeout");
 
 // Derive's helper attributes
@@ -121,6 +121,16 @@ pub fn node(args: TokenStream, item: TokenStream) -> TokenStream {
 ///   indefinitely. If the test times out it will panic. Defaults to 30000 (30
 ///   seconds).
 ///
+/// - `#[ockam::test(simulate)]`: the node runs in a deterministic simulation,
+///   with a virtual clock and a simulated network available from
+///   `Context::simulated_network`. Requires the `simulation` feature of
+///   `ockam_node`. The seed of the network is taken from the
+///   `OCKAM_SIMULATION_SEED` environment variable, or is random, and is
+///   printed by the test.
+///
+/// - `#[ockam::test(seed = 42)]`: run the simulation with the given seed,
+///   to reproduce a failing scenario. Implies `simulate`.
+///
 /// Example of use:
 ///
 /// ```ignore
@@ -4,7 +4,7 @@ use syn::meta::parser;
 use syn::parse::Parser;
 use syn::{parse2, punctuated::Punctuated, Expr, ItemFn, ReturnType};
 
-use crate::internals::attr::{parse_lit_into_int, parse_lit_into_path, Attr};
+use crate::internals::attr::{parse_lit_into_int, parse_lit_into_path, Attr, BoolAttr};
 use crate::internals::{ast, ast::FnVariable, check, ctx::Context, symbol::*};
 
 /// This macro will split the input function in two: the wrapper function that will be
@@ -89,6 +89,17 @@ fn output(mut cont: Container) -> TokenStream {
     let test_fn_ident = &cont.test_fn.sig.ident;
     let ockam_crate = cont.data.attrs.ockam_crate;
     let timeout_ms = cont.data.attrs.timeout_ms;
+    // A simulated node runs with a virtual clock, and a network seeded
+    // with the given seed, or with a seed taken from the environment
+    let node_builder = if cont.data.attrs.simulate {
+        let seed = match cont.data.attrs.seed {
+            Some(seed) => quote! { #seed },
+            None => quote! { #ockam_crate::simulation::seed_from_env() },
+        };
+        quote! { NodeBuilder::new().with_simulation(#seed) }
+    } else {
+        quote! { NodeBuilder::new() }
+    };
     cont.original_fn.block = parse2(quote! {
         {
             use core::panic::AssertUnwindSafe;
@@ -103,7 +114,7 @@ fn output(mut cont: Container) -> TokenStream {
                Some(_) => (),
             };
 
-            let (mut #ctx_ident, mut executor) = NodeBuilder::new().build();
+            let (mut #ctx_ident, mut executor) = #node_builder.build();
             executor
                 .execute(async move {
                     // Wraps the test function call in a `catch_unwind` to catch possible panics.
@@ -217,12 +228,16 @@ impl<'a> Data<'a> {
 struct TestArguments {
     ockam_crate: TokenStream,
     timeout_ms: u64,
+    simulate: bool,
+    seed: Option<u64>,
 }
 
 impl TestArguments {
     fn from_ast(ctx: &Context, args: &TokenStream) -> Self {
         let mut ockam_crate = Attr::none(ctx, OCKAM_CRATE);
         let mut timeout_ms = Attr::none(ctx, TIMEOUT_MS);
+        let mut simulate = BoolAttr::none(ctx, SIMULATE);
+        let mut seed = Attr::none(ctx, SEED);
 
         let p = parser(|meta| {
             if meta.path.is_ident(&OCKAM_CRATE) {
@@ -238,6 +253,15 @@ impl TestArguments {
                     timeout_ms.set(&meta.path, timeout);
                 };
                 Ok(())
+            } else if meta.path.is_ident(&SIMULATE) {
+                simulate.set_true(meta.path);
+                Ok(())
+            } else if meta.path.is_ident(&SEED) {
+                let value_expr: Expr = meta.value()?.parse()?;
+                if let Ok(value) = parse_lit_into_int::<u64>(ctx, SEED, &value_expr) {
+                    seed.set(&meta.path, value);
+                };
+                Ok(())
             } else {
                 ctx.error_spanned_by(
                     meta.path.clone(),
@@ -248,9 +272,13 @@ impl TestArguments {
         });
         p.parse(args.clone().into()).unwrap_or_default();
 
+        let seed = seed.get();
         Self {
             ockam_crate: ockam_crate.get().unwrap_or(quote! { ockam_node }),
             timeout_ms: timeout_ms.get().unwrap_or(30_000),
+            // Setting a seed implies a simulation
+            simulate: simulate.get() || seed.is_some(),
+            seed,
         }
     }
 }
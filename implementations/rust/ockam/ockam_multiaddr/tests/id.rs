@@ -1,5 +1,5 @@
 use core::fmt;
-use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp};
+use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp, Tls};
 use ockam_multiaddr::{Code, Match, MultiAddr, Protocol};
 use quickcheck::{quickcheck, Arbitrary, Gen};
 use rand::distributions::{Alphanumeric, DistString};
@@ -116,6 +116,10 @@ quickcheck! {
                         addr.push_back(Secure::new("secure")).unwrap();
                         prot.push_back(Secure::CODE)
                     }
+                    Tls::CODE => {
+                        addr.push_back(Tls::new("example.com")).unwrap();
+                        prot.push_back(Tls::CODE)
+                    }
                     Service::CODE => {
                         addr.push_back(Service::new("service")).unwrap();
                         prot.push_back(Service::CODE);
@@ -149,6 +153,7 @@ const PROTOS: &[Code] = &[
     Ip4::CODE,
     Ip6::CODE,
     Secure::CODE,
+    Tls::CODE,
     Service::CODE,
     Node::CODE,
     Project::CODE,
@@ -165,6 +170,7 @@ impl Arbitrary for Addr {
                 Ip4::CODE => a.push_back(Ip4::new(Ipv4Addr::arbitrary(g))).unwrap(),
                 Ip6::CODE => a.push_back(Ip6::new(Ipv6Addr::arbitrary(g))).unwrap(),
                 Secure::CODE => a.push_back(Secure::new(gen_string())).unwrap(),
+                Tls::CODE => a.push_back(Tls::new(gen_hostname())).unwrap(),
                 Service::CODE => a.push_back(Service::new(gen_string())).unwrap(),
                 Project::CODE => a.push_back(Project::new(gen_string())).unwrap(),
                 Space::CODE => a.push_back(Space::new(gen_string())).unwrap(),
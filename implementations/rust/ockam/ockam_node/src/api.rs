@@ -16,6 +16,12 @@ use {
 
 use crate::{Context, MessageSendReceiveOptions};
 
+mod service;
+mod stream;
+
+pub use service::*;
+pub use stream::*;
+
 #[cfg(feature = "tag")]
 pub fn cddl() -> &'static BasicContext {
     static INSTANCE: OnceBox<BasicContext> = OnceBox::new();
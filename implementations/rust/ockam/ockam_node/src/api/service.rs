@@ -0,0 +1,385 @@
+use core::marker::PhantomData;
+use minicbor::Decoder;
+use ockam_core::api::{
+    self, decode_body, Endpoint, FromLocalInfo, Method, PathParams, Request, RequestError,
+    Response, StreamEndpoint,
+};
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::vec::Vec;
+use ockam_core::{async_trait, LocalInfo, Result, Route, Routed, Worker};
+
+use crate::api::ResponseStreamSender;
+use crate::Context;
+
+/// Information about a request, besides its body
+pub struct RequestInfo<'a> {
+    header: &'a Request,
+    params: PathParams,
+    local_info: &'a [LocalInfo],
+    return_route: &'a Route,
+}
+
+impl<'a> RequestInfo<'a> {
+    /// Header of the request
+    pub fn header(&self) -> &Request {
+        self.header
+    }
+
+    /// Value of a parameter of the endpoint path
+    pub fn param(&self, name: &str) -> Result<&str, RequestError> {
+        self.params
+            .get(name)
+            .ok_or_else(|| RequestError::bad_request(format!("missing path parameter {}", name)))
+    }
+
+    /// Local information of the request message
+    pub fn local_info(&self) -> &[LocalInfo] {
+        self.local_info
+    }
+
+    /// Return route of the request message
+    pub fn return_route(&self) -> &Route {
+        self.return_route
+    }
+
+    /// Extract a value from the local information of the request message,
+    /// like the identity of its sender
+    pub fn extract<T: FromLocalInfo>(&self) -> Result<T, RequestError> {
+        T::from_local_info(self.local_info)
+    }
+}
+
+/// Handler of the requests to an [`Endpoint`], implemented by the state of
+/// an [`ApiService`]
+#[async_trait]
+pub trait Handle<E: Endpoint>: Send + 'static {
+    /// Handle a request and return the body of the response.
+    ///
+    /// Errors are sent back as error responses, with a status depending on
+    /// their kind when they are converted from an [`ockam_core::Error`].
+    async fn handle(
+        &mut self,
+        ctx: &mut Context,
+        request: RequestInfo<'_>,
+        body: E::Request,
+    ) -> Result<E::Response, RequestError>;
+}
+
+/// Handler of the requests to a [`StreamEndpoint`], implemented by the state
+/// of an [`ApiService`]
+#[async_trait]
+pub trait HandleStream<E: StreamEndpoint>: Send + 'static {
+    /// Handle a request by sending the chunks of its response with the given sender.
+    ///
+    /// The sender can be kept in the state of the service to push chunks later,
+    /// like the events of a subscription. Errors returned by this function are
+    /// sent back as a single error response.
+    async fn handle_stream(
+        &mut self,
+        ctx: &mut Context,
+        request: RequestInfo<'_>,
+        body: E::Request,
+        stream: ResponseStreamSender<E::Response>,
+    ) -> Result<(), RequestError>;
+}
+
+/// Type-erased handler of an endpoint, returning the encoded response
+/// or `None` if the response is streamed
+#[async_trait]
+trait Call<S>: Send + Sync {
+    async fn call(
+        &self,
+        state: &mut S,
+        ctx: &mut Context,
+        request: RequestInfo<'_>,
+        dec: &mut Decoder<'_>,
+    ) -> Result<Option<Vec<u8>>, RequestError>;
+}
+
+struct EndpointCall<E>(PhantomData<fn() -> E>);
+
+#[async_trait]
+impl<S, E> Call<S> for EndpointCall<E>
+where
+    S: Handle<E>,
+    E: Endpoint,
+{
+    async fn call(
+        &self,
+        state: &mut S,
+        ctx: &mut Context,
+        request: RequestInfo<'_>,
+        dec: &mut Decoder<'_>,
+    ) -> Result<Option<Vec<u8>>, RequestError> {
+        let body = decode_body(dec, request.header().has_body())
+            .map_err(|e| RequestError::bad_request(e.to_string()))?;
+        let id = request.header().id();
+        let response = state.handle(ctx, request, body).await?;
+        Response::ok(id)
+            .body(response)
+            .to_vec()
+            .map(Some)
+            .map_err(|e| RequestError::internal_error(e.to_string()))
+    }
+}
+
+struct StreamCall<E>(PhantomData<fn() -> E>);
+
+#[async_trait]
+impl<S, E> Call<S> for StreamCall<E>
+where
+    S: HandleStream<E>,
+    E: StreamEndpoint,
+{
+    async fn call(
+        &self,
+        state: &mut S,
+        ctx: &mut Context,
+        request: RequestInfo<'_>,
+        dec: &mut Decoder<'_>,
+    ) -> Result<Option<Vec<u8>>, RequestError> {
+        let body = decode_body(dec, request.header().has_body())
+            .map_err(|e| RequestError::bad_request(e.to_string()))?;
+        let stream =
+            ResponseStreamSender::new(ctx, request.header(), request.return_route().clone())
+                .await?;
+        state.handle_stream(ctx, request, body, stream).await?;
+        Ok(None)
+    }
+}
+
+struct ApiRoute<S> {
+    method: Method,
+    path: String,
+    call: Box<dyn Call<S>>,
+}
+
+/// Routes of the [`Endpoint`]s of a service, dispatching the requests to
+/// the [`Handle`] implementations of its state
+///
+/// The routes can be used by a worker which handles other requests as well,
+/// while [`ApiService`] is a worker serving its routes only.
+pub struct ApiRoutes<S> {
+    routes: Vec<ApiRoute<S>>,
+}
+
+impl<S> Default for ApiRoutes<S> {
+    fn default() -> Self {
+        Self { routes: Vec::new() }
+    }
+}
+
+impl<S: Send + 'static> ApiRoutes<S> {
+    /// Create routes without endpoints
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve an endpoint at its path
+    pub fn route<E: Endpoint>(self) -> Self
+    where
+        S: Handle<E>,
+    {
+        self.route_at::<E>(E::PATH)
+    }
+
+    /// Serve an endpoint at another path than its own, to keep
+    /// supporting the clients using a previous path for example
+    pub fn route_at<E: Endpoint>(mut self, path: &str) -> Self
+    where
+        S: Handle<E>,
+    {
+        self.routes.push(ApiRoute {
+            method: E::METHOD,
+            path: path.to_string(),
+            call: Box::new(EndpointCall::<E>(PhantomData)),
+        });
+        self
+    }
+
+    /// Serve a streaming endpoint at its path
+    pub fn route_stream<E: StreamEndpoint>(self) -> Self
+    where
+        S: HandleStream<E>,
+    {
+        self.route_stream_at::<E>(E::PATH)
+    }
+
+    /// Serve a streaming endpoint at another path than its own
+    pub fn route_stream_at<E: StreamEndpoint>(mut self, path: &str) -> Self
+    where
+        S: HandleStream<E>,
+    {
+        self.routes.push(ApiRoute {
+            method: E::METHOD,
+            path: path.to_string(),
+            call: Box::new(StreamCall::<E>(PhantomData)),
+        });
+        self
+    }
+
+    /// Return true if an endpoint is served at this request path,
+    /// with any method
+    pub fn serves(&self, path: &str) -> bool {
+        self.routes
+            .iter()
+            .any(|route| PathParams::matching(&route.path, path).is_some())
+    }
+
+    /// Handle an encoded request with the given state and return the encoded
+    /// response, or `None` if the response is streamed to the return route
+    pub async fn respond(
+        &self,
+        state: &mut S,
+        ctx: &mut Context,
+        return_route: &Route,
+        local_info: &[LocalInfo],
+        request: &[u8],
+    ) -> Result<Option<Vec<u8>>> {
+        let mut dec = Decoder::new(request);
+        let header: Request = dec.decode()?;
+        trace! {
+            target: "ockam_node::api",
+            id     = %header.id(),
+            method = ?header.method(),
+            path   = %header.path(),
+            body   = %header.has_body(),
+            "request"
+        }
+
+        let mut path_found = false;
+        for route in &self.routes {
+            let params = match PathParams::matching(&route.path, header.path()) {
+                Some(params) => params,
+                None => continue,
+            };
+            path_found = true;
+            if header.method() != Some(route.method) {
+                continue;
+            }
+
+            let info = RequestInfo {
+                header: &header,
+                params,
+                local_info,
+                return_route,
+            };
+            return match route.call.call(state, ctx, info, &mut dec).await {
+                Ok(response) => Ok(response),
+                Err(e) => {
+                    debug!("Request {} failed: {}", header.path(), e.message());
+                    Ok(Some(e.to_response(&header).to_vec()?))
+                }
+            };
+        }
+
+        let response = if path_found {
+            api::invalid_method(&header)
+        } else {
+            api::unknown_path(&header)
+        };
+        Ok(Some(response.to_vec()?))
+    }
+}
+
+/// Worker serving the [`Endpoint`]s of a service
+///
+/// Each endpoint is handled by the [`Handle`] implementation of the state of
+/// the service, with its typed request body. Requests to unknown paths or with
+/// unknown methods get an error response.
+///
+/// ```ignore
+/// let service = ApiService::new(DirectAuthenticator::new(...))
+///     .route::<AddMemberEndpoint>()
+///     .route::<DeleteMemberEndpoint>();
+/// ctx.start_worker("authenticator", service).await?;
+/// ```
+pub struct ApiService<S> {
+    state: S,
+    routes: ApiRoutes<S>,
+}
+
+impl<S: Send + 'static> ApiService<S> {
+    /// Create a service without endpoints
+    pub fn new(state: S) -> Self {
+        Self {
+            state,
+            routes: ApiRoutes::new(),
+        }
+    }
+
+    /// Serve an endpoint at its path
+    pub fn route<E: Endpoint>(mut self) -> Self
+    where
+        S: Handle<E>,
+    {
+        self.routes = self.routes.route::<E>();
+        self
+    }
+
+    /// Serve an endpoint at another path than its own, to keep
+    /// supporting the clients using a previous path for example
+    pub fn route_at<E: Endpoint>(mut self, path: &str) -> Self
+    where
+        S: Handle<E>,
+    {
+        self.routes = self.routes.route_at::<E>(path);
+        self
+    }
+
+    /// Serve a streaming endpoint at its path
+    pub fn route_stream<E: StreamEndpoint>(mut self) -> Self
+    where
+        S: HandleStream<E>,
+    {
+        self.routes = self.routes.route_stream::<E>();
+        self
+    }
+
+    /// Serve a streaming endpoint at another path than its own
+    pub fn route_stream_at<E: StreamEndpoint>(mut self, path: &str) -> Self
+    where
+        S: HandleStream<E>,
+    {
+        self.routes = self.routes.route_stream_at::<E>(path);
+        self
+    }
+
+    /// State of the service
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// Handle an encoded request and return the encoded response,
+    /// or `None` if the response is streamed to the return route
+    pub async fn respond(
+        &mut self,
+        ctx: &mut Context,
+        return_route: &Route,
+        local_info: &[LocalInfo],
+        request: &[u8],
+    ) -> Result<Option<Vec<u8>>> {
+        self.routes
+            .respond(&mut self.state, ctx, return_route, local_info, request)
+            .await
+    }
+}
+
+#[async_trait]
+impl<S: Send + 'static> Worker for ApiService<S> {
+    type Message = Vec<u8>;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Vec<u8>>) -> Result<()> {
+        let return_route = msg.return_route();
+        let local_info = msg.local_message().local_info().to_vec();
+        match self
+            .respond(ctx, &return_route, &local_info, msg.as_body())
+            .await?
+        {
+            Some(response) => ctx.send(return_route, response).await,
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,335 @@
+use core::marker::PhantomData;
+use core::time::Duration;
+use minicbor::{Decode, Decoder, Encode};
+use ockam_core::api::{
+    decode_body, Id, Request, RequestError, Response, Status, StreamControl, STREAM_WINDOW,
+};
+use ockam_core::compat::format;
+use ockam_core::compat::string::ToString;
+use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{AllowOnwardAddress, Error, Result, Route};
+
+use crate::rpc_client::error;
+use crate::{Context, DetachedContext, MessageReceiveOptions};
+
+/// Time a stream sender waits for more credit before it considers the client gone
+const STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Number of chunks a stream sender drops, waiting for more credit,
+/// before it considers the client gone
+const STREAM_MAX_DROPPED: u32 = 1024;
+
+/// Sender of the chunks of a streaming response
+///
+/// The chunks are sent from the address of the sender, where the client sends
+/// [`StreamControl`] messages to grant more credit or to cancel the stream.
+/// The sender can send [`STREAM_WINDOW`] chunks before it needs more credit.
+pub struct ResponseStreamSender<T> {
+    ctx: DetachedContext,
+    route: Route,
+    request: Request,
+    seq: u32,
+    credit: u32,
+    /// Number of chunks dropped since the last one which was sent
+    dropped: u32,
+    cancelled: bool,
+    idle_timeout: Duration,
+    _item: PhantomData<fn(T)>,
+}
+
+impl<T: Encode<()>> ResponseStreamSender<T> {
+    /// Create the sender of a streaming response to a request
+    /// received with the given return route
+    pub async fn new(ctx: &Context, request: &Request, return_route: Route) -> Result<Self> {
+        let next = return_route.next()?.clone();
+        let ctx = ctx
+            .new_detached_for_route(
+                "ResponseStreamSender",
+                &next,
+                Arc::new(AllowOnwardAddress(next.clone())),
+            )
+            .await?;
+        Ok(Self {
+            ctx,
+            route: return_route,
+            request: request.clone(),
+            seq: 0,
+            credit: STREAM_WINDOW,
+            dropped: 0,
+            cancelled: false,
+            idle_timeout: STREAM_IDLE_TIMEOUT,
+            _item: PhantomData,
+        })
+    }
+
+    /// Set how long [`send`](Self::send) waits for more credit from the client
+    pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Identifier of the request answered by this stream
+    pub fn request_id(&self) -> Id {
+        self.request.id()
+    }
+
+    /// Return true if the client cancelled the stream
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    /// Send a chunk, waiting for more credit from the client if needed.
+    ///
+    /// Fail if the client cancelled the stream or did not grant credit in time.
+    pub async fn send(&mut self, item: T) -> Result<()> {
+        self.poll_control().await?;
+        while self.credit == 0 {
+            let options = MessageReceiveOptions::new().with_timeout(self.idle_timeout);
+            let control = self.receive_control(options).await?;
+            self.apply(control)?;
+        }
+        self.send_chunk(item).await
+    }
+
+    /// Send a chunk if the client granted enough credit. This never blocks,
+    /// so it can be used by a worker pushing events to its subscribers.
+    ///
+    /// When the window of credit is full, the chunk is dropped and a
+    /// [`Kind::ResourceExhausted`] error is returned. The stream can still be
+    /// used: once the client grants more credit, the next chunk is preceded
+    /// by an overflow marker, so that the client knows it missed some chunks.
+    ///
+    /// Fail if the client cancelled the stream, or if it did not grant credit
+    /// before too many chunks were dropped.
+    pub async fn try_send(&mut self, item: T) -> Result<()> {
+        self.poll_control().await?;
+        if self.credit == 0 {
+            self.dropped += 1;
+            trace!("Dropping a chunk of the stream {}", self.request.id());
+            if self.dropped > STREAM_MAX_DROPPED {
+                return Err(Error::new(
+                    Origin::Api,
+                    Kind::Timeout,
+                    "the client stopped granting credit",
+                ));
+            }
+            return Err(Error::new(
+                Origin::Api,
+                Kind::ResourceExhausted,
+                "the stream window is full",
+            ));
+        }
+        self.send_chunk(item).await
+    }
+
+    /// End the stream
+    pub async fn end(self) -> Result<()> {
+        if self.cancelled {
+            return Ok(());
+        }
+        let end = Response::end_of_stream(self.request.id(), self.seq).to_vec()?;
+        self.ctx.send(self.route.clone(), end).await
+    }
+
+    /// End the stream with an error response
+    pub async fn fail(self, error: RequestError) -> Result<()> {
+        if self.cancelled {
+            return Ok(());
+        }
+        let response = error.to_response(&self.request).to_vec()?;
+        self.ctx.send(self.route.clone(), response).await
+    }
+
+    async fn send_chunk(&mut self, item: T) -> Result<()> {
+        // The overflow marker is not counted against the credit, so that
+        // it always precedes the next chunk
+        if self.dropped > 0 {
+            let overflow =
+                Response::overflow(self.request.id(), self.seq, self.dropped).to_vec()?;
+            self.ctx.send(self.route.clone(), overflow).await?;
+            self.dropped = 0;
+        }
+        let chunk = Response::chunk(self.request.id(), self.seq)
+            .body(item)
+            .to_vec()?;
+        self.ctx.send(self.route.clone(), chunk).await?;
+        self.seq += 1;
+        self.credit -= 1;
+        Ok(())
+    }
+
+    /// Apply the control messages already received, without waiting
+    async fn poll_control(&mut self) -> Result<()> {
+        loop {
+            let options = MessageReceiveOptions::new().with_timeout(Duration::from_secs(0));
+            match self.receive_control(options).await {
+                Ok(control) => self.apply(control)?,
+                Err(e) if e.code().kind == Kind::Timeout => return Ok(()),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    async fn receive_control(&mut self, options: MessageReceiveOptions) -> Result<StreamControl> {
+        let msg = self.ctx.receive_extended::<Vec<u8>>(options).await?;
+        Ok(minicbor::decode(msg.as_body())?)
+    }
+
+    fn apply(&mut self, control: StreamControl) -> Result<()> {
+        match control {
+            StreamControl::Credit(n) => {
+                self.credit = self.credit.saturating_add(n);
+                Ok(())
+            }
+            StreamControl::Cancel => {
+                debug!("The stream {} was cancelled", self.request.id());
+                self.cancelled = true;
+                Err(Error::new(
+                    Origin::Api,
+                    Kind::Cancelled,
+                    "the client cancelled the stream",
+                ))
+            }
+        }
+    }
+}
+
+/// Streaming response to a request, received chunk by chunk
+///
+/// The stream grants more credit to the sender of the chunks as they are
+/// received, and ends with an end-of-stream marker or an error response.
+///
+/// If the sender dropped some chunks, see [`ResponseStreamSender::try_send`],
+/// [`next`](Self::next) returns a [`Kind::ResourceExhausted`] error before
+/// the next chunk. The stream can still be used after that error.
+pub struct ResponseStream<T> {
+    ctx: DetachedContext,
+    re: Id,
+    sender: Option<Route>,
+    /// Context sending the control messages to the sender of the chunks
+    control_ctx: Option<DetachedContext>,
+    received: u32,
+    timeout: Option<Duration>,
+    done: bool,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<T: for<'a> Decode<'a, ()>> ResponseStream<T> {
+    pub(crate) fn new(ctx: DetachedContext, re: Id, timeout: Duration) -> Self {
+        Self {
+            ctx,
+            re,
+            sender: None,
+            control_ctx: None,
+            received: 0,
+            timeout: Some(timeout),
+            done: false,
+            _item: PhantomData,
+        }
+    }
+
+    /// Wait for the chunks without a timeout, for subscriptions to
+    /// events which can be far apart
+    pub fn without_timeout(mut self) -> Self {
+        self.timeout = None;
+        self
+    }
+
+    /// Wait for the next chunk and return its body,
+    /// or `None` at the end of the stream
+    pub async fn next(&mut self) -> Result<Option<T>> {
+        if self.done {
+            return Ok(None);
+        }
+
+        loop {
+            let options = match self.timeout {
+                Some(timeout) => MessageReceiveOptions::new().with_timeout(timeout),
+                None => MessageReceiveOptions::new().without_timeout(),
+            };
+            let msg = self.ctx.receive_extended::<Vec<u8>>(options).await?;
+            let return_route = msg.return_route();
+            let body = msg.body();
+            let mut dec = Decoder::new(&body);
+            let header: Response = dec.decode()?;
+            if header.re() != self.re {
+                trace!("Dropping a response to another request {}", header.re());
+                continue;
+            }
+
+            if header.status() != Some(Status::Ok) {
+                self.done = true;
+                return Err(error("stream", &header, &mut dec));
+            }
+            if !header.is_chunk() {
+                // The server answered with a single response
+                self.done = true;
+                return if header.has_body() {
+                    Ok(Some(dec.decode()?))
+                } else {
+                    Ok(None)
+                };
+            }
+            if header.is_end_of_stream() {
+                self.done = true;
+                return Ok(None);
+            }
+
+            self.sender = Some(return_route);
+            if let Some(dropped) = header.dropped() {
+                let msg = format!("{} chunks of the stream were dropped", dropped);
+                return Err(Error::new(Origin::Api, Kind::ResourceExhausted, msg));
+            }
+            let item = decode_body(&mut dec, header.has_body())?;
+            self.received += 1;
+            if self.received >= STREAM_WINDOW / 2 {
+                // The sender may already be gone after sending its last chunks
+                if let Err(e) = self.control(StreamControl::Credit(self.received)).await {
+                    debug!("Failed to grant credit to the stream {}: {}", self.re, e);
+                }
+                self.received = 0;
+            }
+            return Ok(Some(item));
+        }
+    }
+
+    /// Ask the sender to stop the stream.
+    ///
+    /// The sender can only be reached once a first chunk was received.
+    pub async fn cancel(mut self) -> Result<()> {
+        if self.done {
+            return Ok(());
+        }
+        self.done = true;
+        self.control(StreamControl::Cancel).await
+    }
+
+    async fn control(&mut self, control: StreamControl) -> Result<()> {
+        let sender = match &self.sender {
+            Some(sender) => sender.clone(),
+            None => return Ok(()),
+        };
+        // The sender of the chunks is not the server which got the request,
+        // so its route starts with another hop when it is on the same node
+        if self.control_ctx.is_none() {
+            let next = sender.next()?.clone();
+            let ctx = self
+                .ctx
+                .new_detached_for_route(
+                    "ResponseStream.control",
+                    &next,
+                    Arc::new(AllowOnwardAddress(next.clone())),
+                )
+                .await?;
+            self.control_ctx = Some(ctx);
+        }
+        let msg = minicbor::to_vec(control)
+            .map_err(|e| Error::new(Origin::Api, Kind::Serialization, e.to_string()))?;
+        match &self.control_ctx {
+            Some(ctx) => ctx.send(sender, msg).await,
+            None => Ok(()),
+        }
+    }
+}
@@ -1,11 +1,9 @@
-/// Sender used to send payload messages
-pub type MessageSender<T> = crate::tokio::sync::mpsc::Sender<T>;
-/// Receiver used to receive payload messages
-pub type MessageReceiver<T> = crate::tokio::sync::mpsc::Receiver<T>;
+use crate::message_channel::{mailbox_channel, MailboxOptions};
+pub use crate::message_channel::{MessageReceiver, MessageSender};
 
-/// Create message channel
+/// Create message channel, with the default capacity and overflow policy
 pub fn message_channel<T>() -> (MessageSender<T>, MessageReceiver<T>) {
-    crate::tokio::sync::mpsc::channel(16)
+    mailbox_channel(MailboxOptions::default())
 }
 
 /// Router sender
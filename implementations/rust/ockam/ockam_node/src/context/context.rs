@@ -1,12 +1,17 @@
-use crate::channel_types::{SmallReceiver, SmallSender};
+use crate::channel_types::{small_channel, MessageReceiver, SmallSender};
+use crate::durable_mailbox::DurableMailbox;
 use crate::tokio::runtime::Handle;
-use crate::{error::*, AsyncDropSender, NodeMessage};
+use crate::{
+    error::*, AsyncDropSender, DrainRegistry, MailboxMetrics, MetricsRegistry, NodeMessage,
+};
 use core::sync::atomic::AtomicUsize;
 use ockam_core::compat::collections::HashMap;
 use ockam_core::compat::sync::{Arc, RwLock};
 use ockam_core::compat::{string::String, vec::Vec};
 use ockam_core::flow_control::FlowControls;
-use ockam_core::{async_trait, Address, Mailboxes, RelayMessage, Result, TransportType};
+use ockam_core::{
+    async_trait, Address, Mailboxes, Priority, RelayMessage, Result, TraceContext, TransportType,
+};
 
 #[cfg(feature = "std")]
 use core::fmt::{Debug, Formatter};
@@ -15,17 +20,65 @@ use ockam_transport_core::Transport;
 /// A default timeout in seconds
 pub const DEFAULT_TIMEOUT: u64 = 30;
 
+/// Environment variable enabling the propagation of tracing contexts
+#[cfg(feature = "std")]
+pub const TRACING_CONTEXT_ENV: &str = "OCKAM_TRACING_CONTEXT";
+
+/// Return true if the propagation of tracing contexts is enabled with the
+/// `OCKAM_TRACING_CONTEXT` environment variable
+///
+/// The variable is read once, the first time a message needs it, since this
+/// is checked for every message carrying a tracing context.
+#[cfg(feature = "std")]
+pub(crate) fn tracing_context_enabled() -> bool {
+    use core::sync::atomic::{AtomicU8, Ordering};
+
+    const UNKNOWN: u8 = 0;
+    const DISABLED: u8 = 1;
+    const ENABLED: u8 = 2;
+    static STATE: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+    match STATE.load(Ordering::Relaxed) {
+        UNKNOWN => {
+            let enabled =
+                ockam_core::env::get_env_with_default(TRACING_CONTEXT_ENV, false).unwrap_or(false);
+            STATE.store(if enabled { ENABLED } else { DISABLED }, Ordering::Relaxed);
+            enabled
+        }
+        state => state == ENABLED,
+    }
+}
+
+/// Tracing contexts are never propagated without `std`
+#[cfg(not(feature = "std"))]
+pub(crate) fn tracing_context_enabled() -> bool {
+    false
+}
+
 /// Context contains Node state and references to the runtime.
 pub struct Context {
     pub(super) mailboxes: Mailboxes,
     pub(super) sender: SmallSender<NodeMessage>,
     pub(super) rt: Handle,
-    pub(super) receiver: SmallReceiver<RelayMessage>,
+    pub(super) receiver: MessageReceiver<RelayMessage>,
     pub(super) async_drop_sender: Option<AsyncDropSender>,
     pub(super) mailbox_count: Arc<AtomicUsize>,
     /// List of transports used to resolve external addresses to local workers in routes
     pub(super) transports: Arc<RwLock<HashMap<TransportType, Arc<dyn Transport>>>>,
     pub(super) flow_controls: FlowControls,
+    /// Registry of the runtime metrics of the node
+    pub(super) metrics: MetricsRegistry,
+    /// Registry of the parts of the node to drain before stopping it
+    pub(super) drains: DrainRegistry,
+    /// Tracing context attached to the messages sent from this context
+    pub(super) tracing_context: Option<TraceContext>,
+    /// Priority of the messages sent from this context, if set by the worker
+    pub(super) priority: Option<Priority>,
+    /// Priority of the message currently handled with this context
+    pub(super) inherited_priority: Priority,
+    /// Network connecting the nodes of a simulation
+    #[cfg(feature = "simulation")]
+    pub(crate) simulation: Option<crate::simulation::SimulatedNetwork>,
 }
 
 /// This trait can be used to integrate transports into a node
@@ -81,6 +134,81 @@ impl Context {
     pub fn flow_controls(&self) -> &FlowControls {
         &self.flow_controls
     }
+
+    /// Return a reference to the metrics registry of the node
+    pub fn metrics(&self) -> &MetricsRegistry {
+        &self.metrics
+    }
+
+    /// Return a reference to the drain registry of the node
+    pub fn drains(&self) -> &DrainRegistry {
+        &self.drains
+    }
+
+    /// Tracing context attached to the messages sent or forwarded from this context
+    ///
+    /// While a worker handles a message carrying a tracing context, this is
+    /// the context of the span created for the handling of that message.
+    pub fn tracing_context(&self) -> Option<&TraceContext> {
+        self.tracing_context.as_ref()
+    }
+
+    /// Set the tracing context attached to the messages sent or forwarded from this context
+    pub fn set_tracing_context(&mut self, tracing_context: Option<TraceContext>) {
+        self.tracing_context = tracing_context;
+    }
+
+    /// Priority of the messages sent from this context
+    ///
+    /// Unless a priority is set with [`Context::set_message_priority`], the
+    /// messages sent while a worker handles a message have the priority of
+    /// that message, so that replies to control messages are not delivered
+    /// behind bulk payloads.
+    pub fn message_priority(&self) -> Priority {
+        self.priority.unwrap_or(self.inherited_priority)
+    }
+
+    /// Set the priority of the messages sent or forwarded from this context
+    pub fn set_message_priority(&mut self, priority: Priority) {
+        self.priority = Some(priority);
+    }
+
+    /// Clear the priority set with [`Context::set_message_priority`], so that
+    /// messages inherit the priority of the message being handled again
+    pub fn clear_message_priority(&mut self) {
+        self.priority = None;
+    }
+
+    /// Set the priority of the message currently handled with this context
+    pub(crate) fn set_inherited_priority(&mut self, priority: Priority) {
+        self.inherited_priority = priority;
+    }
+
+    /// Durable mailbox persisting the messages of this context, if any
+    pub(crate) fn durable_mailbox(&self) -> Option<DurableMailbox> {
+        self.receiver.durable_mailbox().cloned()
+    }
+
+    /// Network connecting the nodes of the simulation this node is part of, if any
+    ///
+    /// See [`NodeBuilder::with_simulation`](crate::NodeBuilder::with_simulation).
+    #[cfg(feature = "simulation")]
+    pub fn simulated_network(&self) -> Option<crate::simulation::SimulatedNetwork> {
+        self.simulation.clone()
+    }
+
+    /// Start a new trace for the messages sent or forwarded from this context
+    ///
+    /// Messages carrying a tracing context can't be decoded by nodes older than
+    /// this version, so a trace is only started if the propagation of tracing
+    /// contexts is enabled with the `OCKAM_TRACING_CONTEXT` environment variable.
+    #[cfg(feature = "std")]
+    pub fn start_trace(&mut self) -> Option<&TraceContext> {
+        if tracing_context_enabled() {
+            self.tracing_context = Some(TraceContext::new_root());
+        }
+        self.tracing_context.as_ref()
+    }
 }
 
 impl Context {
@@ -127,6 +255,25 @@ impl Context {
             .take_workers()
     }
 
+    /// Return the metrics of the mailbox of the worker or processor
+    /// with the given address: queue depth, capacity and overflows
+    pub async fn mailbox_metrics(&self, address: impl Into<Address>) -> Result<MailboxMetrics> {
+        let (reply_tx, mut reply_rx) = small_channel();
+
+        self.sender
+            .send(NodeMessage::SenderReq(address.into(), reply_tx))
+            .await
+            .map_err(NodeError::from_send_err)?;
+
+        let (_, sender) = reply_rx
+            .recv()
+            .await
+            .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())??
+            .take_sender()?;
+
+        Ok(sender.metrics())
+    }
+
     /// Send a shutdown acknowledgement to the router
     pub(crate) async fn send_stop_ack(&self) -> Result<()> {
         self.sender
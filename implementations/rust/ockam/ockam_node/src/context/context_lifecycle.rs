@@ -6,14 +6,15 @@ use ockam_core::flow_control::FlowControls;
 use ockam_core::{
     errcode::{Kind, Origin},
     Address, AsyncTryClone, DenyAll, Error, IncomingAccessControl, Mailboxes,
-    OutgoingAccessControl, Result, TransportType,
+    OutgoingAccessControl, Priority, Result, TransportType,
 };
 use ockam_transport_core::Transport;
 
 use crate::async_drop::AsyncDrop;
-use crate::channel_types::{message_channel, small_channel, SmallReceiver, SmallSender};
+use crate::channel_types::{small_channel, SmallReceiver, SmallSender};
+use crate::message_channel::{mailbox_channel, MailboxOptions};
 use crate::tokio::{self, runtime::Handle};
-use crate::{debugger, Context};
+use crate::{debugger, Context, DrainRegistry, MetricsRegistry};
 use crate::{error::*, relay::CtrlSignal, router::SenderPair, NodeMessage};
 
 /// A special type of `Context` that has no worker relay and inherits
@@ -55,6 +56,10 @@ impl Context {
     ///
     /// `async_drop_sender` must be provided when creating a detached
     /// Context type (i.e. not backed by a worker relay).
+    ///
+    /// `mailbox_options` set the capacity and overflow policy of the
+    /// mailbox receiving the messages sent to this Context.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         rt: Handle,
         sender: SmallSender<NodeMessage>,
@@ -62,8 +67,11 @@ impl Context {
         async_drop_sender: Option<AsyncDropSender>,
         transports: Arc<RwLock<HashMap<TransportType, Arc<dyn Transport>>>>,
         flow_controls: &FlowControls,
+        metrics: &MetricsRegistry,
+        drains: &DrainRegistry,
+        mailbox_options: MailboxOptions,
     ) -> (Self, SenderPair, SmallReceiver<CtrlSignal>) {
-        let (mailbox_tx, receiver) = message_channel();
+        let (mailbox_tx, receiver) = mailbox_channel(mailbox_options);
         let (ctrl_tx, ctrl_rx) = small_channel();
         (
             Self {
@@ -75,6 +83,13 @@ impl Context {
                 mailbox_count: Arc::new(0.into()),
                 transports,
                 flow_controls: flow_controls.clone(),
+                metrics: metrics.clone(),
+                drains: drains.clone(),
+                tracing_context: None,
+                priority: None,
+                inherited_priority: Priority::default(),
+                #[cfg(feature = "simulation")]
+                simulation: None,
             },
             SenderPair {
                 msgs: mailbox_tx,
@@ -87,15 +102,20 @@ impl Context {
     pub(crate) fn copy_with_mailboxes(
         &self,
         mailboxes: Mailboxes,
+        mailbox_options: MailboxOptions,
     ) -> (Context, SenderPair, SmallReceiver<CtrlSignal>) {
-        Context::new(
+        let (ctx, sender, ctrl_rx) = Context::new(
             self.runtime().clone(),
             self.sender().clone(),
             mailboxes,
             None,
             self.transports.clone(),
             &self.flow_controls,
-        )
+            &self.metrics,
+            &self.drains,
+            mailbox_options,
+        );
+        (self.inherit_simulation(ctx), sender, ctrl_rx)
     }
 
     pub(crate) fn copy_with_mailboxes_detached(
@@ -103,14 +123,28 @@ impl Context {
         mailboxes: Mailboxes,
         drop_sender: AsyncDropSender,
     ) -> (Context, SenderPair, SmallReceiver<CtrlSignal>) {
-        Context::new(
+        let (ctx, sender, ctrl_rx) = Context::new(
             self.runtime().clone(),
             self.sender().clone(),
             mailboxes,
             Some(drop_sender),
             self.transports.clone(),
             &self.flow_controls,
-        )
+            &self.metrics,
+            &self.drains,
+            MailboxOptions::default(),
+        );
+        (self.inherit_simulation(ctx), sender, ctrl_rx)
+    }
+
+    /// Contexts created from this context are part of the same simulation
+    #[cfg_attr(not(feature = "simulation"), allow(unused_mut))]
+    fn inherit_simulation(&self, mut ctx: Context) -> Context {
+        #[cfg(feature = "simulation")]
+        {
+            ctx.simulation = self.simulation.clone();
+        }
+        ctx
     }
 
     /// Utility function to sleep tasks from other crates
@@ -195,7 +229,7 @@ mod tests {
 
         // after a copy with new mailboxes the list of transports should be intact
         let mailboxes = Mailboxes::new(Mailbox::deny_all("address"), vec![]);
-        let (copy, _, _) = ctx.copy_with_mailboxes(mailboxes.clone());
+        let (copy, _, _) = ctx.copy_with_mailboxes(mailboxes.clone(), MailboxOptions::default());
         assert!(copy.is_transport_registered(transport.transport_type()));
 
         // after a detached copy with new mailboxes the list of transports should be intact
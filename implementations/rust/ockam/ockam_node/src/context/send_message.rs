@@ -1,13 +1,13 @@
-use crate::channel_types::small_channel;
+use crate::channel_types::{small_channel, MessageSender};
 use crate::context::MessageWait;
-use crate::{debugger, Context, MessageReceiveOptions, DEFAULT_TIMEOUT};
+use crate::{debugger, Context, DetachedContext, MessageReceiveOptions, DEFAULT_TIMEOUT};
 use crate::{error::*, NodeMessage};
 use core::time::Duration;
 use ockam_core::compat::{sync::Arc, vec::Vec};
 use ockam_core::{
     errcode::{Kind, Origin},
     route, Address, AllowAll, AllowOnwardAddress, Error, LocalMessage, Mailboxes, Message,
-    RelayMessage, Result, Route, Routed, TransportMessage,
+    OutgoingAccessControl, Priority, RelayMessage, Result, Route, Routed, TransportMessage,
 };
 use ockam_core::{LocalInfo, Mailbox};
 
@@ -85,33 +85,48 @@ impl Context {
         let route: Route = route.into();
 
         let next = route.next()?.clone();
-        let address = Address::random_tagged("Context.send_and_receive.detached");
-        let mailboxes = Mailboxes::new(
-            Mailbox::new(
-                address.clone(),
-                Arc::new(AllowAll),
+        let mut child_ctx = self
+            .new_detached_for_route(
+                "Context.send_and_receive.detached",
+                &next,
                 Arc::new(AllowOnwardAddress(next.clone())),
-            ),
+            )
+            .await?;
+
+        child_ctx.send(route, msg).await?;
+        child_ctx
+            .receive_extended::<M>(
+                MessageReceiveOptions::new().with_message_wait(options.message_wait),
+            )
+            .await
+    }
+
+    /// Create a detached context with a random address, sending messages on
+    /// a route starting with `next` and receiving the replies to these messages
+    pub(crate) async fn new_detached_for_route(
+        &self,
+        tag: &str,
+        next: &Address,
+        outgoing: Arc<dyn OutgoingAccessControl>,
+    ) -> Result<DetachedContext> {
+        let address = Address::random_tagged(tag);
+        let mailboxes = Mailboxes::new(
+            Mailbox::new(address.clone(), Arc::new(AllowAll), outgoing),
             vec![],
         );
 
         if let Some(flow_control_id) = self
             .flow_controls
-            .find_flow_control_with_producer_address(&next)
+            .find_flow_control_with_producer_address(next)
             .map(|x| x.flow_control_id().clone())
         {
-            // To be able to receive the response
+            // To be able to receive the replies
             self.flow_controls.add_consumer(address, &flow_control_id);
         }
 
-        let mut child_ctx = self.new_detached_with_mailboxes(mailboxes).await?;
-
-        child_ctx.send(route, msg).await?;
-        child_ctx
-            .receive_extended::<M>(
-                MessageReceiveOptions::new().with_message_wait(options.message_wait),
-            )
-            .await
+        let mut ctx = self.new_detached_with_mailboxes(mailboxes).await?;
+        ctx.set_message_priority(self.message_priority());
+        Ok(ctx)
     }
 
     /// Send a message to another address associated with this worker
@@ -252,10 +267,13 @@ impl Context {
 
         // Pack the payload into a TransportMessage
         let payload = msg.encode().map_err(|_| NodeError::Data.internal())?;
-        let transport_msg = TransportMessage::v1(route, route![sending_address.clone()], payload);
+        let transport_msg = TransportMessage::v1(route, route![sending_address.clone()], payload)
+            .with_tracing_context(self.tracing_context.clone());
 
         // Pack transport message into a LocalMessage wrapper
-        let local_msg = LocalMessage::new(transport_msg, local_info);
+        let local_msg =
+            LocalMessage::new(transport_msg, local_info).with_priority(self.message_priority());
+        let priority = local_msg.priority();
 
         // Pack local message into a RelayMessage wrapper
         let relay_msg = RelayMessage::new(sending_address.clone(), addr, local_msg);
@@ -272,12 +290,7 @@ impl Context {
         }
 
         // Send the packed user message with associated route
-        sender
-            .send(relay_msg)
-            .await
-            .map_err(NodeError::from_send_err)?;
-
-        Ok(())
+        deliver(&sender, relay_msg, priority).await
     }
 
     /// Forward a transport message to its next routing destination
@@ -290,7 +303,12 @@ impl Context {
     /// [`Context::send`] instead, unless you are writing an
     /// external router implementation for ockam node.
     ///
+    /// If this context has a tracing context, it replaces the tracing
+    /// context of the forwarded message. The forwarded message keeps its
+    /// priority, unless one is set with [`Context::set_message_priority`].
+    ///
     /// [`Context::send`]: crate::Context::send
+    /// [`Context::set_message_priority`]: crate::Context::set_message_priority
     /// [`TransportMessage`]: ockam_core::TransportMessage
     pub async fn forward(&self, local_msg: LocalMessage) -> Result<()> {
         self.forward_from_address(local_msg, self.address()).await
@@ -310,7 +328,7 @@ impl Context {
     /// [`TransportMessage`]: ockam_core::TransportMessage
     pub async fn forward_from_address(
         &self,
-        local_msg: LocalMessage,
+        mut local_msg: LocalMessage,
         sending_address: Address,
     ) -> Result<()> {
         // Check if the sender address exists
@@ -318,6 +336,18 @@ impl Context {
             return Err(Error::new_without_cause(Origin::Node, Kind::Invalid));
         }
 
+        // The forwarded message becomes a child of the span of this context
+        if let Some(tracing_context) = &self.tracing_context {
+            local_msg
+                .transport_mut()
+                .set_tracing_context(Some(tracing_context.clone()));
+        }
+
+        if let Some(priority) = self.priority {
+            local_msg.set_priority(priority);
+        }
+        let priority = local_msg.priority();
+
         // First resolve the next hop in the route
         let (reply_tx, mut reply_rx) = small_channel();
         let next = match local_msg.transport().onward_route.next() {
@@ -357,11 +387,41 @@ impl Context {
         }
 
         // Forward the message
-        sender
-            .send(relay_msg)
-            .await
-            .map_err(NodeError::from_send_err)?;
+        deliver(&sender, relay_msg, priority).await
+    }
+}
 
-        Ok(())
+/// Queue a message in the mailbox of its recipient, after persisting it
+/// if that mailbox is durable
+async fn deliver(
+    sender: &MessageSender<RelayMessage>,
+    relay_msg: RelayMessage,
+    priority: Priority,
+) -> Result<()> {
+    let durable = sender.durable_mailbox();
+    let relay_msg = match durable {
+        Some(durable) => match durable.append(relay_msg).await? {
+            Some(relay_msg) => relay_msg,
+            // A message with the same id was already delivered
+            None => return Ok(()),
+        },
+        None => relay_msg,
+    };
+
+    // The messages which are not queued are not kept in the durable mailbox either
+    match sender.send_with_priority(relay_msg, priority).await {
+        Ok(None) => Ok(()),
+        Ok(Some(dropped)) => {
+            if let Some(durable) = durable {
+                durable.remove(&dropped).await?;
+            }
+            Ok(())
+        }
+        Err(e) => {
+            if let Some(durable) = durable {
+                durable.remove(e.message()).await?;
+            }
+            Err(NodeError::from_mailbox_send_err(e))
+        }
     }
 }
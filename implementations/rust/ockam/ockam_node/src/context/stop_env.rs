@@ -1,10 +1,15 @@
 use crate::Context;
 use crate::{error::*, NodeMessage, ShutdownType};
+use core::time::Duration;
+use ockam_core::compat::{string::String, vec::Vec};
 use ockam_core::{
     errcode::{Kind, Origin},
     Error, Result,
 };
 
+/// Interval between two checks of the drains of a node
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
 impl Context {
     /// Signal to the local runtime to shut down immediately
     ///
@@ -49,4 +54,49 @@ impl Context {
             .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())??;
         Ok(())
     }
+
+    /// Return true if the node is draining before being stopped
+    pub fn is_draining(&self) -> bool {
+        self.drains.is_draining()
+    }
+
+    /// Drain the node
+    ///
+    /// The registered [`Drain`](crate::Drain)s stop accepting new work
+    /// and notify their peers, then this call waits until their work in
+    /// progress is finished, or until the deadline is reached.
+    /// Return the names of the drains which were still busy at the deadline.
+    pub async fn drain(&self, deadline: Duration) -> Result<Vec<String>> {
+        let drains = self.drains.start();
+        info!("Draining {} parts of the node", drains.len());
+        for (name, drain) in drains {
+            if let Err(e) = drain.start_drain(self).await {
+                warn!("Could not start draining {}: {}", name, e);
+            }
+        }
+
+        let mut waited = Duration::from_secs(0);
+        loop {
+            let pending = self.drains.pending();
+            if pending.is_empty() {
+                info!("The node is drained");
+                return Ok(pending);
+            }
+            if waited >= deadline {
+                warn!("Drain deadline reached, still busy: {}", pending.join(", "));
+                return Ok(pending);
+            }
+            self.sleep(DRAIN_POLL_INTERVAL).await;
+            waited += DRAIN_POLL_INTERVAL;
+        }
+    }
+
+    /// Drain the node for at most `deadline`, then shut it down
+    ///
+    /// See [`Context::drain`](Context::drain) and
+    /// [`Context::stop`](Context::stop).
+    pub async fn stop_draining(&mut self, deadline: Duration) -> Result<()> {
+        self.drain(deadline).await?;
+        self.stop().await
+    }
 }
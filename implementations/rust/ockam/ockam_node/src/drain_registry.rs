@@ -0,0 +1,122 @@
+use crate::Context;
+use core::sync::atomic::{AtomicBool, Ordering};
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::string::String;
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::compat::vec::Vec;
+use ockam_core::{async_trait, Result};
+
+/// A part of a node which finishes its work before the node is stopped
+///
+/// When a node is drained, it calls [`Drain::start_drain`] on every
+/// registered drain, then waits until they are all drained or until
+/// the drain deadline is reached.
+#[async_trait]
+pub trait Drain: Send + Sync + 'static {
+    /// Stop accepting new work and notify the peers that the node is going away
+    async fn start_drain(&self, ctx: &Context) -> Result<()>;
+
+    /// Return true once the work in progress is finished
+    fn is_drained(&self) -> bool;
+}
+
+/// Drains registered on a node, shared by all the contexts of the node
+#[derive(Clone)]
+pub struct DrainRegistry {
+    draining: Arc<AtomicBool>,
+    drains: Arc<Mutex<BTreeMap<String, Arc<dyn Drain>>>>,
+}
+
+impl Default for DrainRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DrainRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self {
+            draining: Arc::new(AtomicBool::new(false)),
+            drains: Arc::new(Mutex::new(BTreeMap::new())),
+        }
+    }
+
+    /// Register a drain under a name, replacing the drain
+    /// previously registered under the same name
+    pub fn register(&self, name: impl Into<String>, drain: Arc<dyn Drain>) {
+        self.drains.lock().unwrap().insert(name.into(), drain);
+    }
+
+    /// Unregister the drain registered under a name
+    pub fn unregister(&self, name: &str) {
+        self.drains.lock().unwrap().remove(name);
+    }
+
+    /// Return true if the node started draining
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::Relaxed)
+    }
+
+    /// Names of the registered drains which are not drained yet
+    pub fn pending(&self) -> Vec<String> {
+        self.drains
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, drain)| !drain.is_drained())
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Mark the node as draining and return the registered drains
+    pub(crate) fn start(&self) -> Vec<(String, Arc<dyn Drain>)> {
+        self.draining.store(true, Ordering::Relaxed);
+        self.drains
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, drain)| (name.clone(), drain.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::sync::atomic::AtomicUsize;
+
+    struct Connections(AtomicUsize);
+
+    #[async_trait]
+    impl Drain for Connections {
+        async fn start_drain(&self, _ctx: &Context) -> Result<()> {
+            Ok(())
+        }
+
+        fn is_drained(&self) -> bool {
+            self.0.load(Ordering::Relaxed) == 0
+        }
+    }
+
+    #[test]
+    fn pending_drains_are_the_ones_not_drained_yet() {
+        let registry = DrainRegistry::new();
+        let open = Arc::new(Connections(AtomicUsize::new(2)));
+        registry.register("open", open.clone());
+        registry.register("closed", Arc::new(Connections(AtomicUsize::new(0))));
+        assert!(!registry.is_draining());
+
+        assert_eq!(registry.start().len(), 2);
+        assert!(registry.is_draining());
+        assert_eq!(registry.pending(), vec!["open".to_string()]);
+
+        open.0.store(0, Ordering::Relaxed);
+        assert!(registry.pending().is_empty());
+
+        registry.unregister("open");
+        registry.unregister("closed");
+        assert!(registry.start().is_empty());
+    }
+}
@@ -0,0 +1,466 @@
+use crate::compat::asynchronous::Mutex as AsyncMutex;
+use crate::{KeyValueStorage, ToStringKey};
+use core::fmt;
+use ockam_core::compat::collections::{BTreeSet, VecDeque};
+use ockam_core::compat::rand::random;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{
+    Address, Decodable, Encodable, Error, LocalInfo, LocalMessage, Priority, RelayMessage, Result,
+};
+use serde::{Deserialize, Serialize};
+
+/// Number of acknowledged message ids kept by a durable mailbox to drop duplicates
+const ACKNOWLEDGED_IDS: usize = 1024;
+
+/// MessageId LocalInfo unique Identifier
+pub const MESSAGE_ID_IDENTIFIER: &str = "MESSAGE_ID_IDENTIFIER";
+
+/// Storage of the durable mailboxes
+///
+/// Each message is stored in its own entry, so that storing or removing a
+/// message doesn't rewrite the other messages of the mailbox.
+pub type MailboxStorage = Arc<dyn KeyValueStorage<MailboxKey, MailboxEntry>>;
+
+/// Identifier of a message sent to a durable mailbox
+///
+/// A message gets a random identifier when it is stored, unless its sender
+/// already set one with [`MessageId::to_local_info`]. A sender retrying a
+/// message with the same identifier gets it handled only once, unless the
+/// worker failed to handle it.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct MessageId(String);
+
+impl MessageId {
+    /// Create a message identifier
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    /// Create a random message identifier
+    pub fn random() -> Self {
+        let id: u128 = random();
+        Self(format!("{:032x}", id))
+    }
+
+    /// Identifier as a string
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Try to decode `MessageId` from general `LocalInfo`
+    pub fn from_local_info(value: &LocalInfo) -> Result<Self> {
+        if value.type_identifier() != MESSAGE_ID_IDENTIFIER {
+            return Err(Error::new(
+                Origin::Node,
+                Kind::Invalid,
+                "the local info is not a message id",
+            ));
+        }
+
+        Self::decode(value.data())
+    }
+
+    /// Encode `MessageId` to general `LocalInfo`
+    pub fn to_local_info(&self) -> Result<LocalInfo> {
+        Ok(LocalInfo::new(MESSAGE_ID_IDENTIFIER.into(), self.encode()?))
+    }
+
+    /// Find `MessageId` in the general `LocalInfo` of that `LocalMessage`
+    pub fn find_info(local_msg: &LocalMessage) -> Result<Self> {
+        match local_msg
+            .local_info()
+            .iter()
+            .find(|x| x.type_identifier() == MESSAGE_ID_IDENTIFIER)
+        {
+            Some(local_info) => Self::from_local_info(local_info),
+            None => Err(Error::new(
+                Origin::Node,
+                Kind::NotFound,
+                "the message has no message id",
+            )),
+        }
+    }
+}
+
+impl fmt::Display for MessageId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Key of an entry of a [`MailboxStorage`]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum MailboxKey {
+    /// State of the mailbox of the worker with this address
+    Mailbox(Address),
+    /// Message stored at this position of the mailbox
+    Message(Address, u64),
+    /// Position of the pending message with this id
+    Pending(Address, MessageId),
+}
+
+impl ToStringKey for MailboxKey {
+    fn to_string_key(&self) -> String {
+        match self {
+            Self::Mailbox(address) => address.to_string(),
+            Self::Message(address, position) => format!("{}/{}", address, position),
+            Self::Pending(address, id) => format!("{}#{}", address, id),
+        }
+    }
+}
+
+/// Entry of a [`MailboxStorage`]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum MailboxEntry {
+    /// State of a mailbox
+    Mailbox(StoredMailbox),
+    /// Message not handled yet
+    Message(StoredMessage),
+    /// Position of a pending message
+    Pending(u64),
+}
+
+impl Default for MailboxEntry {
+    fn default() -> Self {
+        Self::Mailbox(StoredMailbox::default())
+    }
+}
+
+/// State of a durable mailbox, as persisted in a [`MailboxStorage`]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct StoredMailbox {
+    /// Position of the oldest message which may still be pending
+    first: u64,
+    /// Position of the next stored message
+    next: u64,
+    /// Ids of the last handled messages
+    acknowledged: VecDeque<MessageId>,
+}
+
+impl StoredMailbox {
+    /// Upper bound of the number of messages not handled yet
+    pub fn len(&self) -> usize {
+        (self.next - self.first) as usize
+    }
+
+    /// Return true if all the messages were handled
+    pub fn is_empty(&self) -> bool {
+        self.first == self.next
+    }
+}
+
+/// Message of a durable mailbox, as persisted in a [`MailboxStorage`]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StoredMessage {
+    id: MessageId,
+    source: Address,
+    destination: Address,
+    local_message: LocalMessage,
+    /// The priority is not part of the encoding of the local message
+    #[serde(default)]
+    priority: Priority,
+}
+
+/// Mailbox persisting the messages of a worker until it handles them
+#[derive(Clone)]
+pub(crate) struct DurableMailbox {
+    address: Address,
+    storage: MailboxStorage,
+    /// Serializes the updates of the stored mailbox, and holds the ids of
+    /// the pending messages which the worker failed to handle
+    lock: Arc<AsyncMutex<BTreeSet<MessageId>>>,
+}
+
+impl fmt::Debug for DurableMailbox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DurableMailbox")
+            .field("address", &self.address)
+            .finish()
+    }
+}
+
+impl DurableMailbox {
+    pub(crate) fn new(address: Address, storage: MailboxStorage) -> Self {
+        Self {
+            address,
+            storage,
+            lock: Arc::new(AsyncMutex::new(BTreeSet::new())),
+        }
+    }
+
+    /// Store a message, giving it an id if it has none.
+    /// Return `None` if a message with the same id is queued or was already handled.
+    ///
+    /// A pending message which the worker failed to handle is not stored again,
+    /// but it is returned, to be handled again.
+    pub(crate) async fn append(&self, relay_msg: RelayMessage) -> Result<Option<RelayMessage>> {
+        let source = relay_msg.source().clone();
+        let destination = relay_msg.destination().clone();
+        let mut local_message = relay_msg.into_local_message();
+        let id = match MessageId::find_info(&local_message) {
+            Ok(id) => id,
+            Err(_) => {
+                let id = MessageId::random();
+                local_message.append_local_info(id.to_local_info()?);
+                id
+            }
+        };
+
+        let mut failed = self.lock.lock().await;
+        let mut mailbox = self.load().await?;
+        let pending = self.position(&id).await?.is_some();
+        if pending && failed.remove(&id) {
+            trace!("Retrying failed message {} for {}", id, self.address);
+            return Ok(Some(RelayMessage::new(source, destination, local_message)));
+        }
+        if pending || mailbox.acknowledged.contains(&id) {
+            trace!("Dropping duplicate message {} for {}", id, self.address);
+            return Ok(None);
+        }
+
+        let position = mailbox.next;
+        self.storage
+            .put(
+                self.message_key(position),
+                MailboxEntry::Message(StoredMessage {
+                    id: id.clone(),
+                    source: source.clone(),
+                    destination: destination.clone(),
+                    local_message: local_message.clone(),
+                    priority: local_message.priority(),
+                }),
+            )
+            .await?;
+        self.storage
+            .put(self.pending_key(&id), MailboxEntry::Pending(position))
+            .await?;
+        mailbox.next += 1;
+        self.save(mailbox).await?;
+
+        Ok(Some(RelayMessage::new(source, destination, local_message)))
+    }
+
+    /// Remove a message which couldn't be queued in the mailbox of the worker,
+    /// so that it can be sent again with the same id
+    pub(crate) async fn remove(&self, relay_msg: &RelayMessage) -> Result<()> {
+        let id = MessageId::find_info(relay_msg.local_message())?;
+        let _lock = self.lock.lock().await;
+        let mut mailbox = self.load().await?;
+        self.remove_pending(&mut mailbox, &id).await?;
+        self.save(mailbox).await
+    }
+
+    /// Remove a handled message, and remember its id to drop its duplicates
+    pub(crate) async fn acknowledge(&self, id: &MessageId) -> Result<()> {
+        let mut failed = self.lock.lock().await;
+        failed.remove(id);
+        let mut mailbox = self.load().await?;
+        self.remove_pending(&mut mailbox, id).await?;
+        if !mailbox.acknowledged.contains(id) {
+            mailbox.acknowledged.push_back(id.clone());
+        }
+        while mailbox.acknowledged.len() > ACKNOWLEDGED_IDS {
+            mailbox.acknowledged.pop_front();
+        }
+        self.save(mailbox).await
+    }
+
+    /// Keep a message which the worker failed to handle pending, until it is
+    /// sent again with the same id or the worker is restarted
+    pub(crate) async fn fail(&self, id: &MessageId) {
+        self.lock.lock().await.insert(id.clone());
+    }
+
+    /// Return true if the message with this id was already handled
+    pub(crate) async fn is_acknowledged(&self, id: &MessageId) -> Result<bool> {
+        let _lock = self.lock.lock().await;
+        Ok(self.load().await?.acknowledged.contains(id))
+    }
+
+    /// Messages not handled yet, in the order they were sent
+    pub(crate) async fn pending(&self) -> Result<Vec<RelayMessage>> {
+        let _lock = self.lock.lock().await;
+        let mailbox = self.load().await?;
+        let mut pending = Vec::new();
+        for position in mailbox.first..mailbox.next {
+            if let Some(m) = self.message(position).await? {
+                let local_message = m.local_message.with_priority(m.priority);
+                pending.push(RelayMessage::new(m.source, m.destination, local_message));
+            }
+        }
+        Ok(pending)
+    }
+
+    /// Delete a pending message, then skip the positions of the oldest
+    /// messages which are not stored anymore
+    async fn remove_pending(&self, mailbox: &mut StoredMailbox, id: &MessageId) -> Result<()> {
+        if let Some(position) = self.position(id).await? {
+            self.storage.delete(&self.message_key(position)).await?;
+            self.storage.delete(&self.pending_key(id)).await?;
+        }
+        while mailbox.first < mailbox.next && self.message(mailbox.first).await?.is_none() {
+            mailbox.first += 1;
+        }
+        Ok(())
+    }
+
+    async fn position(&self, id: &MessageId) -> Result<Option<u64>> {
+        match self.storage.get(&self.pending_key(id)).await? {
+            Some(MailboxEntry::Pending(position)) => Ok(Some(position)),
+            _ => Ok(None),
+        }
+    }
+
+    async fn message(&self, position: u64) -> Result<Option<StoredMessage>> {
+        match self.storage.get(&self.message_key(position)).await? {
+            Some(MailboxEntry::Message(message)) => Ok(Some(message)),
+            _ => Ok(None),
+        }
+    }
+
+    async fn load(&self) -> Result<StoredMailbox> {
+        match self.storage.get(&self.mailbox_key()).await? {
+            Some(MailboxEntry::Mailbox(mailbox)) => Ok(mailbox),
+            _ => Ok(StoredMailbox::default()),
+        }
+    }
+
+    async fn save(&self, mailbox: StoredMailbox) -> Result<()> {
+        self.storage
+            .put(self.mailbox_key(), MailboxEntry::Mailbox(mailbox))
+            .await
+    }
+
+    fn mailbox_key(&self) -> MailboxKey {
+        MailboxKey::Mailbox(self.address.clone())
+    }
+
+    fn message_key(&self, position: u64) -> MailboxKey {
+        MailboxKey::Message(self.address.clone(), position)
+    }
+
+    fn pending_key(&self, id: &MessageId) -> MailboxKey {
+        MailboxKey::Pending(self.address.clone(), id.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemoryKeyValueStorage;
+    use ockam_core::{route, TransportMessage};
+
+    fn relay_message(local_info: Vec<LocalInfo>) -> RelayMessage {
+        let transport = TransportMessage::v1(route!["worker"], route!["sender"], vec![1, 2, 3]);
+        RelayMessage::new(
+            "sender".into(),
+            "worker".into(),
+            LocalMessage::new(transport, local_info),
+        )
+    }
+
+    fn message_id(relay_msg: &RelayMessage) -> MessageId {
+        MessageId::find_info(relay_msg.local_message()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn messages_are_pending_until_acknowledged() -> Result<()> {
+        let storage = InMemoryKeyValueStorage::create();
+        let mailbox = DurableMailbox::new("worker".into(), storage.clone());
+
+        let first = mailbox.append(relay_message(vec![])).await?.unwrap();
+        let second = mailbox.append(relay_message(vec![])).await?.unwrap();
+        assert_ne!(message_id(&first), message_id(&second));
+
+        mailbox.acknowledge(&message_id(&first)).await?;
+        let pending = mailbox.pending().await?;
+        assert_eq!(pending.len(), 1);
+        assert_eq!(message_id(&pending[0]), message_id(&second));
+
+        // The mailbox is restored from the storage
+        let restored = DurableMailbox::new("worker".into(), storage);
+        assert_eq!(restored.pending().await?.len(), 1);
+        assert!(restored.is_acknowledged(&message_id(&first)).await?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn messages_with_the_same_id_are_stored_once() -> Result<()> {
+        let mailbox = DurableMailbox::new("worker".into(), InMemoryKeyValueStorage::create());
+        let id = MessageId::new("telemetry-1");
+        let local_info = vec![id.to_local_info()?];
+
+        assert!(mailbox
+            .append(relay_message(local_info.clone()))
+            .await?
+            .is_some());
+        assert!(mailbox
+            .append(relay_message(local_info.clone()))
+            .await?
+            .is_none());
+
+        mailbox.acknowledge(&id).await?;
+        assert!(mailbox.append(relay_message(local_info)).await?.is_none());
+        assert!(mailbox.pending().await?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn removed_messages_can_be_sent_again() -> Result<()> {
+        let mailbox = DurableMailbox::new("worker".into(), InMemoryKeyValueStorage::create());
+        let id = MessageId::new("telemetry-1");
+        let local_info = vec![id.to_local_info()?];
+
+        let first = mailbox
+            .append(relay_message(local_info.clone()))
+            .await?
+            .unwrap();
+        mailbox.remove(&first).await?;
+        assert!(mailbox.pending().await?.is_empty());
+        assert!(!mailbox.is_acknowledged(&id).await?);
+
+        assert!(mailbox.append(relay_message(local_info)).await?.is_some());
+        assert_eq!(mailbox.pending().await?.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn failed_messages_can_be_sent_again() -> Result<()> {
+        let mailbox = DurableMailbox::new("worker".into(), InMemoryKeyValueStorage::create());
+        let id = MessageId::new("telemetry-1");
+        let local_info = vec![id.to_local_info()?];
+
+        mailbox.append(relay_message(local_info.clone())).await?;
+        mailbox.fail(&id).await;
+        assert!(mailbox
+            .append(relay_message(local_info.clone()))
+            .await?
+            .is_some());
+        assert_eq!(mailbox.pending().await?.len(), 1);
+
+        // The message is queued again, so further copies are duplicates
+        assert!(mailbox.append(relay_message(local_info)).await?.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn handled_messages_are_deleted_from_the_storage() -> Result<()> {
+        let storage = InMemoryKeyValueStorage::create();
+        let mailbox = DurableMailbox::new("worker".into(), storage.clone());
+
+        let first = mailbox.append(relay_message(vec![])).await?.unwrap();
+        let second = mailbox.append(relay_message(vec![])).await?.unwrap();
+        assert_eq!(storage.keys().await?.len(), 5);
+
+        // The first message stays pending while the second one is handled
+        mailbox.acknowledge(&message_id(&second)).await?;
+        assert_eq!(mailbox.load().await?.len(), 2);
+        mailbox.acknowledge(&message_id(&first)).await?;
+        assert!(mailbox.load().await?.is_empty());
+        assert_eq!(storage.keys().await?, vec![mailbox.mailbox_key()]);
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use crate::message_channel::MailboxSendError;
 use crate::tokio::{sync::mpsc::error::SendError, time::error::Elapsed};
 use core::fmt;
 use ockam_core::{
@@ -51,6 +52,23 @@ impl NodeError {
         .context("SendError", err)
     }
 
+    /// Create an ockam_core::Error based on a MailboxSendError
+    pub(crate) fn from_mailbox_send_err<T>(err: MailboxSendError<T>) -> Error {
+        match err {
+            MailboxSendError::Closed(_) => Error::new(
+                Origin::Node,
+                Kind::Internal,
+                NodeError::NodeState(NodeReason::Unknown),
+            ),
+            MailboxSendError::Full(_) => Error::new(
+                Origin::Node,
+                Kind::ResourceExhausted,
+                NodeError::WorkerState(WorkerReason::MailboxFull),
+            ),
+        }
+        .context("SendError", err)
+    }
+
     /// Create an ockam_core::Error from a tokio::Elapsed
     pub(crate) fn with_elapsed(self, err: Elapsed) -> Error {
         Error::new(Origin::Node, Kind::Timeout, err).context("Type", self)
@@ -142,6 +160,8 @@ pub enum WorkerReason {
     Faulty,
     /// The worker is otherwise corrupt and can not be recovered
     Corrupt,
+    /// The mailbox of the worker is full and rejects new messages
+    MailboxFull,
 }
 
 impl fmt::Display for WorkerReason {
@@ -153,6 +173,7 @@ impl fmt::Display for WorkerReason {
                 Self::Shutdown => "target worker is shutting down",
                 Self::Faulty => "target worker is faulty and waiting for supervisor",
                 Self::Corrupt => "target worker is corrupt and can not be recovered",
+                Self::MailboxFull => "target worker mailbox is full",
             }
         )
     }
@@ -4,7 +4,7 @@ use crate::channel_types::SmallSender;
 use crate::{
     router::{Router, SenderPair},
     tokio::runtime::{Handle, Runtime},
-    NodeMessage,
+    MetricsRegistry, NodeMessage,
 };
 use core::future::Future;
 use ockam_core::{Address, Result};
@@ -41,9 +41,17 @@ pub struct Executor {
 
 impl Executor {
     /// Create a new Ockam node [`Executor`] instance
-    pub fn new(flow_controls: &FlowControls) -> Self {
-        let rt = Runtime::new().unwrap();
-        let router = Router::new(flow_controls);
+    pub fn new(flow_controls: &FlowControls, metrics: &MetricsRegistry) -> Self {
+        Self::with_runtime(Runtime::new().unwrap(), flow_controls, metrics)
+    }
+
+    /// Create a new Ockam node [`Executor`] instance running on the given runtime
+    pub(crate) fn with_runtime(
+        rt: Runtime,
+        flow_controls: &FlowControls,
+        metrics: &MetricsRegistry,
+    ) -> Self {
+        let router = Router::new(flow_controls, metrics);
         #[cfg(feature = "metrics")]
         let metrics = Metrics::new(&rt, router.get_metrics_readout());
         Self {
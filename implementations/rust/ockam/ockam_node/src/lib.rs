@@ -40,6 +40,9 @@ pub mod channel_types;
 #[cfg(feature = "metrics")]
 mod metrics;
 
+#[cfg(feature = "simulation")]
+pub mod simulation;
+
 /// Api helpers
 pub mod api;
 
@@ -52,15 +55,21 @@ pub mod callback;
 mod async_drop;
 mod context;
 mod delayed;
+mod drain_registry;
+mod durable_mailbox;
 mod error;
 mod executor;
+mod message_channel;
 mod messages;
+mod metrics_registry;
 mod node;
 mod parser;
 mod processor_builder;
 mod relay;
 mod router;
 mod rpc_client;
+#[cfg(feature = "std")]
+mod supervisor;
 
 /// Support for storing persistent values
 pub mod storage;
@@ -68,12 +77,24 @@ mod worker_builder;
 
 pub use context::*;
 pub use delayed::*;
+pub use drain_registry::*;
+pub use durable_mailbox::{
+    MailboxEntry, MailboxKey, MailboxStorage, MessageId, StoredMailbox, StoredMessage,
+    MESSAGE_ID_IDENTIFIER,
+};
 pub use error::*;
 pub use executor::*;
+pub use message_channel::{
+    MailboxMessage, MailboxMetrics, MailboxSendError, OverflowPolicy, DEFAULT_MAILBOX_CAPACITY,
+    HIGH_PRIORITY_HEADROOM,
+};
 pub use messages::*;
+pub use metrics_registry::*;
 pub use processor_builder::ProcessorBuilder;
 pub use rpc_client::*;
 pub use storage::*;
+#[cfg(feature = "std")]
+pub use supervisor::*;
 pub use worker_builder::WorkerBuilder;
 
 pub use node::{NodeBuilder, NullWorker};
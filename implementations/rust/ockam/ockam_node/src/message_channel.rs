@@ -0,0 +1,628 @@
+use crate::compat::asynchronous::Mutex as AsyncMutex;
+use crate::compat::futures::FutureExt;
+use crate::durable_mailbox::DurableMailbox;
+use crate::tokio::sync::mpsc;
+use core::fmt;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use ockam_core::compat::collections::VecDeque;
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::{Address, Priority, RelayMessage};
+
+/// Default capacity of the mailbox of a worker or processor
+pub const DEFAULT_MAILBOX_CAPACITY: usize = 16;
+
+/// Number of [`Priority::High`] messages a mailbox accepts beyond its capacity
+pub const HIGH_PRIORITY_HEADROOM: usize = 4;
+
+/// What happens to a message sent to a full mailbox
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// The sender waits until the mailbox has room for the message
+    Block,
+    /// The oldest message of the mailbox is dropped to make room for the new one
+    DropOldest,
+    /// The new message is dropped
+    DropNewest,
+    /// The sender gets an error
+    Reject,
+}
+
+impl Default for OverflowPolicy {
+    fn default() -> Self {
+        Self::Block
+    }
+}
+
+/// Capacity, overflow policy and persistence of a mailbox
+#[derive(Clone, Debug)]
+pub(crate) struct MailboxOptions {
+    pub(crate) capacity: usize,
+    pub(crate) overflow_policy: OverflowPolicy,
+    pub(crate) durable: Option<DurableMailbox>,
+}
+
+impl Default for MailboxOptions {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_MAILBOX_CAPACITY,
+            overflow_policy: OverflowPolicy::default(),
+            durable: None,
+        }
+    }
+}
+
+/// Metrics of the mailbox of a worker or processor
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MailboxMetrics {
+    depth: usize,
+    capacity: usize,
+    overflow_policy: OverflowPolicy,
+    dropped: usize,
+    rejected: usize,
+}
+
+impl MailboxMetrics {
+    /// Number of messages waiting in the mailbox
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Maximum number of messages waiting in the mailbox
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Policy applied to the messages sent to the mailbox when it is full
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        self.overflow_policy
+    }
+
+    /// Number of messages dropped because the mailbox was full
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Number of messages rejected because the mailbox was full
+    pub fn rejected(&self) -> usize {
+        self.rejected
+    }
+}
+
+/// Error returned when a message can't be sent to a mailbox
+pub enum MailboxSendError<T> {
+    /// The mailbox was closed
+    Closed(T),
+    /// The mailbox was full, and its overflow policy is [`OverflowPolicy::Reject`]
+    Full(T),
+}
+
+impl<T> MailboxSendError<T> {
+    /// The message which couldn't be sent
+    pub fn message(&self) -> &T {
+        match self {
+            Self::Closed(value) | Self::Full(value) => value,
+        }
+    }
+}
+
+impl<T> fmt::Debug for MailboxSendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Closed(_) => f.write_str("Closed(..)"),
+            Self::Full(_) => f.write_str("Full(..)"),
+        }
+    }
+}
+
+impl<T> fmt::Display for MailboxSendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Closed(_) => f.write_str("mailbox closed"),
+            Self::Full(_) => f.write_str("mailbox full"),
+        }
+    }
+}
+
+/// A message which can be queued in a mailbox
+pub trait MailboxMessage {
+    /// Identifies the sender of a message
+    type Sender: PartialEq;
+
+    /// The sender of the message. The messages of one sender are received in
+    /// the order they were sent, whatever their priorities.
+    fn sender(&self) -> &Self::Sender;
+}
+
+impl MailboxMessage for RelayMessage {
+    type Sender = Address;
+
+    fn sender(&self) -> &Address {
+        self.source()
+    }
+}
+
+/// Result of queueing a message
+enum Push<T> {
+    Queued,
+    /// A message, the new one or an evicted one, was dropped by the overflow policy
+    Dropped(T),
+    Full(T),
+}
+
+/// Messages waiting in a mailbox, with one lane per [`Priority`]
+///
+/// A message never overtakes an older message of the same sender, so that
+/// the messages of one sender are received in the order they were sent.
+/// Reordering them would break the senders relying on that order, e.g. a
+/// secure channel encryptor, whose messages are rejected by the decryptor
+/// once their nonces fall behind its window of accepted nonces.
+struct Lanes<T> {
+    lanes: [VecDeque<T>; 3],
+}
+
+impl<T> Lanes<T> {
+    fn new() -> Self {
+        Self {
+            lanes: [VecDeque::new(), VecDeque::new(), VecDeque::new()],
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.lanes.iter().map(|lane| lane.len()).sum()
+    }
+
+    /// Pop the oldest message of the highest priority
+    fn pop_front(&mut self) -> Option<T> {
+        self.lanes
+            .iter_mut()
+            .rev()
+            .find_map(|lane| lane.pop_front())
+    }
+
+    /// Evict the oldest message of the lowest priority,
+    /// if that priority is not higher than the given one
+    fn evict(&mut self, priority: Priority) -> Option<T> {
+        self.lanes[..=priority as usize]
+            .iter_mut()
+            .find_map(|lane| lane.pop_front())
+    }
+}
+
+impl<T: MailboxMessage> Lanes<T> {
+    /// Queue a message in the lane of its priority, or in the lowest lane
+    /// where its sender still has messages, so that it is not received
+    /// before them
+    fn push_back(&mut self, value: T, priority: Priority) {
+        let lane = self.lanes[..priority as usize]
+            .iter()
+            .position(|lane| lane.iter().any(|v| v.sender() == value.sender()))
+            .unwrap_or(priority as usize);
+        self.lanes[lane].push_back(value)
+    }
+}
+
+/// State shared by the senders and the receiver of a mailbox
+struct Shared<T> {
+    queue: Mutex<Lanes<T>>,
+    options: MailboxOptions,
+    dropped: AtomicUsize,
+    rejected: AtomicUsize,
+    receiver_closed: AtomicBool,
+    /// Wakes up a sender waiting for room in the mailbox
+    not_full_tx: mpsc::Sender<()>,
+    not_full_rx: AsyncMutex<mpsc::Receiver<()>>,
+}
+
+impl<T: MailboxMessage> Shared<T> {
+    fn push(&self, value: T, priority: Priority) -> Push<T> {
+        let mut queue = self.queue.lock().unwrap();
+        let capacity = self.options.capacity;
+
+        // High priority messages can use some headroom beyond the capacity,
+        // so that they are not held back by a mailbox full of payloads
+        let limit = if priority == Priority::High {
+            capacity + HIGH_PRIORITY_HEADROOM
+        } else {
+            capacity
+        };
+
+        if queue.len() < limit {
+            queue.push_back(value, priority);
+            // Other senders may be waiting for the remaining room
+            if queue.len() < capacity {
+                self.wake_sender();
+            }
+            return Push::Queued;
+        }
+
+        match self.options.overflow_policy {
+            OverflowPolicy::Block => Push::Full(value),
+            OverflowPolicy::DropOldest => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                // Messages of a higher priority are kept over the new one
+                match queue.evict(priority) {
+                    Some(evicted) => {
+                        queue.push_back(value, priority);
+                        Push::Dropped(evicted)
+                    }
+                    None => Push::Dropped(value),
+                }
+            }
+            OverflowPolicy::DropNewest => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                Push::Dropped(value)
+            }
+            OverflowPolicy::Reject => {
+                self.rejected.fetch_add(1, Ordering::Relaxed);
+                Push::Full(value)
+            }
+        }
+    }
+}
+
+impl<T> Shared<T> {
+    fn pop(&self) -> Option<T> {
+        let value = self.queue.lock().unwrap().pop_front();
+        if value.is_some() {
+            self.wake_sender();
+        }
+        value
+    }
+
+    fn wake_sender(&self) {
+        // A pending wake up is enough
+        let _ = self.not_full_tx.send(()).now_or_never();
+    }
+
+    fn metrics(&self) -> MailboxMetrics {
+        MailboxMetrics {
+            depth: self.queue.lock().unwrap().len(),
+            capacity: self.options.capacity,
+            overflow_policy: self.options.overflow_policy,
+            dropped: self.dropped.load(Ordering::Relaxed),
+            rejected: self.rejected.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Sender used to send payload messages to a mailbox
+pub struct MessageSender<T> {
+    shared: Arc<Shared<T>>,
+    not_empty: mpsc::Sender<()>,
+}
+
+impl<T> Clone for MessageSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+            not_empty: self.not_empty.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for MessageSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MessageSender")
+            .field("options", &self.shared.options)
+            .finish()
+    }
+}
+
+impl<T: MailboxMessage> MessageSender<T> {
+    /// Send a message to the mailbox with the [`Priority::Normal`] priority,
+    /// applying its overflow policy if it is full
+    ///
+    /// Return the message dropped by the overflow policy, if any.
+    pub async fn send(&self, value: T) -> Result<Option<T>, MailboxSendError<T>> {
+        self.send_with_priority(value, Priority::Normal).await
+    }
+
+    /// Send a message to the mailbox with the given priority,
+    /// applying its overflow policy if it is full
+    ///
+    /// Messages of a higher priority are received first, except that a
+    /// message is never received before an older message of the same
+    /// [`MailboxMessage::sender`]. Messages of the [`Priority::High`]
+    /// priority are queued even if the mailbox is full, as long as it has
+    /// less than [`HIGH_PRIORITY_HEADROOM`] extra messages.
+    ///
+    /// Return the message dropped by the overflow policy, if any. It is
+    /// either the new message or, with [`OverflowPolicy::DropOldest`], the
+    /// message evicted to make room for it.
+    pub async fn send_with_priority(
+        &self,
+        value: T,
+        priority: Priority,
+    ) -> Result<Option<T>, MailboxSendError<T>> {
+        let mut value = value;
+        loop {
+            if self.shared.receiver_closed.load(Ordering::Acquire) {
+                // Let the other waiting senders find out too
+                self.shared.wake_sender();
+                return Err(MailboxSendError::Closed(value));
+            }
+
+            match self.shared.push(value, priority) {
+                Push::Queued => {
+                    // A pending wake up is enough
+                    let _ = self.not_empty.send(()).now_or_never();
+                    return Ok(None);
+                }
+                Push::Dropped(dropped) => {
+                    let _ = self.not_empty.send(()).now_or_never();
+                    return Ok(Some(dropped));
+                }
+                Push::Full(v) => {
+                    if self.shared.options.overflow_policy == OverflowPolicy::Reject {
+                        return Err(MailboxSendError::Full(v));
+                    }
+                    value = v;
+                }
+            }
+
+            // Wait until the mailbox has room, or is closed
+            let mut not_full = self.shared.not_full_rx.lock().await;
+            let _ = not_full.recv().await;
+        }
+    }
+}
+
+impl<T> MessageSender<T> {
+    /// Current metrics of the mailbox
+    pub fn metrics(&self) -> MailboxMetrics {
+        self.shared.metrics()
+    }
+
+    /// Durable mailbox persisting the messages, if any
+    pub(crate) fn durable_mailbox(&self) -> Option<&DurableMailbox> {
+        self.shared.options.durable.as_ref()
+    }
+}
+
+/// Receiver used to receive payload messages from a mailbox
+pub struct MessageReceiver<T> {
+    shared: Arc<Shared<T>>,
+    not_empty: mpsc::Receiver<()>,
+}
+
+impl<T> MessageReceiver<T> {
+    /// Receive the next message of the mailbox, which is the oldest
+    /// message of the highest priority.
+    /// Return `None` once all the senders are dropped and the mailbox is empty
+    pub async fn recv(&mut self) -> Option<T> {
+        loop {
+            if let Some(value) = self.shared.pop() {
+                return Some(value);
+            }
+            if self.not_empty.recv().await.is_none() {
+                return self.shared.pop();
+            }
+        }
+    }
+
+    /// Current metrics of the mailbox
+    pub fn metrics(&self) -> MailboxMetrics {
+        self.shared.metrics()
+    }
+
+    /// Durable mailbox persisting the messages, if any
+    pub(crate) fn durable_mailbox(&self) -> Option<&DurableMailbox> {
+        self.shared.options.durable.as_ref()
+    }
+}
+
+impl<T> Drop for MessageReceiver<T> {
+    fn drop(&mut self) {
+        self.shared.receiver_closed.store(true, Ordering::Release);
+        self.shared.wake_sender();
+    }
+}
+
+/// Create a mailbox channel with the given capacity and overflow policy
+pub(crate) fn mailbox_channel<T>(
+    options: MailboxOptions,
+) -> (MessageSender<T>, MessageReceiver<T>) {
+    let options = MailboxOptions {
+        capacity: options.capacity.max(1),
+        ..options
+    };
+    let (not_empty_tx, not_empty_rx) = mpsc::channel(1);
+    let (not_full_tx, not_full_rx) = mpsc::channel(1);
+
+    let shared = Arc::new(Shared {
+        queue: Mutex::new(Lanes::new()),
+        options,
+        dropped: AtomicUsize::new(0),
+        rejected: AtomicUsize::new(0),
+        receiver_closed: AtomicBool::new(false),
+        not_full_tx,
+        not_full_rx: AsyncMutex::new(not_full_rx),
+    });
+
+    (
+        MessageSender {
+            shared: shared.clone(),
+            not_empty: not_empty_tx,
+        },
+        MessageReceiver {
+            shared,
+            not_empty: not_empty_rx,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::time::Duration;
+
+    /// Each value is sent by a different sender
+    impl MailboxMessage for u32 {
+        type Sender = u32;
+
+        fn sender(&self) -> &u32 {
+            self
+        }
+    }
+
+    /// Values sent by the sender of the first field
+    impl MailboxMessage for (u8, u32) {
+        type Sender = u8;
+
+        fn sender(&self) -> &u8 {
+            &self.0
+        }
+    }
+
+    fn channel(
+        capacity: usize,
+        overflow_policy: OverflowPolicy,
+    ) -> (MessageSender<u32>, MessageReceiver<u32>) {
+        mailbox_channel(MailboxOptions {
+            capacity,
+            overflow_policy,
+            durable: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn drop_oldest_keeps_the_newest_messages() {
+        let (tx, mut rx) = channel(2, OverflowPolicy::DropOldest);
+        tx.send(0).await.unwrap();
+        tx.send(1).await.unwrap();
+        assert_eq!(tx.send(2).await.unwrap(), Some(0));
+        assert_eq!(tx.send(3).await.unwrap(), Some(1));
+
+        assert_eq!(tx.metrics().depth(), 2);
+        assert_eq!(tx.metrics().dropped(), 2);
+        assert_eq!(rx.recv().await, Some(2));
+        assert_eq!(rx.recv().await, Some(3));
+    }
+
+    #[tokio::test]
+    async fn drop_newest_keeps_the_oldest_messages() {
+        let (tx, mut rx) = channel(2, OverflowPolicy::DropNewest);
+        tx.send(0).await.unwrap();
+        tx.send(1).await.unwrap();
+        assert_eq!(tx.send(2).await.unwrap(), Some(2));
+        assert_eq!(tx.send(3).await.unwrap(), Some(3));
+
+        assert_eq!(tx.metrics().dropped(), 2);
+        assert_eq!(rx.recv().await, Some(0));
+        assert_eq!(rx.recv().await, Some(1));
+    }
+
+    #[tokio::test]
+    async fn reject_returns_an_error() {
+        let (tx, mut rx) = channel(1, OverflowPolicy::Reject);
+        tx.send(0).await.unwrap();
+
+        assert!(matches!(tx.send(1).await, Err(MailboxSendError::Full(1))));
+        assert_eq!(tx.metrics().rejected(), 1);
+        assert_eq!(rx.recv().await, Some(0));
+        tx.send(2).await.unwrap();
+        assert_eq!(rx.recv().await, Some(2));
+    }
+
+    #[tokio::test]
+    async fn block_waits_for_room() {
+        let (tx, mut rx) = channel(1, OverflowPolicy::Block);
+        tx.send(0).await.unwrap();
+
+        let blocked = tokio::spawn({
+            let tx = tx.clone();
+            async move { tx.send(1).await.is_ok() }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!blocked.is_finished());
+
+        assert_eq!(rx.recv().await, Some(0));
+        assert!(blocked.await.unwrap());
+        assert_eq!(rx.recv().await, Some(1));
+    }
+
+    #[tokio::test]
+    async fn receiver_ends_when_senders_are_dropped() {
+        let (tx, mut rx) = channel(2, OverflowPolicy::Block);
+        tx.send(0).await.unwrap();
+        drop(tx);
+
+        assert_eq!(rx.recv().await, Some(0));
+        assert_eq!(rx.recv().await, None);
+    }
+
+    #[tokio::test]
+    async fn higher_priorities_are_received_first() {
+        let (tx, mut rx) = channel(4, OverflowPolicy::Block);
+        tx.send_with_priority(0, Priority::Low).await.unwrap();
+        tx.send(1).await.unwrap();
+        tx.send_with_priority(2, Priority::High).await.unwrap();
+        tx.send(3).await.unwrap();
+
+        assert_eq!(rx.recv().await, Some(2));
+        assert_eq!(rx.recv().await, Some(1));
+        assert_eq!(rx.recv().await, Some(3));
+        assert_eq!(rx.recv().await, Some(0));
+    }
+
+    #[tokio::test]
+    async fn messages_of_one_sender_keep_their_order() {
+        let (tx, mut rx) = mailbox_channel(MailboxOptions::default());
+        tx.send_with_priority((1, 0), Priority::Low).await.unwrap();
+        tx.send_with_priority((2, 1), Priority::Normal)
+            .await
+            .unwrap();
+        tx.send_with_priority((1, 2), Priority::High).await.unwrap();
+        tx.send_with_priority((2, 3), Priority::High).await.unwrap();
+        tx.send_with_priority((3, 4), Priority::High).await.unwrap();
+
+        for value in [(3, 4), (2, 1), (2, 3), (1, 0), (1, 2)] {
+            assert_eq!(rx.recv().await, Some(value));
+        }
+
+        // Once its older messages are received, a sender gets its priority back
+        tx.send_with_priority((1, 5), Priority::Low).await.unwrap();
+        tx.send_with_priority((2, 6), Priority::High).await.unwrap();
+        assert_eq!(rx.recv().await, Some((2, 6)));
+        assert_eq!(rx.recv().await, Some((1, 5)));
+    }
+
+    #[tokio::test]
+    async fn high_priority_uses_a_bounded_headroom() {
+        let (tx, mut rx) = channel(1, OverflowPolicy::Reject);
+        tx.send(0).await.unwrap();
+
+        assert!(matches!(tx.send(1).await, Err(MailboxSendError::Full(1))));
+        for i in 0..HIGH_PRIORITY_HEADROOM as u32 {
+            tx.send_with_priority(10 + i, Priority::High).await.unwrap();
+        }
+        assert!(matches!(
+            tx.send_with_priority(2, Priority::High).await,
+            Err(MailboxSendError::Full(2))
+        ));
+        assert_eq!(tx.metrics().depth(), 1 + HIGH_PRIORITY_HEADROOM);
+        assert_eq!(tx.metrics().rejected(), 2);
+        assert_eq!(rx.recv().await, Some(10));
+    }
+
+    #[tokio::test]
+    async fn drop_oldest_evicts_the_lowest_priority() {
+        let (tx, mut rx) = channel(2, OverflowPolicy::DropOldest);
+        tx.send(0).await.unwrap();
+        tx.send_with_priority(1, Priority::Low).await.unwrap();
+        tx.send(2).await.unwrap();
+        tx.send_with_priority(3, Priority::Low).await.unwrap();
+
+        assert_eq!(tx.metrics().dropped(), 2);
+        assert_eq!(rx.recv().await, Some(0));
+        assert_eq!(rx.recv().await, Some(2));
+    }
+
+    #[tokio::test]
+    async fn sending_to_a_dropped_receiver_fails() {
+        let (tx, rx) = channel(2, OverflowPolicy::Block);
+        drop(rx);
+
+        assert!(matches!(tx.send(0).await, Err(MailboxSendError::Closed(0))));
+    }
+}
@@ -0,0 +1,289 @@
+use core::fmt::Write;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::compat::vec::Vec;
+
+/// Type of the metrics of a family
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetricType {
+    /// A value which only goes up, like a number of messages
+    Counter,
+    /// A value which goes up and down, like a number of connections
+    Gauge,
+}
+
+impl MetricType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MetricType::Counter => "counter",
+            MetricType::Gauge => "gauge",
+        }
+    }
+}
+
+/// Names and values of the labels of a metric
+type Labels = Vec<(&'static str, String)>;
+
+/// All the metrics sharing a name, distinguished by their labels
+struct Family {
+    help: &'static str,
+    metric_type: MetricType,
+    metrics: BTreeMap<Labels, Arc<AtomicUsize>>,
+}
+
+/// A counter registered in a [`MetricsRegistry`]
+#[derive(Clone, Debug, Default)]
+pub struct Counter(Arc<AtomicUsize>);
+
+impl Counter {
+    /// Increment the counter by 1
+    pub fn inc(&self) {
+        self.add(1)
+    }
+
+    /// Increment the counter by `value`
+    pub fn add(&self, value: usize) {
+        self.0.fetch_add(value, Ordering::Relaxed);
+    }
+
+    /// Current value of the counter
+    pub fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A gauge registered in a [`MetricsRegistry`]
+#[derive(Clone, Debug, Default)]
+pub struct Gauge(Arc<AtomicUsize>);
+
+impl Gauge {
+    /// Increment the gauge by 1
+    pub fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Decrement the gauge by 1, without going below 0
+    pub fn dec(&self) {
+        let _ = self
+            .0
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |v| v.checked_sub(1));
+    }
+
+    /// Set the value of the gauge
+    pub fn set(&self, value: usize) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+
+    /// Current value of the gauge
+    pub fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Registry of the runtime metrics of a node
+///
+/// Metrics are grouped in families sharing a name, and distinguished by
+/// their labels. A node has a single registry, shared by all the
+/// contexts of the node, and available with [`Context::metrics`](crate::Context::metrics).
+/// Handles to counters and gauges can be kept to update them without
+/// looking them up again.
+///
+/// The whole registry can be rendered in the OpenMetrics text format
+/// with [`MetricsRegistry::render`].
+#[derive(Clone)]
+pub struct MetricsRegistry {
+    families: Arc<Mutex<BTreeMap<&'static str, Family>>>,
+}
+
+impl core::fmt::Debug for MetricsRegistry {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MetricsRegistry").finish()
+    }
+}
+
+impl Default for MetricsRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricsRegistry {
+    /// Create a new, empty, registry
+    pub fn new() -> Self {
+        Self {
+            families: Arc::new(Mutex::new(BTreeMap::new())),
+        }
+    }
+
+    /// Return the counter with the given name and labels, registering it if needed
+    pub fn counter(
+        &self,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&'static str, &str)],
+    ) -> Counter {
+        Counter(self.get_or_register(name, help, MetricType::Counter, labels))
+    }
+
+    /// Return the gauge with the given name and labels, registering it if needed
+    pub fn gauge(
+        &self,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&'static str, &str)],
+    ) -> Gauge {
+        Gauge(self.get_or_register(name, help, MetricType::Gauge, labels))
+    }
+
+    /// Remove the metric with the given name and labels.
+    /// Existing handles to that metric are not updated in the registry anymore
+    pub fn remove(&self, name: &'static str, labels: &[(&'static str, &str)]) {
+        let mut families = self.families.lock().unwrap();
+        if let Some(family) = families.get_mut(name) {
+            family.metrics.remove(&to_labels(labels));
+        }
+    }
+
+    /// Remove all the metrics of the given family having a label with the given value
+    pub fn remove_with_label(&self, name: &'static str, label: &'static str, value: &str) {
+        let mut families = self.families.lock().unwrap();
+        if let Some(family) = families.get_mut(name) {
+            family
+                .metrics
+                .retain(|labels, _| !labels.iter().any(|(n, v)| *n == label && v == value));
+        }
+    }
+
+    /// Render all the metrics in the OpenMetrics text format
+    pub fn render(&self) -> String {
+        let families = self.families.lock().unwrap();
+        let mut out = String::new();
+
+        for (name, family) in families.iter() {
+            let _ = writeln!(out, "# TYPE {} {}", name, family.metric_type.as_str());
+            let _ = writeln!(out, "# HELP {} {}", name, family.help);
+            let suffix = match family.metric_type {
+                MetricType::Counter => "_total",
+                MetricType::Gauge => "",
+            };
+            for (labels, value) in family.metrics.iter() {
+                let _ = write!(out, "{}{}", name, suffix);
+                if !labels.is_empty() {
+                    let labels: Vec<String> = labels
+                        .iter()
+                        .map(|(n, v)| format!("{}=\"{}\"", n, escape(v)))
+                        .collect();
+                    let _ = write!(out, "{{{}}}", labels.join(","));
+                }
+                let _ = writeln!(out, " {}", value.load(Ordering::Relaxed));
+            }
+        }
+        out.push_str("# EOF\n");
+
+        out
+    }
+
+    fn get_or_register(
+        &self,
+        name: &'static str,
+        help: &'static str,
+        metric_type: MetricType,
+        labels: &[(&'static str, &str)],
+    ) -> Arc<AtomicUsize> {
+        let mut families = self.families.lock().unwrap();
+        let family = families.entry(name).or_insert_with(|| Family {
+            help,
+            metric_type,
+            metrics: BTreeMap::new(),
+        });
+        debug_assert_eq!(
+            family.metric_type, metric_type,
+            "metric {} registered with another type",
+            name
+        );
+
+        family
+            .metrics
+            .entry(to_labels(labels))
+            .or_insert_with(Default::default)
+            .clone()
+    }
+}
+
+fn to_labels(labels: &[(&'static str, &str)]) -> Labels {
+    labels.iter().map(|(n, v)| (*n, v.to_string())).collect()
+}
+
+/// Escape a label value as required by the OpenMetrics text format
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_openmetrics() {
+        let registry = MetricsRegistry::new();
+        registry
+            .counter("ockam_test_messages", "Messages", &[("address", "a\"b")])
+            .add(3);
+        let gauge = registry.gauge("ockam_test_connections", "Connections", &[]);
+        gauge.inc();
+        gauge.inc();
+        gauge.dec();
+
+        assert_eq!(
+            registry.render(),
+            "# TYPE ockam_test_connections gauge\n\
+             # HELP ockam_test_connections Connections\n\
+             ockam_test_connections 1\n\
+             # TYPE ockam_test_messages counter\n\
+             # HELP ockam_test_messages Messages\n\
+             ockam_test_messages_total{address=\"a\\\"b\"} 3\n\
+             # EOF\n"
+        );
+    }
+
+    #[test]
+    fn handles_share_the_same_metric() {
+        let registry = MetricsRegistry::new();
+        let labels = [("transport", "tcp")];
+        registry
+            .counter("ockam_test_bytes", "Bytes", &labels)
+            .add(2);
+        registry
+            .counter("ockam_test_bytes", "Bytes", &labels)
+            .add(3);
+
+        assert_eq!(
+            registry.counter("ockam_test_bytes", "Bytes", &labels).get(),
+            5
+        );
+
+        registry.remove("ockam_test_bytes", &labels);
+        assert_eq!(
+            registry.counter("ockam_test_bytes", "Bytes", &labels).get(),
+            0
+        );
+    }
+
+    #[test]
+    fn gauge_does_not_go_below_zero() {
+        let gauge = Gauge::default();
+        gauge.dec();
+        assert_eq!(gauge.get(), 0);
+    }
+}
@@ -2,7 +2,10 @@ use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::FlowControls;
 use ockam_core::{Address, AllowAll, Mailbox, Mailboxes};
 
-use crate::{debugger, Context, Executor};
+use crate::channel_types::SmallSender;
+use crate::router::SenderPair;
+use crate::tokio::runtime::Handle;
+use crate::{debugger, Context, DrainRegistry, Executor, MetricsRegistry, NodeMessage};
 
 /// A minimal worker implementation that does nothing
 pub struct NullWorker;
@@ -19,6 +22,8 @@ impl ockam_core::Worker for NullWorker {
 /// builder API to customise the underlying node that is created.
 pub struct NodeBuilder {
     logging: bool,
+    #[cfg(feature = "simulation")]
+    simulation: Option<u64>,
 }
 
 impl Default for NodeBuilder {
@@ -30,12 +35,30 @@ impl Default for NodeBuilder {
 impl NodeBuilder {
     /// Create a node
     pub fn new() -> Self {
-        Self { logging: true }
+        Self {
+            logging: true,
+            #[cfg(feature = "simulation")]
+            simulation: None,
+        }
     }
 
     /// Disable logging on this node
-    pub fn no_logging(self) -> Self {
-        Self { logging: false }
+    pub fn no_logging(mut self) -> Self {
+        self.logging = false;
+        self
+    }
+
+    /// Run the node in a deterministic simulation, with a virtual clock
+    ///
+    /// The root context of the node is given a [`SimulatedNetwork`] drawing
+    /// its random decisions from the given seed, which can start other nodes
+    /// on the same runtime. See the [`simulation`](crate::simulation) module.
+    ///
+    /// [`SimulatedNetwork`]: crate::simulation::SimulatedNetwork
+    #[cfg(feature = "simulation")]
+    pub fn with_simulation(mut self, seed: u64) -> Self {
+        self.simulation = Some(seed);
+        self
     }
 
     /// Consume this builder and yield a new Ockam Node
@@ -49,31 +72,66 @@ impl NodeBuilder {
 
         // Shared instance of FlowControls
         let flow_controls = FlowControls::new();
+        // Shared registry of runtime metrics
+        let metrics = MetricsRegistry::new();
+
+        #[cfg(feature = "simulation")]
+        let mut exe = match self.simulation {
+            Some(_) => {
+                Executor::with_runtime(crate::simulation::runtime(), &flow_controls, &metrics)
+            }
+            None => Executor::new(&flow_controls, &metrics),
+        };
+        #[cfg(not(feature = "simulation"))]
+        let mut exe = Executor::new(&flow_controls, &metrics);
+
+        #[cfg_attr(not(feature = "simulation"), allow(unused_mut))]
+        let (mut ctx, sender) =
+            Self::root_context(exe.runtime(), exe.sender(), &flow_controls, &metrics);
+
+        #[cfg(feature = "simulation")]
+        {
+            ctx.simulation = self
+                .simulation
+                .map(crate::simulation::SimulatedNetwork::new);
+        }
 
-        let mut exe = Executor::new(&flow_controls);
+        // Register this mailbox handle with the executor
+        exe.initialize_system("app", sender);
+
+        // Then return the root context and executor
+        (ctx, exe)
+    }
+
+    /// Create the context of the root application worker of a node
+    pub(crate) fn root_context(
+        rt: &Handle,
+        router_sender: SmallSender<NodeMessage>,
+        flow_controls: &FlowControls,
+        metrics: &MetricsRegistry,
+    ) -> (Context, SenderPair) {
         let addr: Address = "app".into();
 
         // The root application worker needs a mailbox and relay to accept
         // messages from workers, and to buffer incoming transcoded data.
         let (ctx, sender, _) = Context::new(
-            exe.runtime().clone(),
-            exe.sender(),
+            rt.clone(),
+            router_sender,
             Mailboxes::new(
                 Mailbox::new(addr, Arc::new(AllowAll), Arc::new(AllowAll)),
                 vec![],
             ),
             None,
             Default::default(),
-            &flow_controls,
+            flow_controls,
+            metrics,
+            &DrainRegistry::new(),
+            Default::default(),
         );
 
         debugger::log_inherit_context("NODE", &ctx, &ctx);
 
-        // Register this mailbox handle with the executor
-        exe.initialize_system("app", sender);
-
-        // Then return the root context and executor
-        (ctx, exe)
+        (ctx, sender)
     }
 }
 
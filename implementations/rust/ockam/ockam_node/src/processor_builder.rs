@@ -1,5 +1,6 @@
 use crate::debugger;
 use crate::error::{NodeError, NodeReason};
+use crate::message_channel::{MailboxOptions, OverflowPolicy};
 use crate::{relay::ProcessorRelay, Context, NodeMessage};
 use ockam_core::compat::sync::Arc;
 use ockam_core::{
@@ -40,6 +41,7 @@ where
             outgoing_ac: Arc::new(DenyAll),
             processor: self.processor,
             address: address.into(),
+            mailbox_options: MailboxOptions::default(),
         }
     }
 
@@ -48,6 +50,7 @@ where
         ProcessorBuilderMultipleAddresses {
             mailboxes,
             processor: self.processor,
+            mailbox_options: MailboxOptions::default(),
         }
     }
 }
@@ -58,6 +61,7 @@ where
 {
     mailboxes: Mailboxes,
     processor: P,
+    mailbox_options: MailboxOptions,
 }
 
 impl<P> ProcessorBuilderMultipleAddresses<P>
@@ -66,7 +70,25 @@ where
 {
     /// Consume this builder and start a new Ockam [`Processor`] from the given context
     pub async fn start(self, context: &Context) -> Result<()> {
-        start(context, self.mailboxes, self.processor).await
+        start(
+            context,
+            self.mailboxes,
+            self.mailbox_options,
+            self.processor,
+        )
+        .await
+    }
+
+    /// Set the maximum number of messages waiting in the mailbox of the processor
+    pub fn with_mailbox_capacity(mut self, capacity: usize) -> Self {
+        self.mailbox_options.capacity = capacity;
+        self
+    }
+
+    /// Set what happens to the messages sent to the processor when its mailbox is full
+    pub fn with_overflow_policy(mut self, overflow_policy: OverflowPolicy) -> Self {
+        self.mailbox_options.overflow_policy = overflow_policy;
+        self
     }
 }
 
@@ -78,6 +100,7 @@ where
     outgoing_ac: Arc<dyn OutgoingAccessControl>,
     address: Address,
     processor: P,
+    mailbox_options: MailboxOptions,
 }
 
 impl<P> ProcessorBuilderOneAddress<P>
@@ -89,6 +112,7 @@ where
         start(
             context,
             Mailboxes::main(self.address, self.incoming_ac, self.outgoing_ac),
+            self.mailbox_options,
             self.processor,
         )
         .await
@@ -134,10 +158,27 @@ where
         self.outgoing_ac = outgoing_access_control.clone();
         self
     }
+
+    /// Set the maximum number of messages waiting in the mailbox of the processor
+    pub fn with_mailbox_capacity(mut self, capacity: usize) -> Self {
+        self.mailbox_options.capacity = capacity;
+        self
+    }
+
+    /// Set what happens to the messages sent to the processor when its mailbox is full
+    pub fn with_overflow_policy(mut self, overflow_policy: OverflowPolicy) -> Self {
+        self.mailbox_options.overflow_policy = overflow_policy;
+        self
+    }
 }
 
 /// Consume this builder and start a new Ockam [`Processor`] from the given context
-pub async fn start<P>(context: &Context, mailboxes: Mailboxes, processor: P) -> Result<()>
+pub(crate) async fn start<P>(
+    context: &Context,
+    mailboxes: Mailboxes,
+    mailbox_options: MailboxOptions,
+    processor: P,
+) -> Result<()>
 where
     P: Processor<Context = Context>,
 {
@@ -151,7 +192,7 @@ where
     let main_address = mailboxes.main_address().clone();
 
     // Pass it to the context
-    let (ctx, sender, ctrl_rx) = context.copy_with_mailboxes(mailboxes);
+    let (ctx, sender, ctrl_rx) = context.copy_with_mailboxes(mailboxes, mailbox_options);
 
     debugger::log_inherit_context("PROCESSOR", context, &ctx);
 
@@ -4,6 +4,9 @@ mod worker_relay;
 pub use processor_relay::*;
 pub use worker_relay::*;
 
+use ockam_core::{Address, TraceContext};
+use tracing::Span;
+
 /// A signal type used to communicate between router and worker relay
 #[derive(Clone, Debug)]
 pub enum CtrlSignal {
@@ -12,3 +15,26 @@ pub enum CtrlSignal {
     /// Interrupt current message execution and shut down
     InterruptStop,
 }
+
+/// Create the span of one message handling or processing step of a relay
+///
+/// `parent` is the tracing context the step was started with, and `current`
+/// the context of the new span, attached to the messages sent during that step.
+/// The ids of the trace and of the spans are recorded as span fields, so that
+/// a trace can be followed across the logs of the nodes. The fields use the
+/// OpenTelemetry naming, but the node does not export the spans itself.
+pub(crate) fn trace_span(
+    name: &'static str,
+    address: &Address,
+    parent: &TraceContext,
+    current: &TraceContext,
+) -> Span {
+    info_span!(
+        "ockam",
+        otel.name = name,
+        address = %address,
+        trace_id = %parent.trace_id(),
+        span_id = %current.parent_id(),
+        parent_span_id = %parent.parent_id(),
+    )
+}
@@ -1,6 +1,8 @@
 use crate::channel_types::SmallReceiver;
-use crate::{relay::CtrlSignal, tokio::runtime::Handle, Context};
+use crate::relay::{trace_span, CtrlSignal};
+use crate::{tokio::runtime::Handle, Context};
 use ockam_core::{Processor, Result};
+use tracing::Instrument;
 
 pub struct ProcessorRelay<P>
 where
@@ -46,7 +48,21 @@ where
                 // protect against accidental async executor deadlock
                 crate::tokio::task::yield_now().await;
 
-                match processor.process(&mut ctx).await {
+                // Processors which started a trace process within a span of that trace
+                let result = match ctx.tracing_context().cloned() {
+                    Some(parent) => {
+                        let current = parent.new_span();
+                        let span = trace_span("process", &ctx_addr, &parent, &current);
+
+                        ctx.set_tracing_context(Some(current));
+                        let result = processor.process(&mut ctx).instrument(span).await;
+                        ctx.set_tracing_context(Some(parent));
+                        result
+                    }
+                    None => processor.process(&mut ctx).await,
+                };
+
+                match result {
                     Ok(should_continue) => {
                         if !should_continue {
                             break;
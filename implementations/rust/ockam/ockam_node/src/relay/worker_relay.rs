@@ -1,8 +1,10 @@
 use crate::channel_types::SmallReceiver;
-use crate::relay::CtrlSignal;
+use crate::context::tracing_context_enabled;
+use crate::relay::{trace_span, CtrlSignal};
 use crate::tokio::runtime::Handle;
-use crate::{parser, Context};
-use ockam_core::{Message, RelayMessage, Result, Routed, Worker};
+use crate::{parser, Context, MessageId};
+use ockam_core::{Message, Priority, RelayMessage, Result, Routed, Worker};
+use tracing::Instrument;
 
 /// Worker relay machinery
 ///
@@ -69,14 +71,112 @@ where
             }
         };
 
-        // Call the worker handle function - pass errors up
-        let routed = Self::wrap_direct_message(relay_msg)?;
-        self.worker.handle_message(&mut self.ctx, routed).await?;
+        self.dispatch(relay_msg).await?;
 
         // Signal to the outer loop that we would like to run again
         Ok(true)
     }
 
+    /// Let the worker handle a message, then acknowledge it if the
+    /// mailbox of the worker is durable
+    async fn dispatch(&mut self, relay_msg: RelayMessage) -> Result<()> {
+        let durable = self.ctx.durable_mailbox();
+        let id = match &durable {
+            Some(durable) => match MessageId::find_info(relay_msg.local_message()) {
+                Ok(id) => {
+                    // The message was already redelivered from the durable mailbox
+                    if durable.is_acknowledged(&id).await? {
+                        return Ok(());
+                    }
+                    Some(id)
+                }
+                Err(_) => None,
+            },
+            None => None,
+        };
+
+        // Call the worker handle function - pass errors up
+        let routed = Self::wrap_direct_message(relay_msg)?;
+        // Messages sent while handling this message inherit its priority
+        self.ctx.set_inherited_priority(routed.priority());
+        let result = self.handle_message(routed).await;
+        self.ctx.set_inherited_priority(Priority::default());
+
+        // A message which failed to be handled stays in the durable mailbox,
+        // to be handled again when the worker is restarted or when it is sent
+        // again with the same id
+        if let (Some(durable), Some(id)) = (durable, id) {
+            match &result {
+                Ok(()) => durable.acknowledge(&id).await?,
+                Err(_) => durable.fail(&id).await,
+            }
+        }
+
+        result
+    }
+
+    /// Handle the messages left in the durable mailbox of the worker, if
+    /// any, by a previous run of the node
+    async fn redeliver(&mut self) -> Result<()> {
+        let pending = match self.ctx.durable_mailbox() {
+            Some(durable) => durable.pending().await?,
+            None => return Ok(()),
+        };
+
+        if !pending.is_empty() {
+            debug!(
+                "Redelivering {} messages to worker {}",
+                pending.len(),
+                self.ctx.address()
+            );
+        }
+        for relay_msg in pending {
+            if let Err(e) = self.dispatch(relay_msg).await {
+                error!(
+                    "Error encountered during '{}' message redelivery: {}",
+                    self.ctx.address(),
+                    e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Let the worker handle a message, within a child span of its tracing context if any
+    async fn handle_message(&mut self, routed: Routed<M>) -> Result<()> {
+        let parent = routed.tracing_context().cloned();
+        match parent {
+            // Handle the message within a child span of the span which sent it
+            Some(parent) => {
+                let current = parent.new_span();
+                let span = trace_span("handle_message", &self.ctx.address(), &parent, &current);
+
+                // The messages sent by the worker only carry the tracing context,
+                // and need a newer version of the transport messages, if the
+                // propagation of tracing contexts is enabled on this node too
+                if !tracing_context_enabled() {
+                    return self
+                        .worker
+                        .handle_message(&mut self.ctx, routed)
+                        .instrument(span)
+                        .await;
+                }
+
+                let previous = self.ctx.tracing_context().cloned();
+                self.ctx.set_tracing_context(Some(current));
+                let result = self
+                    .worker
+                    .handle_message(&mut self.ctx, routed)
+                    .instrument(span)
+                    .await;
+                self.ctx.set_tracing_context(previous);
+                result
+            }
+            None => self.worker.handle_message(&mut self.ctx, routed).await,
+        }
+    }
+
     #[cfg_attr(not(feature = "std"), allow(unused_mut))]
     #[cfg_attr(not(feature = "std"), allow(unused_variables))]
     async fn run(mut self, mut ctrl_rx: SmallReceiver<CtrlSignal>) {
@@ -97,6 +197,10 @@ where
             error!("Failed to mark worker '{}' as 'ready': {}", address, e);
         }
 
+        if let Err(e) = self.redeliver().await {
+            error!("Failed to redeliver the messages of '{}': {}", address, e);
+        }
+
         #[cfg(feature = "std")]
         loop {
             crate::tokio::select! {
@@ -17,7 +17,7 @@ use crate::channel_types::{router_channel, MessageSender, RouterReceiver, SmallS
 use crate::{
     error::{NodeError, NodeReason},
     relay::CtrlSignal,
-    NodeMessage, NodeReplyResult, RouterReply, ShutdownType,
+    Gauge, MetricsRegistry, NodeMessage, NodeReplyResult, RouterReply, ShutdownType,
 };
 use ockam_core::compat::{collections::BTreeMap, sync::Arc};
 use ockam_core::flow_control::FlowControls;
@@ -48,8 +48,15 @@ pub struct Router {
     external: BTreeMap<TransportType, Address>,
     /// Receiver for messages from node
     receiver: Option<RouterReceiver<NodeMessage>>,
+    /// Number of messages waiting to be handled by the router
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    queue_depth: Gauge,
 }
 
+/// Number of messages waiting to be handled by the router
+const QUEUE_DEPTH: &str = "ockam_node_router_queue_depth";
+const QUEUE_DEPTH_HELP: &str = "Number of messages waiting to be handled by the router";
+
 enum RouteType {
     Internal,
     External(TransportType),
@@ -64,13 +71,14 @@ fn determine_type(next: &Address) -> RouteType {
 }
 
 impl Router {
-    pub fn new(flow_controls: &FlowControls) -> Self {
+    pub fn new(flow_controls: &FlowControls, metrics: &MetricsRegistry) -> Self {
         let (sender, receiver) = router_channel();
         Self {
             state: RouterState::new(sender),
-            map: InternalMap::new(flow_controls),
+            map: InternalMap::new(flow_controls, metrics),
             external: BTreeMap::new(),
             receiver: Some(receiver),
+            queue_depth: metrics.gauge(QUEUE_DEPTH, QUEUE_DEPTH_HELP, &[]),
         }
     }
 
@@ -183,14 +191,18 @@ impl Router {
                 detached,
                 mailbox_count,
                 ref reply,
-            } => start_worker::exec(self, addrs, senders, detached, mailbox_count, reply).await?,
+            } => {
+                start_worker::exec(self, addrs, senders, detached, mailbox_count, reply).await?;
+                self.map.record_worker_counts();
+            }
             StopWorker(ref addr, ref detached, ref reply) => {
                 stop_worker::exec(self, addr, *detached, reply).await?
             }
 
             //// ==! Basic processor control
             StartProcessor(addr, senders, ref reply) => {
-                start_processor::exec(self, addr, senders, reply).await?
+                start_processor::exec(self, addr, senders, reply).await?;
+                self.map.record_worker_counts();
             }
             StopProcessor(ref addr, ref reply) => stop_processor::exec(self, addr, reply).await?,
 
@@ -226,6 +238,7 @@ impl Router {
             StopAck(addr) if self.state.running() => {
                 trace!("Received shutdown ACK for address {}", addr);
                 self.map.free_address(addr);
+                self.map.record_worker_counts();
             }
 
             StopAck(addr) => {
@@ -251,6 +264,7 @@ impl Router {
             SetCluster(addr, label, reply) => {
                 debug!("Setting cluster on address {}", addr);
                 let msg = self.map.set_cluster(label, addr);
+                self.map.record_worker_counts();
                 reply
                     .send(msg)
                     .await
@@ -298,6 +312,9 @@ impl Router {
     /// Block current task running this router.  Return fatal errors
     async fn run_inner(&mut self) -> Result<()> {
         while let Some(msg) = self.get_recv()?.recv().await {
+            #[cfg(feature = "std")]
+            self.queue_depth
+                .set(self.state.sender.max_capacity() - self.state.sender.capacity());
             let msg_str = format!("{}", msg);
             match self.handle_msg(msg).await {
                 Ok(should_break) => {
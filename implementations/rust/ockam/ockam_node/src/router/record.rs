@@ -2,13 +2,13 @@ use crate::channel_types::{MessageSender, SmallSender};
 use crate::relay::CtrlSignal;
 use crate::{
     error::{NodeError, NodeReason},
-    NodeReplyResult, RouterReply,
+    MetricsRegistry, NodeReplyResult, RouterReply,
 };
 use core::sync::atomic::{AtomicUsize, Ordering};
 use ockam_core::{
     compat::{
         collections::{BTreeMap, BTreeSet},
-        string::String,
+        string::{String, ToString},
         sync::Arc,
         vec::Vec,
     },
@@ -16,6 +16,13 @@ use ockam_core::{
     Address, RelayMessage, Result,
 };
 
+/// Number of workers and processors per cluster
+const WORKERS: &str = "ockam_node_workers";
+const WORKERS_HELP: &str = "Number of workers and processors per cluster";
+/// Number of messages routed per address
+const MESSAGES_ROUTED: &str = "ockam_node_messages_routed";
+const MESSAGES_ROUTED_HELP: &str = "Number of messages routed to a worker or processor";
+
 /// Address states and associated logic
 pub struct InternalMap {
     /// Registry of primary address to worker address record state
@@ -30,13 +37,15 @@ pub struct InternalMap {
     stopping: BTreeSet<Address>,
     /// Access to [`FlowControls`] to clean resources
     flow_controls: FlowControls,
+    /// Registry of the runtime metrics of the node
+    registry: MetricsRegistry,
     /// Metrics collection and sharing
     #[cfg(feature = "metrics")]
     metrics: (Arc<AtomicUsize>, Arc<AtomicUsize>),
 }
 
 impl InternalMap {
-    pub(super) fn new(flow_controls: &FlowControls) -> Self {
+    pub(super) fn new(flow_controls: &FlowControls, registry: &MetricsRegistry) -> Self {
         Self {
             address_records_map: Default::default(),
             alias_map: Default::default(),
@@ -44,6 +53,7 @@ impl InternalMap {
             clusters: Default::default(),
             stopping: Default::default(),
             flow_controls: flow_controls.clone(),
+            registry: registry.clone(),
             #[cfg(feature = "metrics")]
             metrics: Default::default(),
         }
@@ -75,6 +85,8 @@ impl InternalMap {
         primary_address: &Address,
     ) -> Option<AddressRecord> {
         self.flow_controls.cleanup_address(primary_address);
+        self.registry
+            .remove_with_label(MESSAGES_ROUTED, "address", &primary_address.to_string());
         self.address_records_map.remove(primary_address)
     }
 
@@ -121,6 +133,42 @@ impl InternalMap {
         self.metrics.0.load(Ordering::Acquire)
     }
 
+    /// Count a message routed to a worker or processor
+    pub(super) fn record_message_routed(&self, primary_address: &Address) {
+        self.registry
+            .counter(
+                MESSAGES_ROUTED,
+                MESSAGES_ROUTED_HELP,
+                &[("address", &primary_address.to_string())],
+            )
+            .inc();
+    }
+
+    /// Update the number of workers and processors per cluster.
+    /// Those which are not part of a cluster are counted with an empty cluster name
+    pub(super) fn record_worker_counts(&self) {
+        let mut clustered = BTreeSet::new();
+        for (label, addresses) in self.clusters.iter() {
+            let count = addresses
+                .iter()
+                .filter(|a| self.address_records_map.contains_key(a))
+                .count();
+            clustered.extend(addresses.iter());
+            self.registry
+                .gauge(WORKERS, WORKERS_HELP, &[("cluster", label)])
+                .set(count);
+        }
+
+        let non_clustered = self
+            .address_records_map
+            .iter()
+            .filter(|(addr, rec)| !rec.meta.detached && !clustered.contains(addr))
+            .count();
+        self.registry
+            .gauge(WORKERS, WORKERS_HELP, &[("cluster", "")])
+            .set(non_clustered);
+    }
+
     /// Add an address to a particular cluster
     pub(super) fn set_cluster(&mut self, label: String, primary: Address) -> NodeReplyResult {
         let rec = self
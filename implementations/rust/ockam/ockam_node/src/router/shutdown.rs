@@ -26,24 +26,29 @@ pub(super) async fn ack(router: &mut Router, addr: Address) -> Result<bool> {
 }
 
 async fn stop_next_cluster(r: &mut Router) -> Result<bool> {
-    match r.map.next_cluster() {
-        Some(mut vec) => {
-            let mut addrs = vec![];
-
-            for record in vec.iter_mut() {
-                record.stop().await?;
-                if let Some(first_address) = record.address_set().first().cloned() {
-                    addrs.push(first_address);
-                } else {
-                    error!("Empty Address Set during cluster stop");
+    loop {
+        let mut addrs = vec![];
+        match r.map.next_cluster() {
+            Some(mut vec) => {
+                for record in vec.iter_mut() {
+                    record.stop().await?;
+                    if let Some(first_address) = record.address_set().first().cloned() {
+                        addrs.push(first_address);
+                    } else {
+                        error!("Empty Address Set during cluster stop");
+                    }
                 }
             }
+            // If not, we are done!
+            None => return Ok(true),
+        }
 
+        // The workers of a cluster may all be stopped already, in which case
+        // no ACK will come and we go on with the next cluster
+        if !addrs.is_empty() {
             addrs.into_iter().for_each(|addr| r.map.init_stop(addr));
-            Ok(false)
+            return Ok(false);
         }
-        // If not, we are done!
-        None => Ok(true),
     }
 }
 
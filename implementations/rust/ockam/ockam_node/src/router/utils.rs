@@ -34,6 +34,7 @@ pub(super) async fn resolve(
         Some(record) if record.check() => {
             trace!("{} OK", base);
             record.increment_msg_count();
+            router.map.record_message_routed(&primary_address);
             reply.send(RouterReply::sender(addr.clone(), record.sender()))
         }
         Some(_) => {
@@ -1,10 +1,11 @@
+use crate::api::ResponseStream;
 use crate::{Context, MessageSendReceiveOptions};
 use core::time::Duration;
 use minicbor::{Decode, Decoder, Encode};
-use ockam_core::api::{RequestBuilder, Response, Status};
-use ockam_core::compat::{fmt, vec::Vec};
+use ockam_core::api::{decode_body, Endpoint, RequestBuilder, Response, Status, StreamEndpoint};
+use ockam_core::compat::{fmt, sync::Arc, vec::Vec};
 use ockam_core::errcode::{Kind, Origin};
-use ockam_core::{Address, DenyAll, Error, Result, Route};
+use ockam_core::{Address, AllowOnwardAddress, DenyAll, Error, Result, Route};
 
 const DEFAULT_CLIENT_TIMEOUT: Duration = Duration::from_secs(30);
 
@@ -89,10 +90,68 @@ impl RpcClient {
             Err(error("request", &resp, &mut d))
         }
     }
+
+    /// Send a request to an endpoint of the server, with the given values of
+    /// the endpoint path parameters, and return the body of its response.
+    ///
+    /// The types of the request and response bodies are the ones of the endpoint.
+    pub async fn call<E: Endpoint>(
+        &self,
+        params: &[&str],
+        body: E::Request,
+    ) -> Result<E::Response> {
+        let buf = E::request(params, body)?.to_vec()?;
+        let vec = self
+            .ctx
+            .send_and_receive_extended::<Vec<u8>>(self.route.clone(), buf, self.options())
+            .await?
+            .body();
+        let mut d = Decoder::new(&vec);
+        let resp: Response = d.decode()?;
+        if resp.status() == Some(Status::Ok) {
+            decode_body(&mut d, resp.has_body())
+        } else {
+            Err(error(E::PATH, &resp, &mut d))
+        }
+    }
+
+    /// Encode request header and body (if any), send the package to the server
+    /// and return the stream of the chunks of its response.
+    ///
+    /// Each chunk is awaited at most for the timeout of the client, unless
+    /// the stream is set [`without_timeout`](ResponseStream::without_timeout).
+    pub async fn request_stream<T, R>(&self, req: &RequestBuilder<T>) -> Result<ResponseStream<R>>
+    where
+        T: Encode<()>,
+        R: for<'a> Decode<'a, ()>,
+    {
+        let buf = req.to_vec()?;
+        let next = self.route.next()?.clone();
+        let ctx = self
+            .ctx
+            .new_detached_for_route(
+                "RpcClient.stream",
+                &next,
+                Arc::new(AllowOnwardAddress(next.clone())),
+            )
+            .await?;
+        ctx.send(self.route.clone(), buf).await?;
+        Ok(ResponseStream::new(ctx, req.header().id(), self.timeout))
+    }
+
+    /// Send a request to a streaming endpoint of the server, with the given values
+    /// of the endpoint path parameters, and return the stream of its response chunks.
+    pub async fn call_stream<E: StreamEndpoint>(
+        &self,
+        params: &[&str],
+        body: E::Request,
+    ) -> Result<ResponseStream<E::Response>> {
+        self.request_stream(&E::request(params, body)?).await
+    }
 }
 
 /// Decode, log and map response error to ockam_core error.
-fn error(label: &str, res: &Response, dec: &mut Decoder<'_>) -> Error {
+pub(crate) fn error(label: &str, res: &Response, dec: &mut Decoder<'_>) -> Error {
     if res.has_body() {
         let err = match dec.decode::<ockam_core::api::Error>() {
             Ok(e) => e,
@@ -0,0 +1,61 @@
+//! Deterministic simulation of multi-node topologies
+//!
+//! A node built with [`NodeBuilder::with_simulation`](crate::NodeBuilder::with_simulation)
+//! runs on a single threaded runtime with a virtual clock: timers don't wait
+//! for the wall clock, and time jumps forward whenever all the tasks of the
+//! runtime are waiting. Tests relying on timeouts, heartbeats or retries run
+//! instantly and always schedule their tasks in the same order.
+//!
+//! Nodes of the same runtime are connected by a [`SimulatedNetwork`], an
+//! in-process transport with configurable latency, jitter (which reorders
+//! messages), loss, and partitions. All the random decisions of the network
+//! are drawn from a seeded generator, so that a failing scenario can be
+//! reproduced from its seed.
+//!
+//! Tasks doing blocking I/O, like file storage, still run on other threads
+//! and may let the virtual time advance while they are waiting.
+//!
+//! ```rust,ignore
+//! #[ockam_macros::test(simulate, seed = 42)]
+//! async fn relay_failover(ctx: &mut Context) -> Result<()> {
+//!     let network = ctx.simulated_network().unwrap();
+//!     network.add_node(ctx, "a").await?;
+//!     let node_b = network.start_node(ctx, "b").await?;
+//!     network.partition("a", "b");
+//!     // ...
+//!     ctx.stop().await
+//! }
+//! ```
+mod network;
+mod workers;
+
+pub use network::*;
+
+use crate::tokio::runtime::{Builder, Runtime};
+
+/// Environment variable setting the seed of the simulations
+/// which are not given an explicit seed
+pub const SIMULATION_SEED_ENV: &str = "OCKAM_SIMULATION_SEED";
+
+/// Return the seed set with the `OCKAM_SIMULATION_SEED` environment variable,
+/// or a random seed.
+///
+/// The seed is printed so that a failing simulation can be run again with the same seed.
+pub fn seed_from_env() -> u64 {
+    let seed = ockam_core::env::get_env::<u64>(SIMULATION_SEED_ENV)
+        .ok()
+        .flatten()
+        .unwrap_or_else(ockam_core::compat::rand::random);
+    eprintln!("Simulation seed: {}", seed);
+    seed
+}
+
+/// Create a single threaded runtime with a paused clock, which
+/// advances automatically when all the tasks are waiting
+pub(crate) fn runtime() -> Runtime {
+    Builder::new_current_thread()
+        .enable_time()
+        .start_paused(true)
+        .build()
+        .unwrap()
+}
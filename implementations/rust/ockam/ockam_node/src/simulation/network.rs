@@ -0,0 +1,309 @@
+use super::workers::{Delivery, SimulatedLink, SimulatedReceiver};
+use crate::router::Router;
+use crate::tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use crate::{Context, MetricsRegistry, NodeBuilder, ProcessorBuilder};
+use core::fmt;
+use core::time::Duration;
+use ockam_core::compat::collections::{BTreeMap, BTreeSet};
+use ockam_core::compat::rand::{prelude::SeedableRng, rngs::StdRng, Rng};
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::flow_control::FlowControls;
+use ockam_core::{Address, AllowAll, DenyAll, Error, Result, TransportMessage};
+
+/// Conditions of the messages sent from one simulated node to another
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LinkConditions {
+    latency: Duration,
+    jitter: Duration,
+    loss: f64,
+}
+
+impl LinkConditions {
+    /// Messages are delivered instantly, and never lost
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Delay every message by the given latency
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Delay every message by a random duration, up to the given jitter,
+    /// on top of the latency. A message can then overtake the messages sent before it
+    pub fn with_jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Lose messages with the given probability, between 0 and 1
+    pub fn with_loss(mut self, loss: f64) -> Self {
+        self.loss = loss.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Fixed delay of the messages
+    pub fn latency(&self) -> Duration {
+        self.latency
+    }
+
+    /// Maximum random delay of the messages, on top of the latency
+    pub fn jitter(&self) -> Duration {
+        self.jitter
+    }
+
+    /// Probability of losing a message
+    pub fn loss(&self) -> f64 {
+        self.loss
+    }
+}
+
+/// A node connected to the network
+struct SimulatedNode {
+    /// Detached context used to start the links to the nodes joining later
+    ctx: Arc<Context>,
+    /// Messages delivered to this node
+    incoming: UnboundedSender<Delivery>,
+}
+
+struct NetworkState {
+    rng: StdRng,
+    nodes: BTreeMap<String, SimulatedNode>,
+    default_conditions: LinkConditions,
+    conditions: BTreeMap<(String, String), LinkConditions>,
+    partitions: BTreeSet<(String, String)>,
+}
+
+impl NetworkState {
+    fn conditions(&self, from: &str, to: &str) -> LinkConditions {
+        self.conditions
+            .get(&(from.to_string(), to.to_string()))
+            .copied()
+            .unwrap_or(self.default_conditions)
+    }
+
+    fn is_partitioned(&self, from: &str, to: &str) -> bool {
+        self.partitions.contains(&pair(from, to))
+    }
+}
+
+/// In-process network between simulated nodes
+///
+/// Every node joining the network gets a link worker to every other node,
+/// at the address returned by [`SimulatedNetwork::link_address`]. Sending a
+/// message to `route![SimulatedNetwork::link_address("b"), "echoer"]` delivers
+/// it to the `echoer` worker of node `b`, with a return route going through
+/// the link of node `b` back to the sending node.
+///
+/// The delivery of the messages depends on the [`LinkConditions`] of each
+/// direction of a link, and on the partitions of the network. All the random
+/// decisions are drawn from a generator seeded with the seed of the network.
+#[derive(Clone)]
+pub struct SimulatedNetwork {
+    seed: u64,
+    state: Arc<Mutex<NetworkState>>,
+}
+
+impl fmt::Debug for SimulatedNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SimulatedNetwork")
+            .field("seed", &self.seed)
+            .finish()
+    }
+}
+
+impl SimulatedNetwork {
+    /// Create a network without nodes, drawing its random decisions from the given seed
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            state: Arc::new(Mutex::new(NetworkState {
+                rng: StdRng::seed_from_u64(seed),
+                nodes: BTreeMap::new(),
+                default_conditions: LinkConditions::default(),
+                conditions: BTreeMap::new(),
+                partitions: BTreeSet::new(),
+            })),
+        }
+    }
+
+    /// Seed of the random decisions of the network
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Local address of the link to the node with the given name
+    pub fn link_address(node: &str) -> Address {
+        Address::from_string(format!("simulation.{}", node))
+    }
+
+    /// Connect the node of the given context to the network, with the given name
+    pub async fn add_node(&self, ctx: &Context, name: &str) -> Result<()> {
+        if self.state.lock().unwrap().nodes.contains_key(name) {
+            return Err(Error::new(
+                Origin::Node,
+                Kind::AlreadyExists,
+                format!("the simulated node {} already exists", name),
+            ));
+        }
+
+        let node_ctx = ctx
+            .new_detached(
+                Address::random_tagged("SimulatedNetwork.node"),
+                DenyAll,
+                DenyAll,
+            )
+            .await?;
+
+        let (incoming, receiver) = unbounded_channel();
+        ProcessorBuilder::new(SimulatedReceiver::new(receiver))
+            .with_address(Address::random_tagged("SimulatedNetwork.receiver"))
+            .with_outgoing_access_control(AllowAll)
+            .start(ctx)
+            .await?;
+
+        let peers: Vec<(String, Arc<Context>)> = {
+            let mut state = self.state.lock().unwrap();
+            let peers = state
+                .nodes
+                .iter()
+                .map(|(peer, node)| (peer.clone(), node.ctx.clone()))
+                .collect();
+            state.nodes.insert(
+                name.to_string(),
+                SimulatedNode {
+                    ctx: Arc::new(node_ctx),
+                    incoming,
+                },
+            );
+            peers
+        };
+
+        for (peer, peer_ctx) in peers {
+            self.start_link(ctx, name, &peer).await?;
+            self.start_link(&peer_ctx, &peer, name).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Start a new node on the runtime of the given context, and connect it
+    /// to the network with the given name. Return the root context of the new node
+    pub async fn start_node(&self, ctx: &Context, name: &str) -> Result<Context> {
+        let flow_controls = FlowControls::new();
+        let metrics = MetricsRegistry::new();
+        let mut router = Router::new(&flow_controls, &metrics);
+
+        let (mut node_ctx, senders) =
+            NodeBuilder::root_context(ctx.runtime(), router.sender(), &flow_controls, &metrics);
+        node_ctx.simulation = Some(self.clone());
+        router.init("app".into(), senders);
+
+        ctx.runtime().spawn(async move {
+            if let Err(e) = router.run().await {
+                error!("Simulated node router failed: {}", e);
+            }
+        });
+
+        self.add_node(&node_ctx, name).await?;
+        Ok(node_ctx)
+    }
+
+    /// Set the conditions of all the links without specific conditions
+    pub fn set_default_conditions(&self, conditions: LinkConditions) {
+        self.state.lock().unwrap().default_conditions = conditions;
+    }
+
+    /// Set the conditions of the messages sent from one node to another
+    pub fn set_link_conditions(&self, from: &str, to: &str, conditions: LinkConditions) {
+        self.state
+            .lock()
+            .unwrap()
+            .conditions
+            .insert((from.to_string(), to.to_string()), conditions);
+    }
+
+    /// Drop all the messages exchanged between two nodes, in both directions
+    pub fn partition(&self, node1: &str, node2: &str) {
+        self.state
+            .lock()
+            .unwrap()
+            .partitions
+            .insert(pair(node1, node2));
+    }
+
+    /// Deliver the messages exchanged between two partitioned nodes again
+    pub fn heal(&self, node1: &str, node2: &str) {
+        self.state
+            .lock()
+            .unwrap()
+            .partitions
+            .remove(&pair(node1, node2));
+    }
+
+    /// Deliver the messages exchanged between all the partitioned nodes again
+    pub fn heal_all(&self) {
+        self.state.lock().unwrap().partitions.clear();
+    }
+
+    /// Deliver a message from one node to another, according to the link conditions
+    pub(super) fn transmit(&self, from: &str, to: &str, message: TransportMessage) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let incoming = match state.nodes.get(to) {
+            Some(node) => node.incoming.clone(),
+            None => {
+                return Err(Error::new(
+                    Origin::Node,
+                    Kind::NotFound,
+                    format!("the simulated node {} doesn't exist", to),
+                ))
+            }
+        };
+
+        if state.is_partitioned(from, to) {
+            trace!("Message from {} to {} dropped by a partition", from, to);
+            return Ok(());
+        }
+
+        let conditions = state.conditions(from, to);
+        if conditions.loss > 0.0 && state.rng.gen_bool(conditions.loss) {
+            trace!("Message from {} to {} lost", from, to);
+            return Ok(());
+        }
+
+        let mut delay = conditions.latency;
+        if conditions.jitter > Duration::ZERO {
+            delay += state.rng.gen_range(Duration::ZERO..=conditions.jitter);
+        }
+
+        let delivery = Delivery::new(from.to_string(), message);
+        if delay == Duration::ZERO {
+            let _ = incoming.send(delivery);
+        } else {
+            crate::tokio::spawn(async move {
+                crate::tokio::time::sleep(delay).await;
+                let _ = incoming.send(delivery);
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn start_link(&self, ctx: &Context, from: &str, to: &str) -> Result<()> {
+        let link = SimulatedLink::new(self.clone(), from.to_string(), to.to_string());
+        ctx.start_worker(Self::link_address(to), link).await
+    }
+}
+
+/// Key of the partition between two nodes, independent of their order
+fn pair(node1: &str, node2: &str) -> (String, String) {
+    if node1 <= node2 {
+        (node1.to_string(), node2.to_string())
+    } else {
+        (node2.to_string(), node1.to_string())
+    }
+}
@@ -0,0 +1,86 @@
+use super::SimulatedNetwork;
+use crate::tokio::sync::mpsc::UnboundedReceiver;
+use crate::Context;
+use ockam_core::compat::string::String;
+use ockam_core::{
+    async_trait, Any, LocalMessage, Processor, Result, Routed, TransportMessage, Worker,
+};
+
+/// A message in transit on the simulated network
+pub(super) struct Delivery {
+    from: String,
+    message: TransportMessage,
+}
+
+impl Delivery {
+    pub(super) fn new(from: String, message: TransportMessage) -> Self {
+        Self { from, message }
+    }
+}
+
+/// Worker sending the messages it receives to another simulated node
+pub(super) struct SimulatedLink {
+    network: SimulatedNetwork,
+    from: String,
+    to: String,
+}
+
+impl SimulatedLink {
+    pub(super) fn new(network: SimulatedNetwork, from: String, to: String) -> Self {
+        Self { network, from, to }
+    }
+}
+
+#[async_trait]
+impl Worker for SimulatedLink {
+    type Message = Any;
+    type Context = Context;
+
+    async fn handle_message(&mut self, _ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
+        let mut message = msg.into_transport_message();
+        // Remove the address of this link from the onward route
+        message.onward_route.step()?;
+
+        self.network.transmit(&self.from, &self.to, message)
+    }
+}
+
+/// Processor forwarding the messages delivered to a simulated node
+pub(super) struct SimulatedReceiver {
+    incoming: UnboundedReceiver<Delivery>,
+}
+
+impl SimulatedReceiver {
+    pub(super) fn new(incoming: UnboundedReceiver<Delivery>) -> Self {
+        Self { incoming }
+    }
+}
+
+#[async_trait]
+impl Processor for SimulatedReceiver {
+    type Context = Context;
+
+    async fn process(&mut self, ctx: &mut Context) -> Result<bool> {
+        let Delivery { from, mut message } = match self.incoming.recv().await {
+            Some(delivery) => delivery,
+            None => return Ok(false),
+        };
+
+        if message.onward_route.next().is_err() {
+            warn!("Simulated message from {} without onward route", from);
+            return Ok(true);
+        }
+
+        // Replies go back through the link to the sending node
+        message
+            .return_route
+            .modify()
+            .prepend(SimulatedNetwork::link_address(&from));
+
+        if let Err(e) = ctx.forward(LocalMessage::new(message, vec![])).await {
+            warn!("Failed to forward a simulated message from {}: {}", from, e);
+        }
+
+        Ok(true)
+    }
+}
@@ -1,4 +1,5 @@
-use ockam_core::compat::string::String;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::Address;
 
 /// This trait needs to be implemented by structs which are used as keys in a key/value file
 /// This constraint is necessary due to the persistence of values as JSON in the underlying
@@ -7,3 +8,9 @@ pub trait ToStringKey {
     /// Return a string representation to be used as a key in a JSON map
     fn to_string_key(&self) -> String;
 }
+
+impl ToStringKey for Address {
+    fn to_string_key(&self) -> String {
+        self.to_string()
+    }
+}
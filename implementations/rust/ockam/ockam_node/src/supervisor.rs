@@ -0,0 +1,368 @@
+use crate::compat::futures::FutureExt;
+use crate::tokio::sync::broadcast;
+use crate::Context;
+use core::future::Future;
+use core::panic::AssertUnwindSafe;
+use core::time::Duration;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::collections::VecDeque;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::{async_trait, Address, Result, Routed, Worker};
+use std::time::Instant;
+
+/// Number of lifecycle events kept for slow subscribers of a [`Supervisor`]
+const EVENTS_CAPACITY: usize = 64;
+
+/// Restart policy of the workers started under a [`Supervisor`]
+///
+/// A failed worker is restarted on its own, with an exponential backoff
+/// between consecutive restarts. The supervisor gives up, and stops the
+/// worker, when it was restarted more than `max_restarts` times within `window`.
+#[derive(Clone, Debug)]
+pub struct RestartPolicy {
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    max_restarts: usize,
+    window: Duration,
+    restart_on_errors: bool,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            max_restarts: 5,
+            window: Duration::from_secs(60),
+            restart_on_errors: true,
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Default policy: up to 5 restarts per minute, with a backoff from 100ms to 10s
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the delay before the first restart, doubled for every
+    /// following restart up to `max`
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Set the maximum number of restarts allowed within `window`
+    pub fn with_max_restarts(mut self, max_restarts: usize, window: Duration) -> Self {
+        self.max_restarts = max_restarts;
+        self.window = window;
+        self
+    }
+
+    /// Only restart the worker when it panics or fails to initialize
+    ///
+    /// Errors returned by `handle_message` are then handled as for a worker
+    /// which is not supervised: they are logged and the worker keeps running.
+    pub fn without_restart_on_errors(mut self) -> Self {
+        self.restart_on_errors = false;
+        self
+    }
+
+    /// Delay before the given restart, starting at 1
+    fn backoff(&self, restart: usize) -> Duration {
+        let exponent = restart.saturating_sub(1).min(31) as u32;
+        self.initial_backoff
+            .saturating_mul(1 << exponent)
+            .min(self.max_backoff)
+    }
+}
+
+/// Lifecycle event of a worker started under a [`Supervisor`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SupervisorEvent {
+    /// The worker was initialized
+    Started {
+        /// Main address of the worker
+        address: Address,
+    },
+    /// The worker returned an error or panicked
+    Failed {
+        /// Main address of the worker
+        address: Address,
+        /// Error returned by the worker, or panic message
+        reason: String,
+    },
+    /// A new instance of the worker was initialized after a failure
+    Restarted {
+        /// Main address of the worker
+        address: Address,
+        /// Number of restarts within the window of the restart policy
+        restarts: usize,
+    },
+    /// The worker failed too many times and was stopped
+    GaveUp {
+        /// Main address of the worker
+        address: Address,
+    },
+    /// The worker was stopped
+    Stopped {
+        /// Main address of the worker
+        address: Address,
+    },
+}
+
+/// Supervisor restarting the workers started under it when they fail
+///
+/// A worker fails when its `initialize` or `handle_message` function
+/// returns an error or panics, unless the restart policy is created
+/// [`without_restart_on_errors`](RestartPolicy::without_restart_on_errors),
+/// in which case only panics and `initialize` errors are failures.
+/// A failed worker is replaced by a new instance, created by the factory
+/// given to [`Supervisor::supervise`], which keeps the same addresses,
+/// mailboxes and access controls. The message which caused the failure is
+/// dropped, messages received in the meantime are handled by the new instance.
+///
+/// ```rust
+/// use ockam_node::{Context, RestartPolicy, Supervisor, WorkerBuilder};
+/// # use ockam_core::{Result, Worker};
+/// # struct MyWorker;
+/// # #[ockam_core::worker]
+/// # impl Worker for MyWorker {
+/// #     type Message = String;
+/// #     type Context = Context;
+/// # }
+/// # async fn test(ctx: &Context) -> Result<()> {
+/// let supervisor = Supervisor::new(RestartPolicy::new());
+/// let events = supervisor.subscribe();
+///
+/// WorkerBuilder::new(supervisor.supervise(|| MyWorker))
+///     .with_address("my_worker")
+///     .start(ctx)
+///     .await?;
+/// # Ok(()) }
+/// ```
+#[derive(Clone)]
+pub struct Supervisor {
+    policy: RestartPolicy,
+    events: broadcast::Sender<SupervisorEvent>,
+}
+
+impl Supervisor {
+    /// Create a new supervisor with the given restart policy
+    pub fn new(policy: RestartPolicy) -> Self {
+        let (events, _) = broadcast::channel(EVENTS_CAPACITY);
+        Self { policy, events }
+    }
+
+    /// Receive the lifecycle events of the workers started under this supervisor
+    pub fn subscribe(&self) -> broadcast::Receiver<SupervisorEvent> {
+        self.events.subscribe()
+    }
+
+    /// Create a worker supervised by this supervisor, to be started like any other worker
+    pub fn supervise<W, F>(&self, factory: F) -> SupervisedWorker<W>
+    where
+        W: Worker<Context = Context>,
+        F: Fn() -> W + Send + Sync + 'static,
+    {
+        SupervisedWorker {
+            supervisor: self.clone(),
+            factory: Box::new(factory),
+            worker: None,
+            restarts: VecDeque::new(),
+        }
+    }
+
+    fn notify(&self, event: SupervisorEvent) {
+        debug!("Supervisor event: {:?}", event);
+        // There may be no subscriber
+        let _ = self.events.send(event);
+    }
+}
+
+/// A worker started under a [`Supervisor`]
+///
+/// Create this worker type by calling [`Supervisor::supervise`].
+pub struct SupervisedWorker<W> {
+    supervisor: Supervisor,
+    factory: Box<dyn Fn() -> W + Send + Sync>,
+    worker: Option<W>,
+    restarts: VecDeque<Instant>,
+}
+
+/// Failure of a supervised worker
+struct Failure {
+    reason: String,
+    panicked: bool,
+    /// Error returned by the worker, if it did not panic
+    error: Option<ockam_core::Error>,
+}
+
+/// Run a function of a supervised worker, turning errors and panics into failures
+async fn guarded(f: impl Future<Output = Result<()>>) -> core::result::Result<(), Failure> {
+    match AssertUnwindSafe(f).catch_unwind().await {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(e)) => Err(Failure {
+            reason: e.to_string(),
+            panicked: false,
+            error: Some(e),
+        }),
+        Err(panic) => {
+            let message = if let Some(message) = panic.downcast_ref::<&str>() {
+                message.to_string()
+            } else if let Some(message) = panic.downcast_ref::<String>() {
+                message.clone()
+            } else {
+                "unknown panic".to_string()
+            };
+            Err(Failure {
+                reason: format!("panicked: {}", message),
+                panicked: true,
+                error: None,
+            })
+        }
+    }
+}
+
+impl<W> SupervisedWorker<W>
+where
+    W: Worker<Context = Context>,
+{
+    /// Replace the failed worker by a new instance, unless it failed too many times
+    async fn restart(&mut self, ctx: &mut Context, mut failure: Failure) -> Result<()> {
+        let address = ctx.address();
+
+        // A worker which panicked may be in an inconsistent state, so only
+        // a worker which returned an error is shut down
+        if let Some(mut worker) = self.worker.take() {
+            if !failure.panicked {
+                let _ = guarded(worker.shutdown(ctx)).await;
+            }
+        }
+
+        loop {
+            warn!("Supervised worker {} failed: {}", address, failure.reason);
+            self.supervisor.notify(SupervisorEvent::Failed {
+                address: address.clone(),
+                reason: failure.reason,
+            });
+
+            let now = Instant::now();
+            let window = self.supervisor.policy.window;
+            while let Some(restart) = self.restarts.front() {
+                if now.duration_since(*restart) <= window {
+                    break;
+                }
+                self.restarts.pop_front();
+            }
+
+            if self.restarts.len() >= self.supervisor.policy.max_restarts {
+                error!(
+                    "Supervised worker {} failed too many times, stopping it",
+                    address
+                );
+                self.supervisor.notify(SupervisorEvent::GaveUp {
+                    address: address.clone(),
+                });
+                return ctx.stop_worker(address).await;
+            }
+
+            self.restarts.push_back(now);
+            let restarts = self.restarts.len();
+            let backoff = self.supervisor.policy.backoff(restarts);
+            debug!("Restarting supervised worker {} in {:?}", address, backoff);
+            crate::tokio::time::sleep(backoff).await;
+
+            let mut worker = (self.factory)();
+            match guarded(worker.initialize(ctx)).await {
+                Ok(()) => {
+                    info!("Supervised worker {} restarted", address);
+                    self.worker = Some(worker);
+                    self.supervisor
+                        .notify(SupervisorEvent::Restarted { address, restarts });
+                    return Ok(());
+                }
+                Err(f) => failure = f,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<W> Worker for SupervisedWorker<W>
+where
+    W: Worker<Context = Context>,
+{
+    type Message = W::Message;
+    type Context = Context;
+
+    async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        let mut worker = (self.factory)();
+        match guarded(worker.initialize(ctx)).await {
+            Ok(()) => {
+                self.worker = Some(worker);
+                self.supervisor.notify(SupervisorEvent::Started {
+                    address: ctx.address(),
+                });
+                Ok(())
+            }
+            Err(failure) => self.restart(ctx, failure).await,
+        }
+    }
+
+    async fn shutdown(&mut self, ctx: &mut Context) -> Result<()> {
+        if let Some(mut worker) = self.worker.take() {
+            if let Err(failure) = guarded(worker.shutdown(ctx)).await {
+                warn!(
+                    "Supervised worker {} failed to shut down: {}",
+                    ctx.address(),
+                    failure.reason
+                );
+            }
+        }
+
+        self.supervisor.notify(SupervisorEvent::Stopped {
+            address: ctx.address(),
+        });
+
+        Ok(())
+    }
+
+    async fn handle_message(
+        &mut self,
+        ctx: &mut Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        let worker = match self.worker.as_mut() {
+            Some(worker) => worker,
+            // The worker gave up and is being stopped
+            None => return Ok(()),
+        };
+
+        match guarded(worker.handle_message(ctx, msg)).await {
+            Ok(()) => Ok(()),
+            Err(Failure { error: Some(e), .. }) if !self.supervisor.policy.restart_on_errors => {
+                Err(e)
+            }
+            Err(failure) => self.restart(ctx, failure).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_is_exponential_and_capped() {
+        let policy = RestartPolicy::new()
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(500));
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(4), Duration::from_millis(500));
+        assert_eq!(policy.backoff(100), Duration::from_millis(500));
+    }
+}
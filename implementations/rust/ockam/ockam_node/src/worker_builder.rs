@@ -1,5 +1,7 @@
 use crate::debugger;
+use crate::durable_mailbox::{DurableMailbox, MailboxStorage};
 use crate::error::{NodeError, NodeReason};
+use crate::message_channel::{MailboxOptions, OverflowPolicy};
 use crate::{relay::WorkerRelay, Context, NodeMessage};
 use ockam_core::compat::sync::Arc;
 use ockam_core::{
@@ -40,6 +42,7 @@ where
             outgoing_ac: Arc::new(AllowAll),
             worker: self.worker,
             address: address.into(),
+            mailbox_options: MailboxOptions::default(),
         }
     }
 
@@ -48,6 +51,7 @@ where
         WorkerBuilderMultipleAddresses {
             mailboxes,
             worker: self.worker,
+            mailbox_options: MailboxOptions::default(),
         }
     }
 }
@@ -58,6 +62,7 @@ where
 {
     mailboxes: Mailboxes,
     worker: W,
+    mailbox_options: MailboxOptions,
 }
 
 impl<W> WorkerBuilderMultipleAddresses<W>
@@ -66,7 +71,19 @@ where
 {
     /// Consume this builder and start a new Ockam [`Worker`] from the given context
     pub async fn start(self, context: &Context) -> Result<()> {
-        start(context, self.mailboxes, self.worker).await
+        start(context, self.mailboxes, self.mailbox_options, self.worker).await
+    }
+
+    /// Set the maximum number of messages waiting in the mailbox of the worker
+    pub fn with_mailbox_capacity(mut self, capacity: usize) -> Self {
+        self.mailbox_options.capacity = capacity;
+        self
+    }
+
+    /// Set what happens to the messages sent to the worker when its mailbox is full
+    pub fn with_overflow_policy(mut self, overflow_policy: OverflowPolicy) -> Self {
+        self.mailbox_options.overflow_policy = overflow_policy;
+        self
     }
 }
 
@@ -78,6 +95,7 @@ where
     outgoing_ac: Arc<dyn OutgoingAccessControl>,
     address: Address,
     worker: W,
+    mailbox_options: MailboxOptions,
 }
 
 impl<W> WorkerBuilderOneAddress<W>
@@ -89,6 +107,7 @@ where
         start(
             context,
             Mailboxes::main(self.address, self.incoming_ac, self.outgoing_ac),
+            self.mailbox_options,
             self.worker,
         )
         .await
@@ -134,10 +153,40 @@ where
         self.outgoing_ac = outgoing_access_control.clone();
         self
     }
+
+    /// Set the maximum number of messages waiting in the mailbox of the worker
+    pub fn with_mailbox_capacity(mut self, capacity: usize) -> Self {
+        self.mailbox_options.capacity = capacity;
+        self
+    }
+
+    /// Set what happens to the messages sent to the worker when its mailbox is full
+    pub fn with_overflow_policy(mut self, overflow_policy: OverflowPolicy) -> Self {
+        self.mailbox_options.overflow_policy = overflow_policy;
+        self
+    }
+
+    /// Persist the messages sent to the worker in the given storage, until
+    /// the worker handles them successfully
+    ///
+    /// The messages left in the storage are redelivered when a worker is
+    /// started again at the same address with the same storage, after a
+    /// restart of the node for example. The worker may then handle a message
+    /// twice, so its handling should be idempotent. Messages with the same
+    /// [`MessageId`](crate::MessageId) are only delivered once.
+    pub fn with_durable_mailbox(mut self, storage: MailboxStorage) -> Self {
+        self.mailbox_options.durable = Some(DurableMailbox::new(self.address.clone(), storage));
+        self
+    }
 }
 
 /// Consume this builder and start a new Ockam [`Worker`] from the given context
-async fn start<W>(context: &Context, mailboxes: Mailboxes, worker: W) -> Result<()>
+async fn start<W>(
+    context: &Context,
+    mailboxes: Mailboxes,
+    mailbox_options: MailboxOptions,
+    worker: W,
+) -> Result<()>
 where
     W: Worker<Context = Context>,
 {
@@ -151,7 +200,7 @@ where
     let addresses = mailboxes.addresses();
 
     // Pass it to the context
-    let (ctx, sender, ctrl_rx) = context.copy_with_mailboxes(mailboxes);
+    let (ctx, sender, ctrl_rx) = context.copy_with_mailboxes(mailboxes, mailbox_options);
 
     debugger::log_inherit_context("WORKER", context, &ctx);
 
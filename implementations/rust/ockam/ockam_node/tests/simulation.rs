@@ -0,0 +1,127 @@
+#![cfg(feature = "simulation")]
+
+use core::time::Duration;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::vec::Vec;
+use ockam_core::{async_trait, route, Address, AllowAll, Result, Routed, Worker};
+use ockam_node::simulation::{LinkConditions, SimulatedNetwork};
+use ockam_node::tokio::time::Instant;
+use ockam_node::{Context, MessageReceiveOptions, MessageSendReceiveOptions};
+
+struct Echoer;
+
+#[async_trait]
+impl Worker for Echoer {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        ctx.send(msg.return_route(), msg.body()).await
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(seed = 1)]
+async fn simulation__link_latency__should_advance_virtual_time(ctx: &mut Context) -> Result<()> {
+    let network = ctx.simulated_network().unwrap();
+    network.add_node(ctx, "a").await?;
+    let mut node_b = network.start_node(ctx, "b").await?;
+    node_b.start_worker("echoer", Echoer).await?;
+
+    let latency = LinkConditions::new().with_latency(Duration::from_secs(10));
+    network.set_link_conditions("a", "b", latency);
+    network.set_link_conditions("b", "a", latency);
+
+    let start = Instant::now();
+    let reply: String = ctx
+        .send_and_receive(
+            route![SimulatedNetwork::link_address("b"), "echoer"],
+            "Hello".to_string(),
+        )
+        .await?;
+    assert_eq!(reply, "Hello");
+    assert!(start.elapsed() >= Duration::from_secs(20));
+
+    node_b.stop().await?;
+    ctx.stop().await
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(seed = 2)]
+async fn simulation__partition__should_drop_messages_until_healed(ctx: &mut Context) -> Result<()> {
+    let network = ctx.simulated_network().unwrap();
+    network.add_node(ctx, "a").await?;
+    let mut node_b = network.start_node(ctx, "b").await?;
+    node_b.start_worker("echoer", Echoer).await?;
+    let route = route![SimulatedNetwork::link_address("b"), "echoer"];
+    let options = || MessageSendReceiveOptions::new().with_timeout(Duration::from_secs(5));
+
+    network.partition("b", "a");
+    let reply = ctx
+        .send_and_receive_extended::<String>(route.clone(), "Hello".to_string(), options())
+        .await;
+    assert!(reply.is_err());
+
+    network.heal("a", "b");
+    let reply = ctx
+        .send_and_receive_extended::<String>(route, "Hello".to_string(), options())
+        .await?;
+    assert_eq!(reply.body(), "Hello");
+
+    node_b.stop().await?;
+    ctx.stop().await
+}
+
+/// Send messages on a lossy link and return the replies
+async fn lossy_exchange(
+    ctx: &Context,
+    network: &SimulatedNetwork,
+    a: &str,
+    b: &str,
+) -> Result<Vec<String>> {
+    network.add_node(ctx, a).await?;
+    let mut node_b = network.start_node(ctx, b).await?;
+    node_b.start_worker("echoer", Echoer).await?;
+    network.set_link_conditions(
+        a,
+        b,
+        LinkConditions::new()
+            .with_loss(0.5)
+            .with_jitter(Duration::from_millis(100)),
+    );
+
+    let mut child = ctx
+        .new_detached(Address::random_local(), AllowAll, AllowAll)
+        .await?;
+    for i in 0..20 {
+        child
+            .send(
+                route![SimulatedNetwork::link_address(b), "echoer"],
+                i.to_string(),
+            )
+            .await?;
+    }
+
+    let mut replies = Vec::new();
+    let options = || MessageReceiveOptions::new().with_timeout(Duration::from_secs(1));
+    while let Ok(reply) = child.receive_extended::<String>(options()).await {
+        replies.push(reply.body());
+    }
+
+    node_b.stop().await?;
+    Ok(replies)
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(seed = 3)]
+async fn simulation__same_seed__should_lose_and_reorder_the_same_messages(
+    ctx: &mut Context,
+) -> Result<()> {
+    let first = lossy_exchange(ctx, &SimulatedNetwork::new(42), "a1", "b1").await?;
+    let second = lossy_exchange(ctx, &SimulatedNetwork::new(42), "a2", "b2").await?;
+
+    assert!(!first.is_empty() && first.len() < 20);
+    assert_eq!(first, second);
+
+    ctx.stop().await
+}
@@ -1,14 +1,24 @@
 use core::sync::atomic::{AtomicBool, Ordering};
 use core::time::Duration;
+use ockam_core::api::{Endpoint, Method, RequestError, Status, StreamEndpoint};
 use ockam_core::compat::{
     boxed::Box,
     string::{String, ToString},
     sync::Arc,
 };
-use ockam_core::{async_trait, Address, AllowAll, Any, Decodable, DenyAll, Message, LOCAL};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{
+    async_trait, Address, AllowAll, Any, Decodable, DenyAll, Encodable, Error, LocalMessage,
+    Message, Priority, TraceContext, TransportMessage, LOCAL,
+};
 use ockam_core::{route, Processor, Result, Routed, Worker};
+use ockam_node::api::{ApiService, Handle, HandleStream, RequestInfo, ResponseStreamSender};
 use ockam_node::compat::futures::FutureExt;
-use ockam_node::{Context, MessageReceiveOptions, NodeBuilder};
+use ockam_node::{
+    Context, Drain, FileKeyValueStorage, InMemoryKeyValueStorage, KeyValueStorage, MailboxEntry,
+    MailboxKey, MessageId, MessageReceiveOptions, NodeBuilder, OverflowPolicy, RestartPolicy,
+    RpcClient, Supervisor, SupervisorEvent, WorkerBuilder,
+};
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicI8, AtomicU32};
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -414,6 +424,33 @@ async fn wait_for_worker(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+struct ClusterWorker;
+
+#[ockam_core::worker]
+impl Worker for ClusterWorker {
+    type Context = Context;
+    type Message = ();
+
+    async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.set_cluster("_internals.cluster").await
+    }
+}
+
+/// This test enforces that a node shuts down when the workers of a
+/// cluster were all stopped before
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn shutdown__stopped_cluster__should_not_block(ctx: &mut Context) -> Result<()> {
+    ctx.start_worker_with_access_control("clustered", ClusterWorker, DenyAll, DenyAll)
+        .await?;
+    ctx.stop_worker("clustered").await?;
+    ctx.sleep(Duration::from_millis(100)).await;
+
+    ockam_node::tokio::time::timeout(Duration::from_secs(2), ctx.stop())
+        .await
+        .unwrap()
+}
+
 struct StopFromHandleMessageWorker {
     counter_a: Arc<AtomicU32>,
     counter_b: Arc<AtomicU32>,
@@ -560,3 +597,895 @@ async fn starting_worker_with_dup_address_should_fail(ctx: &mut Context) -> Resu
         .is_err());
     ctx.stop().await
 }
+
+struct TracingContextWorker;
+
+#[async_trait]
+impl Worker for TracingContextWorker {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(
+        &mut self,
+        ctx: &mut Self::Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        let traceparent = ctx
+            .tracing_context()
+            .map(|c| c.to_traceparent())
+            .unwrap_or_default();
+        ctx.send(msg.return_route(), traceparent).await
+    }
+}
+
+// The propagation of tracing contexts, enabled with OCKAM_TRACING_CONTEXT,
+// is tested in tracing_context.rs
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn tracing_context__propagation_disabled__should_not_be_propagated(
+    ctx: &mut Context,
+) -> Result<()> {
+    ctx.start_worker("tracing_context_worker", TracingContextWorker)
+        .await?;
+
+    let mut child_ctx = ctx.new_detached("child", AllowAll, AllowAll).await?;
+    child_ctx.set_tracing_context(Some(TraceContext::new_root()));
+    child_ctx
+        .send(route!["tracing_context_worker"], String::new())
+        .await?;
+
+    let reply = child_ctx.receive::<String>().await?;
+    assert!(reply.tracing_context().is_none());
+    assert!(reply.body().is_empty());
+
+    ctx.stop().await
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn tracing_context__message_without_context__should_not_be_traced(
+    ctx: &mut Context,
+) -> Result<()> {
+    ctx.start_worker("tracing_context_worker", TracingContextWorker)
+        .await?;
+
+    let mut child_ctx = ctx.new_detached("child", AllowAll, AllowAll).await?;
+    child_ctx
+        .send(route!["tracing_context_worker"], String::new())
+        .await?;
+
+    let reply = child_ctx.receive::<String>().await?;
+    assert!(reply.tracing_context().is_none());
+    assert!(reply.body().is_empty());
+
+    ctx.stop().await
+}
+
+struct FlakyWorker {
+    initializations: Arc<AtomicU32>,
+}
+
+#[async_trait]
+impl Worker for FlakyWorker {
+    type Message = String;
+    type Context = Context;
+
+    async fn initialize(&mut self, _context: &mut Self::Context) -> Result<()> {
+        self.initializations.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    async fn handle_message(
+        &mut self,
+        ctx: &mut Self::Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        match msg.as_body().as_str() {
+            "fail" => Err(ockam_core::Error::new(
+                ockam_core::errcode::Origin::Node,
+                ockam_core::errcode::Kind::Invalid,
+                "failure",
+            )),
+            "panic" => panic!("flaky worker panicked"),
+            _ => ctx.send(msg.return_route(), msg.body()).await,
+        }
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn supervisor__failing_worker__should_be_restarted(ctx: &mut Context) -> Result<()> {
+    let initializations = Arc::new(AtomicU32::new(0));
+    let supervisor = Supervisor::new(
+        RestartPolicy::new().with_backoff(Duration::from_millis(10), Duration::from_millis(100)),
+    );
+    let mut events = supervisor.subscribe();
+
+    let counter = initializations.clone();
+    WorkerBuilder::new(supervisor.supervise(move || FlakyWorker {
+        initializations: counter.clone(),
+    }))
+    .with_address("flaky")
+    .start(ctx)
+    .await?;
+
+    let address = Address::from_string("flaky");
+    assert_eq!(
+        events.recv().await.unwrap(),
+        SupervisorEvent::Started {
+            address: address.clone()
+        }
+    );
+
+    ctx.send(route!["flaky"], "fail".to_string()).await?;
+    assert!(matches!(
+        events.recv().await.unwrap(),
+        SupervisorEvent::Failed { .. }
+    ));
+    assert_eq!(
+        events.recv().await.unwrap(),
+        SupervisorEvent::Restarted {
+            address: address.clone(),
+            restarts: 1
+        }
+    );
+
+    ctx.send(route!["flaky"], "panic".to_string()).await?;
+    match events.recv().await.unwrap() {
+        SupervisorEvent::Failed { reason, .. } => assert!(reason.contains("flaky worker panicked")),
+        event => panic!("unexpected event {:?}", event),
+    }
+    assert_eq!(
+        events.recv().await.unwrap(),
+        SupervisorEvent::Restarted {
+            address,
+            restarts: 2
+        }
+    );
+    assert_eq!(initializations.load(Ordering::Relaxed), 3);
+
+    let reply: String = ctx
+        .send_and_receive(route!["flaky"], "Hello".to_string())
+        .await?;
+    assert_eq!(reply, "Hello");
+
+    ctx.stop().await
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn supervisor__too_many_failures__should_stop_worker(ctx: &mut Context) -> Result<()> {
+    let supervisor = Supervisor::new(
+        RestartPolicy::new()
+            .with_backoff(Duration::from_millis(10), Duration::from_millis(10))
+            .with_max_restarts(1, Duration::from_secs(60)),
+    );
+    let mut events = supervisor.subscribe();
+
+    let initializations = Arc::new(AtomicU32::new(0));
+    WorkerBuilder::new(supervisor.supervise(move || FlakyWorker {
+        initializations: initializations.clone(),
+    }))
+    .with_address("flaky")
+    .start(ctx)
+    .await?;
+
+    ctx.send(route!["flaky"], "fail".to_string()).await?;
+    ctx.send(route!["flaky"], "fail".to_string()).await?;
+
+    let address = Address::from_string("flaky");
+    loop {
+        match events.recv().await.unwrap() {
+            SupervisorEvent::GaveUp { address: a } => {
+                assert_eq!(a, address);
+                break;
+            }
+            SupervisorEvent::Started { .. }
+            | SupervisorEvent::Failed { .. }
+            | SupervisorEvent::Restarted { .. } => {}
+            event => panic!("unexpected event {:?}", event),
+        }
+    }
+    assert_eq!(
+        events.recv().await.unwrap(),
+        SupervisorEvent::Stopped { address }
+    );
+    ctx.sleep(Duration::from_millis(100)).await;
+    assert!(!ctx.list_workers().await?.contains(&"flaky".into()));
+
+    ctx.stop().await
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn supervisor__without_restart_on_errors__should_only_restart_on_panics(
+    ctx: &mut Context,
+) -> Result<()> {
+    let initializations = Arc::new(AtomicU32::new(0));
+    let supervisor = Supervisor::new(
+        RestartPolicy::new()
+            .with_backoff(Duration::from_millis(10), Duration::from_millis(10))
+            .without_restart_on_errors(),
+    );
+    let mut events = supervisor.subscribe();
+
+    let counter = initializations.clone();
+    WorkerBuilder::new(supervisor.supervise(move || FlakyWorker {
+        initializations: counter.clone(),
+    }))
+    .with_address("flaky")
+    .start(ctx)
+    .await?;
+    assert!(matches!(
+        events.recv().await.unwrap(),
+        SupervisorEvent::Started { .. }
+    ));
+
+    // Errors don't restart the worker
+    for _ in 0..10 {
+        ctx.send(route!["flaky"], "fail".to_string()).await?;
+    }
+    let reply: String = ctx
+        .send_and_receive(route!["flaky"], "Hello".to_string())
+        .await?;
+    assert_eq!(reply, "Hello");
+    assert_eq!(initializations.load(Ordering::Relaxed), 1);
+
+    ctx.send(route!["flaky"], "panic".to_string()).await?;
+    assert!(matches!(
+        events.recv().await.unwrap(),
+        SupervisorEvent::Failed { .. }
+    ));
+    assert!(matches!(
+        events.recv().await.unwrap(),
+        SupervisorEvent::Restarted { restarts: 1, .. }
+    ));
+    assert_eq!(initializations.load(Ordering::Relaxed), 2);
+
+    ctx.stop().await
+}
+
+struct SlowWorker;
+
+#[async_trait]
+impl Worker for SlowWorker {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, _msg: Routed<String>) -> Result<()> {
+        ctx.sleep(Duration::from_millis(500)).await;
+        Ok(())
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn bounded_mailbox__reject_policy__should_return_error(ctx: &mut Context) -> Result<()> {
+    WorkerBuilder::new(SlowWorker)
+        .with_address("slow")
+        .with_mailbox_capacity(1)
+        .with_overflow_policy(OverflowPolicy::Reject)
+        .start(ctx)
+        .await?;
+
+    // The worker handles at most one message while the others wait in its mailbox
+    let mut results = vec![];
+    for _ in 0..3 {
+        results.push(ctx.send(route!["slow"], "Hello".to_string()).await);
+    }
+    let err = results.pop().unwrap().unwrap_err();
+    assert_eq!(err.code().kind, Kind::ResourceExhausted);
+
+    let metrics = ctx.mailbox_metrics("slow").await?;
+    assert_eq!(metrics.capacity(), 1);
+    assert_eq!(metrics.overflow_policy(), OverflowPolicy::Reject);
+    assert!(metrics.depth() <= 1);
+    assert!(metrics.rejected() >= 1);
+
+    ctx.stop().await
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn bounded_mailbox__drop_oldest_policy__should_count_dropped_messages(
+    ctx: &mut Context,
+) -> Result<()> {
+    WorkerBuilder::new(SlowWorker)
+        .with_address("slow")
+        .with_mailbox_capacity(2)
+        .with_overflow_policy(OverflowPolicy::DropOldest)
+        .start(ctx)
+        .await?;
+
+    for _ in 0..10 {
+        ctx.send(route!["slow"], "Hello".to_string()).await?;
+    }
+
+    let metrics = ctx.mailbox_metrics("slow").await?;
+    assert_eq!(metrics.depth(), 2);
+    assert!(metrics.dropped() >= 7);
+
+    ctx.stop().await
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn durable_mailbox__dropped_messages__should_not_stay_pending(
+    ctx: &mut Context,
+) -> Result<()> {
+    let storage = InMemoryKeyValueStorage::create();
+    WorkerBuilder::new(SlowWorker)
+        .with_address("slow")
+        .with_mailbox_capacity(2)
+        .with_overflow_policy(OverflowPolicy::DropOldest)
+        .with_durable_mailbox(storage.clone())
+        .start(ctx)
+        .await?;
+
+    for _ in 0..10 {
+        ctx.send(route!["slow"], "Hello".to_string()).await?;
+    }
+
+    // Only the message being handled and the queued ones are stored
+    let stored = storage
+        .keys()
+        .await?
+        .into_iter()
+        .filter(|key| matches!(key, MailboxKey::Message(..)))
+        .count();
+    assert!(stored <= 3);
+
+    ctx.stop().await
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn metrics__routed_messages__should_be_rendered(ctx: &mut Context) -> Result<()> {
+    ctx.start_worker("echoer", DummyWorker).await?;
+
+    for _ in 0..2 {
+        let _: String = ctx
+            .send_and_receive(route!["echoer"], "Hello".to_string())
+            .await?;
+    }
+
+    let metrics = ctx.metrics().render();
+    assert!(metrics.contains("# TYPE ockam_node_messages_routed counter\n"));
+    assert!(metrics.contains("ockam_node_messages_routed_total{address=\"0#echoer\"} 2\n"));
+    assert!(metrics.contains("# TYPE ockam_node_workers gauge\n"));
+    assert!(metrics.ends_with("# EOF\n"));
+
+    // The metrics of a stopped worker are removed
+    ctx.stop_worker("echoer").await?;
+    ctx.sleep(Duration::from_millis(100)).await;
+    assert!(!ctx.metrics().render().contains("0#echoer"));
+
+    ctx.stop().await
+}
+
+struct SlowEchoer;
+
+#[async_trait]
+impl Worker for SlowEchoer {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        ctx.sleep(Duration::from_millis(200)).await;
+        ctx.send(msg.return_route(), msg.body()).await
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn priority__queued_messages__should_be_handled_by_priority(ctx: &mut Context) -> Result<()> {
+    ctx.start_worker("echoer", SlowEchoer).await?;
+
+    // Keep the worker busy while the other messages are queued
+    ctx.send(route!["echoer"], "first".to_string()).await?;
+    ctx.sleep(Duration::from_millis(50)).await;
+
+    // Only the messages of different senders are reordered
+    let mut senders = vec![];
+    for (msg, priority) in [
+        ("low", Priority::Low),
+        ("normal", Priority::Normal),
+        ("high", Priority::High),
+    ] {
+        let mut sender = ctx
+            .new_detached(Address::random_local(), DenyAll, AllowAll)
+            .await?;
+        sender.set_message_priority(priority);
+        let transport = TransportMessage::v1(
+            route!["echoer"],
+            route![ctx.address()],
+            msg.to_string().encode()?,
+        );
+        sender.forward(LocalMessage::new(transport, vec![])).await?;
+        senders.push(sender);
+    }
+
+    // The replies inherit the priority of the handled messages
+    for (msg, priority) in [
+        ("first", Priority::Normal),
+        ("high", Priority::High),
+        ("normal", Priority::Normal),
+        ("low", Priority::Low),
+    ] {
+        let reply = ctx.receive::<String>().await?;
+        assert_eq!(reply.priority(), priority);
+        assert_eq!(reply.body(), msg);
+    }
+
+    ctx.stop().await
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn priority__messages_of_one_sender__should_keep_their_order(
+    ctx: &mut Context,
+) -> Result<()> {
+    ctx.start_worker("echoer", SlowEchoer).await?;
+
+    ctx.send(route!["echoer"], "first".to_string()).await?;
+    ctx.sleep(Duration::from_millis(50)).await;
+
+    for (msg, priority) in [
+        ("low", Priority::Low),
+        ("normal", Priority::Normal),
+        ("high", Priority::High),
+    ] {
+        ctx.set_message_priority(priority);
+        ctx.send(route!["echoer"], msg.to_string()).await?;
+    }
+    ctx.clear_message_priority();
+
+    for msg in ["first", "low", "normal", "high"] {
+        let reply = ctx.receive::<String>().await?;
+        assert_eq!(reply.body(), msg);
+    }
+
+    ctx.stop().await
+}
+
+/// Worker sending back the messages it receives, once online
+struct Uplink {
+    online: bool,
+}
+
+#[async_trait]
+impl Worker for Uplink {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        if !self.online {
+            return Err(Error::new(Origin::Node, Kind::Io, "the uplink is offline"));
+        }
+        ctx.send(msg.return_route(), msg.body()).await
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn durable_mailbox__worker_restarted__should_redeliver_pending_messages(
+    ctx: &mut Context,
+) -> Result<()> {
+    let path = std::env::temp_dir().join(Address::random_local().address());
+    let storage = Arc::new(FileKeyValueStorage::create(&path).await?);
+    WorkerBuilder::new(Uplink { online: false })
+        .with_address("uplink")
+        .with_durable_mailbox(storage)
+        .start(ctx)
+        .await?;
+
+    for i in 0..3 {
+        ctx.send(route!["uplink"], i.to_string()).await?;
+    }
+    ctx.sleep(Duration::from_millis(100)).await;
+    ctx.stop_worker("uplink").await?;
+    ctx.sleep(Duration::from_millis(100)).await;
+
+    // A new storage reads the pending messages back from the file, as after a node restart
+    let storage = Arc::new(FileKeyValueStorage::create(&path).await?);
+    WorkerBuilder::new(Uplink { online: true })
+        .with_address("uplink")
+        .with_durable_mailbox(storage.clone())
+        .start(ctx)
+        .await?;
+
+    for i in 0..3 {
+        assert_eq!(ctx.receive::<String>().await?.body(), i.to_string());
+    }
+    ctx.sleep(Duration::from_millis(100)).await;
+    let stored = storage
+        .get(&MailboxKey::Mailbox("uplink".into()))
+        .await?
+        .unwrap();
+    assert!(matches!(stored, MailboxEntry::Mailbox(mailbox) if mailbox.is_empty()));
+
+    std::fs::remove_file(path).unwrap();
+    ctx.stop().await
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn durable_mailbox__same_message_id__should_be_delivered_once(
+    ctx: &mut Context,
+) -> Result<()> {
+    let path = std::env::temp_dir().join(Address::random_local().address());
+    let storage = Arc::new(FileKeyValueStorage::create(&path).await?);
+    WorkerBuilder::new(Uplink { online: true })
+        .with_address("uplink")
+        .with_durable_mailbox(storage)
+        .start(ctx)
+        .await?;
+
+    let id = MessageId::new("telemetry-1");
+    for _ in 0..2 {
+        ctx.send_with_local_info(
+            route!["uplink"],
+            "telemetry".to_string(),
+            vec![id.to_local_info()?],
+        )
+        .await?;
+    }
+
+    assert_eq!(ctx.receive::<String>().await?.body(), "telemetry");
+    let duplicate = ctx
+        .receive_extended::<String>(
+            MessageReceiveOptions::new().with_timeout(Duration::from_millis(200)),
+        )
+        .await;
+    assert!(duplicate.is_err());
+
+    std::fs::remove_file(path).unwrap();
+    ctx.stop().await
+}
+
+/// Worker failing to handle the first message it receives
+struct Flaky {
+    failed: bool,
+}
+
+#[async_trait]
+impl Worker for Flaky {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        if !self.failed {
+            self.failed = true;
+            return Err(Error::new(Origin::Node, Kind::Io, "the uplink is offline"));
+        }
+        ctx.send(msg.return_route(), msg.body()).await
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn durable_mailbox__failed_message_sent_again__should_be_handled(
+    ctx: &mut Context,
+) -> Result<()> {
+    WorkerBuilder::new(Flaky { failed: false })
+        .with_address("uplink")
+        .with_durable_mailbox(InMemoryKeyValueStorage::create())
+        .start(ctx)
+        .await?;
+
+    let id = MessageId::new("telemetry-1");
+    for _ in 0..2 {
+        ctx.send_with_local_info(
+            route!["uplink"],
+            "telemetry".to_string(),
+            vec![id.to_local_info()?],
+        )
+        .await?;
+        ctx.sleep(Duration::from_millis(100)).await;
+    }
+
+    assert_eq!(ctx.receive::<String>().await?.body(), "telemetry");
+    ctx.stop().await
+}
+
+#[derive(Default)]
+struct Counters {
+    counters: std::collections::BTreeMap<String, u64>,
+}
+
+struct IncrementCounter;
+
+impl Endpoint for IncrementCounter {
+    const METHOD: Method = Method::Post;
+    const PATH: &'static str = "/counters/:name";
+    type Request = u64;
+    type Response = u64;
+}
+
+struct GetCounter;
+
+impl Endpoint for GetCounter {
+    const METHOD: Method = Method::Get;
+    const PATH: &'static str = "/counters/:name";
+    type Request = ();
+    type Response = u64;
+}
+
+struct DeleteCounter;
+
+impl Endpoint for DeleteCounter {
+    const METHOD: Method = Method::Delete;
+    const PATH: &'static str = "/counters/:name";
+    type Request = ();
+    type Response = ();
+}
+
+#[async_trait]
+impl Handle<IncrementCounter> for Counters {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context,
+        request: RequestInfo<'_>,
+        body: u64,
+    ) -> Result<u64, RequestError> {
+        let counter = self
+            .counters
+            .entry(request.param("name")?.to_string())
+            .or_default();
+        *counter += body;
+        Ok(*counter)
+    }
+}
+
+#[async_trait]
+impl Handle<GetCounter> for Counters {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context,
+        request: RequestInfo<'_>,
+        _body: (),
+    ) -> Result<u64, RequestError> {
+        let name = request.param("name")?;
+        match self.counters.get(name) {
+            Some(counter) => Ok(*counter),
+            None => Err(Error::new(Origin::Application, Kind::NotFound, "unknown counter").into()),
+        }
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn api_service__typed_requests__should_be_routed_to_their_endpoint(
+    ctx: &mut Context,
+) -> Result<()> {
+    let service = ApiService::new(Counters::default())
+        .route::<IncrementCounter>()
+        .route::<GetCounter>();
+    ctx.start_worker("counters", service).await?;
+    let client = RpcClient::new(route!["counters"], ctx).await?;
+
+    assert_eq!(client.call::<IncrementCounter>(&["a"], 2).await?, 2);
+    assert_eq!(client.call::<IncrementCounter>(&["a"], 3).await?, 5);
+    assert_eq!(client.call::<GetCounter>(&["a"], ()).await?, 5);
+
+    // Errors are mapped to error responses
+    let unknown = client.call::<GetCounter>(&["b"], ()).await;
+    assert!(unknown.unwrap_err().to_string().contains("unknown counter"));
+
+    // The method of an endpoint which is not served is not allowed
+    let not_served = ockam_core::api::Request::delete(DeleteCounter::path(&["a"])?).to_vec()?;
+    let response: Vec<u8> = ctx.send_and_receive(route!["counters"], not_served).await?;
+    let (header, _) = ockam_core::api::Response::parse_response_header(&response)?;
+    assert_eq!(header.status(), Some(Status::MethodNotAllowed));
+
+    ctx.stop().await
+}
+
+struct CountTo;
+
+impl Endpoint for CountTo {
+    const METHOD: Method = Method::Get;
+    const PATH: &'static str = "/count/:n";
+    type Request = ();
+    type Response = u64;
+}
+
+impl StreamEndpoint for CountTo {}
+
+#[derive(Default)]
+struct Counting {
+    cancelled: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl HandleStream<CountTo> for Counting {
+    async fn handle_stream(
+        &mut self,
+        _ctx: &mut Context,
+        request: RequestInfo<'_>,
+        _body: (),
+        mut stream: ResponseStreamSender<u64>,
+    ) -> Result<(), RequestError> {
+        let n: u64 = request
+            .param("n")?
+            .parse()
+            .map_err(|_| RequestError::bad_request("not a number"))?;
+        for i in 0..n {
+            if stream.send(i).await.is_err() {
+                self.cancelled
+                    .store(stream.is_cancelled(), Ordering::Relaxed);
+                return Ok(());
+            }
+        }
+        Ok(stream.end().await?)
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn api_service__streaming_response__should_be_received_chunk_by_chunk(
+    ctx: &mut Context,
+) -> Result<()> {
+    let service = ApiService::new(Counting::default()).route_stream::<CountTo>();
+    ctx.start_worker("counting", service).await?;
+    let client = RpcClient::new(route!["counting"], ctx).await?;
+
+    // More chunks than the initial credit of the sender
+    let mut stream = client.call_stream::<CountTo>(&["40"], ()).await?;
+    let mut received = vec![];
+    while let Some(i) = stream.next().await? {
+        received.push(i);
+    }
+    assert_eq!(received, (0..40).collect::<Vec<u64>>());
+    assert!(stream.next().await?.is_none());
+
+    // Errors before the first chunk end the stream
+    let mut stream = client.call_stream::<CountTo>(&["forty"], ()).await?;
+    assert!(stream.next().await.is_err());
+
+    ctx.stop().await
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn api_service__cancelled_stream__should_stop_its_sender(ctx: &mut Context) -> Result<()> {
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let service = ApiService::new(Counting {
+        cancelled: cancelled.clone(),
+    })
+    .route_stream::<CountTo>();
+    ctx.start_worker("counting", service).await?;
+    let client = RpcClient::new(route!["counting"], ctx).await?;
+
+    let mut stream = client.call_stream::<CountTo>(&["1000"], ()).await?;
+    for expected in 0..5 {
+        assert_eq!(stream.next().await?, Some(expected));
+    }
+    stream.cancel().await?;
+
+    let mut attempts = 0;
+    while !cancelled.load(Ordering::Relaxed) && attempts < 50 {
+        sleep(Duration::from_millis(100)).await;
+        attempts += 1;
+    }
+    assert!(cancelled.load(Ordering::Relaxed));
+
+    ctx.stop().await
+}
+
+/// Pushes more chunks than the window of credit without waiting,
+/// then waits for credit to send a last chunk
+#[derive(Default)]
+struct Bursting {
+    window_full: Arc<AtomicU32>,
+}
+
+#[async_trait]
+impl HandleStream<CountTo> for Bursting {
+    async fn handle_stream(
+        &mut self,
+        _ctx: &mut Context,
+        _request: RequestInfo<'_>,
+        _body: (),
+        mut stream: ResponseStreamSender<u64>,
+    ) -> Result<(), RequestError> {
+        for i in 0..20 {
+            if let Err(e) = stream.try_send(i).await {
+                if e.code().kind != Kind::ResourceExhausted {
+                    return Err(e.into());
+                }
+                self.window_full.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        stream.send(99).await?;
+        Ok(stream.end().await?)
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn api_service__full_stream_window__should_report_the_dropped_chunks(
+    ctx: &mut Context,
+) -> Result<()> {
+    let window_full = Arc::new(AtomicU32::new(0));
+    let service = ApiService::new(Bursting {
+        window_full: window_full.clone(),
+    })
+    .route_stream::<CountTo>();
+    ctx.start_worker("bursting", service).await?;
+    let client = RpcClient::new(route!["bursting"], ctx).await?;
+
+    let mut stream = client.call_stream::<CountTo>(&["20"], ()).await?;
+    // No credit is granted until the chunks are read
+    sleep(Duration::from_millis(200)).await;
+    assert_eq!(window_full.load(Ordering::Relaxed), 4);
+    for expected in 0..16 {
+        assert_eq!(stream.next().await?, Some(expected));
+    }
+
+    // The client is told about the dropped chunks, and can go on
+    let error = stream.next().await.unwrap_err();
+    assert_eq!(error.code().kind, Kind::ResourceExhausted);
+    assert_eq!(stream.next().await?, Some(99));
+    assert!(stream.next().await?.is_none());
+
+    ctx.stop().await
+}
+
+struct OpenConnections {
+    started: AtomicBool,
+    connections: AtomicU32,
+}
+
+#[async_trait]
+impl Drain for OpenConnections {
+    async fn start_drain(&self, _ctx: &Context) -> Result<()> {
+        self.started.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn is_drained(&self) -> bool {
+        self.connections.load(Ordering::Relaxed) == 0
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn drain__connections_closed__should_finish_before_deadline(ctx: &mut Context) -> Result<()> {
+    let drain = Arc::new(OpenConnections {
+        started: AtomicBool::new(false),
+        connections: AtomicU32::new(1),
+    });
+    ctx.drains().register("connections", drain.clone());
+
+    let child_ctx = ctx.new_detached("child", AllowAll, AllowAll).await?;
+    assert!(!child_ctx.is_draining());
+
+    let closing = drain.clone();
+    tokio::spawn(async move {
+        sleep(Duration::from_millis(300)).await;
+        closing.connections.store(0, Ordering::Relaxed);
+    });
+
+    let pending = ctx.drain(Duration::from_secs(5)).await?;
+    assert!(pending.is_empty());
+    assert!(drain.started.load(Ordering::Relaxed));
+    assert!(child_ctx.is_draining());
+
+    ctx.stop().await
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn drain__connections_still_open__should_stop_at_deadline(ctx: &mut Context) -> Result<()> {
+    let drain = Arc::new(OpenConnections {
+        started: AtomicBool::new(false),
+        connections: AtomicU32::new(1),
+    });
+    ctx.drains().register("connections", drain);
+
+    let pending = ctx.drain(Duration::from_millis(300)).await?;
+    assert_eq!(pending, vec!["connections".to_string()]);
+
+    ctx.stop_draining(Duration::from_millis(0)).await
+}
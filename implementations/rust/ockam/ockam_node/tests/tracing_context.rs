@@ -0,0 +1,48 @@
+use ockam_core::compat::string::String;
+use ockam_core::{async_trait, route, AllowAll, Result, Routed, TraceContext, Worker};
+use ockam_node::{Context, TRACING_CONTEXT_ENV};
+
+struct TracingContextWorker;
+
+#[async_trait]
+impl Worker for TracingContextWorker {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(
+        &mut self,
+        ctx: &mut Self::Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        let traceparent = ctx
+            .tracing_context()
+            .map(|c| c.to_traceparent())
+            .unwrap_or_default();
+        ctx.send(msg.return_route(), traceparent).await
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn tracing_context__message_with_context__should_be_handled_in_child_span(
+    ctx: &mut Context,
+) -> Result<()> {
+    std::env::set_var(TRACING_CONTEXT_ENV, "true");
+    ctx.start_worker("tracing_context_worker", TracingContextWorker)
+        .await?;
+
+    let mut child_ctx = ctx.new_detached("child", AllowAll, AllowAll).await?;
+    let root = TraceContext::new_root();
+    child_ctx.set_tracing_context(Some(root.clone()));
+    child_ctx
+        .send(route!["tracing_context_worker"], String::new())
+        .await?;
+
+    let reply = child_ctx.receive::<String>().await?;
+    let span = reply.tracing_context().cloned().unwrap();
+    assert_eq!(span.trace_id(), root.trace_id());
+    assert_ne!(span.parent_id(), root.parent_id());
+    assert_eq!(Some(span), TraceContext::from_traceparent(&reply.body()));
+
+    ctx.stop().await
+}
@@ -0,0 +1,117 @@
+use crate::TransportError;
+use data_encoding::BASE64;
+use ockam_core::compat::string::String;
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Maximum size of the response head sent back by a proxy to a `CONNECT` request
+const MAX_RESPONSE_HEAD_SIZE: usize = 8 * 1024;
+
+/// Open a tunnel to the `host:port` target with an HTTP `CONNECT` request
+/// sent on a stream connected to an HTTP proxy.
+///
+/// The proxy is authenticated with the basic authentication scheme if
+/// credentials are given.
+pub async fn http_connect<S>(
+    stream: &mut S,
+    target: &str,
+    credentials: Option<(&str, &str)>,
+) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    stream
+        .write_all(connect_request(target, credentials).as_bytes())
+        .await
+        .map_err(TransportError::from)?;
+
+    let head = read_response_head(stream).await?;
+    let status_line = head.lines().next().unwrap_or_default();
+    match parse_status(status_line) {
+        Some(status) if (200..300).contains(&status) => Ok(()),
+        Some(407) => Err(Error::new(
+            Origin::Transport,
+            Kind::Invalid,
+            format!("proxy authentication failed: {}", status_line),
+        )),
+        Some(_) => Err(Error::new(
+            Origin::Transport,
+            Kind::Protocol,
+            format!("request refused: {}", status_line),
+        )),
+        None => Err(Error::new(
+            Origin::Transport,
+            Kind::Protocol,
+            "invalid HTTP response",
+        )),
+    }
+}
+
+fn connect_request(target: &str, credentials: Option<(&str, &str)>) -> String {
+    let mut request = format!("CONNECT {} HTTP/1.1\r\nHost: {}\r\n", target, target);
+    if let Some((username, password)) = credentials {
+        let token = BASE64.encode(format!("{}:{}", username, password).as_bytes());
+        request.push_str(&format!("Proxy-Authorization: Basic {}\r\n", token));
+    }
+    request.push_str("\r\n");
+    request
+}
+
+/// Read the response head of the proxy, up to the empty line.
+///
+/// The stream is read one byte at a time so that no byte sent by the target
+/// after the response is consumed.
+async fn read_response_head<S: AsyncRead + Unpin>(stream: &mut S) -> Result<String> {
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        if head.len() >= MAX_RESPONSE_HEAD_SIZE {
+            return Err(TransportError::Capacity.into());
+        }
+        head.push(stream.read_u8().await.map_err(TransportError::from)?);
+    }
+
+    Ok(String::from_utf8_lossy(&head).into_owned())
+}
+
+/// Return the status code of an HTTP/1.x status line
+fn parse_status(status_line: &str) -> Option<u16> {
+    let mut parts = status_line.split_whitespace();
+    if !parts.next()?.starts_with("HTTP/1.") {
+        return None;
+    }
+    parts.next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connect_request() {
+        assert_eq!(
+            connect_request("relay.example.com:443", None),
+            "CONNECT relay.example.com:443 HTTP/1.1\r\nHost: relay.example.com:443\r\n\r\n"
+        );
+        assert_eq!(
+            connect_request("relay.example.com:443", Some(("alice", "s3cret"))),
+            "CONNECT relay.example.com:443 HTTP/1.1\r\nHost: relay.example.com:443\r\n\
+             Proxy-Authorization: Basic YWxpY2U6czNjcmV0\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_status() {
+        assert_eq!(
+            parse_status("HTTP/1.1 200 Connection established"),
+            Some(200)
+        );
+        assert_eq!(
+            parse_status("HTTP/1.0 407 Proxy Authentication Required"),
+            Some(407)
+        );
+        assert_eq!(parse_status("SSH-2.0-OpenSSH"), None);
+        assert_eq!(parse_status(""), None);
+    }
+}
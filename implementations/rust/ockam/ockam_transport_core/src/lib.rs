@@ -14,7 +14,15 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 mod error;
+#[cfg(feature = "http-proxy")]
+mod http_proxy;
+#[cfg(feature = "tls")]
+mod tls;
 mod transport;
 
 pub use error::TransportError;
+#[cfg(feature = "http-proxy")]
+pub use http_proxy::http_connect;
+#[cfg(feature = "tls")]
+pub use tls::{read_pem_certificates, read_pem_private_key};
 pub use transport::*;
@@ -0,0 +1,51 @@
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use rustls::{Certificate, PrivateKey};
+
+/// Read the certificates of a PEM file, e.g. a certificate chain or root certificates
+pub fn read_pem_certificates(pem: &[u8]) -> Result<Vec<Certificate>> {
+    let certificates = rustls_pemfile::certs(&mut &*pem)
+        .map_err(|e| invalid(format!("invalid PEM certificates: {}", e)))?;
+    if certificates.is_empty() {
+        return Err(invalid("no PEM certificate found"));
+    }
+
+    Ok(certificates.into_iter().map(Certificate).collect())
+}
+
+/// Read the first PKCS#8, RSA or EC private key of a PEM file
+pub fn read_pem_private_key(mut pem: &[u8]) -> Result<PrivateKey> {
+    loop {
+        match rustls_pemfile::read_one(&mut pem)
+            .map_err(|e| invalid(format!("invalid PEM private key: {}", e)))?
+        {
+            Some(rustls_pemfile::Item::PKCS8Key(key))
+            | Some(rustls_pemfile::Item::RSAKey(key))
+            | Some(rustls_pemfile::Item::ECKey(key)) => return Ok(PrivateKey(key)),
+            Some(_) => continue,
+            None => return Err(invalid("no PEM private key found")),
+        }
+    }
+}
+
+fn invalid(msg: impl Into<String>) -> Error {
+    Error::new(Origin::Transport, Kind::Invalid, msg.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CERTIFICATE: &[u8] = include_bytes!("../tests/certificates/server.pem");
+    const KEY: &[u8] = include_bytes!("../tests/certificates/server.key");
+
+    #[test]
+    fn test_read_pem() {
+        assert!(!read_pem_certificates(CERTIFICATE).unwrap().is_empty());
+        assert!(read_pem_private_key(KEY).is_ok());
+
+        assert!(read_pem_certificates(KEY).is_err());
+        assert!(read_pem_private_key(CERTIFICATE).is_err());
+    }
+}
@@ -0,0 +1,31 @@
+//! This crate provides a QUIC Transport for Ockam's Routing Protocol.
+//!
+//! Messages sent to different next hops, for example different secure
+//! channels, are carried on independent QUIC streams of the same
+//! connection, so that a large transfer on one of them doesn't delay the
+//! others.
+//!
+//! This crate requires the rust standard library `"std"`
+#![deny(unsafe_code)]
+#![warn(
+    missing_docs,
+    dead_code,
+    trivial_casts,
+    trivial_numeric_casts,
+    unused_import_braces,
+    unused_qualifications
+)]
+
+mod options;
+mod transport;
+
+use ockam_core::TransportType;
+pub use options::{QuicConnectionOptions, QuicListenerOptions, DEFAULT_MAX_MESSAGE_SIZE};
+pub use transport::*;
+
+mod workers;
+
+pub(crate) const CLUSTER_NAME: &str = "_internals.transport.quic";
+
+/// Transport type for QUIC addresses
+pub const QUIC: TransportType = TransportType::new(6);
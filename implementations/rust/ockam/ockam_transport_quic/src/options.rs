@@ -0,0 +1,252 @@
+use crate::workers::Addresses;
+use core::fmt;
+use core::time::Duration;
+use ockam_core::compat::sync::Arc;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl, FlowControls};
+use ockam_core::{Address, AllowAll, Error, IncomingAccessControl, OutgoingAccessControl, Result};
+use ockam_transport_core::{read_pem_certificates, read_pem_private_key};
+use quinn::{ClientConfig, ServerConfig, TransportConfig};
+use rustls::{Certificate, PrivateKey, RootCertStore};
+
+/// Default maximum size of a message sent or received over a QUIC connection (16 MiB)
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+
+/// Interval of the keep alive packets, which keep NAT bindings open
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+pub(crate) struct QuicConnectionAccessControl {
+    pub sender_incoming_access_control: Arc<dyn IncomingAccessControl>,
+    pub receiver_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+}
+
+/// Trust Options for a QUIC connection
+pub struct QuicConnectionOptions {
+    pub(super) consumer: Vec<FlowControlId>,
+    pub(crate) flow_control_id: FlowControlId,
+    pub(crate) max_message_size: usize,
+    pub(crate) root_certificates: Option<RootCertStore>,
+    pub(crate) server_name: Option<String>,
+}
+
+impl fmt::Debug for QuicConnectionOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QuicConnectionOptions")
+            .field("consumer", &self.consumer)
+            .field("flow_control_id", &self.flow_control_id)
+            .field("max_message_size", &self.max_message_size)
+            .field("server_name", &self.server_name)
+            .finish()
+    }
+}
+
+impl QuicConnectionOptions {
+    #[allow(clippy::new_without_default)]
+    /// Mark this Quic Receiver as a Producer with a random [`FlowControlId`].
+    /// The server is authenticated with the root certificates of the platform
+    pub fn new() -> Self {
+        Self {
+            consumer: vec![],
+            flow_control_id: FlowControls::generate_flow_control_id(),
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            root_certificates: None,
+            server_name: None,
+        }
+    }
+
+    /// Mark that this Connection is a Consumer for to the given [`FlowControlId`]
+    pub fn as_consumer(mut self, id: &FlowControlId) -> Self {
+        self.consumer.push(id.clone());
+
+        self
+    }
+
+    /// Set the maximum size of a message sent or received over this connection
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = max_message_size;
+
+        self
+    }
+
+    /// Authenticate the server with the PEM encoded root certificates
+    /// instead of the root certificates of the platform
+    pub fn with_root_certificates(mut self, pem: &[u8]) -> Result<Self> {
+        let mut roots = RootCertStore::empty();
+        for c in read_pem_certificates(pem)? {
+            roots
+                .add(&c)
+                .map_err(|e| invalid(format!("invalid root certificate: {e}")))?;
+        }
+        self.root_certificates = Some(roots);
+
+        Ok(self)
+    }
+
+    /// Expect the server certificate to be valid for this name.
+    /// By default the host name of the connected peer is used
+    pub fn with_server_name(mut self, server_name: impl Into<String>) -> Self {
+        self.server_name = Some(server_name.into());
+
+        self
+    }
+
+    /// Getter for freshly generated [`FlowControlId`]
+    pub fn flow_control_id(&self) -> FlowControlId {
+        self.flow_control_id.clone()
+    }
+}
+
+impl QuicConnectionOptions {
+    pub(crate) fn client_config(&self) -> Result<ClientConfig> {
+        let roots = match &self.root_certificates {
+            Some(roots) => roots.clone(),
+            None => {
+                let mut roots = RootCertStore::empty();
+                let certificates = rustls_native_certs::load_native_certs().map_err(|e| {
+                    invalid(format!(
+                        "failed to load the platform root certificates: {e}"
+                    ))
+                })?;
+                for c in certificates {
+                    // Ignore the platform certificates which can not be parsed
+                    let _ = roots.add(&Certificate(c.0));
+                }
+                roots
+            }
+        };
+
+        let mut transport = TransportConfig::default();
+        transport.keep_alive_interval(Some(KEEP_ALIVE_INTERVAL));
+
+        let mut config = ClientConfig::with_root_certificates(roots);
+        config.transport_config(Arc::new(transport));
+        Ok(config)
+    }
+
+    pub(crate) fn setup_flow_control(&self, flow_controls: &FlowControls, addresses: &Addresses) {
+        flow_controls.add_producer(
+            addresses.receiver_address().clone(),
+            &self.flow_control_id,
+            None,
+            vec![addresses.sender_address().clone()],
+        );
+
+        for id in &self.consumer {
+            flow_controls.add_consumer(addresses.sender_address().clone(), id);
+        }
+    }
+
+    pub(crate) fn create_access_control(
+        self,
+        flow_controls: &FlowControls,
+    ) -> QuicConnectionAccessControl {
+        QuicConnectionAccessControl {
+            sender_incoming_access_control: Arc::new(AllowAll),
+            receiver_outgoing_access_control: Arc::new(FlowControlOutgoingAccessControl::new(
+                flow_controls,
+                self.flow_control_id,
+                None,
+            )),
+        }
+    }
+}
+
+/// Trust Options for a QUIC listener
+pub struct QuicListenerOptions {
+    pub(crate) flow_control_id: FlowControlId,
+    pub(crate) max_message_size: usize,
+    certificate_chain: Vec<Certificate>,
+    key: PrivateKey,
+}
+
+impl fmt::Debug for QuicListenerOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QuicListenerOptions")
+            .field("flow_control_id", &self.flow_control_id)
+            .field("max_message_size", &self.max_message_size)
+            .field("certificate_chain", &self.certificate_chain.len())
+            .finish()
+    }
+}
+
+impl QuicListenerOptions {
+    /// Mark this Quic Listener as a Spawner with given [`FlowControlId`].
+    /// The listener is authenticated with the PEM encoded certificate chain and private key.
+    /// NOTE: Spawned connections get fresh random [`FlowControlId`], however they are still marked
+    /// with Spawner's [`FlowControlId`]
+    pub fn new(certificate_chain: &[u8], key: &[u8]) -> Result<Self> {
+        Ok(Self {
+            flow_control_id: FlowControls::generate_flow_control_id(),
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            certificate_chain: read_pem_certificates(certificate_chain)?,
+            key: read_pem_private_key(key)?,
+        })
+    }
+
+    /// Set the maximum size of a message sent or received over accepted connections
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = max_message_size;
+
+        self
+    }
+
+    /// Getter for freshly generated [`FlowControlId`]
+    pub fn spawner_flow_control_id(&self) -> FlowControlId {
+        self.flow_control_id.clone()
+    }
+}
+
+impl QuicListenerOptions {
+    pub(crate) fn server_config(&self) -> Result<ServerConfig> {
+        let mut config =
+            ServerConfig::with_single_cert(self.certificate_chain.clone(), self.key.clone())
+                .map_err(|e| invalid(format!("invalid server certificate: {e}")))?;
+        // Clients keep their connections when their address changes
+        config.migration(true);
+        Ok(config)
+    }
+
+    pub(crate) fn setup_flow_control_for_listener(
+        &self,
+        flow_controls: &FlowControls,
+        address: &Address,
+    ) {
+        flow_controls.add_spawner(address.clone(), &self.flow_control_id);
+    }
+
+    pub(crate) fn setup_flow_control_for_connection(
+        &self,
+        flow_controls: &FlowControls,
+        addresses: &Addresses,
+    ) -> FlowControlId {
+        let flow_control_id = FlowControls::generate_flow_control_id();
+
+        flow_controls.add_producer(
+            addresses.receiver_address().clone(),
+            &flow_control_id,
+            Some(&self.flow_control_id),
+            vec![addresses.sender_address().clone()],
+        );
+
+        flow_control_id
+    }
+
+    pub(crate) fn create_access_control(
+        &self,
+        flow_controls: &FlowControls,
+        flow_control_id: FlowControlId,
+    ) -> QuicConnectionAccessControl {
+        QuicConnectionAccessControl {
+            sender_incoming_access_control: Arc::new(AllowAll),
+            receiver_outgoing_access_control: Arc::new(FlowControlOutgoingAccessControl::new(
+                flow_controls,
+                flow_control_id,
+                Some(self.flow_control_id.clone()),
+            )),
+        }
+    }
+}
+
+fn invalid(msg: impl Into<String>) -> Error {
+    Error::new(Origin::Transport, Kind::Invalid, msg.into())
+}
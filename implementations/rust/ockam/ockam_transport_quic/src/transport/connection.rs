@@ -0,0 +1,96 @@
+use crate::transport::common::{host_name, parse_socket_addr, resolve_peer, QuicConnection};
+use crate::workers::{Addresses, QuicRecvProcessor, QuicSendWorker};
+use crate::{QuicConnectionOptions, QuicTransport};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Address, Error, Result};
+use ockam_transport_core::TransportError;
+use tracing::debug;
+
+impl QuicTransport {
+    /// Establish an outgoing QUIC connection.
+    ///
+    /// ```rust
+    /// use ockam_transport_quic::{QuicConnectionOptions, QuicTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::Result;
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let quic = QuicTransport::create(&ctx).await?;
+    /// let connection = quic.connect("example.com:5000", QuicConnectionOptions::new()).await?; // connect to port 5000
+    /// # Ok(()) }
+    /// ```
+    pub async fn connect(
+        &self,
+        peer: impl Into<String>,
+        options: QuicConnectionOptions,
+    ) -> Result<QuicConnection> {
+        // Resolve peer address
+        let peer = peer.into();
+        let socket = resolve_peer(&peer)?;
+
+        let server_name = match &options.server_name {
+            Some(name) => name.as_str(),
+            None => host_name(&peer),
+        };
+        debug!(addr = %socket, %server_name, "Connecting");
+        let connection = self
+            .endpoint
+            .connect_with(options.client_config()?, socket, server_name)
+            .map_err(|e| Error::new(Origin::Transport, Kind::Invalid, e))?
+            .await
+            .map_err(|e| {
+                debug!(addr = %socket, err = %e, "Failed to connect");
+                Error::new(Origin::Transport, Kind::Io, e)
+            })?;
+        debug!(addr = %socket, "Connected");
+
+        let addresses = Addresses::generate();
+
+        options.setup_flow_control(self.ctx.flow_controls(), &addresses);
+        let flow_control_id = options.flow_control_id.clone();
+        let max_message_size = options.max_message_size;
+        let access_control = options.create_access_control(self.ctx.flow_controls());
+
+        QuicSendWorker::start(
+            &self.ctx,
+            connection.clone(),
+            &addresses,
+            access_control.sender_incoming_access_control,
+            max_message_size,
+        )
+        .await?;
+
+        QuicRecvProcessor::start(
+            &self.ctx,
+            connection,
+            &addresses,
+            access_control.receiver_outgoing_access_control,
+            max_message_size,
+        )
+        .await?;
+
+        Ok(QuicConnection::new(
+            addresses.sender_address().clone(),
+            addresses.receiver_address().clone(),
+            socket,
+            flow_control_id,
+        ))
+    }
+
+    /// Interrupt an active QUIC connection given its Sender `Address`
+    pub async fn disconnect(&self, address: impl Into<Address>) -> Result<()> {
+        self.ctx.stop_worker(address.into()).await
+    }
+
+    /// Move all the outgoing connections to a new local UDP socket.
+    ///
+    /// The connections are kept open and migrated to the new address,
+    /// e.g. when a device switches from one network to another.
+    pub fn rebind(&self, bind_addr: impl AsRef<str>) -> Result<()> {
+        let bind_addr = parse_socket_addr(bind_addr.as_ref())?;
+        let socket = std::net::UdpSocket::bind(bind_addr).map_err(TransportError::from)?;
+        self.endpoint.rebind(socket).map_err(TransportError::from)?;
+        debug!(addr = %bind_addr, "Outgoing QUIC connections migrated");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use ockam_core::compat::net::SocketAddr;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{async_trait, Address, AsyncTryClone, Error, Result, TransportType};
+use ockam_node::Context;
+use ockam_transport_core::{Transport, TransportError};
+use quinn::Endpoint;
+use std::sync::Arc;
+
+use crate::{QuicConnectionOptions, QuicTransport, QUIC};
+
+impl QuicTransport {
+    /// Create a QUIC transport
+    ///
+    /// Outgoing connections use a UDP socket bound to an ephemeral port
+    /// on all the IPv4 interfaces.
+    ///
+    /// ```rust
+    /// use ockam_transport_quic::QuicTransport;
+    /// # use ockam_node::Context;
+    /// # use ockam_core::Result;
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let quic = QuicTransport::create(&ctx).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn create(ctx: &Context) -> Result<Self> {
+        let endpoint =
+            Endpoint::client(SocketAddr::from(([0, 0, 0, 0], 0))).map_err(TransportError::from)?;
+        let quic = Self {
+            ctx: ctx.async_try_clone().await?,
+            endpoint,
+        };
+        // make the QUIC transport available in the list of supported transports for
+        // later address resolution when socket addresses will need to be instantiated as QUIC
+        // worker addresses
+        ctx.register_transport(Arc::new(quic.async_try_clone().await?));
+        Ok(quic)
+    }
+}
+
+impl QuicTransport {
+    /// Getter
+    pub fn ctx(&self) -> &Context {
+        &self.ctx
+    }
+}
+
+#[async_trait]
+impl Transport for QuicTransport {
+    fn transport_type(&self) -> TransportType {
+        QUIC
+    }
+
+    async fn resolve_address(&self, address: Address) -> Result<Address> {
+        if address.transport_type() == QUIC {
+            Ok(self
+                .connect(address.address().to_string(), QuicConnectionOptions::new())
+                .await?
+                .into())
+        } else {
+            Err(Error::new(
+                Origin::Transport,
+                Kind::NotFound,
+                format!(
+                    "this address can not be resolved by a QUIC transport {}",
+                    address
+                ),
+            ))
+        }
+    }
+}
@@ -0,0 +1,53 @@
+pub(crate) mod common;
+mod connection;
+mod lifecycle;
+mod listener;
+
+pub use common::*;
+
+use ockam_core::{async_trait, AsyncTryClone, Result};
+use ockam_node::{Context, HasContext};
+use quinn::Endpoint;
+
+/// High level management interface for QUIC transports
+///
+/// Be aware that only one `QuicTransport` can exist per node, as it
+/// registers itself as a transport for the `QUIC` address type.
+///
+/// To listen for incoming connections use
+/// [`quic.listen()`](crate::QuicTransport::listen).
+///
+/// To establish outgoing connections use
+/// [`quic.connect()`](crate::QuicTransport::connect).
+/// All outgoing connections share the same local UDP socket, which can be
+/// changed with [`quic.rebind()`](crate::QuicTransport::rebind) without
+/// interrupting them, e.g. when the network of the device changes.
+///
+/// ```rust
+/// use ockam_transport_quic::{QuicConnectionOptions, QuicListenerOptions, QuicTransport};
+/// # use ockam_node::Context;
+/// # use ockam_core::Result;
+/// # async fn test(ctx: Context, certificate_chain: &[u8], key: &[u8]) -> Result<()> {
+/// let quic = QuicTransport::create(&ctx).await?;
+/// quic.listen("127.0.0.1:8000", QuicListenerOptions::new(certificate_chain, key)?).await?; // Listen on port 8000
+/// quic.connect("example.com:5000", QuicConnectionOptions::new()).await?; // And connect to port 5000
+/// # Ok(()) }
+/// ```
+#[derive(AsyncTryClone)]
+#[async_try_clone(crate = "ockam_core")]
+pub struct QuicTransport {
+    ctx: Context,
+    endpoint: Endpoint,
+}
+
+/// This trait adds a `create_quic_transport` method to any struct returning a Context.
+/// This is the case for an ockam::Node, so you can write `node.create_quic_transport()`
+#[async_trait]
+pub trait QuicTransportExtension: HasContext {
+    /// Create a QUIC transport
+    async fn create_quic_transport(&self) -> Result<QuicTransport> {
+        QuicTransport::create(self.get_context()).await
+    }
+}
+
+impl<A: HasContext> QuicTransportExtension for A {}
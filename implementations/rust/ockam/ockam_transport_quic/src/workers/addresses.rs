@@ -0,0 +1,24 @@
+use ockam_core::Address;
+
+#[derive(Clone, Debug)]
+pub(crate) struct Addresses {
+    /// Used to receive messages from other workers which are then serialized and sent over the wire
+    sender_address: Address,
+    /// Receiver Processor Address
+    receiver_address: Address,
+}
+
+impl Addresses {
+    pub(crate) fn generate() -> Self {
+        Self {
+            sender_address: Address::random_tagged("QuicSendWorker_tx_addr"),
+            receiver_address: Address::random_tagged("QuicRecvProcessor"),
+        }
+    }
+    pub fn sender_address(&self) -> &Address {
+        &self.sender_address
+    }
+    pub fn receiver_address(&self) -> &Address {
+        &self.receiver_address
+    }
+}
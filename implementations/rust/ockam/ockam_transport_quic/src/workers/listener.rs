@@ -0,0 +1,117 @@
+use crate::workers::{Addresses, QuicRecvProcessor, QuicSendWorker};
+use crate::QuicListenerOptions;
+use core::time::Duration;
+use ockam_core::{async_trait, compat::net::SocketAddr};
+use ockam_core::{Address, Processor, Result};
+use ockam_node::Context;
+use ockam_transport_core::TransportError;
+use quinn::Endpoint;
+use tokio::time::timeout;
+use tracing::{debug, warn};
+
+/// Maximum duration of the handshake of an accepted connection
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A QUIC Listen processor
+///
+/// QUIC listen processors are created by `QuicTransport`
+/// after a call is made to
+/// [`QuicTransport::listen`](crate::QuicTransport::listen).
+pub(crate) struct QuicListenProcessor {
+    endpoint: Endpoint,
+    options: QuicListenerOptions,
+}
+
+impl QuicListenProcessor {
+    pub(crate) async fn start(
+        ctx: &Context,
+        addr: SocketAddr,
+        options: QuicListenerOptions,
+    ) -> Result<(SocketAddr, Address)> {
+        debug!("Binding QUIC endpoint to {}", addr);
+        let endpoint =
+            Endpoint::server(options.server_config()?, addr).map_err(TransportError::from)?;
+        let saddr = endpoint.local_addr().map_err(TransportError::from)?;
+
+        let address = Address::random_tagged("QuicListenProcessor");
+        options.setup_flow_control_for_listener(ctx.flow_controls(), &address);
+
+        let processor = Self { endpoint, options };
+
+        ctx.start_processor(address.clone(), processor).await?;
+
+        Ok((saddr, address))
+    }
+}
+
+#[async_trait]
+impl Processor for QuicListenProcessor {
+    type Context = Context;
+
+    async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.set_cluster(crate::CLUSTER_NAME).await
+    }
+
+    async fn shutdown(&mut self, _ctx: &mut Self::Context) -> Result<()> {
+        self.endpoint.close(0u32.into(), b"closed");
+
+        Ok(())
+    }
+
+    async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
+        debug!("Waiting for incoming QUIC connection...");
+
+        // Wait for an incoming connection
+        let connecting = match self.endpoint.accept().await {
+            Some(connecting) => connecting,
+            // The endpoint was closed
+            None => return Ok(false),
+        };
+        let peer = connecting.remote_address();
+
+        // A failed handshake only drops this connection
+        let connection = match timeout(HANDSHAKE_TIMEOUT, connecting).await {
+            Ok(Ok(connection)) => connection,
+            Ok(Err(e)) => {
+                warn!(%peer, "Dropping incoming QUIC connection: {e}");
+                return Ok(true);
+            }
+            Err(_) => {
+                warn!(%peer, "Dropping incoming QUIC connection: handshake timed out");
+                return Ok(true);
+            }
+        };
+        debug!(%peer, "QUIC connection accepted");
+
+        let addresses = Addresses::generate();
+
+        let receiver_flow_control_id = self
+            .options
+            .setup_flow_control_for_connection(ctx.flow_controls(), &addresses);
+        let access_control = self
+            .options
+            .create_access_control(ctx.flow_controls(), receiver_flow_control_id);
+
+        // Worker to receive messages from the Node and send them over the wire
+        QuicSendWorker::start(
+            ctx,
+            connection.clone(),
+            &addresses,
+            access_control.sender_incoming_access_control,
+            self.options.max_message_size,
+        )
+        .await?;
+
+        // Processor to receive messages over the wire and forward them to the node
+        QuicRecvProcessor::start(
+            ctx,
+            connection,
+            &addresses,
+            access_control.receiver_outgoing_access_control,
+            self.options.max_message_size,
+        )
+        .await?;
+
+        Ok(true)
+    }
+}
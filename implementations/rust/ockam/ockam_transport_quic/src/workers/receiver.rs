@@ -0,0 +1,205 @@
+use crate::workers::Addresses;
+use ockam_core::compat::sync::Arc;
+use ockam_core::{
+    async_trait, Decodable, DenyAll, LocalMessage, Mailbox, Mailboxes, OutgoingAccessControl,
+    Processor, Result, TransportMessage,
+};
+use ockam_node::{Context, ProcessorBuilder};
+use quinn::{Connection, RecvStream};
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, trace, warn};
+
+/// Maximum number of received messages waiting to be relayed.
+///
+/// When it is reached, the streams are not read until the processor catches
+/// up, and QUIC flow control slows down the peer.
+const MAX_QUEUED_MESSAGES: usize = 128;
+
+/// A QUIC receiving message processor
+///
+/// The streams opened by the peer are read concurrently, and their
+/// messages are relayed into the node message system as soon as they are
+/// complete.
+pub(crate) struct QuicRecvProcessor {
+    connection: Connection,
+    addresses: Addresses,
+    max_message_size: usize,
+    messages_tx: Option<Sender<TransportMessage>>,
+    messages_rx: Receiver<TransportMessage>,
+    accept_task: Option<JoinHandle<()>>,
+}
+
+impl QuicRecvProcessor {
+    /// Start a receiver for an established connection
+    pub(crate) async fn start(
+        ctx: &Context,
+        connection: Connection,
+        addresses: &Addresses,
+        receiver_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        max_message_size: usize,
+    ) -> Result<()> {
+        let (messages_tx, messages_rx) = channel(MAX_QUEUED_MESSAGES);
+        let receiver = Self {
+            connection,
+            addresses: addresses.clone(),
+            max_message_size,
+            messages_tx: Some(messages_tx),
+            messages_rx,
+            accept_task: None,
+        };
+
+        let mailbox = Mailbox::new(
+            addresses.receiver_address().clone(),
+            Arc::new(DenyAll),
+            receiver_outgoing_access_control,
+        );
+        ProcessorBuilder::new(receiver)
+            .with_mailboxes(Mailboxes::new(mailbox, vec![]))
+            .start(ctx)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Processor for QuicRecvProcessor {
+    type Context = Context;
+
+    async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.set_cluster(crate::CLUSTER_NAME).await?;
+
+        if let Some(messages_tx) = self.messages_tx.take() {
+            self.accept_task = Some(tokio::spawn(accept_streams(
+                self.connection.clone(),
+                messages_tx,
+                self.max_message_size,
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn shutdown(&mut self, _ctx: &mut Self::Context) -> Result<()> {
+        if let Some(accept_task) = self.accept_task.take() {
+            accept_task.abort();
+        }
+
+        Ok(())
+    }
+
+    /// Get the next message received on any stream of the connection and
+    /// forward it to the next hop in the route.
+    async fn process(&mut self, ctx: &mut Context) -> Result<bool> {
+        let mut msg = match self.messages_rx.recv().await {
+            Some(msg) => msg,
+            // All the streams and the connection are closed
+            None => {
+                info!(
+                    "Connection to peer '{}' was closed; dropping connection",
+                    self.connection.remote_address()
+                );
+                let _ = ctx
+                    .stop_worker(self.addresses.sender_address().clone())
+                    .await;
+
+                return Ok(false);
+            }
+        };
+
+        // Heartbeat message
+        if msg.onward_route.next().is_err() {
+            trace!(
+                "Got heartbeat message from: {}",
+                self.connection.remote_address()
+            );
+            return Ok(true);
+        }
+
+        // Insert the peer address into the return route so that
+        // reply routing can be properly resolved
+        msg.return_route
+            .modify()
+            .prepend(self.addresses.sender_address().clone());
+
+        trace!("Message onward route: {}", msg.onward_route);
+        trace!("Message return route: {}", msg.return_route);
+
+        // Forward the message to the next hop in the route
+        ctx.forward_from_address(
+            LocalMessage::new(msg, vec![]),
+            self.addresses.receiver_address().clone(),
+        )
+        .await?;
+
+        Ok(true)
+    }
+}
+
+/// Read every stream opened by the peer until the connection is closed
+async fn accept_streams(
+    connection: Connection,
+    messages_tx: Sender<TransportMessage>,
+    max_message_size: usize,
+) {
+    loop {
+        match connection.accept_uni().await {
+            Ok(stream) => {
+                tokio::spawn(read_frames(stream, messages_tx.clone(), max_message_size));
+            }
+            Err(e) => {
+                debug!(
+                    "Connection to peer '{}' was closed: {}",
+                    connection.remote_address(),
+                    e
+                );
+                return;
+            }
+        }
+    }
+}
+
+/// Read the length-prefixed messages of a stream until it is finished
+async fn read_frames(
+    mut stream: RecvStream,
+    messages_tx: Sender<TransportMessage>,
+    max_message_size: usize,
+) {
+    let mut header = [0; 4];
+    loop {
+        // The stream is finished or the connection is closed
+        if stream.read_exact(&mut header).await.is_err() {
+            return;
+        }
+
+        let len = u32::from_be_bytes(header) as usize;
+        if len > max_message_size {
+            warn!(
+                "Message of {} bytes exceeds the maximum message size; dropping stream",
+                len
+            );
+            let _ = stream.stop(0u32.into());
+            return;
+        }
+
+        let mut buf = vec![0; len];
+        if let Err(e) = stream.read_exact(&mut buf).await {
+            debug!("Failed to receive message of length {}: {}", len, e);
+            return;
+        }
+
+        match TransportMessage::decode(&buf) {
+            Ok(msg) => {
+                if messages_tx.send(msg).await.is_err() {
+                    return;
+                }
+            }
+            Err(_) => {
+                warn!("Received an invalid message; dropping stream");
+                let _ = stream.stop(0u32.into());
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use crate::workers::Addresses;
+use core::hash::{Hash, Hasher};
+use ockam_core::compat::sync::Arc;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{
+    async_trait, Address, Any, DenyAll, Encodable, Error, IncomingAccessControl, Mailbox,
+    Mailboxes, Result, Routed, Worker,
+};
+use ockam_node::{Context, WorkerBuilder};
+use ockam_transport_core::TransportError;
+use quinn::{Connection, SendStream};
+use std::collections::hash_map::DefaultHasher;
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tracing::{debug, trace, warn};
+
+/// Maximum number of streams opened by a sender.
+///
+/// Next hops are spread over the streams, messages to the same next hop
+/// always use the same stream and are received in order.
+const MAX_STREAMS: usize = 64;
+
+/// Maximum number of frames waiting to be written to a stream.
+///
+/// When a stream is full, the sender waits for it to drain, so that a slow
+/// peer can't make the node buffer an unbounded amount of messages.
+const MAX_QUEUED_FRAMES: usize = 32;
+
+/// A QUIC sending message worker
+///
+/// Each message is written to the stream of its next hop, so that
+/// messages to different workers, e.g. different secure channels, don't
+/// wait for each other.
+pub(crate) struct QuicSendWorker {
+    connection: Connection,
+    addresses: Addresses,
+    max_message_size: usize,
+    streams: Vec<Option<Sender<Vec<u8>>>>,
+}
+
+impl QuicSendWorker {
+    /// Start a sender for an established connection
+    pub(crate) async fn start(
+        ctx: &Context,
+        connection: Connection,
+        addresses: &Addresses,
+        sender_incoming_access_control: Arc<dyn IncomingAccessControl>,
+        max_message_size: usize,
+    ) -> Result<()> {
+        trace!("Creating new QUIC sender");
+        let sender_worker = Self {
+            connection,
+            addresses: addresses.clone(),
+            max_message_size,
+            streams: vec![None; MAX_STREAMS],
+        };
+
+        let mailbox = Mailbox::new(
+            addresses.sender_address().clone(),
+            sender_incoming_access_control,
+            Arc::new(DenyAll),
+        );
+
+        WorkerBuilder::new(sender_worker)
+            .with_mailboxes(Mailboxes::new(mailbox, vec![]))
+            .start(ctx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Queue a frame on the stream of a next hop, opening the stream if needed
+    async fn send_frame(&mut self, next_hop: &Address, frame: Vec<u8>) -> Result<()> {
+        let index = stream_index(next_hop);
+        let frame = match &self.streams[index] {
+            Some(tx) => match tx.send(frame).await {
+                Ok(()) => return Ok(()),
+                // The stream failed, open a new one
+                Err(e) => e.0,
+            },
+            None => frame,
+        };
+
+        let stream = self
+            .connection
+            .open_uni()
+            .await
+            .map_err(|e| Error::new(Origin::Transport, Kind::Io, e))?;
+        let (tx, rx) = channel(MAX_QUEUED_FRAMES);
+        tokio::spawn(write_frames(stream, rx));
+        tx.send(frame)
+            .await
+            .map_err(|_| TransportError::SendBadMessage)?;
+        self.streams[index] = Some(tx);
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Worker for QuicSendWorker {
+    type Context = Context;
+    type Message = Any;
+
+    async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        ctx.set_cluster(crate::CLUSTER_NAME).await
+    }
+
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        self.connection.close(0u32.into(), b"closed");
+        let _ = ctx
+            .stop_processor(self.addresses.receiver_address().clone())
+            .await;
+
+        Ok(())
+    }
+
+    async fn handle_message(
+        &mut self,
+        ctx: &mut Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        let mut msg = msg.into_transport_message();
+        // Remove our own address from the route so the other end
+        // knows what to do with the incoming message
+        msg.onward_route.step()?;
+        // Heartbeats have no next hop, any stream can be used
+        let next_hop = msg
+            .onward_route
+            .next()
+            .unwrap_or(self.addresses.sender_address())
+            .clone();
+
+        let msg = msg.encode().map_err(|_| TransportError::SendBadMessage)?;
+        if msg.len() > self.max_message_size || msg.len() > u32::MAX as usize {
+            warn!(
+                "Dropping message of {} bytes to peer {}: maximum message size is {} bytes",
+                msg.len(),
+                self.connection.remote_address(),
+                self.max_message_size
+            );
+
+            return Ok(());
+        }
+
+        // Create a message buffer with prepended length
+        let mut frame = Vec::with_capacity(4 + msg.len());
+        frame.extend_from_slice(&(msg.len() as u32).to_be_bytes());
+        frame.extend_from_slice(&msg);
+
+        if let Err(e) = self.send_frame(&next_hop, frame).await {
+            warn!(
+                "Failed to send message to peer {}: {}",
+                self.connection.remote_address(),
+                e
+            );
+            ctx.stop_worker(self.addresses.sender_address().clone())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Index of the stream used for a next hop
+fn stream_index(next_hop: &Address) -> usize {
+    let mut hasher = DefaultHasher::new();
+    next_hop.hash(&mut hasher);
+    (hasher.finish() % MAX_STREAMS as u64) as usize
+}
+
+/// Write the frames queued for a stream until the sender is dropped
+async fn write_frames(mut stream: SendStream, mut rx: Receiver<Vec<u8>>) {
+    while let Some(frame) = rx.recv().await {
+        if let Err(e) = stream.write_all(&frame).await {
+            debug!("Failed to write to QUIC stream: {}", e);
+            return;
+        }
+    }
+    let _ = stream.finish().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{stream_index, MAX_STREAMS};
+    use ockam_core::Address;
+
+    #[test]
+    fn same_next_hop_uses_same_stream() {
+        let a = Address::from_string("0#channel_a");
+        assert_eq!(stream_index(&a), stream_index(&a.clone()));
+        for i in 0..1000 {
+            let address = Address::from_string(format!("0#channel_{i}"));
+            assert!(stream_index(&address) < MAX_STREAMS);
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use ockam_core::{route, Result, Routed, Worker};
+use ockam_node::Context;
+use ockam_transport_quic::{QuicConnectionOptions, QuicListenerOptions, QuicTransport};
+
+const CA: &[u8] = include_bytes!("../../ockam_transport_core/tests/certificates/ca.pem");
+const SERVER_CERTIFICATE: &[u8] =
+    include_bytes!("../../ockam_transport_core/tests/certificates/server.pem");
+const SERVER_KEY: &[u8] =
+    include_bytes!("../../ockam_transport_core/tests/certificates/server.key");
+
+pub struct Echoer;
+
+#[ockam_core::worker]
+impl Worker for Echoer {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        ctx.send(msg.return_route(), msg.body()).await
+    }
+}
+
+#[ockam_macros::test]
+async fn send_receive(ctx: &mut Context) -> Result<()> {
+    let options = QuicListenerOptions::new(SERVER_CERTIFICATE, SERVER_KEY)?;
+    ctx.flow_controls()
+        .add_consumer("echoer", &options.spawner_flow_control_id());
+    ctx.start_worker("echoer", Echoer).await?;
+
+    let transport = QuicTransport::create(ctx).await?;
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+
+    let options = QuicConnectionOptions::new()
+        .with_root_certificates(CA)?
+        .with_server_name("localhost");
+    let addr = transport
+        .connect(listener.socket_string(), options)
+        .await?
+        .sender_address()
+        .clone();
+
+    // A small message and a message larger than a QUIC packet
+    for msg in ["Hello over QUIC".to_string(), "x".repeat(1024 * 1024)] {
+        let reply = ctx
+            .send_and_receive::<String>(route![addr.clone(), "echoer"], msg.clone())
+            .await?;
+        assert_eq!(reply, msg, "Should receive the same message");
+    }
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
@@ -18,15 +18,22 @@ extern crate core;
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+mod metrics;
 mod options;
 mod portal;
+mod proxy;
 mod registry;
+#[cfg(feature = "tls")]
+mod tls;
 mod transport;
 
 use ockam_core::TransportType;
-pub use options::{TcpConnectionOptions, TcpListenerOptions};
+pub use options::{TcpConnectionOptions, TcpListenerOptions, DEFAULT_MAX_MESSAGE_SIZE};
 pub use portal::{PortalInternalMessage, PortalMessage, MAX_PAYLOAD_SIZE};
+pub use proxy::{TcpProxy, TcpProxyKind};
 pub use registry::*;
+#[cfg(feature = "tls")]
+pub use tls::{TlsClientOptions, TlsServerOptions};
 pub use transport::common::*;
 pub use transport::*;
 
@@ -0,0 +1,29 @@
+use ockam_node::{Context, Counter, Gauge};
+
+const BYTES_RECEIVED: &str = "ockam_transport_bytes_received";
+const BYTES_RECEIVED_HELP: &str = "Bytes of transport messages received from peers";
+const BYTES_SENT: &str = "ockam_transport_bytes_sent";
+const BYTES_SENT_HELP: &str = "Bytes of transport messages sent to peers";
+const PORTAL_CONNECTIONS: &str = "ockam_portal_connections";
+const PORTAL_CONNECTIONS_HELP: &str = "Number of open TCP portal connections";
+
+/// Bytes received by all the TCP connections of the node
+pub(crate) fn bytes_received(ctx: &Context) -> Counter {
+    ctx.metrics()
+        .counter(BYTES_RECEIVED, BYTES_RECEIVED_HELP, &[("transport", "tcp")])
+}
+
+/// Bytes sent by all the TCP connections of the node
+pub(crate) fn bytes_sent(ctx: &Context) -> Counter {
+    ctx.metrics()
+        .counter(BYTES_SENT, BYTES_SENT_HELP, &[("transport", "tcp")])
+}
+
+/// Open connections of the given portal type, `inlet` or `outlet`
+pub(crate) fn portal_connections(ctx: &Context, portal_type: &str) -> Gauge {
+    ctx.metrics().gauge(
+        PORTAL_CONNECTIONS,
+        PORTAL_CONNECTIONS_HELP,
+        &[("portal", portal_type)],
+    )
+}
@@ -1,18 +1,36 @@
-use crate::workers::Addresses;
+#[cfg(feature = "tls")]
+use crate::tls::{TlsClientOptions, TlsServerOptions};
+use crate::transport::common::resolve_peer;
+use crate::workers::{
+    split_stream, Addresses, ConnectionReadHalf, ConnectionWriteHalf,
+    TcpPooledOutgoingAccessControl, TcpSendWorker,
+};
+use crate::{TcpProxy, TcpRegistry};
+use ockam_core::compat::net::SocketAddr;
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl, FlowControls};
-use ockam_core::{Address, AllowAll, IncomingAccessControl, OutgoingAccessControl};
+use ockam_core::{Address, AllowAll, IncomingAccessControl, OutgoingAccessControl, Result};
+use ockam_transport_core::TransportError;
+use tokio::net::TcpStream;
 
 pub(crate) struct TcpConnectionAccessControl {
     pub sender_incoming_access_control: Arc<dyn IncomingAccessControl>,
     pub receiver_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
 }
 
+/// Default maximum size of a message sent or received over a TCP connection (16 MiB)
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+
 /// Trust Options for a TCP connection
 #[derive(Debug)]
 pub struct TcpConnectionOptions {
     pub(super) consumer: Vec<FlowControlId>,
     pub(crate) flow_control_id: FlowControlId,
+    pub(crate) max_message_size: usize,
+    pub(crate) proxy: Option<TcpProxy>,
+    pub(crate) pooled: bool,
+    #[cfg(feature = "tls")]
+    pub(crate) tls: Option<TlsClientOptions>,
 }
 
 impl TcpConnectionOptions {
@@ -22,6 +40,11 @@ impl TcpConnectionOptions {
         Self {
             consumer: vec![],
             flow_control_id: FlowControls::generate_flow_control_id(),
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            proxy: None,
+            pooled: false,
+            #[cfg(feature = "tls")]
+            tls: None,
         }
     }
 
@@ -32,6 +55,54 @@ impl TcpConnectionOptions {
         self
     }
 
+    /// Set the maximum size of a message sent or received over this connection.
+    /// Messages larger than 64 KiB are fragmented on the wire and reassembled by the peer
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = max_message_size;
+
+        self
+    }
+
+    /// Tunnel the connection through an HTTP or SOCKS5 proxy
+    pub fn with_proxy(mut self, proxy: TcpProxy) -> Self {
+        self.proxy = Some(proxy);
+
+        self
+    }
+
+    /// Tunnel the connection through the proxy set in the `HTTPS_PROXY` or
+    /// `ALL_PROXY` environment variables, if any.
+    /// See [`TcpProxy::from_env`]
+    pub fn with_proxy_from_env(mut self) -> Result<Self> {
+        if let Some(proxy) = TcpProxy::from_env()? {
+            self.proxy = Some(proxy);
+        }
+
+        Ok(self)
+    }
+
+    /// Reuse an existing pooled connection to the same socket address, if any,
+    /// instead of opening a new socket.
+    ///
+    /// The connection still gets its own sender and receiver [`Address`] and its own
+    /// [`FlowControlId`]. Messages received on the shared socket are only delivered
+    /// to the connection which sent the messages they reply to, and the shared socket
+    /// is closed when its last user disconnects.
+    /// Connections tunnelled through a proxy or using TLS are never pooled
+    pub fn with_pooling(mut self) -> Self {
+        self.pooled = true;
+
+        self
+    }
+
+    /// Establish a TLS session on top of the TCP connection
+    #[cfg(feature = "tls")]
+    pub fn with_tls(mut self, tls: TlsClientOptions) -> Self {
+        self.tls = Some(tls);
+
+        self
+    }
+
     /// Getter for freshly generated [`FlowControlId`]
     pub fn flow_control_id(&self) -> FlowControlId {
         self.flow_control_id.clone()
@@ -39,6 +110,59 @@ impl TcpConnectionOptions {
 }
 
 impl TcpConnectionOptions {
+    /// Connect to the `host:port` peer, through the proxy if one is used for this peer.
+    ///
+    /// Return the stream and the address of the connected socket,
+    /// which is the address of the proxy if the connection is tunnelled
+    pub(crate) async fn connect(&self, peer: &str) -> Result<(TcpStream, SocketAddr)> {
+        match &self.proxy {
+            Some(proxy) if proxy.is_used_for(peer) => {
+                let stream = proxy.connect(peer).await?;
+                let socket_address = stream.peer_addr().map_err(TransportError::from)?;
+                Ok((stream, socket_address))
+            }
+            _ => {
+                let socket_address = resolve_peer(peer.to_string())?;
+                let stream = TcpSendWorker::connect(socket_address).await?;
+                Ok((stream, socket_address))
+            }
+        }
+    }
+
+    /// Return the socket address identifying the pooled connection to the peer,
+    /// or `None` if the connection to this peer can't be pooled
+    pub(crate) fn pooled_socket_address(&self, peer: &str) -> Result<Option<SocketAddr>> {
+        if !self.pooled {
+            return Ok(None);
+        }
+
+        if matches!(&self.proxy, Some(proxy) if proxy.is_used_for(peer)) {
+            return Ok(None);
+        }
+
+        #[cfg(feature = "tls")]
+        if self.tls.is_some() {
+            return Ok(None);
+        }
+
+        Ok(Some(resolve_peer(peer.to_string())?))
+    }
+
+    /// Split a connected stream, performing a TLS handshake first if required
+    #[allow(unused_variables)]
+    pub(crate) async fn split_stream(
+        &self,
+        peer: &str,
+        stream: TcpStream,
+    ) -> Result<(ConnectionReadHalf, ConnectionWriteHalf)> {
+        #[cfg(feature = "tls")]
+        if let Some(tls) = &self.tls {
+            return tls.connect(peer, stream).await;
+        }
+
+        Ok(split_stream(stream))
+    }
+
     pub(crate) fn setup_flow_control(&self, flow_controls: &FlowControls, addresses: &Addresses) {
         flow_controls.add_producer(
             addresses.receiver_address().clone(),
@@ -52,6 +176,57 @@ impl TcpConnectionOptions {
         }
     }
 
+    /// Set up the flow control of a logical connection over a pooled connection
+    ///
+    /// The messages of the socket addressed to the logical connection are
+    /// produced by its inbound address
+    pub(crate) fn setup_flow_control_for_handle(
+        &self,
+        flow_controls: &FlowControls,
+        handle_address: &Address,
+        inbound_address: &Address,
+    ) {
+        flow_controls.add_producer(
+            inbound_address.clone(),
+            &self.flow_control_id,
+            None,
+            vec![handle_address.clone()],
+        );
+
+        for id in &self.consumer {
+            flow_controls.add_consumer(handle_address.clone(), id);
+        }
+    }
+
+    /// Set up the flow control of a socket shared by pooled connections.
+    ///
+    /// The socket gets its own [`FlowControlId`], and its receiver is only allowed
+    /// to send messages to the inbound addresses of the logical connections using it
+    pub(crate) fn setup_flow_control_for_pooled_connection(
+        flow_controls: &FlowControls,
+        registry: TcpRegistry,
+        addresses: &Addresses,
+    ) -> (FlowControlId, TcpConnectionAccessControl) {
+        let flow_control_id = FlowControls::generate_flow_control_id();
+
+        flow_controls.add_producer(
+            addresses.receiver_address().clone(),
+            &flow_control_id,
+            None,
+            vec![addresses.sender_address().clone()],
+        );
+
+        let access_control = TcpConnectionAccessControl {
+            sender_incoming_access_control: Arc::new(AllowAll),
+            receiver_outgoing_access_control: Arc::new(TcpPooledOutgoingAccessControl::new(
+                registry,
+                addresses.receiver_address().clone(),
+            )),
+        };
+
+        (flow_control_id, access_control)
+    }
+
     pub(crate) fn create_access_control(
         self,
         flow_controls: &FlowControls,
@@ -71,6 +246,9 @@ impl TcpConnectionOptions {
 #[derive(Debug)]
 pub struct TcpListenerOptions {
     pub(crate) flow_control_id: FlowControlId,
+    pub(crate) max_message_size: usize,
+    #[cfg(feature = "tls")]
+    pub(crate) tls: Option<TlsServerOptions>,
 }
 
 impl TcpListenerOptions {
@@ -81,9 +259,28 @@ impl TcpListenerOptions {
     pub fn new() -> Self {
         Self {
             flow_control_id: FlowControls::generate_flow_control_id(),
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            #[cfg(feature = "tls")]
+            tls: None,
         }
     }
 
+    /// Set the maximum size of a message sent or received over accepted connections.
+    /// Messages larger than 64 KiB are fragmented on the wire and reassembled by the peer
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = max_message_size;
+
+        self
+    }
+
+    /// Require a TLS handshake on every accepted connection
+    #[cfg(feature = "tls")]
+    pub fn with_tls(mut self, tls: TlsServerOptions) -> Self {
+        self.tls = Some(tls);
+
+        self
+    }
+
     /// Getter for freshly generated [`FlowControlId`]
     pub fn spawner_flow_control_id(&self) -> FlowControlId {
         self.flow_control_id.clone()
@@ -91,6 +288,21 @@ impl TcpListenerOptions {
 }
 
 impl TcpListenerOptions {
+    /// Split an accepted stream, performing a TLS handshake first if required
+    #[allow(unused_variables)]
+    pub(crate) async fn split_stream(
+        &self,
+        peer: &str,
+        stream: TcpStream,
+    ) -> Result<(ConnectionReadHalf, ConnectionWriteHalf)> {
+        #[cfg(feature = "tls")]
+        if let Some(tls) = &self.tls {
+            return tls.accept(peer, stream).await;
+        }
+
+        Ok(split_stream(stream))
+    }
+
     pub(crate) fn setup_flow_control_for_listener(
         &self,
         flow_controls: &FlowControls,
@@ -0,0 +1,41 @@
+use crate::portal::addresses::PortalType;
+use ockam_core::{async_trait, Result};
+use ockam_node::{Context, Drain, Gauge};
+use tracing::info;
+
+/// Name of the drain of the TCP portals in the drain registry of the node
+pub(crate) const PORTALS_DRAIN: &str = "tcp_portals";
+
+/// Drain of the TCP portals of a node
+///
+/// While the node is draining, the inlets and outlets refuse new
+/// connections, and the node waits for the open ones to be closed.
+pub(crate) struct PortalsDrain {
+    inlets: Gauge,
+    outlets: Gauge,
+}
+
+impl PortalsDrain {
+    pub(crate) fn new(ctx: &Context) -> Self {
+        Self {
+            inlets: crate::metrics::portal_connections(ctx, PortalType::Inlet.str()),
+            outlets: crate::metrics::portal_connections(ctx, PortalType::Outlet.str()),
+        }
+    }
+}
+
+#[async_trait]
+impl Drain for PortalsDrain {
+    async fn start_drain(&self, _ctx: &Context) -> Result<()> {
+        info!(
+            inlets = self.inlets.get(),
+            outlets = self.outlets.get(),
+            "Waiting for the open portal connections to be closed"
+        );
+        Ok(())
+    }
+
+    fn is_drained(&self) -> bool {
+        self.inlets.get() == 0 && self.outlets.get() == 0
+    }
+}
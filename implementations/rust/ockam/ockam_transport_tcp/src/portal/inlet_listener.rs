@@ -6,7 +6,7 @@ use ockam_core::{Address, Processor, Result, Route};
 use ockam_node::Context;
 use ockam_transport_core::TransportError;
 use tokio::net::TcpListener;
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
 /// A TCP Portal Inlet listen processor
 ///
@@ -81,6 +81,21 @@ impl Processor for TcpInletListenProcessor {
     }
 
     async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
+        let (stream, peer) = self.inner.accept().await.map_err(TransportError::from)?;
+
+        if ctx.is_draining() {
+            warn!(%peer, "The node is draining; dropping stream");
+            return Ok(true);
+        }
+
+        let limits = match self.options.limits.try_acquire_connection() {
+            Some(limits) => limits,
+            None => {
+                warn!(%peer, "Tcp Inlet reached its maximum number of connections; dropping stream");
+                return Ok(true);
+            }
+        };
+
         let addresses = Addresses::generate(PortalType::Inlet);
         let outlet_listener_route = self.outlet_listener_route.clone();
 
@@ -90,7 +105,6 @@ impl Processor for TcpInletListenProcessor {
             outlet_listener_route.next()?,
         );
 
-        let (stream, peer) = self.inner.accept().await.map_err(TransportError::from)?;
         TcpPortalWorker::start_new_inlet(
             ctx,
             self.registry.clone(),
@@ -99,6 +113,8 @@ impl Processor for TcpInletListenProcessor {
             outlet_listener_route,
             addresses,
             self.options.incoming_access_control.clone(),
+            self.options.outgoing_access_control.clone(),
+            limits,
         )
         .await?;
 
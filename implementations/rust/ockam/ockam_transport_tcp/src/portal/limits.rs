@@ -0,0 +1,199 @@
+use core::fmt;
+use core::fmt::Formatter;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
+use ockam_core::compat::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Token bucket limiting the bandwidth of a portal, in bytes per second.
+///
+/// The bucket holds up to one second of traffic. A transfer larger than the
+/// available tokens puts the bucket in debt, and the caller waits until
+/// that debt is paid back.
+pub(crate) struct TokenBucket {
+    bytes_per_second: u64,
+    state: Mutex<TokenBucketState>,
+}
+
+struct TokenBucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl fmt::Debug for TokenBucket {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenBucket")
+            .field("bytes_per_second", &self.bytes_per_second)
+            .finish()
+    }
+}
+
+impl TokenBucket {
+    pub(crate) fn new(bytes_per_second: u64) -> Self {
+        let bytes_per_second = bytes_per_second.max(1);
+        Self {
+            bytes_per_second,
+            state: Mutex::new(TokenBucketState {
+                tokens: bytes_per_second as f64,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Take `bytes` tokens, return how long to wait before transferring them
+    fn reserve(&self, bytes: usize) -> Duration {
+        let rate = self.bytes_per_second as f64;
+        let mut state = self.state.lock().unwrap();
+
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * rate).min(rate);
+        state.last_refill = now;
+
+        state.tokens -= bytes as f64;
+        if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / rate)
+        }
+    }
+
+    /// Wait until `bytes` can be transferred
+    pub(crate) async fn consume(&self, bytes: usize) {
+        let wait = self.reserve(bytes);
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Bandwidth limits applying to one connection of a portal
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PortalRateLimiter {
+    connection: Option<Arc<TokenBucket>>,
+    portal: Option<Arc<TokenBucket>>,
+}
+
+impl PortalRateLimiter {
+    pub(crate) fn new(
+        connection_rate_limit: Option<u64>,
+        portal: Option<Arc<TokenBucket>>,
+    ) -> Self {
+        Self {
+            connection: connection_rate_limit.map(|r| Arc::new(TokenBucket::new(r))),
+            portal,
+        }
+    }
+
+    /// Wait until `bytes` can be transferred over the connection
+    pub(crate) async fn consume(&self, bytes: usize) {
+        if let Some(connection) = &self.connection {
+            connection.consume(bytes).await;
+        }
+        if let Some(portal) = &self.portal {
+            portal.consume(bytes).await;
+        }
+    }
+}
+
+/// Number of open connections of a portal
+#[derive(Clone, Debug, Default)]
+struct ConnectionCounter {
+    count: Arc<AtomicUsize>,
+}
+
+impl ConnectionCounter {
+    /// Count a new connection, unless there are already `max` open connections
+    fn try_acquire(&self, max: Option<usize>) -> Option<ConnectionGuard> {
+        let max = max.unwrap_or(usize::MAX);
+        self.count
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+                if count < max {
+                    Some(count + 1)
+                } else {
+                    None
+                }
+            })
+            .ok()?;
+
+        Some(ConnectionGuard {
+            count: self.count.clone(),
+        })
+    }
+}
+
+/// An open connection of a portal, no longer counted once dropped
+#[derive(Debug)]
+struct ConnectionGuard {
+    count: Arc<AtomicUsize>,
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.count.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Bandwidth and connection limits of a portal
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PortalLimits {
+    pub(crate) rate_limit: Option<Arc<TokenBucket>>,
+    pub(crate) connection_rate_limit: Option<u64>,
+    pub(crate) max_connections: Option<usize>,
+    connections: ConnectionCounter,
+}
+
+impl PortalLimits {
+    /// Limits of a new connection, unless the portal has reached its maximum number of connections
+    pub(crate) fn try_acquire_connection(&self) -> Option<ConnectionLimits> {
+        let guard = self.connections.try_acquire(self.max_connections)?;
+
+        Some(ConnectionLimits {
+            rate_limiter: PortalRateLimiter::new(
+                self.connection_rate_limit,
+                self.rate_limit.clone(),
+            ),
+            _guard: guard,
+        })
+    }
+}
+
+/// Limits of an open connection of a portal.
+/// The connection stops counting towards the maximum number of connections once this is dropped
+#[derive(Debug)]
+pub(crate) struct ConnectionLimits {
+    pub(crate) rate_limiter: PortalRateLimiter,
+    _guard: ConnectionGuard,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transfers_within_the_burst_do_not_wait() {
+        let bucket = TokenBucket::new(1000);
+        assert_eq!(bucket.reserve(600), Duration::ZERO);
+        assert_eq!(bucket.reserve(400), Duration::ZERO);
+    }
+
+    #[test]
+    fn transfers_over_the_burst_wait_for_the_debt() {
+        let bucket = TokenBucket::new(1000);
+        let wait = bucket.reserve(1500);
+        assert!(wait > Duration::from_millis(450) && wait <= Duration::from_millis(500));
+    }
+
+    #[test]
+    fn connection_counter_enforces_the_maximum() {
+        let counter = ConnectionCounter::default();
+        let first = counter.try_acquire(Some(2));
+        let second = counter.try_acquire(Some(2));
+        assert!(first.is_some() && second.is_some());
+        assert!(counter.try_acquire(Some(2)).is_none());
+
+        drop(first);
+        assert!(counter.try_acquire(Some(2)).is_some());
+        assert!(counter.try_acquire(None).is_some());
+    }
+}
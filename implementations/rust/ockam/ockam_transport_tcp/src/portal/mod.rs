@@ -1,12 +1,18 @@
 mod addresses;
+mod drain;
 mod inlet_listener;
+mod limits;
 pub mod options;
+mod outgoing;
 mod outlet_listener;
 mod portal_message;
 mod portal_receiver;
 mod portal_worker;
 
+pub(crate) use drain::*;
 pub(crate) use inlet_listener::*;
+pub(crate) use limits::*;
+pub(crate) use outgoing::*;
 pub(crate) use outlet_listener::*;
 pub use portal_message::*;
 pub(crate) use portal_receiver::*;
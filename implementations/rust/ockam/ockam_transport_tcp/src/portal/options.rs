@@ -1,19 +1,24 @@
 use crate::portal::addresses::Addresses;
+use crate::portal::limits::{PortalLimits, TokenBucket};
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::{FlowControlId, FlowControls};
-use ockam_core::{Address, AllowAll, IncomingAccessControl};
+use ockam_core::{Address, AllowAll, IncomingAccessControl, OutgoingAccessControl};
 
 /// Trust Options for an Inlet
 #[derive(Debug)]
 pub struct TcpInletOptions {
     pub(super) incoming_access_control: Arc<dyn IncomingAccessControl>,
+    pub(super) outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    pub(super) limits: PortalLimits,
 }
 
 impl TcpInletOptions {
-    /// Default constructor without Incoming Access Control
+    /// Default constructor without Incoming or Outgoing Access Control
     pub fn new() -> Self {
         Self {
             incoming_access_control: Arc::new(AllowAll),
+            outgoing_access_control: Arc::new(AllowAll),
+            limits: PortalLimits::default(),
         }
     }
 
@@ -35,6 +40,47 @@ impl TcpInletOptions {
         self
     }
 
+    /// Set Outgoing Access Control, applied to every message the portal sends
+    /// to the other side
+    pub fn with_outgoing_access_control_impl(
+        mut self,
+        access_control: impl OutgoingAccessControl,
+    ) -> Self {
+        self.outgoing_access_control = Arc::new(access_control);
+        self
+    }
+
+    /// Set Outgoing Access Control, applied to every message the portal sends
+    /// to the other side
+    pub fn with_outgoing_access_control(
+        mut self,
+        access_control: Arc<dyn OutgoingAccessControl>,
+    ) -> Self {
+        self.outgoing_access_control = access_control;
+        self
+    }
+
+    /// Limit the bandwidth of all the connections of this Inlet together,
+    /// in bytes per second and in both directions
+    pub fn with_rate_limit(mut self, bytes_per_second: u64) -> Self {
+        self.limits.rate_limit = Some(Arc::new(TokenBucket::new(bytes_per_second)));
+        self
+    }
+
+    /// Limit the bandwidth of each connection of this Inlet,
+    /// in bytes per second and in both directions
+    pub fn with_connection_rate_limit(mut self, bytes_per_second: u64) -> Self {
+        self.limits.connection_rate_limit = Some(bytes_per_second);
+        self
+    }
+
+    /// Limit the number of concurrent connections of this Inlet.
+    /// Further TCP connections are closed as soon as they are accepted
+    pub fn with_max_connections(mut self, max_connections: usize) -> Self {
+        self.limits.max_connections = Some(max_connections);
+        self
+    }
+
     pub(super) fn setup_flow_control(
         &self,
         flow_controls: &FlowControls,
@@ -62,14 +108,18 @@ impl Default for TcpInletOptions {
 pub struct TcpOutletOptions {
     pub(super) consumer: Vec<FlowControlId>,
     pub(super) incoming_access_control: Arc<dyn IncomingAccessControl>,
+    pub(super) outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    pub(super) limits: PortalLimits,
 }
 
 impl TcpOutletOptions {
-    /// Default constructor without Incoming Access Control
+    /// Default constructor without Incoming or Outgoing Access Control
     pub fn new() -> Self {
         Self {
             consumer: vec![],
             incoming_access_control: Arc::new(AllowAll),
+            outgoing_access_control: Arc::new(AllowAll),
+            limits: PortalLimits::default(),
         }
     }
 
@@ -91,6 +141,47 @@ impl TcpOutletOptions {
         self
     }
 
+    /// Set Outgoing Access Control, applied to every message the portal sends
+    /// to the other side
+    pub fn with_outgoing_access_control_impl(
+        mut self,
+        access_control: impl OutgoingAccessControl,
+    ) -> Self {
+        self.outgoing_access_control = Arc::new(access_control);
+        self
+    }
+
+    /// Set Outgoing Access Control, applied to every message the portal sends
+    /// to the other side
+    pub fn with_outgoing_access_control(
+        mut self,
+        access_control: Arc<dyn OutgoingAccessControl>,
+    ) -> Self {
+        self.outgoing_access_control = access_control;
+        self
+    }
+
+    /// Limit the bandwidth of all the connections of this Outlet together,
+    /// in bytes per second and in both directions
+    pub fn with_rate_limit(mut self, bytes_per_second: u64) -> Self {
+        self.limits.rate_limit = Some(Arc::new(TokenBucket::new(bytes_per_second)));
+        self
+    }
+
+    /// Limit the bandwidth of each connection of this Outlet,
+    /// in bytes per second and in both directions
+    pub fn with_connection_rate_limit(mut self, bytes_per_second: u64) -> Self {
+        self.limits.connection_rate_limit = Some(bytes_per_second);
+        self
+    }
+
+    /// Limit the number of concurrent connections of this Outlet.
+    /// Further connections are refused, and the Inlet closes their TCP connection
+    pub fn with_max_connections(mut self, max_connections: usize) -> Self {
+        self.limits.max_connections = Some(max_connections);
+        self
+    }
+
     /// Mark that this Outlet listener is a Consumer for to the given [`FlowControlId`]
     /// Also, in this case spawned Outlets will be marked as Consumers with [`FlowControlId`]
     /// of the message that was used to create the Outlet
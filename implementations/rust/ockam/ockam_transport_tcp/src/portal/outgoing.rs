@@ -0,0 +1,134 @@
+use ockam_core::compat::{boxed::Box, sync::Arc};
+use ockam_core::{async_trait, Address, OutgoingAccessControl, RelayMessage, Result};
+
+/// Outgoing Access Control of a [`TcpPortalRecvProcessor`](super::TcpPortalRecvProcessor)
+///
+/// Messages to the portal worker internal address are always allowed, since
+/// they never leave the node. Messages to the other side of the portal must
+/// go to `next_hop` and be allowed by the portal outgoing access control.
+/// The portal access control is evaluated for every message, so that a policy
+/// change also applies to the connections which are already open.
+#[derive(Debug)]
+pub(crate) struct PortalOutgoingAccessControl {
+    internal: Address,
+    next_hop: Address,
+    access_control: Arc<dyn OutgoingAccessControl>,
+}
+
+impl PortalOutgoingAccessControl {
+    pub(crate) fn new(
+        internal: Address,
+        next_hop: Address,
+        access_control: Arc<dyn OutgoingAccessControl>,
+    ) -> Self {
+        Self {
+            internal,
+            next_hop,
+            access_control,
+        }
+    }
+}
+
+#[async_trait]
+impl OutgoingAccessControl for PortalOutgoingAccessControl {
+    async fn is_authorized(&self, relay_msg: &RelayMessage) -> Result<bool> {
+        let next = relay_msg.onward_route().next()?;
+
+        if next == &self.internal {
+            return ockam_core::allow();
+        }
+
+        if next != &self.next_hop {
+            return ockam_core::deny();
+        }
+
+        self.access_control.is_authorized(relay_msg).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use ockam_core::{route, LocalMessage, TransportMessage};
+
+    /// Allows messages once `allow` is set, and counts the evaluations
+    #[derive(Debug, Default)]
+    struct Counting {
+        allow: AtomicBool,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl OutgoingAccessControl for Counting {
+        async fn is_authorized(&self, _relay_msg: &RelayMessage) -> Result<bool> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            Ok(self.allow.load(Ordering::Relaxed))
+        }
+    }
+
+    fn message(receiver: &Address, destination: &Address) -> RelayMessage {
+        let local_message = LocalMessage::new(
+            TransportMessage::v1(route![destination.clone()], route![], vec![]),
+            vec![],
+        );
+        RelayMessage::new(receiver.clone(), destination.clone(), local_message)
+    }
+
+    #[tokio::test]
+    async fn internal_address_is_not_checked_by_the_portal_access_control() -> Result<()> {
+        let receiver = Address::random_local();
+        let internal = Address::random_local();
+        let next_hop = Address::random_local();
+        let policy = Arc::new(Counting::default());
+        let ac = PortalOutgoingAccessControl::new(internal.clone(), next_hop, policy.clone());
+
+        assert!(ac.is_authorized(&message(&receiver, &internal)).await?);
+        assert_eq!(policy.calls.load(Ordering::Relaxed), 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn other_addresses_are_denied() -> Result<()> {
+        let receiver = Address::random_local();
+        let policy = Arc::new(Counting::default());
+        policy.allow.store(true, Ordering::Relaxed);
+        let ac = PortalOutgoingAccessControl::new(
+            Address::random_local(),
+            Address::random_local(),
+            policy.clone(),
+        );
+
+        assert!(
+            !ac.is_authorized(&message(&receiver, &Address::random_local()))
+                .await?
+        );
+        assert_eq!(policy.calls.load(Ordering::Relaxed), 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn revoked_access_denies_the_next_messages() -> Result<()> {
+        let receiver = Address::random_local();
+        let next_hop = Address::random_local();
+        let policy = Arc::new(Counting::default());
+        policy.allow.store(true, Ordering::Relaxed);
+        let ac = PortalOutgoingAccessControl::new(
+            Address::random_local(),
+            next_hop.clone(),
+            policy.clone(),
+        );
+
+        for _ in 0..3 {
+            assert!(ac.is_authorized(&message(&receiver, &next_hop)).await?);
+        }
+
+        policy.allow.store(false, Ordering::Relaxed);
+        assert!(!ac.is_authorized(&message(&receiver, &next_hop)).await?);
+        assert_eq!(policy.calls.load(Ordering::Relaxed), 4);
+
+        Ok(())
+    }
+}
@@ -1,10 +1,10 @@
 use crate::portal::addresses::{Addresses, PortalType};
 use crate::{portal::TcpPortalWorker, PortalMessage, TcpOutletOptions, TcpRegistry};
-use ockam_core::{async_trait, Address, DenyAll, Result, Routed, Worker};
+use ockam_core::{async_trait, Address, Result, Routed, Worker};
 use ockam_node::{Context, WorkerBuilder};
 use ockam_transport_core::TransportError;
 use std::net::SocketAddr;
-use tracing::debug;
+use tracing::{debug, warn};
 
 /// A TCP Portal Outlet listen worker
 ///
@@ -34,15 +34,17 @@ impl TcpOutletListenWorker {
         peer: SocketAddr,
         options: TcpOutletOptions,
     ) -> Result<()> {
-        let access_control = options.incoming_access_control.clone();
+        let incoming_access_control = options.incoming_access_control.clone();
+        // Only used to refuse connections over the maximum number of connections
+        let outgoing_access_control = options.outgoing_access_control.clone();
 
         options.setup_flow_control_for_outlet_listener(ctx.flow_controls(), &address);
 
         let worker = Self::new(registry, peer, options);
         WorkerBuilder::new(worker)
             .with_address(address)
-            .with_incoming_access_control_arc(access_control)
-            .with_outgoing_access_control(DenyAll)
+            .with_incoming_access_control_arc(incoming_access_control)
+            .with_outgoing_access_control_arc(outgoing_access_control)
             .start(ctx)
             .await?;
 
@@ -80,6 +82,25 @@ impl Worker for TcpOutletListenWorker {
             return Err(TransportError::Protocol.into());
         }
 
+        if ctx.is_draining() {
+            warn!(
+                "The node is draining; refusing connection to Tcp Outlet {}",
+                self.peer
+            );
+            return ctx.send(return_route, PortalMessage::Disconnect).await;
+        }
+
+        let limits = match self.options.limits.try_acquire_connection() {
+            Some(limits) => limits,
+            None => {
+                warn!(
+                    "Tcp Outlet to {} reached its maximum number of connections; refusing connection",
+                    self.peer
+                );
+                return ctx.send(return_route, PortalMessage::Disconnect).await;
+            }
+        };
+
         let addresses = Addresses::generate(PortalType::Outlet);
 
         self.options
@@ -92,6 +113,9 @@ impl Worker for TcpOutletListenWorker {
             return_route.clone(),
             addresses.clone(),
             self.options.incoming_access_control.clone(),
+            self.options.outgoing_access_control.clone(),
+            limits,
+            ctx.tracing_context().cloned(),
         )
         .await?;
 
@@ -1,7 +1,10 @@
 use crate::portal::portal_message::MAX_PAYLOAD_SIZE;
+use crate::portal::PortalRateLimiter;
 use crate::{PortalInternalMessage, PortalMessage, TcpRegistry};
 use ockam_core::compat::vec::Vec;
-use ockam_core::{async_trait, Encodable, LocalMessage, Route, TransportMessage};
+use ockam_core::{
+    async_trait, Encodable, LocalMessage, Priority, Route, TraceContext, TransportMessage,
+};
 use ockam_core::{route, Address, Processor, Result};
 use ockam_node::Context;
 use tokio::{io::AsyncReadExt, net::tcp::OwnedReadHalf};
@@ -18,6 +21,8 @@ pub(crate) struct TcpPortalRecvProcessor {
     read_half: OwnedReadHalf,
     sender_address: Address,
     onward_route: Route,
+    rate_limiter: PortalRateLimiter,
+    tracing_context: Option<TraceContext>,
 }
 
 impl TcpPortalRecvProcessor {
@@ -27,6 +32,8 @@ impl TcpPortalRecvProcessor {
         read_half: OwnedReadHalf,
         sender_address: Address,
         onward_route: Route,
+        rate_limiter: PortalRateLimiter,
+        tracing_context: Option<TraceContext>,
     ) -> Self {
         Self {
             registry,
@@ -34,6 +41,8 @@ impl TcpPortalRecvProcessor {
             read_half,
             sender_address,
             onward_route,
+            rate_limiter,
+            tracing_context,
         }
     }
 }
@@ -44,6 +53,10 @@ impl Processor for TcpPortalRecvProcessor {
 
     async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
         self.registry.add_portal_receiver_processor(&ctx.address());
+        // The data read from the connection belongs to the trace of the portal worker
+        ctx.set_tracing_context(self.tracing_context.take());
+        // The data read from the connection is bulk traffic
+        ctx.set_message_priority(Priority::Low);
 
         Ok(())
     }
@@ -91,6 +104,8 @@ impl Processor for TcpPortalRecvProcessor {
             return Ok(false);
         }
 
+        self.rate_limiter.consume(self.buf.len()).await;
+
         // Loop just in case buf was extended (should not happen though)
         for chunk in self.buf.chunks(MAX_PAYLOAD_SIZE) {
             let msg = TransportMessage::v1(
@@ -1,13 +1,14 @@
 use crate::portal::addresses::{Addresses, PortalType};
-use crate::{portal::TcpPortalRecvProcessor, PortalInternalMessage, PortalMessage, TcpRegistry};
+use crate::portal::{ConnectionLimits, PortalOutgoingAccessControl, TcpPortalRecvProcessor};
+use crate::{PortalInternalMessage, PortalMessage, TcpRegistry};
 use core::time::Duration;
 use ockam_core::compat::{boxed::Box, net::SocketAddr, sync::Arc};
 use ockam_core::{
-    async_trait, AllowAll, AllowOnwardAddresses, AllowSourceAddress, Decodable, DenyAll,
-    IncomingAccessControl, Mailbox, Mailboxes,
+    async_trait, AllowSourceAddress, Decodable, DenyAll, IncomingAccessControl, Mailbox, Mailboxes,
+    OutgoingAccessControl,
 };
-use ockam_core::{Any, Result, Route, Routed, Worker};
-use ockam_node::{Context, ProcessorBuilder, WorkerBuilder};
+use ockam_core::{Any, Result, Route, Routed, TraceContext, Worker};
+use ockam_node::{Context, Gauge, ProcessorBuilder, WorkerBuilder};
 use ockam_transport_core::TransportError;
 use tokio::io::AsyncWriteExt;
 use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
@@ -44,10 +45,16 @@ pub(crate) struct TcpPortalWorker {
     remote_route: Option<Route>,
     is_disconnecting: bool,
     portal_type: PortalType,
+    outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    limits: ConnectionLimits,
+    tracing_context: Option<TraceContext>,
+    /// Open connections of this portal type, set once the connection is initialized
+    connections: Option<Gauge>,
 }
 
 impl TcpPortalWorker {
     /// Start a new `TcpPortalWorker` of type [`TypeName::Inlet`]
+    #[allow(clippy::too_many_arguments)]
     pub(super) async fn start_new_inlet(
         ctx: &Context,
         registry: TcpRegistry,
@@ -55,7 +62,9 @@ impl TcpPortalWorker {
         peer: SocketAddr,
         ping_route: Route,
         addresses: Addresses,
-        access_control: Arc<dyn IncomingAccessControl>,
+        incoming_access_control: Arc<dyn IncomingAccessControl>,
+        outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        limits: ConnectionLimits,
     ) -> Result<()> {
         Self::start(
             ctx,
@@ -65,19 +74,26 @@ impl TcpPortalWorker {
             Some(stream),
             addresses,
             PortalType::Inlet,
-            access_control,
+            incoming_access_control,
+            outgoing_access_control,
+            limits,
+            None,
         )
         .await
     }
 
     /// Start a new `TcpPortalWorker` of type [`TypeName::Outlet`]
+    #[allow(clippy::too_many_arguments)]
     pub(super) async fn start_new_outlet(
         ctx: &Context,
         registry: TcpRegistry,
         peer: SocketAddr,
         pong_route: Route,
         addresses: Addresses,
-        access_control: Arc<dyn IncomingAccessControl>,
+        incoming_access_control: Arc<dyn IncomingAccessControl>,
+        outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        limits: ConnectionLimits,
+        tracing_context: Option<TraceContext>,
     ) -> Result<()> {
         Self::start(
             ctx,
@@ -87,7 +103,10 @@ impl TcpPortalWorker {
             None,
             addresses,
             PortalType::Outlet,
-            access_control,
+            incoming_access_control,
+            outgoing_access_control,
+            limits,
+            tracing_context,
         )
         .await
     }
@@ -102,7 +121,10 @@ impl TcpPortalWorker {
         stream: Option<TcpStream>,
         addresses: Addresses,
         portal_type: PortalType,
-        access_control: Arc<dyn IncomingAccessControl>,
+        incoming_access_control: Arc<dyn IncomingAccessControl>,
+        outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        limits: ConnectionLimits,
+        tracing_context: Option<TraceContext>,
     ) -> Result<()> {
         info!(
             "Creating new {:?} at internal: {}, remote: {}",
@@ -129,6 +151,10 @@ impl TcpPortalWorker {
             remote_route: None,
             is_disconnecting: false,
             portal_type,
+            outgoing_access_control: outgoing_access_control.clone(),
+            limits,
+            tracing_context,
+            connections: None,
         };
 
         let internal_mailbox = Mailbox::new(
@@ -139,8 +165,9 @@ impl TcpPortalWorker {
 
         let remote_mailbox = Mailbox::new(
             addresses.remote,
-            access_control,
-            Arc::new(AllowAll), // FIXME: @ac Allow to respond anywhere using return_route
+            incoming_access_control,
+            // Ping, Pong and Disconnect messages to the other side
+            outgoing_access_control,
         );
 
         // start worker
@@ -157,6 +184,7 @@ enum DisconnectionReason {
     FailedTx,
     FailedRx,
     Remote,
+    Refused,
 }
 
 impl TcpPortalWorker {
@@ -173,14 +201,19 @@ impl TcpPortalWorker {
                 rx,
                 self.addresses.internal.clone(),
                 onward_route,
+                self.limits.rate_limiter.clone(),
+                ctx.tracing_context().cloned(),
             );
 
+            // Only sends messages to `onward_route` and Sender
+            let access_control = PortalOutgoingAccessControl::new(
+                self.addresses.internal.clone(),
+                next_hop,
+                self.outgoing_access_control.clone(),
+            );
             ProcessorBuilder::new(receiver)
                 .with_address(self.addresses.receiver.clone())
-                .with_outgoing_access_control(AllowOnwardAddresses(vec![
-                    next_hop,
-                    self.addresses.internal.clone(),
-                ])) // Only sends messages to `onward_route` and Sender
+                .with_outgoing_access_control(access_control)
                 .start(ctx)
                 .await?;
 
@@ -259,6 +292,8 @@ impl TcpPortalWorker {
             DisconnectionReason::Remote => {
                 self.stop_receiver(ctx).await?;
             }
+            // The receiver was never started
+            DisconnectionReason::Refused => {}
         }
 
         ctx.stop_worker(self.addresses.internal.clone()).await?;
@@ -328,9 +363,14 @@ impl Worker for TcpPortalWorker {
 
         match state {
             State::SendPing { ping_route } => {
+                // A connection to an inlet starts a new trace, which is
+                // followed by all the messages of this portal connection
+                ctx.start_trace();
                 self.state = self.handle_send_ping(ctx, ping_route.clone()).await?;
             }
             State::SendPong { pong_route } => {
+                // Continue the trace of the inlet
+                ctx.set_tracing_context(self.tracing_context.take());
                 self.state = self.handle_send_pong(ctx, pong_route.clone()).await?;
             }
             State::ReceivePong | State::Initialized { .. } => {
@@ -340,12 +380,20 @@ impl Worker for TcpPortalWorker {
 
         self.registry.add_portal_worker(&self.addresses.remote);
 
+        let connections = crate::metrics::portal_connections(ctx, self.portal_type.str());
+        connections.inc();
+        self.connections = Some(connections);
+
         Ok(())
     }
 
     async fn shutdown(&mut self, _ctx: &mut Self::Context) -> Result<()> {
         self.registry.remove_portal_worker(&self.addresses.remote);
 
+        if let Some(connections) = self.connections.take() {
+            connections.dec();
+        }
+
         Ok(())
     }
 
@@ -377,9 +425,20 @@ impl Worker for TcpPortalWorker {
 
                 let msg = PortalMessage::decode(msg.payload())?;
 
-                if let PortalMessage::Pong = msg {
-                } else {
-                    return Err(TransportError::Protocol.into());
+                match msg {
+                    PortalMessage::Pong => {}
+                    PortalMessage::Disconnect => {
+                        info!(
+                            "Inlet at: {} was refused by the outlet",
+                            self.addresses.internal
+                        );
+                        self.start_disconnection(ctx, DisconnectionReason::Refused)
+                            .await?;
+                        return Ok(());
+                    }
+                    PortalMessage::Ping | PortalMessage::Payload(_) => {
+                        return Err(TransportError::Protocol.into());
+                    }
                 }
 
                 self.start_receiver(ctx, return_route.clone()).await?;
@@ -422,6 +481,7 @@ impl Worker for TcpPortalWorker {
 
                     match msg {
                         PortalMessage::Payload(payload) => {
+                            self.limits.rate_limiter.consume(payload.len()).await;
                             if let Some(tx) = &mut self.write_half {
                                 match tx.write_all(&payload).await {
                                     Ok(()) => {}
@@ -0,0 +1,194 @@
+use super::host_and_port;
+use ockam_core::compat::net::IpAddr;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use ockam_transport_core::TransportError;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+const VERSION: u8 = 5;
+const NO_AUTHENTICATION: u8 = 0;
+const USERNAME_PASSWORD: u8 = 2;
+const NO_ACCEPTABLE_METHOD: u8 = 0xff;
+const USERNAME_PASSWORD_VERSION: u8 = 1;
+const CONNECT: u8 = 1;
+const IPV4: u8 = 1;
+const DOMAIN_NAME: u8 = 3;
+const IPV6: u8 = 4;
+
+/// Open a tunnel to the `host:port` peer with a SOCKS5 `CONNECT` request (RFC 1928).
+///
+/// Host names are resolved by the proxy.
+pub(super) async fn connect(
+    stream: &mut TcpStream,
+    peer: &str,
+    credentials: Option<(&str, &str)>,
+) -> Result<()> {
+    // Negotiate the authentication method
+    let greeting = match credentials {
+        Some(_) => vec![VERSION, 2, NO_AUTHENTICATION, USERNAME_PASSWORD],
+        None => vec![VERSION, 1, NO_AUTHENTICATION],
+    };
+    write(stream, &greeting).await?;
+    let [version, method] = read::<2>(stream).await?;
+    if version != VERSION {
+        return Err(protocol("invalid SOCKS version"));
+    }
+    match (method, credentials) {
+        (NO_AUTHENTICATION, _) => {}
+        (USERNAME_PASSWORD, Some((username, password))) => {
+            authenticate(stream, username, password).await?
+        }
+        (NO_ACCEPTABLE_METHOD, _) => {
+            return Err(Error::new(
+                Origin::Transport,
+                Kind::Invalid,
+                "no acceptable authentication method",
+            ))
+        }
+        _ => return Err(protocol("unexpected authentication method")),
+    }
+
+    write(stream, &connect_request(peer)?).await?;
+    let [version, reply, _reserved, address_type] = read::<4>(stream).await?;
+    if version != VERSION {
+        return Err(protocol("invalid SOCKS version"));
+    }
+    if reply != 0 {
+        return Err(Error::new(
+            Origin::Transport,
+            Kind::Protocol,
+            format!("request refused: {}", reply_message(reply)),
+        ));
+    }
+
+    // Skip the bound address and port
+    let len = match address_type {
+        IPV4 => 4,
+        IPV6 => 16,
+        DOMAIN_NAME => read::<1>(stream).await?[0] as usize,
+        _ => return Err(protocol("invalid address type")),
+    };
+    let mut bound_address = vec![0; len + 2];
+    stream
+        .read_exact(&mut bound_address)
+        .await
+        .map_err(TransportError::from)?;
+
+    Ok(())
+}
+
+/// Authenticate with a username and password (RFC 1929)
+async fn authenticate(stream: &mut TcpStream, username: &str, password: &str) -> Result<()> {
+    if username.len() > 255 || password.len() > 255 {
+        return Err(Error::new(
+            Origin::Transport,
+            Kind::Invalid,
+            "SOCKS credentials can not exceed 255 bytes",
+        ));
+    }
+
+    let mut request = vec![USERNAME_PASSWORD_VERSION, username.len() as u8];
+    request.extend_from_slice(username.as_bytes());
+    request.push(password.len() as u8);
+    request.extend_from_slice(password.as_bytes());
+    write(stream, &request).await?;
+
+    let [_version, status] = read::<2>(stream).await?;
+    if status != 0 {
+        return Err(Error::new(
+            Origin::Transport,
+            Kind::Invalid,
+            "proxy authentication failed",
+        ));
+    }
+
+    Ok(())
+}
+
+fn connect_request(peer: &str) -> Result<Vec<u8>> {
+    let (host, port) = host_and_port(peer)?;
+
+    let mut request = vec![VERSION, CONNECT, 0];
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            request.push(IPV4);
+            request.extend_from_slice(&ip.octets());
+        }
+        Ok(IpAddr::V6(ip)) => {
+            request.push(IPV6);
+            request.extend_from_slice(&ip.octets());
+        }
+        Err(_) => {
+            if host.len() > 255 {
+                return Err(TransportError::InvalidAddress.into());
+            }
+            request.push(DOMAIN_NAME);
+            request.push(host.len() as u8);
+            request.extend_from_slice(host.as_bytes());
+        }
+    }
+    request.extend_from_slice(&port.to_be_bytes());
+
+    Ok(request)
+}
+
+fn reply_message(reply: u8) -> &'static str {
+    match reply {
+        1 => "general SOCKS server failure",
+        2 => "connection not allowed by ruleset",
+        3 => "network unreachable",
+        4 => "host unreachable",
+        5 => "connection refused",
+        6 => "TTL expired",
+        7 => "command not supported",
+        8 => "address type not supported",
+        _ => "unknown error",
+    }
+}
+
+async fn write(stream: &mut TcpStream, bytes: &[u8]) -> Result<()> {
+    Ok(stream
+        .write_all(bytes)
+        .await
+        .map_err(TransportError::from)?)
+}
+
+async fn read<const N: usize>(stream: &mut TcpStream) -> Result<[u8; N]> {
+    let mut buffer = [0; N];
+    stream
+        .read_exact(&mut buffer)
+        .await
+        .map_err(TransportError::from)?;
+
+    Ok(buffer)
+}
+
+fn protocol(msg: &str) -> Error {
+    Error::new(Origin::Transport, Kind::Protocol, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::connect_request;
+
+    #[test]
+    fn test_connect_request() {
+        assert_eq!(
+            connect_request("127.0.0.1:4000").unwrap(),
+            vec![5, 1, 0, 1, 127, 0, 0, 1, 0x0f, 0xa0]
+        );
+
+        let mut expected = vec![5, 1, 0, 4];
+        expected.extend_from_slice(&[0; 15]);
+        expected.extend_from_slice(&[1, 0x01, 0xbb]);
+        assert_eq!(connect_request("[::1]:443").unwrap(), expected);
+
+        let mut expected = vec![5, 1, 0, 3, 11];
+        expected.extend_from_slice(b"example.com");
+        expected.extend_from_slice(&[0x01, 0xbb]);
+        assert_eq!(connect_request("example.com:443").unwrap(), expected);
+
+        assert!(connect_request("example.com").is_err());
+    }
+}
@@ -157,3 +157,98 @@ impl TcpListenerInfo {
         &self.flow_control_id
     }
 }
+
+/// Information about a Tcp connection shared by several logical connections to the same peer
+#[derive(Debug, Clone)]
+pub struct TcpPooledConnectionInfo {
+    socket_address: SocketAddr,
+    sender_address: Address,
+    receiver_address: Address,
+    flow_control_id: FlowControlId,
+    handles: Vec<PooledHandle>,
+}
+
+/// A logical connection using a pooled connection
+#[derive(Debug, Clone)]
+struct PooledHandle {
+    address: Address,
+    inbound_address: Address,
+    flow_control_id: FlowControlId,
+}
+
+impl TcpPooledConnectionInfo {
+    /// Constructor
+    pub fn new(
+        socket_address: SocketAddr,
+        sender_address: Address,
+        receiver_address: Address,
+        flow_control_id: FlowControlId,
+    ) -> Self {
+        Self {
+            socket_address,
+            sender_address,
+            receiver_address,
+            flow_control_id,
+            handles: vec![],
+        }
+    }
+
+    /// Corresponding socket address
+    pub fn socket_address(&self) -> SocketAddr {
+        self.socket_address
+    }
+    /// Address of the shared Sender worker
+    pub fn sender_address(&self) -> &Address {
+        &self.sender_address
+    }
+    /// Address of the shared Receiver processor
+    pub fn receiver_address(&self) -> &Address {
+        &self.receiver_address
+    }
+    /// [`FlowControlId`] of the shared socket
+    pub fn flow_control_id(&self) -> &FlowControlId {
+        &self.flow_control_id
+    }
+    /// Addresses of the logical connections using this socket
+    pub fn handle_addresses(&self) -> Vec<Address> {
+        self.handles.iter().map(|h| h.address.clone()).collect()
+    }
+    /// Addresses receiving the messages of the socket addressed to each logical connection
+    pub fn inbound_addresses(&self) -> Vec<Address> {
+        self.handles
+            .iter()
+            .map(|h| h.inbound_address.clone())
+            .collect()
+    }
+    /// [`FlowControlId`]s of the logical connections using this socket
+    pub fn flow_control_ids(&self) -> Vec<FlowControlId> {
+        self.handles
+            .iter()
+            .map(|h| h.flow_control_id.clone())
+            .collect()
+    }
+    /// Number of logical connections using this socket
+    pub fn ref_count(&self) -> usize {
+        self.handles.len()
+    }
+
+    pub(crate) fn add_handle(
+        &mut self,
+        address: Address,
+        inbound_address: Address,
+        flow_control_id: FlowControlId,
+    ) {
+        self.handles.push(PooledHandle {
+            address,
+            inbound_address,
+            flow_control_id,
+        })
+    }
+
+    /// Remove a logical connection, return `true` if it was found
+    pub(crate) fn remove_handle(&mut self, address: &Address) -> bool {
+        let len = self.handles.len();
+        self.handles.retain(|h| &h.address != address);
+        self.handles.len() != len
+    }
+}
@@ -1,4 +1,8 @@
-use crate::{TcpListenerInfo, TcpReceiverInfo, TcpRegistry, TcpSenderInfo};
+use crate::{
+    TcpListenerInfo, TcpPooledConnectionInfo, TcpReceiverInfo, TcpRegistry, TcpSenderInfo,
+};
+use ockam_core::compat::net::SocketAddr;
+use ockam_core::flow_control::FlowControlId;
 use ockam_core::Address;
 
 impl TcpRegistry {
@@ -72,4 +76,50 @@ impl TcpRegistry {
             lock.remove_receiver_processor(addr);
         }
     }
+    pub(crate) fn add_pooled_connection(&self, info: TcpPooledConnectionInfo) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.add_pooled_connection(info);
+        }
+    }
+    /// Register a new logical connection on the pooled connection to the given socket address,
+    /// if there is one
+    pub(crate) fn acquire_pooled_connection(
+        &self,
+        socket_address: SocketAddr,
+        handle: &Address,
+        inbound: &Address,
+        flow_control_id: &FlowControlId,
+    ) -> Option<TcpPooledConnectionInfo> {
+        self.registry.write().ok()?.acquire_pooled_connection(
+            socket_address,
+            handle,
+            inbound,
+            flow_control_id,
+        )
+    }
+    /// Unregister a logical connection, return the address of the shared sender
+    /// if it was the last logical connection using it
+    pub(crate) fn release_pooled_connection(&self, handle: &Address) -> Option<Address> {
+        self.registry
+            .write()
+            .ok()?
+            .release_pooled_connection(handle)
+    }
+    /// Unregister the pooled connection of a stopped sender,
+    /// return the addresses of the logical connections which were using it
+    pub(crate) fn remove_pooled_connection(&self, sender_address: &Address) -> Vec<Address> {
+        match self.registry.write() {
+            Ok(mut lock) => lock.remove_pooled_connection(sender_address),
+            Err(_) => vec![],
+        }
+    }
+    pub(crate) fn get_pooled_connection(
+        &self,
+        receiver_address: &Address,
+    ) -> Option<TcpPooledConnectionInfo> {
+        self.registry
+            .read()
+            .ok()?
+            .get_pooled_connection(receiver_address)
+    }
 }
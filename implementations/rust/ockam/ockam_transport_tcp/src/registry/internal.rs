@@ -1,4 +1,6 @@
-use crate::{TcpListenerInfo, TcpReceiverInfo, TcpSenderInfo};
+use crate::{TcpListenerInfo, TcpPooledConnectionInfo, TcpReceiverInfo, TcpSenderInfo};
+use ockam_core::compat::net::SocketAddr;
+use ockam_core::flow_control::FlowControlId;
 use ockam_core::Address;
 
 #[derive(Default)]
@@ -10,6 +12,7 @@ pub(super) struct InternalRegistry {
     pub(super) listener_processors: Vec<TcpListenerInfo>,
     pub(super) sender_workers: Vec<TcpSenderInfo>,
     pub(super) receiver_processors: Vec<TcpReceiverInfo>,
+    pub(super) pooled_connections: Vec<TcpPooledConnectionInfo>,
 }
 
 impl InternalRegistry {
@@ -55,4 +58,53 @@ impl InternalRegistry {
     pub(super) fn remove_receiver_processor(&mut self, addr: &Address) {
         self.receiver_processors.retain(|x| x.address() != addr);
     }
+    pub(super) fn add_pooled_connection(&mut self, info: TcpPooledConnectionInfo) {
+        self.pooled_connections.push(info)
+    }
+    pub(super) fn acquire_pooled_connection(
+        &mut self,
+        socket_address: SocketAddr,
+        handle: &Address,
+        inbound: &Address,
+        flow_control_id: &FlowControlId,
+    ) -> Option<TcpPooledConnectionInfo> {
+        let info = self
+            .pooled_connections
+            .iter_mut()
+            .find(|x| x.socket_address() == socket_address)?;
+        info.add_handle(handle.clone(), inbound.clone(), flow_control_id.clone());
+        Some(info.clone())
+    }
+    pub(super) fn release_pooled_connection(&mut self, handle: &Address) -> Option<Address> {
+        let index = self
+            .pooled_connections
+            .iter_mut()
+            .position(|x| x.remove_handle(handle))?;
+        if self.pooled_connections[index].ref_count() > 0 {
+            return None;
+        }
+        let info = self.pooled_connections.remove(index);
+        Some(info.sender_address().clone())
+    }
+    pub(super) fn remove_pooled_connection(&mut self, sender_address: &Address) -> Vec<Address> {
+        let mut handles = vec![];
+        self.pooled_connections.retain(|x| {
+            if x.sender_address() == sender_address {
+                handles.extend(x.handle_addresses());
+                false
+            } else {
+                true
+            }
+        });
+        handles
+    }
+    pub(super) fn get_pooled_connection(
+        &self,
+        receiver_address: &Address,
+    ) -> Option<TcpPooledConnectionInfo> {
+        self.pooled_connections
+            .iter()
+            .find(|x| x.receiver_address() == receiver_address)
+            .cloned()
+    }
 }
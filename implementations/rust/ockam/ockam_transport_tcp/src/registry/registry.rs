@@ -1,5 +1,5 @@
 use crate::registry::internal::InternalRegistry;
-use crate::{TcpListenerInfo, TcpReceiverInfo, TcpSenderInfo};
+use crate::{TcpListenerInfo, TcpPooledConnectionInfo, TcpReceiverInfo, TcpSenderInfo};
 use ockam_core::compat::sync::{Arc, RwLock};
 
 /// Registry of all active workers and processors in TCP Transport to ease their lifecycle management
@@ -23,4 +23,9 @@ impl TcpRegistry {
     pub fn get_all_listeners(&self) -> Vec<TcpListenerInfo> {
         self.registry.read().unwrap().listener_processors.clone()
     }
+
+    /// Return all Tcp connections shared by several logical connections
+    pub fn get_all_pooled_connections(&self) -> Vec<TcpPooledConnectionInfo> {
+        self.registry.read().unwrap().pooled_connections.clone()
+    }
 }
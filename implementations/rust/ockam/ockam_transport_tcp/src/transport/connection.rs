@@ -1,11 +1,19 @@
-use crate::transport::common::{resolve_peer, TcpConnection};
-use crate::workers::{Addresses, TcpRecvProcessor, TcpSendWorker};
-use crate::{TcpConnectionMode, TcpConnectionOptions, TcpTransport};
+use crate::transport::common::TcpConnection;
+use crate::workers::{Addresses, TcpConnectionHandle, TcpRecvProcessor, TcpSendWorker};
+use crate::{TcpConnectionMode, TcpConnectionOptions, TcpPooledConnectionInfo, TcpTransport};
+use ockam_core::compat::net::SocketAddr;
 use ockam_core::{Address, Result};
+use tracing::debug;
 
 impl TcpTransport {
     /// Establish an outgoing TCP connection.
     ///
+    /// The connection is tunnelled through the proxy of the options, if any.
+    /// The socket address of a tunnelled connection is the address of the proxy.
+    ///
+    /// With [`TcpConnectionOptions::with_pooling`], the connection reuses the socket
+    /// of an existing pooled connection to the same peer, if there is one.
+    ///
     /// ```rust
     /// use ockam_transport_tcp::{TcpConnectionOptions, TcpListenerOptions, TcpTransport};
     /// # use ockam_node::Context;
@@ -21,16 +29,20 @@ impl TcpTransport {
         peer: impl Into<String>,
         options: TcpConnectionOptions,
     ) -> Result<TcpConnection> {
-        // Resolve peer address
-        let socket = resolve_peer(peer.into())?;
+        let peer = peer.into();
+        if let Some(socket) = options.pooled_socket_address(&peer)? {
+            return self.connect_pooled(&peer, socket, options).await;
+        }
 
-        let (read_half, write_half) = TcpSendWorker::connect(socket).await?;
+        let (stream, socket) = options.connect(&peer).await?;
+        let (read_half, write_half) = options.split_stream(&peer, stream).await?;
 
         let mode = TcpConnectionMode::Outgoing;
         let addresses = Addresses::generate(mode);
 
         options.setup_flow_control(self.ctx.flow_controls(), &addresses);
         let flow_control_id = options.flow_control_id.clone();
+        let max_message_size = options.max_message_size;
         let access_control = options.create_access_control(self.ctx.flow_controls());
 
         TcpSendWorker::start(
@@ -42,6 +54,7 @@ impl TcpTransport {
             mode,
             access_control.sender_incoming_access_control,
             &flow_control_id,
+            max_message_size,
         )
         .await?;
 
@@ -54,6 +67,7 @@ impl TcpTransport {
             mode,
             &flow_control_id,
             access_control.receiver_outgoing_access_control,
+            max_message_size,
         )
         .await?;
 
@@ -66,7 +80,131 @@ impl TcpTransport {
         ))
     }
 
-    /// Interrupt an active TCP connection given its Sender `Address`
+    /// Establish a logical connection over the pooled connection to the given socket address,
+    /// opening that connection first if there is none
+    async fn connect_pooled(
+        &self,
+        peer: &str,
+        socket: SocketAddr,
+        options: TcpConnectionOptions,
+    ) -> Result<TcpConnection> {
+        let mode = TcpConnectionMode::Outgoing;
+        let handle_address = Address::random_tagged("TcpConnectionHandle");
+        let inbound_address = Address::random_tagged("TcpConnectionHandle.inbound");
+        let flow_control_id = options.flow_control_id.clone();
+
+        let acquired = self.registry.acquire_pooled_connection(
+            socket,
+            &handle_address,
+            &inbound_address,
+            &flow_control_id,
+        );
+        let pooled = match acquired {
+            Some(pooled) => {
+                debug!(addr = %socket, "Reusing pooled connection");
+                pooled
+            }
+            None => {
+                let mut pooled = self.start_pooled_connection(peer, socket, &options).await?;
+                pooled.add_handle(
+                    handle_address.clone(),
+                    inbound_address.clone(),
+                    flow_control_id.clone(),
+                );
+                self.registry.add_pooled_connection(pooled.clone());
+                pooled
+            }
+        };
+
+        options.setup_flow_control_for_handle(
+            self.ctx.flow_controls(),
+            &handle_address,
+            &inbound_address,
+        );
+
+        if let Err(e) = TcpConnectionHandle::start(
+            &self.ctx,
+            self.registry.clone(),
+            handle_address.clone(),
+            inbound_address.clone(),
+            pooled.sender_address().clone(),
+            pooled.receiver_address().clone(),
+            flow_control_id.clone(),
+        )
+        .await
+        {
+            if let Some(sender_address) = self.registry.release_pooled_connection(&handle_address) {
+                let _ = self.ctx.stop_worker(sender_address).await;
+            }
+            return Err(e);
+        }
+
+        Ok(TcpConnection::new(
+            handle_address,
+            inbound_address,
+            socket,
+            mode,
+            flow_control_id,
+        ))
+    }
+
+    /// Open a new socket shared by pooled connections
+    async fn start_pooled_connection(
+        &self,
+        peer: &str,
+        socket: SocketAddr,
+        options: &TcpConnectionOptions,
+    ) -> Result<TcpPooledConnectionInfo> {
+        let stream = TcpSendWorker::connect(socket).await?;
+        let (read_half, write_half) = options.split_stream(peer, stream).await?;
+
+        let mode = TcpConnectionMode::Outgoing;
+        let addresses = Addresses::generate(mode);
+
+        let (flow_control_id, access_control) =
+            TcpConnectionOptions::setup_flow_control_for_pooled_connection(
+                self.ctx.flow_controls(),
+                self.registry.clone(),
+                &addresses,
+            );
+
+        TcpSendWorker::start(
+            &self.ctx,
+            self.registry.clone(),
+            write_half,
+            &addresses,
+            socket,
+            mode,
+            access_control.sender_incoming_access_control,
+            &flow_control_id,
+            options.max_message_size,
+        )
+        .await?;
+
+        TcpRecvProcessor::start(
+            &self.ctx,
+            self.registry.clone(),
+            read_half,
+            &addresses,
+            socket,
+            mode,
+            &flow_control_id,
+            access_control.receiver_outgoing_access_control,
+            options.max_message_size,
+        )
+        .await?;
+
+        Ok(TcpPooledConnectionInfo::new(
+            socket,
+            addresses.sender_address().clone(),
+            addresses.receiver_address().clone(),
+            flow_control_id,
+        ))
+    }
+
+    /// Interrupt an active TCP connection given its Sender `Address`.
+    ///
+    /// A pooled connection only closes its socket when it was the last one using it
     pub async fn disconnect(&self, address: impl Into<Address>) -> Result<()> {
         self.ctx.stop_worker(address.into()).await
     }
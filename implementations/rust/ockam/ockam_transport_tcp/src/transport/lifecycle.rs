@@ -4,6 +4,7 @@ use ockam_node::Context;
 use ockam_transport_core::Transport;
 use std::sync::Arc;
 
+use crate::portal::{PortalsDrain, PORTALS_DRAIN};
 use crate::{TcpConnectionOptions, TcpRegistry, TcpTransport, TCP};
 
 impl TcpTransport {
@@ -26,6 +27,9 @@ impl TcpTransport {
         // later address resolution when socket addresses will need to be instantiated as TCP
         // worker addresses
         ctx.register_transport(Arc::new(tcp.async_try_clone().await?));
+        // wait for the open portal connections to be closed when the node is drained
+        ctx.drains()
+            .register(PORTALS_DRAIN, Arc::new(PortalsDrain::new(ctx)));
         Ok(tcp)
     }
 }
@@ -0,0 +1,72 @@
+/// Length header marking a fragmented message.
+///
+/// Messages are never empty, so a zero length can not be confused with
+/// a message sent in a single frame.
+pub(crate) const FRAGMENT_MARKER: u16 = 0;
+
+/// Maximum size of a frame payload
+pub(crate) const MAX_FRAME_SIZE: usize = u16::MAX as usize;
+
+/// Create the frames carrying an encoded `TransportMessage`.
+///
+/// Messages which fit in one frame are prefixed with their length as a
+/// big-endian 16-bit unsigned integer. Larger messages are split into
+/// fragments, each one prefixed with the [`FRAGMENT_MARKER`], the total
+/// message length as a big-endian 32-bit unsigned integer and the
+/// fragment length as a big-endian 16-bit unsigned integer.
+///
+/// All the frames of a message are returned in one buffer, so that they
+/// are written at once and never interleaved with another message.
+pub(crate) fn encode_frames(msg: &[u8]) -> Vec<u8> {
+    if msg.len() <= MAX_FRAME_SIZE {
+        let mut buf = Vec::with_capacity(2 + msg.len());
+        buf.extend_from_slice(&(msg.len() as u16).to_be_bytes());
+        buf.extend_from_slice(msg);
+        return buf;
+    }
+
+    let total = (msg.len() as u32).to_be_bytes();
+    let fragments = (msg.len() + MAX_FRAME_SIZE - 1) / MAX_FRAME_SIZE;
+    let mut buf = Vec::with_capacity(msg.len() + 8 * fragments);
+    for chunk in msg.chunks(MAX_FRAME_SIZE) {
+        buf.extend_from_slice(&FRAGMENT_MARKER.to_be_bytes());
+        buf.extend_from_slice(&total);
+        buf.extend_from_slice(&(chunk.len() as u16).to_be_bytes());
+        buf.extend_from_slice(chunk);
+    }
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_message_is_sent_in_a_single_frame() {
+        let buf = encode_frames(&[1, 2, 3]);
+        assert_eq!(buf, vec![0, 3, 1, 2, 3]);
+
+        let msg = vec![7; MAX_FRAME_SIZE];
+        let buf = encode_frames(&msg);
+        assert_eq!(buf.len(), MAX_FRAME_SIZE + 2);
+        assert_eq!(&buf[..2], &u16::MAX.to_be_bytes());
+    }
+
+    #[test]
+    fn large_message_is_fragmented() {
+        let msg: Vec<u8> = (0..2 * MAX_FRAME_SIZE + 10).map(|i| i as u8).collect();
+        let buf = encode_frames(&msg);
+
+        let mut rest = buf.as_slice();
+        let mut reassembled = Vec::new();
+        while !rest.is_empty() {
+            assert_eq!(u16::from_be_bytes([rest[0], rest[1]]), FRAGMENT_MARKER);
+            let total = u32::from_be_bytes([rest[2], rest[3], rest[4], rest[5]]);
+            assert_eq!(total as usize, msg.len());
+            let len = u16::from_be_bytes([rest[6], rest[7]]) as usize;
+            reassembled.extend_from_slice(&rest[8..8 + len]);
+            rest = &rest[8 + len..];
+        }
+        assert_eq!(reassembled, msg);
+    }
+}
@@ -0,0 +1,170 @@
+use crate::TcpRegistry;
+use core::fmt::{Debug, Formatter};
+use ockam_core::compat::sync::Arc;
+use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl};
+use ockam_core::{
+    async_trait, Address, AllowAll, AllowOnwardAddress, AllowSourceAddress, Any, Mailbox,
+    Mailboxes, OutgoingAccessControl, RelayMessage, Result, Routed, Worker,
+};
+use ockam_node::{Context, WorkerBuilder};
+use tracing::debug;
+
+/// A logical TCP connection over a pooled connection
+///
+/// Every [`TcpTransport::connect`](crate::TcpTransport::connect) call reusing
+/// a pooled connection starts one of these workers. It has its own address
+/// and [`FlowControlId`](ockam_core::flow_control::FlowControlId), and
+/// forwards the messages it receives to the shared `TcpSendWorker`.
+///
+/// Outgoing messages get the inbound address of the worker prepended to their
+/// return route, so that the replies received on the shared socket are routed
+/// back to the logical connection which sent the request, and only to it.
+///
+/// The shared connection is stopped when its last handle is stopped.
+pub(crate) struct TcpConnectionHandle {
+    registry: TcpRegistry,
+    address: Address,
+    inbound_address: Address,
+    sender_address: Address,
+}
+
+impl TcpConnectionHandle {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn start(
+        ctx: &Context,
+        registry: TcpRegistry,
+        address: Address,
+        inbound_address: Address,
+        sender_address: Address,
+        receiver_address: Address,
+        flow_control_id: FlowControlId,
+    ) -> Result<()> {
+        let mailbox = Mailbox::new(
+            address.clone(),
+            Arc::new(AllowAll),
+            Arc::new(AllowOnwardAddress(sender_address.clone())),
+        );
+        let inbound_mailbox = Mailbox::new(
+            inbound_address.clone(),
+            Arc::new(AllowSourceAddress(receiver_address)),
+            Arc::new(FlowControlOutgoingAccessControl::new(
+                ctx.flow_controls(),
+                flow_control_id,
+                None,
+            )),
+        );
+
+        WorkerBuilder::new(Self {
+            registry,
+            address,
+            inbound_address,
+            sender_address,
+        })
+        .with_mailboxes(Mailboxes::new(mailbox, vec![inbound_mailbox]))
+        .start(ctx)
+        .await
+    }
+}
+
+#[async_trait]
+impl Worker for TcpConnectionHandle {
+    type Context = Context;
+    type Message = Any;
+
+    async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        ctx.set_cluster(crate::CLUSTER_NAME).await
+    }
+
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        if let Some(sender_address) = self.registry.release_pooled_connection(&ctx.address()) {
+            debug!(
+                "Stopping pooled connection {}: no more users",
+                sender_address
+            );
+            let _ = ctx.stop_worker(sender_address).await;
+        }
+
+        Ok(())
+    }
+
+    async fn handle_message(
+        &mut self,
+        ctx: &mut Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        let inbound = msg.msg_addr() == self.inbound_address;
+        let mut msg = msg.into_local_message();
+        let transport = msg.transport_mut();
+        transport.onward_route.step()?;
+
+        let sending_address = if inbound {
+            // Replies go through this logical connection instead of the shared sender
+            transport.return_route.step()?;
+            transport
+                .return_route
+                .modify()
+                .prepend(self.address.clone());
+            self.inbound_address.clone()
+        } else {
+            transport
+                .onward_route
+                .modify()
+                .prepend(self.sender_address.clone());
+            transport
+                .return_route
+                .modify()
+                .prepend(self.inbound_address.clone());
+            self.address.clone()
+        };
+
+        ctx.forward_from_address(msg, sending_address).await
+    }
+}
+
+/// Outgoing access control of the receiver of a pooled connection
+///
+/// A message received on a pooled connection is only allowed if its next hop
+/// is the inbound address of one of the logical connections currently using
+/// the socket. That logical connection then checks the message against its
+/// own [`FlowControlId`].
+pub(crate) struct TcpPooledOutgoingAccessControl {
+    registry: TcpRegistry,
+    receiver_address: Address,
+}
+
+impl Debug for TcpPooledOutgoingAccessControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TcpPooledOutgoingAccessControl")
+            .field("receiver_address", &self.receiver_address)
+            .finish()
+    }
+}
+
+impl TcpPooledOutgoingAccessControl {
+    pub(crate) fn new(registry: TcpRegistry, receiver_address: Address) -> Self {
+        Self {
+            registry,
+            receiver_address,
+        }
+    }
+}
+
+#[async_trait]
+impl OutgoingAccessControl for TcpPooledOutgoingAccessControl {
+    async fn is_authorized(&self, relay_msg: &RelayMessage) -> Result<bool> {
+        let next = relay_msg.onward_route().next()?;
+
+        if let Some(info) = self.registry.get_pooled_connection(&self.receiver_address) {
+            if info.inbound_addresses().contains(next) {
+                return ockam_core::allow();
+            }
+        }
+
+        debug!(
+            "Message from pooled connection {} to {} was denied",
+            self.receiver_address, next
+        );
+
+        ockam_core::deny()
+    }
+}
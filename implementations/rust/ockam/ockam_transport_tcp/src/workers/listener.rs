@@ -1,25 +1,63 @@
-use crate::workers::{Addresses, TcpRecvProcessor};
+use crate::workers::{Addresses, ConnectionReadHalf, ConnectionWriteHalf, TcpRecvProcessor};
 use crate::{TcpConnectionMode, TcpListenerInfo, TcpListenerOptions, TcpRegistry, TcpSendWorker};
+use core::time::Duration;
+use ockam_core::compat::sync::Arc;
 use ockam_core::{async_trait, compat::net::SocketAddr};
 use ockam_core::{Address, Processor, Result};
 use ockam_node::Context;
 use ockam_transport_core::TransportError;
-use tokio::net::TcpListener;
-use tracing::debug;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinSet;
+use tokio::time::{error::Elapsed, timeout};
+use tracing::{debug, warn};
+
+/// Maximum duration of the handshake of an accepted connection
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximum number of accepted connections waiting for their handshake
+const MAX_PENDING_HANDSHAKES: usize = 64;
+
+/// The peer of an accepted connection and the result of its handshake
+type Handshake = (
+    SocketAddr,
+    core::result::Result<Result<(ConnectionReadHalf, ConnectionWriteHalf)>, Elapsed>,
+);
 
 /// A TCP Listen processor
 ///
 /// TCP listen processors are created by `TcpTransport`
 /// after a call is made to
 /// [`TcpTransport::listen`](crate::TcpTransport::listen).
+///
+/// The handshake of each accepted connection runs in its own task, so that
+/// a slow or malicious peer doesn't delay the other connections.
 pub(crate) struct TcpListenProcessor {
     registry: TcpRegistry,
     inner: TcpListener,
     socket_address: SocketAddr,
-    options: TcpListenerOptions,
+    options: Arc<TcpListenerOptions>,
+    handshakes: JoinSet<Handshake>,
 }
 
 impl TcpListenProcessor {
+    /// Perform the handshake of an accepted connection in a new task
+    fn start_handshake(&mut self, stream: TcpStream, peer: SocketAddr) {
+        if self.handshakes.len() >= MAX_PENDING_HANDSHAKES {
+            warn!(%peer, "Dropping incoming TCP connection: too many pending handshakes");
+            return;
+        }
+
+        let options = self.options.clone();
+        self.handshakes.spawn(async move {
+            let halves = timeout(
+                HANDSHAKE_TIMEOUT,
+                options.split_stream(&peer.to_string(), stream),
+            )
+            .await;
+            (peer, halves)
+        });
+    }
+
     pub(crate) async fn start(
         ctx: &Context,
         registry: TcpRegistry,
@@ -39,7 +77,8 @@ impl TcpListenProcessor {
             registry,
             inner,
             socket_address: saddr,
-            options,
+            options: Arc::new(options),
+            handshakes: JoinSet::new(),
         };
 
         ctx.start_processor(address.clone(), processor).await?;
@@ -66,6 +105,7 @@ impl Processor for TcpListenProcessor {
 
     async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
         self.registry.remove_listener_processor(&ctx.address());
+        self.handshakes.abort_all();
 
         Ok(())
     }
@@ -73,9 +113,33 @@ impl Processor for TcpListenProcessor {
     async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
         debug!("Waiting for incoming TCP connection...");
 
-        // Wait for an incoming connection
-        let (stream, peer) = self.inner.accept().await.map_err(TransportError::from)?;
-        debug!("TCP connection accepted");
+        // Wait for an incoming connection or for the end of a handshake
+        let handshake = tokio::select! {
+            accepted = self.inner.accept() => {
+                let (stream, peer) = accepted.map_err(TransportError::from)?;
+                debug!("TCP connection accepted");
+                self.start_handshake(stream, peer);
+                return Ok(true);
+            }
+            Some(handshake) = self.handshakes.join_next(), if !self.handshakes.is_empty() => handshake,
+        };
+
+        // A failed TLS handshake only drops this connection
+        let (peer, (read_half, write_half)) = match handshake {
+            Ok((peer, Ok(Ok(halves)))) => (peer, halves),
+            Ok((peer, Ok(Err(e)))) => {
+                warn!(%peer, "Dropping incoming TCP connection: {e}");
+                return Ok(true);
+            }
+            Ok((peer, Err(_))) => {
+                warn!(%peer, "Dropping incoming TCP connection: handshake timed out");
+                return Ok(true);
+            }
+            Err(e) => {
+                warn!("Dropping incoming TCP connection: handshake failed: {e}");
+                return Ok(true);
+            }
+        };
 
         let mode = TcpConnectionMode::Incoming;
         let addresses = Addresses::generate(mode);
@@ -87,8 +151,6 @@ impl Processor for TcpListenProcessor {
             .options
             .create_access_control(ctx.flow_controls(), receiver_flow_control_id.clone());
 
-        let (read_half, write_half) = stream.into_split();
-
         // Worker to receive messages from the Node and send them over the wire
         TcpSendWorker::start(
             ctx,
@@ -99,6 +161,7 @@ impl Processor for TcpListenProcessor {
             mode,
             access_control.sender_incoming_access_control,
             &receiver_flow_control_id,
+            self.options.max_message_size,
         )
         .await?;
 
@@ -112,6 +175,7 @@ impl Processor for TcpListenProcessor {
             mode,
             &receiver_flow_control_id,
             access_control.receiver_outgoing_access_control,
+            self.options.max_message_size,
         )
         .await?;
 
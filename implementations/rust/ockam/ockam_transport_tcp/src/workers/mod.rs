@@ -1,9 +1,15 @@
 mod addresses;
+mod framing;
+mod handle;
 mod listener;
 mod receiver;
 mod sender;
+mod stream;
 
 pub(crate) use addresses::*;
+pub(crate) use framing::*;
+pub(crate) use handle::*;
 pub(crate) use listener::*;
 pub(crate) use receiver::*;
 pub(crate) use sender::*;
+pub(crate) use stream::*;
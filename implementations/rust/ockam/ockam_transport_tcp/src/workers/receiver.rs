@@ -1,4 +1,4 @@
-use crate::workers::Addresses;
+use crate::workers::{Addresses, ConnectionReadHalf, FRAGMENT_MARKER};
 use crate::{TcpConnectionMode, TcpReceiverInfo, TcpRegistry, TcpSendWorkerMsg};
 use ockam_core::compat::net::SocketAddr;
 use ockam_core::compat::sync::Arc;
@@ -7,9 +7,9 @@ use ockam_core::{
     async_trait, AllowOnwardAddress, DenyAll, Mailbox, Mailboxes, OutgoingAccessControl,
 };
 use ockam_core::{Decodable, LocalMessage, Processor, Result, TransportMessage};
-use ockam_node::{Context, ProcessorBuilder};
+use ockam_node::{Context, Counter, ProcessorBuilder};
 use ockam_transport_core::TransportError;
-use tokio::{io::AsyncReadExt, net::tcp::OwnedReadHalf};
+use tokio::io::AsyncReadExt;
 use tracing::{error, info, trace};
 
 /// A TCP receiving message processor
@@ -22,22 +22,25 @@ use tracing::{error, info, trace};
 /// the node message system.
 pub(crate) struct TcpRecvProcessor {
     registry: TcpRegistry,
-    read_half: OwnedReadHalf,
+    read_half: ConnectionReadHalf,
     socket_address: SocketAddr,
     addresses: Addresses,
     mode: TcpConnectionMode,
     flow_control_id: FlowControlId,
+    max_message_size: usize,
+    bytes_received: Counter,
 }
 
 impl TcpRecvProcessor {
     /// Create a new `TcpRecvProcessor`
     fn new(
         registry: TcpRegistry,
-        read_half: OwnedReadHalf,
+        read_half: ConnectionReadHalf,
         socket_address: SocketAddr,
         addresses: Addresses,
         mode: TcpConnectionMode,
         flow_control_id: FlowControlId,
+        max_message_size: usize,
     ) -> Self {
         Self {
             registry,
@@ -46,6 +49,8 @@ impl TcpRecvProcessor {
             addresses,
             mode,
             flow_control_id,
+            max_message_size,
+            bytes_received: Counter::default(),
         }
     }
 
@@ -53,12 +58,13 @@ impl TcpRecvProcessor {
     pub async fn start(
         ctx: &Context,
         registry: TcpRegistry,
-        read_half: OwnedReadHalf,
+        read_half: ConnectionReadHalf,
         addresses: &Addresses,
         socket_address: SocketAddr,
         mode: TcpConnectionMode,
         flow_control_id: &FlowControlId,
         receiver_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        max_message_size: usize,
     ) -> Result<()> {
         let receiver = TcpRecvProcessor::new(
             registry,
@@ -67,6 +73,7 @@ impl TcpRecvProcessor {
             addresses.clone(),
             mode,
             flow_control_id.clone(),
+            max_message_size,
         );
 
         let mailbox = Mailbox::new(
@@ -88,6 +95,49 @@ impl TcpRecvProcessor {
 
         Ok(())
     }
+
+    /// Read the remaining frames of a fragmented message and reassemble it.
+    ///
+    /// The fragment marker of the first frame has already been read.
+    async fn read_fragments(&mut self) -> core::result::Result<Vec<u8>, TransportError> {
+        let total = self.read_half.read_u32().await? as usize;
+        if total > self.max_message_size {
+            return Err(TransportError::Capacity);
+        }
+
+        let mut buf = Vec::with_capacity(total);
+        loop {
+            let len = self.read_half.read_u16().await? as usize;
+            if len == 0 || buf.len() + len > total {
+                return Err(TransportError::Protocol);
+            }
+
+            let start = buf.len();
+            buf.resize(start + len, 0);
+            self.read_half.read_exact(&mut buf[start..]).await?;
+
+            if buf.len() == total {
+                return Ok(buf);
+            }
+
+            // Every following frame must belong to the same message
+            let marker = self.read_half.read_u16().await?;
+            let next_total = self.read_half.read_u32().await? as usize;
+            if marker != FRAGMENT_MARKER || next_total != total {
+                return Err(TransportError::Protocol);
+            }
+        }
+    }
+
+    /// Notify the sender that the connection is closed, so that it stops
+    async fn notify_connection_closed(&self, ctx: &Context) -> Result<()> {
+        ctx.send_from_address(
+            self.addresses.sender_internal_address().clone(),
+            TcpSendWorkerMsg::ConnectionClosed,
+            self.addresses.receiver_internal_address().clone(),
+        )
+        .await
+    }
 }
 
 #[async_trait]
@@ -96,6 +146,7 @@ impl Processor for TcpRecvProcessor {
 
     async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
         ctx.set_cluster(crate::CLUSTER_NAME).await?;
+        self.bytes_received = crate::metrics::bytes_received(ctx);
 
         self.registry.add_receiver_processor(TcpReceiverInfo::new(
             ctx.address(),
@@ -137,30 +188,56 @@ impl Processor for TcpRecvProcessor {
                 );
 
                 // Notify sender tx is closed
-                ctx.send_from_address(
-                    self.addresses.sender_internal_address().clone(),
-                    TcpSendWorkerMsg::ConnectionClosed,
-                    self.addresses.receiver_internal_address().clone(),
-                )
-                .await?;
+                self.notify_connection_closed(ctx).await?;
 
                 return Ok(false);
             }
         };
 
-        trace!("Received message header for {} bytes", len);
+        let buf = if len == FRAGMENT_MARKER {
+            // ...then either reassemble a fragmented message...
+            match self.read_fragments().await {
+                Ok(buf) => buf,
+                Err(e) => {
+                    error!(
+                        "Failed to receive fragmented message from peer '{}': {}; dropping stream",
+                        self.socket_address, e
+                    );
+                    self.notify_connection_closed(ctx).await?;
 
-        // Allocate a buffer of that size
-        let mut buf = vec![0; len as usize];
+                    return Ok(false);
+                }
+            }
+        } else {
+            trace!("Received message header for {} bytes", len);
 
-        // Then read into the buffer
-        match self.read_half.read_exact(&mut buf).await {
-            Ok(_) => {}
-            _ => {
-                error!("Failed to receive message of length: {}", len);
-                return Ok(true);
+            if len as usize > self.max_message_size {
+                error!(
+                    "Message of {} bytes from peer '{}' exceeds the maximum message size; dropping stream",
+                    len, self.socket_address
+                );
+                self.notify_connection_closed(ctx).await?;
+
+                return Ok(false);
             }
-        }
+
+            // ...or allocate a buffer of that size
+            let mut buf = vec![0; len as usize];
+
+            // Then read into the buffer
+            match self.read_half.read_exact(&mut buf).await {
+                Ok(_) => {}
+                _ => {
+                    error!("Failed to receive message of length: {}", len);
+                    return Ok(true);
+                }
+            }
+
+            buf
+        };
+
+        trace!("Received message of {} bytes", buf.len());
+        self.bytes_received.add(buf.len());
 
         // Deserialize the message now
         let mut msg = TransportMessage::decode(&buf).map_err(|_| TransportError::RecvBadMessage)?;
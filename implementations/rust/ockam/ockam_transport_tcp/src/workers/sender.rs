@@ -1,4 +1,4 @@
-use crate::workers::Addresses;
+use crate::workers::{encode_frames, Addresses, ConnectionWriteHalf};
 use crate::{TcpConnectionMode, TcpRegistry, TcpSenderInfo};
 use cfg_if::cfg_if;
 use core::time::Duration;
@@ -8,16 +8,12 @@ use ockam_core::{
     compat::{net::SocketAddr, sync::Arc},
     AllowSourceAddress, DenyAll, IncomingAccessControl,
 };
-use ockam_core::{
-    Any, Decodable, Encodable, Mailbox, Mailboxes, Message, Result, Routed, TransportMessage,
-    Worker,
-};
-use ockam_node::{Context, WorkerBuilder};
+use ockam_core::{Any, Decodable, Encodable, Mailbox, Mailboxes, Message, Result, Routed, Worker};
+use ockam_node::{Context, Counter, WorkerBuilder};
 use ockam_transport_core::TransportError;
 use serde::{Deserialize, Serialize};
 use socket2::{SockRef, TcpKeepalive};
 use tokio::io::AsyncWriteExt;
-use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::TcpStream;
 use tracing::{debug, info, trace, warn};
 
@@ -36,23 +32,26 @@ pub(crate) enum TcpSendWorkerMsg {
 /// to dispatch to a remote peer.
 pub(crate) struct TcpSendWorker {
     registry: TcpRegistry,
-    write_half: OwnedWriteHalf,
+    write_half: ConnectionWriteHalf,
     socket_address: SocketAddr,
     addresses: Addresses,
     mode: TcpConnectionMode,
     receiver_flow_control_id: FlowControlId,
+    max_message_size: usize,
     rx_should_be_stopped: bool,
+    bytes_sent: Counter,
 }
 
 impl TcpSendWorker {
     /// Create a new `TcpSendWorker`
     fn new(
         registry: TcpRegistry,
-        write_half: OwnedWriteHalf,
+        write_half: ConnectionWriteHalf,
         socket_address: SocketAddr,
         addresses: Addresses,
         mode: TcpConnectionMode,
         receiver_flow_control_id: FlowControlId,
+        max_message_size: usize,
     ) -> Self {
         Self {
             registry,
@@ -61,7 +60,9 @@ impl TcpSendWorker {
             addresses,
             receiver_flow_control_id,
             mode,
+            max_message_size,
             rx_should_be_stopped: true,
+            bytes_sent: Counter::default(),
         }
     }
 }
@@ -73,12 +74,13 @@ impl TcpSendWorker {
     pub(crate) async fn start(
         ctx: &Context,
         registry: TcpRegistry,
-        write_half: OwnedWriteHalf,
+        write_half: ConnectionWriteHalf,
         addresses: &Addresses,
         socket_address: SocketAddr,
         mode: TcpConnectionMode,
         sender_incoming_access_control: Arc<dyn IncomingAccessControl>,
         receiver_flow_control_id: &FlowControlId,
+        max_message_size: usize,
     ) -> Result<()> {
         trace!("Creating new TCP worker pair");
         let sender_worker = Self::new(
@@ -88,6 +90,7 @@ impl TcpSendWorker {
             addresses.clone(),
             mode,
             receiver_flow_control_id.clone(),
+            max_message_size,
         );
 
         let main_mailbox = Mailbox::new(
@@ -119,9 +122,7 @@ impl TcpSendWorker {
         Ok(())
     }
 
-    pub(crate) async fn connect(
-        socket_address: SocketAddr,
-    ) -> Result<(OwnedReadHalf, OwnedWriteHalf)> {
+    pub(crate) async fn connect(socket_address: SocketAddr) -> Result<TcpStream> {
         debug!(addr = %socket_address, "Connecting");
         let connection = match TcpStream::connect(socket_address).await {
             Ok(c) => {
@@ -147,7 +148,7 @@ impl TcpSendWorker {
         let socket = SockRef::from(&connection);
         socket.set_tcp_keepalive(&keepalive).unwrap();
 
-        Ok(connection.into_split())
+        Ok(connection)
     }
 }
 
@@ -158,6 +159,7 @@ impl Worker for TcpSendWorker {
 
     async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
         ctx.set_cluster(crate::CLUSTER_NAME).await?;
+        self.bytes_sent = crate::metrics::bytes_sent(ctx);
 
         self.registry.add_sender_worker(TcpSenderInfo::new(
             self.addresses.sender_address().clone(),
@@ -174,6 +176,14 @@ impl Worker for TcpSendWorker {
         self.registry
             .remove_sender_worker(self.addresses.sender_address());
 
+        // Logical connections over a pooled connection can't outlive its socket
+        for handle in self
+            .registry
+            .remove_pooled_connection(self.addresses.sender_address())
+        {
+            let _ = ctx.stop_worker(handle).await;
+        }
+
         if self.rx_should_be_stopped {
             let _ = ctx
                 .stop_processor(self.addresses.receiver_address().clone())
@@ -213,8 +223,24 @@ impl Worker for TcpSendWorker {
             // Remove our own address from the route so the other end
             // knows what to do with the incoming message
             msg.onward_route.step()?;
-            // Create a message buffer with prepended length
-            let msg = prepare_message(msg)?;
+            let msg = msg.encode().map_err(|_| TransportError::SendBadMessage)?;
+
+            // Oversized messages are dropped, the connection stays usable
+            if msg.len() > self.max_message_size || msg.len() > u32::MAX as usize {
+                warn!(
+                    "Dropping message of {} bytes to peer {}: maximum message size is {} bytes",
+                    msg.len(),
+                    self.socket_address,
+                    self.max_message_size
+                );
+
+                return Ok(());
+            }
+
+            let len = msg.len();
+
+            // Create a message buffer with prepended length, fragmenting large messages
+            let msg = encode_frames(&msg);
 
             if self.write_half.write_all(msg.as_slice()).await.is_err() {
                 warn!("Failed to send message to peer {}", self.socket_address);
@@ -222,32 +248,9 @@ impl Worker for TcpSendWorker {
 
                 return Ok(());
             }
+            self.bytes_sent.add(len);
         }
 
         Ok(())
     }
 }
-
-/// Helper that creates a length-prefixed buffer containing the given
-/// `TransportMessage`'s payload
-///
-/// The length-prefix is encoded as a big-endian 16-bit unsigned
-/// integer.
-fn prepare_message(msg: TransportMessage) -> Result<Vec<u8>> {
-    let mut msg_buf = msg.encode().map_err(|_| TransportError::SendBadMessage)?;
-
-    // Create a buffer that includes the message length in big endian
-    let mut len = (msg_buf.len() as u16).to_be_bytes().to_vec();
-
-    // Fun fact: reversing a vector in place, appending the length,
-    // and then reversing it again is faster for large message sizes
-    // than adding the large chunk of data.
-    //
-    // https://play.rust-lang.org/?version=stable&mode=release&edition=2018&gist=8669a640004ac85c7be38b19e3e73dcb
-    msg_buf.reverse();
-    len.reverse();
-    msg_buf.append(&mut len);
-    msg_buf.reverse();
-
-    Ok(msg_buf)
-}
@@ -0,0 +1,15 @@
+use ockam_core::compat::boxed::Box;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+
+/// Read half of a connection, either a plain TCP stream or a TLS stream
+pub(crate) type ConnectionReadHalf = Box<dyn AsyncRead + Send + Sync + Unpin>;
+
+/// Write half of a connection, either a plain TCP stream or a TLS stream
+pub(crate) type ConnectionWriteHalf = Box<dyn AsyncWrite + Send + Sync + Unpin>;
+
+/// Split a plain TCP stream into its read and write halves
+pub(crate) fn split_stream(stream: TcpStream) -> (ConnectionReadHalf, ConnectionWriteHalf) {
+    let (read_half, write_half) = stream.into_split();
+    (Box::new(read_half), Box::new(write_half))
+}
@@ -0,0 +1,244 @@
+use core::time::Duration;
+use ockam_core::{route, AllowAll, Result, Routed, Worker};
+use ockam_node::{Context, MessageReceiveOptions};
+use ockam_transport_tcp::{
+    TcpConnectionMode, TcpConnectionOptions, TcpListenerOptions, TcpTransport,
+};
+
+pub struct Echoer;
+
+#[ockam_core::worker]
+impl Worker for Echoer {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        ctx.send(msg.return_route(), msg.body()).await
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn tcp_pooling__two_connections__should_share_socket(ctx: &mut Context) -> Result<()> {
+    let options = TcpListenerOptions::new();
+    ctx.flow_controls()
+        .add_consumer("echoer", &options.spawner_flow_control_id());
+    ctx.start_worker("echoer", Echoer).await?;
+
+    let transport = TcpTransport::create(ctx).await?;
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+
+    let connection1 = transport
+        .connect(
+            listener.socket_string(),
+            TcpConnectionOptions::new().with_pooling(),
+        )
+        .await?;
+    let connection2 = transport
+        .connect(
+            listener.socket_string(),
+            TcpConnectionOptions::new().with_pooling(),
+        )
+        .await?;
+
+    assert_ne!(connection1.sender_address(), connection2.sender_address());
+    assert_ne!(connection1.flow_control_id(), connection2.flow_control_id());
+    assert_ne!(
+        connection1.receiver_address(),
+        connection2.receiver_address()
+    );
+
+    ctx.sleep(Duration::from_millis(50)).await; // Wait for workers to add themselves to the registry
+    let outgoing = transport
+        .registry()
+        .get_all_sender_workers()
+        .into_iter()
+        .filter(|x| matches!(x.mode(), TcpConnectionMode::Outgoing))
+        .count();
+    assert_eq!(outgoing, 1);
+
+    let pooled = transport.registry().get_all_pooled_connections();
+    assert_eq!(pooled.len(), 1);
+    assert_eq!(pooled[0].ref_count(), 2);
+
+    let reply1: String = ctx
+        .send_and_receive(route![connection1.clone(), "echoer"], "Hello 1".to_string())
+        .await?;
+    assert_eq!(reply1, "Hello 1");
+
+    let reply2: String = ctx
+        .send_and_receive(route![connection2.clone(), "echoer"], "Hello 2".to_string())
+        .await?;
+    assert_eq!(reply2, "Hello 2");
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn tcp_pooling__disconnect__should_close_socket_after_last_user(
+    ctx: &mut Context,
+) -> Result<()> {
+    let options = TcpListenerOptions::new();
+    ctx.flow_controls()
+        .add_consumer("echoer", &options.spawner_flow_control_id());
+    ctx.start_worker("echoer", Echoer).await?;
+
+    let transport = TcpTransport::create(ctx).await?;
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+
+    let connection1 = transport
+        .connect(
+            listener.socket_string(),
+            TcpConnectionOptions::new().with_pooling(),
+        )
+        .await?;
+    let connection2 = transport
+        .connect(
+            listener.socket_string(),
+            TcpConnectionOptions::new().with_pooling(),
+        )
+        .await?;
+
+    transport
+        .disconnect(connection1.sender_address().clone())
+        .await?;
+    ctx.sleep(Duration::from_millis(100)).await;
+
+    let pooled = transport.registry().get_all_pooled_connections();
+    assert_eq!(pooled.len(), 1);
+    assert_eq!(pooled[0].ref_count(), 1);
+
+    let reply: String = ctx
+        .send_and_receive(route![connection2.clone(), "echoer"], "Hello".to_string())
+        .await?;
+    assert_eq!(reply, "Hello");
+
+    transport
+        .disconnect(connection2.sender_address().clone())
+        .await?;
+    ctx.sleep(Duration::from_millis(100)).await;
+
+    assert!(transport.registry().get_all_pooled_connections().is_empty());
+    let outgoing = transport
+        .registry()
+        .get_all_sender_workers()
+        .into_iter()
+        .filter(|x| matches!(x.mode(), TcpConnectionMode::Outgoing))
+        .count();
+    assert_eq!(outgoing, 0);
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn tcp_pooling__without_pooling__should_open_new_sockets(ctx: &mut Context) -> Result<()> {
+    let transport = TcpTransport::create(ctx).await?;
+    let listener = transport
+        .listen("127.0.0.1:0", TcpListenerOptions::new())
+        .await?;
+
+    transport
+        .connect(
+            listener.socket_string(),
+            TcpConnectionOptions::new().with_pooling(),
+        )
+        .await?;
+    transport
+        .connect(listener.socket_string(), TcpConnectionOptions::new())
+        .await?;
+
+    ctx.sleep(Duration::from_millis(50)).await; // Wait for workers to add themselves to the registry
+    let outgoing = transport
+        .registry()
+        .get_all_sender_workers()
+        .into_iter()
+        .filter(|x| matches!(x.mode(), TcpConnectionMode::Outgoing))
+        .count();
+    assert_eq!(outgoing, 2);
+    assert_eq!(transport.registry().get_all_pooled_connections().len(), 1);
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn tcp_pooling__incoming_messages__should_only_reach_their_connection(
+    ctx: &mut Context,
+) -> Result<()> {
+    let transport = TcpTransport::create(ctx).await?;
+    let listener = transport
+        .listen("127.0.0.1:0", TcpListenerOptions::new())
+        .await?;
+
+    let connection1 = transport
+        .connect(
+            listener.socket_string(),
+            TcpConnectionOptions::new().with_pooling(),
+        )
+        .await?;
+    let connection2 = transport
+        .connect(
+            listener.socket_string(),
+            TcpConnectionOptions::new().with_pooling(),
+        )
+        .await?;
+
+    // Only the first connection is allowed to deliver messages to the sink
+    let mut sink = ctx.new_detached("sink", AllowAll, AllowAll).await?;
+    ctx.flow_controls()
+        .add_consumer("sink", connection1.flow_control_id());
+
+    // Send messages from the other side of the shared socket
+    ctx.sleep(Duration::from_millis(100)).await;
+    let incoming = transport
+        .registry()
+        .get_all_sender_workers()
+        .into_iter()
+        .find(|x| matches!(x.mode(), TcpConnectionMode::Incoming))
+        .unwrap()
+        .address()
+        .clone();
+
+    let routes = [
+        route![incoming.clone(), "sink"],
+        route![
+            incoming.clone(),
+            connection2.receiver_address().clone(),
+            "sink"
+        ],
+        route![incoming, connection1.receiver_address().clone(), "sink"],
+    ];
+    for (i, r) in routes.into_iter().enumerate() {
+        ctx.send(r, i.to_string()).await?;
+    }
+
+    // Only the message addressed to the first connection is delivered
+    let msg = sink.receive::<String>().await?;
+    assert_eq!(msg.body(), "2");
+    let res = sink
+        .receive_extended::<String>(
+            MessageReceiveOptions::new().with_timeout(Duration::from_millis(250)),
+        )
+        .await;
+    assert!(res.is_err());
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
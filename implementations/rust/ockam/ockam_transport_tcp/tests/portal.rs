@@ -1,10 +1,14 @@
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
 
 use ockam_core::compat::rand::random;
-use ockam_core::{route, Result};
+use ockam_core::compat::sync::Arc;
+use ockam_core::{
+    async_trait, route, AllowAll, DenyAll, OutgoingAccessControl, RelayMessage, Result,
+};
 use ockam_node::Context;
 use ockam_transport_tcp::{
     TcpConnectionOptions, TcpInletOptions, TcpListenerOptions, TcpOutletOptions, TcpTransport,
@@ -238,3 +242,260 @@ async fn portal__tcp_connection_with_invalid_message_flow__should_not_succeed(
 
     Ok(())
 }
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__inlet_max_connections__should_close_extra_connections(
+    ctx: &mut Context,
+) -> Result<()> {
+    let tcp = TcpTransport::create(ctx).await?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let bind_address = listener.local_addr().unwrap().to_string();
+    tcp.create_outlet("outlet", bind_address, TcpOutletOptions::new())
+        .await?;
+    let (inlet_saddr, _) = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["outlet"],
+            TcpInletOptions::new().with_max_connections(1),
+        )
+        .await?;
+
+    let _handle = tokio::spawn(async move {
+        let (_stream, _) = listener.accept().await.unwrap();
+        tokio::time::sleep(Duration::from_secs(10)).await;
+    });
+
+    let _stream1 = TcpStream::connect(inlet_saddr).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(250)).await;
+
+    let mut stream2 = TcpStream::connect(inlet_saddr).await.unwrap();
+    let mut payload = [0u8; LENGTH];
+    let length = stream2.read(&mut payload).await.unwrap_or(0);
+    assert_eq!(length, 0, "Extra connection should be closed");
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__outlet_max_connections__should_refuse_extra_connections(
+    ctx: &mut Context,
+) -> Result<()> {
+    let tcp = TcpTransport::create(ctx).await?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let bind_address = listener.local_addr().unwrap().to_string();
+    tcp.create_outlet(
+        "outlet",
+        bind_address,
+        TcpOutletOptions::new().with_max_connections(1),
+    )
+    .await?;
+    let (inlet_saddr, _) = tcp
+        .create_inlet("127.0.0.1:0", route!["outlet"], TcpInletOptions::new())
+        .await?;
+
+    let _handle = tokio::spawn(async move {
+        let (_stream, _) = listener.accept().await.unwrap();
+        tokio::time::sleep(Duration::from_secs(10)).await;
+    });
+
+    let _stream1 = TcpStream::connect(inlet_saddr).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(250)).await;
+
+    let mut stream2 = TcpStream::connect(inlet_saddr).await.unwrap();
+    let mut payload = [0u8; LENGTH];
+    let length = stream2.read(&mut payload).await.unwrap_or(0);
+    assert_eq!(length, 0, "Extra connection should be refused");
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 10000)]
+async fn portal__rate_limit__should_slow_down_transfer(ctx: &mut Context) -> Result<()> {
+    const RATE: usize = 1000;
+    const TOTAL: usize = 3 * RATE;
+
+    let tcp = TcpTransport::create(ctx).await?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let bind_address = listener.local_addr().unwrap().to_string();
+    tcp.create_outlet("outlet", bind_address, TcpOutletOptions::new())
+        .await?;
+    let (inlet_saddr, _) = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["outlet"],
+            TcpInletOptions::new().with_connection_rate_limit(RATE as u64),
+        )
+        .await?;
+
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut payload = vec![0u8; TOTAL];
+        stream.read_exact(&mut payload).await.unwrap();
+    });
+
+    let start = std::time::Instant::now();
+    let mut stream = TcpStream::connect(inlet_saddr).await.unwrap();
+    stream.write_all(&[7u8; TOTAL]).await.unwrap();
+
+    let res = handle.await;
+    assert!(res.is_ok());
+    // The first second of traffic goes through right away, the rest is limited
+    assert!(start.elapsed() >= Duration::from_millis(1500));
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__outgoing_access_control_denied__should_not_connect(
+    ctx: &mut Context,
+) -> Result<()> {
+    let tcp = TcpTransport::create(ctx).await?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let bind_address = listener.local_addr().unwrap().to_string();
+    tcp.create_outlet("outlet", bind_address, TcpOutletOptions::new())
+        .await?;
+    let (inlet_saddr, _) = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["outlet"],
+            TcpInletOptions::new().with_outgoing_access_control_impl(DenyAll),
+        )
+        .await?;
+
+    let _stream = TcpStream::connect(inlet_saddr).await.unwrap();
+
+    // The ping to the outlet is denied, so the outlet never connects
+    let accepted = tokio::time::timeout(Duration::from_secs(1), listener.accept()).await;
+    assert!(accepted.is_err(), "Outlet should not connect");
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__outgoing_access_control__should_propagate_disconnection(
+    ctx: &mut Context,
+) -> Result<()> {
+    let payload = generate_binary();
+
+    let tcp = TcpTransport::create(ctx).await?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let bind_address = listener.local_addr().unwrap().to_string();
+    tcp.create_outlet(
+        "outlet",
+        bind_address,
+        TcpOutletOptions::new().with_outgoing_access_control(Arc::new(AllowAll)),
+    )
+    .await?;
+    let (inlet_saddr, _) = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["outlet"],
+            TcpInletOptions::new().with_outgoing_access_control(Arc::new(AllowAll)),
+        )
+        .await?;
+
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        write_binary(&mut stream, payload).await;
+        // Dropping the stream disconnects the outlet side
+    });
+
+    let mut stream = TcpStream::connect(inlet_saddr).await.unwrap();
+    read_assert_binary(&mut stream, payload).await;
+    assert!(handle.await.is_ok());
+
+    // The disconnection reaches the inlet side, which closes the connection
+    let mut buffer = [0u8; LENGTH];
+    let length = stream.read(&mut buffer).await.unwrap_or(0);
+    assert_eq!(length, 0, "Connection should be closed");
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+/// Allows messages until access is revoked
+#[derive(Debug)]
+struct Revocable(Arc<AtomicBool>);
+
+#[async_trait]
+impl OutgoingAccessControl for Revocable {
+    async fn is_authorized(&self, _relay_msg: &RelayMessage) -> Result<bool> {
+        Ok(self.0.load(Ordering::Relaxed))
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__outgoing_access_revoked__should_stop_an_open_connection(
+    ctx: &mut Context,
+) -> Result<()> {
+    let payload1 = generate_binary();
+    let payload2 = generate_binary();
+
+    let tcp = TcpTransport::create(ctx).await?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let bind_address = listener.local_addr().unwrap().to_string();
+    tcp.create_outlet("outlet", bind_address, TcpOutletOptions::new())
+        .await?;
+    let allowed = Arc::new(AtomicBool::new(true));
+    let (inlet_saddr, _) = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["outlet"],
+            TcpInletOptions::new().with_outgoing_access_control_impl(Revocable(allowed.clone())),
+        )
+        .await?;
+
+    let mut stream = TcpStream::connect(inlet_saddr).await.unwrap();
+    let (mut outlet_stream, _) = listener.accept().await.unwrap();
+
+    write_binary(&mut stream, payload1).await;
+    read_assert_binary(&mut outlet_stream, payload1).await;
+
+    // Revoking the access applies to the connection which is already open
+    allowed.store(false, Ordering::Relaxed);
+    let _ = stream.write_all(&payload2).await;
+
+    let mut buffer = [0u8; LENGTH];
+    let read = tokio::time::timeout(Duration::from_secs(1), outlet_stream.read(&mut buffer)).await;
+    assert!(
+        !matches!(read, Ok(Ok(length)) if length > 0),
+        "Data should not reach the outlet"
+    );
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
@@ -0,0 +1,234 @@
+use ockam_core::{route, Result, Routed, Worker};
+use ockam_node::Context;
+use ockam_transport_tcp::{TcpConnectionOptions, TcpListenerOptions, TcpProxy, TcpTransport};
+use std::net::SocketAddr;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+pub struct Echoer;
+
+#[ockam_core::worker]
+impl Worker for Echoer {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        ctx.send(msg.return_route(), msg.body()).await
+    }
+}
+
+/// Relay the bytes of a tunnelled connection in the background
+fn relay(mut client: TcpStream, mut upstream: TcpStream) {
+    tokio::spawn(async move {
+        let _ = tokio::io::copy_bidirectional(&mut client, &mut upstream).await;
+    });
+}
+
+/// Start a local stand-in for an HTTP proxy which accepts a single `CONNECT` request
+/// authenticated with the given `Proxy-Authorization` header.
+///
+/// Return the target of the request.
+async fn start_http_proxy(authorization: &'static str) -> (SocketAddr, JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let handle = tokio::spawn(async move {
+        let (mut client, _) = listener.accept().await.unwrap();
+        let mut head = Vec::new();
+        while !head.ends_with(b"\r\n\r\n") {
+            head.push(client.read_u8().await.unwrap());
+        }
+        let head = String::from_utf8(head).unwrap();
+        let target = head.split_whitespace().nth(1).unwrap().to_string();
+
+        if !head.contains(&format!("\r\nProxy-Authorization: {authorization}\r\n")) {
+            client
+                .write_all(b"HTTP/1.1 407 Proxy Authentication Required\r\n\r\n")
+                .await
+                .unwrap();
+            return target;
+        }
+
+        let upstream = TcpStream::connect(&target).await.unwrap();
+        client
+            .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+            .await
+            .unwrap();
+        relay(client, upstream);
+
+        target
+    });
+
+    (address, handle)
+}
+
+/// Start a local stand-in for a SOCKS5 proxy which accepts a single IPv4 `CONNECT`
+/// request authenticated with the given username and password.
+///
+/// Return the target of the request.
+async fn start_socks5_proxy(
+    username: &'static str,
+    password: &'static str,
+) -> (SocketAddr, JoinHandle<SocketAddr>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let handle = tokio::spawn(async move {
+        let (mut client, _) = listener.accept().await.unwrap();
+
+        // Select the username/password authentication
+        let mut greeting = [0; 2];
+        client.read_exact(&mut greeting).await.unwrap();
+        let mut methods = vec![0; greeting[1] as usize];
+        client.read_exact(&mut methods).await.unwrap();
+        assert!(methods.contains(&2));
+        client.write_all(&[5, 2]).await.unwrap();
+
+        let mut credentials = vec![0; 2];
+        client.read_exact(&mut credentials).await.unwrap();
+        let mut received_username = vec![0; credentials[1] as usize];
+        client.read_exact(&mut received_username).await.unwrap();
+        let mut received_password = vec![0; client.read_u8().await.unwrap() as usize];
+        client.read_exact(&mut received_password).await.unwrap();
+        assert_eq!(received_username, username.as_bytes());
+        assert_eq!(received_password, password.as_bytes());
+        client.write_all(&[1, 0]).await.unwrap();
+
+        // Connect to the requested IPv4 address
+        let mut request = [0; 10];
+        client.read_exact(&mut request).await.unwrap();
+        assert_eq!(&request[..4], &[5, 1, 0, 1]);
+        let ip = [request[4], request[5], request[6], request[7]];
+        let port = u16::from_be_bytes([request[8], request[9]]);
+        let target = SocketAddr::from((ip, port));
+
+        let upstream = TcpStream::connect(target).await.unwrap();
+        client
+            .write_all(&[5, 0, 0, 1, 0, 0, 0, 0, 0, 0])
+            .await
+            .unwrap();
+        relay(client, upstream);
+
+        target
+    });
+
+    (address, handle)
+}
+
+#[ockam_macros::test]
+async fn send_receive_through_http_proxy(ctx: &mut Context) -> Result<()> {
+    let options = TcpListenerOptions::new();
+    ctx.flow_controls()
+        .add_consumer("echoer", &options.spawner_flow_control_id());
+    ctx.start_worker("echoer", Echoer).await?;
+
+    let transport = TcpTransport::create(ctx).await?;
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+
+    let (proxy_address, proxy_task) = start_http_proxy("Basic YWxpY2U6czNjcmV0").await;
+    let proxy = TcpProxy::parse(&format!("http://alice:s3cret@{proxy_address}"))?;
+    let connection = transport
+        .connect(
+            listener.socket_string(),
+            TcpConnectionOptions::new().with_proxy(proxy),
+        )
+        .await?;
+    // The connection socket is the socket of the proxy
+    assert_eq!(connection.socket_address(), &proxy_address);
+
+    let msg = "Hello through an HTTP proxy".to_string();
+    let reply = ctx
+        .send_and_receive::<String>(route![connection, "echoer"], msg.clone())
+        .await?;
+    assert_eq!(reply, msg, "Should receive the same message");
+    assert_eq!(proxy_task.await.unwrap(), listener.socket_string());
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn send_receive_through_socks5_proxy(ctx: &mut Context) -> Result<()> {
+    let options = TcpListenerOptions::new();
+    ctx.flow_controls()
+        .add_consumer("echoer", &options.spawner_flow_control_id());
+    ctx.start_worker("echoer", Echoer).await?;
+
+    let transport = TcpTransport::create(ctx).await?;
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+
+    let (proxy_address, proxy_task) = start_socks5_proxy("alice", "s3cret").await;
+    let proxy = TcpProxy::socks5(proxy_address.to_string()).with_credentials("alice", "s3cret");
+    let connection = transport
+        .connect(
+            listener.socket_string(),
+            TcpConnectionOptions::new().with_proxy(proxy),
+        )
+        .await?;
+    assert_eq!(connection.socket_address(), &proxy_address);
+
+    let msg = "Hello through a SOCKS5 proxy".to_string();
+    let reply = ctx
+        .send_and_receive::<String>(route![connection, "echoer"], msg.clone())
+        .await?;
+    assert_eq!(reply, msg, "Should receive the same message");
+    assert_eq!(&proxy_task.await.unwrap(), listener.socket_address());
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn http_proxy_authentication_failure(ctx: &mut Context) -> Result<()> {
+    let transport = TcpTransport::create(ctx).await?;
+    let listener = transport
+        .listen("127.0.0.1:0", TcpListenerOptions::new())
+        .await?;
+
+    let (proxy_address, _proxy_task) = start_http_proxy("Basic YWxpY2U6czNjcmV0").await;
+    let proxy = TcpProxy::http(proxy_address.to_string()).with_credentials("alice", "wrong");
+    let res = transport
+        .connect(
+            listener.socket_string(),
+            TcpConnectionOptions::new().with_proxy(proxy),
+        )
+        .await;
+    assert!(res.is_err());
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn bypassed_hosts_are_reached_directly(ctx: &mut Context) -> Result<()> {
+    let transport = TcpTransport::create(ctx).await?;
+    let listener = transport
+        .listen("127.0.0.1:0", TcpListenerOptions::new())
+        .await?;
+
+    // Nothing listens on the proxy port
+    let proxy = TcpProxy::http("127.0.0.1:1").bypass("127.0.0.1");
+    let connection = transport
+        .connect(
+            listener.socket_string(),
+            TcpConnectionOptions::new().with_proxy(proxy),
+        )
+        .await?;
+    assert_eq!(connection.socket_address(), listener.socket_address());
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
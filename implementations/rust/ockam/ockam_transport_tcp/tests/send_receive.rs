@@ -52,3 +52,39 @@ async fn send_receive(ctx: &mut Context) -> Result<()> {
 
     Ok(())
 }
+
+#[ockam_macros::test]
+async fn send_receive_large_message(ctx: &mut Context) -> Result<()> {
+    let options = TcpListenerOptions::new();
+    ctx.flow_controls()
+        .add_consumer("echoer", &options.spawner_flow_control_id());
+    ctx.start_worker("echoer", Echoer).await?;
+
+    let transport = TcpTransport::create(ctx).await?;
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+
+    let addr = transport
+        .connect(listener.socket_string(), TcpConnectionOptions::new())
+        .await?
+        .sender_address()
+        .clone();
+
+    // A message much larger than a single 64 KiB frame
+    let msg: String = rand::thread_rng()
+        .sample_iter(&rand::distributions::Alphanumeric)
+        .take(1024 * 1024)
+        .map(char::from)
+        .collect();
+
+    let reply = ctx
+        .send_and_receive::<String>(route![addr, "echoer"], msg.clone())
+        .await?;
+
+    assert_eq!(reply, msg, "Should receive the same message");
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
@@ -0,0 +1,135 @@
+#![cfg(feature = "tls")]
+
+use ockam_core::{route, Result, Routed, Worker};
+use ockam_node::Context;
+use ockam_transport_tcp::{
+    TcpConnectionOptions, TcpListenerOptions, TcpTransport, TlsClientOptions, TlsServerOptions,
+};
+
+const CA: &[u8] = include_bytes!("../../ockam_transport_core/tests/certificates/ca.pem");
+const SERVER_CERTIFICATE: &[u8] =
+    include_bytes!("../../ockam_transport_core/tests/certificates/server.pem");
+const SERVER_KEY: &[u8] =
+    include_bytes!("../../ockam_transport_core/tests/certificates/server.key");
+const CLIENT_CERTIFICATE: &[u8] =
+    include_bytes!("../../ockam_transport_core/tests/certificates/client.pem");
+const CLIENT_KEY: &[u8] =
+    include_bytes!("../../ockam_transport_core/tests/certificates/client.key");
+
+pub struct Echoer;
+
+#[ockam_core::worker]
+impl Worker for Echoer {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        ctx.send(msg.return_route(), msg.body()).await
+    }
+}
+
+#[ockam_macros::test]
+async fn send_receive_with_mutual_authentication(ctx: &mut Context) -> Result<()> {
+    let tls = TlsServerOptions::new(SERVER_CERTIFICATE, SERVER_KEY)?
+        .with_client_authentication(CA)?
+        .with_alpn_protocol("ockam");
+    let options = TcpListenerOptions::new().with_tls(tls);
+    ctx.flow_controls()
+        .add_consumer("echoer", &options.spawner_flow_control_id());
+    ctx.start_worker("echoer", Echoer).await?;
+
+    let transport = TcpTransport::create(ctx).await?;
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+
+    let tls = TlsClientOptions::new()
+        .with_root_certificates(CA)?
+        .with_client_certificate(CLIENT_CERTIFICATE, CLIENT_KEY)?
+        .with_alpn_protocol("ockam")
+        .with_server_name("localhost");
+    let addr = transport
+        .connect(
+            listener.socket_string(),
+            TcpConnectionOptions::new().with_tls(tls),
+        )
+        .await?
+        .sender_address()
+        .clone();
+
+    let msg = "Hello over TLS".to_string();
+    let reply = ctx
+        .send_and_receive::<String>(route![addr, "echoer"], msg.clone())
+        .await?;
+    assert_eq!(reply, msg, "Should receive the same message");
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn untrusted_server_is_rejected(ctx: &mut Context) -> Result<()> {
+    let tls = TlsServerOptions::new(SERVER_CERTIFICATE, SERVER_KEY)?;
+    let transport = TcpTransport::create(ctx).await?;
+    let listener = transport
+        .listen("127.0.0.1:0", TcpListenerOptions::new().with_tls(tls))
+        .await?;
+
+    // The client does not trust the CA which issued the server certificate
+    let tls = TlsClientOptions::new().with_server_name("localhost");
+    let res = transport
+        .connect(
+            listener.socket_string(),
+            TcpConnectionOptions::new().with_tls(tls),
+        )
+        .await;
+    assert!(res.is_err());
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn stalled_handshake_does_not_block_other_connections(ctx: &mut Context) -> Result<()> {
+    let tls = TlsServerOptions::new(SERVER_CERTIFICATE, SERVER_KEY)?;
+    let options = TcpListenerOptions::new().with_tls(tls);
+    ctx.flow_controls()
+        .add_consumer("echoer", &options.spawner_flow_control_id());
+    ctx.start_worker("echoer", Echoer).await?;
+
+    let transport = TcpTransport::create(ctx).await?;
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+
+    // This peer never starts its handshake
+    let _stalled = tokio::net::TcpStream::connect(*listener.socket_address())
+        .await
+        .unwrap();
+
+    let tls = TlsClientOptions::new()
+        .with_root_certificates(CA)?
+        .with_server_name("localhost");
+    let addr = transport
+        .connect(
+            listener.socket_string(),
+            TcpConnectionOptions::new().with_tls(tls),
+        )
+        .await?
+        .sender_address()
+        .clone();
+
+    let msg = "Hello over TLS".to_string();
+    let reply = ctx
+        .send_and_receive::<String>(route![addr, "echoer"], msg.clone())
+        .await?;
+    assert_eq!(reply, msg, "Should receive the same message");
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
@@ -121,4 +121,11 @@ impl UdpHolePuncher {
     pub fn address(&self) -> Address {
         self.worker_local_addr.clone()
     }
+
+    /// Stop this UDP NAT Hole Puncher's worker.
+    ///
+    /// The worker otherwise keeps the hole open after the handle is dropped.
+    pub async fn stop(self) -> Result<()> {
+        self.ctx.stop_worker(self.worker_main_addr.clone()).await
+    }
 }
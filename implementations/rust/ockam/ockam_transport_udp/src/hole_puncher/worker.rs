@@ -357,10 +357,13 @@ impl Worker for UdpHolePunchWorker {
                     let inner_msg = PunchMessage::decode(msg.payload())?;
                     match inner_msg {
                         PunchMessage::WaitForHoleOpen => {
-                            self.wait_for_hole_open_addr = Some(sender_addr)
-                            // TODO: If the hole is already open, the handle
-                            // will not be informed until it's closed and
-                            // opened again. Is this what we want?
+                            if self.hole_open {
+                                // Inform the handle right away, it would
+                                // otherwise wait for the hole to be re-opened
+                                ctx.send(sender_addr, ()).await?;
+                            } else {
+                                self.wait_for_hole_open_addr = Some(sender_addr)
+                            }
                         }
                         _ => return Err(PunchError::Internal.into()),
                     }
@@ -0,0 +1,85 @@
+pub(crate) use packet::*;
+pub(crate) use receive_window::ReceiveWindow;
+pub(crate) use send_window::SendWindow;
+
+mod packet;
+mod receive_window;
+mod send_window;
+
+use core::time::Duration;
+
+/// Largest payload of a UDP datagram over IPv4
+const MAX_MTU: usize = 65_507;
+
+/// Smallest supported datagram size, leaving room for the header of data packets
+const MIN_MTU: usize = 64;
+
+/// Options of the reliability layer of a [`UdpTransport`](crate::UdpTransport)
+///
+/// With reliability enabled, messages sent over UDP are numbered and
+/// acknowledged by the peer, retransmitted when they are lost, delivered in
+/// order, and split over several datagrams when they do not fit within the MTU.
+/// The number of unacknowledged datagrams sent to a peer is limited by a
+/// congestion window which adapts to losses.
+///
+/// Reliable and plain UDP transports can not talk to each other: both ends
+/// of a route must enable reliability.
+#[derive(Clone, Debug)]
+pub struct UdpReliabilityOptions {
+    pub(crate) mtu: usize,
+    pub(crate) initial_rto: Duration,
+    pub(crate) max_window: u32,
+    pub(crate) max_retransmissions: u32,
+}
+
+impl UdpReliabilityOptions {
+    /// Default options: 1200 bytes datagrams, an initial retransmission timeout of 1 second,
+    /// at most 128 datagrams in flight and 10 retransmissions of a datagram before giving up
+    pub fn new() -> Self {
+        Self {
+            mtu: 1200,
+            initial_rto: Duration::from_secs(1),
+            max_window: 128,
+            max_retransmissions: 10,
+        }
+    }
+
+    /// Set the maximum size of the datagrams sent to peers
+    ///
+    /// Messages which don't fit in a single datagram are fragmented.
+    /// The value is kept between 64 and 65507 bytes.
+    pub fn with_mtu(mut self, mtu: usize) -> Self {
+        self.mtu = mtu.clamp(MIN_MTU, MAX_MTU);
+        self
+    }
+
+    /// Set the retransmission timeout used until a round-trip time to the peer is measured
+    pub fn with_initial_rto(mut self, initial_rto: Duration) -> Self {
+        self.initial_rto = initial_rto;
+        self
+    }
+
+    /// Set the maximum number of unacknowledged datagrams sent to a peer
+    pub fn with_max_window(mut self, max_window: u32) -> Self {
+        self.max_window = max_window.max(1);
+        self
+    }
+
+    /// Set the number of retransmissions of a datagram after which the peer is
+    /// considered unreachable and its pending messages are dropped
+    pub fn with_max_retransmissions(mut self, max_retransmissions: u32) -> Self {
+        self.max_retransmissions = max_retransmissions;
+        self
+    }
+
+    /// Largest payload of a data packet
+    pub(crate) fn max_payload(&self) -> usize {
+        self.mtu - DATA_HEADER_LEN
+    }
+}
+
+impl Default for UdpReliabilityOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,177 @@
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use ockam_core::Result;
+use ockam_transport_core::TransportError;
+use serde::{Deserialize, Serialize};
+
+const DATA: u8 = 1;
+const ACK: u8 = 2;
+
+/// Size of the header of a data packet: type, session, sequence number and fragment
+pub(crate) const DATA_HEADER_LEN: usize = 1 + 8 + 8 + 2 + 2;
+
+/// Size of an acknowledgement packet: type, session, next sequence number and bitmap
+const ACK_LEN: usize = 1 + 8 + 8 + 8;
+
+/// A datagram exchanged by reliable UDP transports
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Packet {
+    /// A fragment of an encoded transport message
+    Data(DataPacket),
+    /// Acknowledgement of the data packets received from a peer
+    Ack(Ack),
+}
+
+/// A fragment of an encoded transport message
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct DataPacket {
+    /// Random identifier of the sending session
+    pub(crate) session: u64,
+    /// Sequence number of the packet within its session
+    pub(crate) seq: u64,
+    /// Index of this fragment within its message
+    pub(crate) fragment: u16,
+    /// Number of fragments of the message
+    pub(crate) fragments: u16,
+    pub(crate) payload: Vec<u8>,
+}
+
+/// Acknowledgement of the data packets received within a session
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Ack {
+    pub(crate) session: u64,
+    /// Every packet before this sequence number was received
+    pub(crate) next: u64,
+    /// Bit `i` is set when packet `next + 1 + i` was received
+    pub(crate) received: u64,
+}
+
+impl Ack {
+    /// Return true if the packet with the given sequence number was received
+    pub(crate) fn acknowledges(&self, seq: u64) -> bool {
+        if seq < self.next {
+            return true;
+        }
+        let offset = seq - self.next;
+        (1..=64).contains(&offset) && self.received & (1 << (offset - 1)) != 0
+    }
+}
+
+impl Packet {
+    pub(crate) fn encode(&self) -> Bytes {
+        let mut buf = BytesMut::new();
+        match self {
+            Packet::Data(data) => {
+                buf.reserve(DATA_HEADER_LEN + data.payload.len());
+                buf.put_u8(DATA);
+                buf.put_u64(data.session);
+                buf.put_u64(data.seq);
+                buf.put_u16(data.fragment);
+                buf.put_u16(data.fragments);
+                buf.put_slice(&data.payload);
+            }
+            Packet::Ack(ack) => {
+                buf.reserve(ACK_LEN);
+                buf.put_u8(ACK);
+                buf.put_u64(ack.session);
+                buf.put_u64(ack.next);
+                buf.put_u64(ack.received);
+            }
+        }
+        buf.freeze()
+    }
+
+    pub(crate) fn decode(mut src: &[u8]) -> Result<Self> {
+        match src.first() {
+            Some(&DATA) if src.len() >= DATA_HEADER_LEN => {
+                src.advance(1);
+                let session = src.get_u64();
+                let seq = src.get_u64();
+                let fragment = src.get_u16();
+                let fragments = src.get_u16();
+                if fragment >= fragments {
+                    return Err(TransportError::RecvBadMessage.into());
+                }
+                Ok(Packet::Data(DataPacket {
+                    session,
+                    seq,
+                    fragment,
+                    fragments,
+                    payload: src.to_vec(),
+                }))
+            }
+            Some(&ACK) if src.len() == ACK_LEN => {
+                src.advance(1);
+                Ok(Packet::Ack(Ack {
+                    session: src.get_u64(),
+                    next: src.get_u64(),
+                    received: src.get_u64(),
+                }))
+            }
+            _ => Err(TransportError::RecvBadMessage.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode() {
+        let data = Packet::Data(DataPacket {
+            session: 42,
+            seq: 7,
+            fragment: 1,
+            fragments: 3,
+            payload: b"hello".to_vec(),
+        });
+        let encoded = data.encode();
+        assert_eq!(encoded.len(), DATA_HEADER_LEN + 5);
+        assert_eq!(Packet::decode(&encoded).unwrap(), data);
+
+        let ack = Packet::Ack(Ack {
+            session: 42,
+            next: 8,
+            received: 0b101,
+        });
+        assert_eq!(Packet::decode(&ack.encode()).unwrap(), ack);
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        assert!(Packet::decode(&[]).is_err());
+        assert!(Packet::decode(&[DATA, 0, 0]).is_err());
+        assert!(Packet::decode(&[ACK; ACK_LEN + 1]).is_err());
+        assert!(Packet::decode(&[3; ACK_LEN]).is_err());
+
+        // The fragment index must be smaller than the number of fragments
+        let mut data = Packet::Data(DataPacket {
+            session: 1,
+            seq: 0,
+            fragment: 0,
+            fragments: 1,
+            payload: vec![],
+        })
+        .encode()
+        .to_vec();
+        data[DATA_HEADER_LEN - 1] = 0;
+        assert!(Packet::decode(&data).is_err());
+    }
+
+    #[test]
+    fn test_acknowledges() {
+        let ack = Ack {
+            session: 1,
+            next: 10,
+            received: 0b110,
+        };
+        assert!(ack.acknowledges(0));
+        assert!(ack.acknowledges(9));
+        assert!(!ack.acknowledges(10));
+        assert!(!ack.acknowledges(11));
+        assert!(ack.acknowledges(12));
+        assert!(ack.acknowledges(13));
+        assert!(!ack.acknowledges(14));
+        assert!(!ack.acknowledges(100));
+    }
+}
@@ -0,0 +1,162 @@
+use super::{Ack, DataPacket};
+use std::collections::BTreeMap;
+
+/// Number of packets after the next expected one which can be buffered
+const RECEIVE_BUFFER: u64 = 1024;
+
+/// Receiving side of a reliable session with a peer
+///
+/// Data packets are buffered until every packet before them is received, then
+/// reassembled into messages which are delivered in order.
+pub(crate) struct ReceiveWindow {
+    session: u64,
+    /// Session replaced by the current one, whose late packets are ignored
+    previous_session: Option<u64>,
+    /// Sequence number of the next packet to deliver
+    next: u64,
+    buffer: BTreeMap<u64, DataPacket>,
+    /// Fragments of the message being reassembled
+    message: Vec<u8>,
+    /// Index of the next fragment of the message being reassembled
+    fragment: u16,
+}
+
+impl ReceiveWindow {
+    pub(crate) fn new(session: u64) -> Self {
+        Self {
+            session,
+            previous_session: None,
+            next: 0,
+            buffer: BTreeMap::new(),
+            message: vec![],
+            fragment: 0,
+        }
+    }
+
+    /// Process a data packet and return the messages it completes, in order
+    pub(crate) fn on_data(&mut self, packet: DataPacket) -> Vec<Vec<u8>> {
+        if packet.session != self.session {
+            if Some(packet.session) == self.previous_session {
+                return vec![];
+            }
+            // The peer started a new session after giving up on the current one
+            *self = Self {
+                previous_session: Some(self.session),
+                ..Self::new(packet.session)
+            };
+        }
+
+        // Duplicates and packets too far ahead are dropped
+        if packet.seq < self.next || packet.seq >= self.next + RECEIVE_BUFFER {
+            return vec![];
+        }
+        self.buffer.entry(packet.seq).or_insert(packet);
+
+        let mut messages = vec![];
+        while let Some(packet) = self.buffer.remove(&self.next) {
+            self.next += 1;
+            if packet.fragment != self.fragment {
+                // Only a misbehaving peer interleaves the fragments of messages
+                self.message.clear();
+                self.fragment = 0;
+                if packet.fragment != 0 {
+                    continue;
+                }
+            }
+            self.message.extend_from_slice(&packet.payload);
+            self.fragment += 1;
+            if self.fragment == packet.fragments {
+                messages.push(core::mem::take(&mut self.message));
+                self.fragment = 0;
+            }
+        }
+        messages
+    }
+
+    /// Acknowledge the packets received so far
+    pub(crate) fn ack(&self) -> Ack {
+        let received = self
+            .buffer
+            .range(self.next + 1..=self.next + 64)
+            .fold(0, |received, (seq, _)| {
+                received | 1 << (seq - self.next - 1)
+            });
+        Ack {
+            session: self.session,
+            next: self.next,
+            received,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packet(session: u64, seq: u64, fragment: u16, fragments: u16, payload: &str) -> DataPacket {
+        DataPacket {
+            session,
+            seq,
+            fragment,
+            fragments,
+            payload: payload.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_in_order_delivery() {
+        let mut window = ReceiveWindow::new(1);
+
+        assert!(window.on_data(packet(1, 1, 0, 1, "b")).is_empty());
+        assert!(window.on_data(packet(1, 3, 1, 2, "d")).is_empty());
+        assert_eq!(
+            window.ack(),
+            Ack {
+                session: 1,
+                next: 0,
+                received: 0b101
+            }
+        );
+
+        assert_eq!(window.on_data(packet(1, 0, 0, 1, "a")), vec![b"a", b"b"]);
+        assert_eq!(window.ack().next, 2);
+        assert_eq!(window.on_data(packet(1, 2, 0, 2, "c")), vec![b"cd"]);
+        assert_eq!(window.ack().next, 4);
+        assert_eq!(window.ack().received, 0);
+
+        // Duplicates are not delivered again
+        assert!(window.on_data(packet(1, 0, 0, 1, "a")).is_empty());
+        assert_eq!(window.ack().next, 4);
+    }
+
+    #[test]
+    fn test_new_session() {
+        let mut window = ReceiveWindow::new(1);
+        assert_eq!(window.on_data(packet(1, 0, 0, 1, "a")), vec![b"a"]);
+        assert!(window.on_data(packet(1, 2, 0, 1, "c")).is_empty());
+
+        // A new session starts over from the first sequence number
+        assert_eq!(window.on_data(packet(2, 0, 0, 1, "x")), vec![b"x"]);
+        assert_eq!(
+            window.ack(),
+            Ack {
+                session: 2,
+                next: 1,
+                received: 0
+            }
+        );
+
+        // Late packets of the previous session are ignored
+        assert!(window.on_data(packet(1, 1, 0, 1, "b")).is_empty());
+        assert_eq!(window.ack().session, 2);
+    }
+
+    #[test]
+    fn test_buffer_limit() {
+        let mut window = ReceiveWindow::new(1);
+        assert!(window
+            .on_data(packet(1, RECEIVE_BUFFER, 0, 1, "z"))
+            .is_empty());
+        assert!(window.buffer.is_empty());
+    }
+}
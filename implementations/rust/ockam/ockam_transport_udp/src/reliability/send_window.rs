@@ -0,0 +1,403 @@
+use super::{Ack, DataPacket, UdpReliabilityOptions};
+use core::time::Duration;
+use ockam_core::Result;
+use ockam_transport_core::TransportError;
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Instant;
+
+/// Lower bound of the retransmission timeout
+const MIN_RTO: Duration = Duration::from_millis(200);
+
+/// Upper bound of the retransmission timeout
+const MAX_RTO: Duration = Duration::from_secs(60);
+
+/// Congestion window of a new session
+const INITIAL_WINDOW: u32 = 4;
+
+/// Number of later packets acknowledged before a missing packet is
+/// retransmitted without waiting for its retransmission timeout
+const FAST_RETRANSMIT_THRESHOLD: u32 = 3;
+
+/// A data packet waiting for its acknowledgement
+struct InFlight {
+    packet: DataPacket,
+    sent_at: Instant,
+    retransmissions: u32,
+    /// Number of later packets acknowledged while this one is missing
+    skipped: u32,
+}
+
+/// Sending side of a reliable session with a peer
+///
+/// Messages are split into numbered data packets which stay in flight until
+/// the peer acknowledges them. The number of packets in flight is limited by a
+/// congestion window which grows as packets are acknowledged (slow start, then
+/// additive increase) and shrinks when packets are lost.
+///
+/// A packet is retransmitted when three later packets are acknowledged before
+/// it, or after a retransmission timeout estimated from the measured
+/// round-trip times, as described in RFC 6298.
+pub(crate) struct SendWindow {
+    session: u64,
+    next_seq: u64,
+    max_payload: usize,
+    max_window: u32,
+    max_retransmissions: u32,
+    /// Packets waiting for room in the congestion window
+    queue: VecDeque<DataPacket>,
+    in_flight: BTreeMap<u64, InFlight>,
+    /// Congestion window, in packets
+    cwnd: u32,
+    /// Slow start threshold, in packets
+    ssthresh: u32,
+    /// Packets acknowledged since the congestion window last grew above the slow start threshold
+    acknowledged: u32,
+    srtt: Option<Duration>,
+    rttvar: Duration,
+    rto: Duration,
+}
+
+impl SendWindow {
+    /// Start a new session with a random identifier
+    pub(crate) fn new(options: &UdpReliabilityOptions) -> Self {
+        Self {
+            session: rand::random(),
+            next_seq: 0,
+            max_payload: options.max_payload(),
+            max_window: options.max_window,
+            max_retransmissions: options.max_retransmissions,
+            queue: VecDeque::new(),
+            in_flight: BTreeMap::new(),
+            cwnd: INITIAL_WINDOW.min(options.max_window),
+            ssthresh: options.max_window,
+            acknowledged: 0,
+            srtt: None,
+            rttvar: Duration::ZERO,
+            rto: options.initial_rto,
+        }
+    }
+
+    /// Split an encoded message into data packets queued for sending
+    pub(crate) fn push(&mut self, message: &[u8]) -> Result<()> {
+        let mut fragments: Vec<&[u8]> = message.chunks(self.max_payload).collect();
+        if fragments.is_empty() {
+            fragments.push(&[]);
+        }
+        let count = u16::try_from(fragments.len()).map_err(|_| TransportError::Capacity)?;
+
+        for (fragment, payload) in fragments.into_iter().enumerate() {
+            self.queue.push_back(DataPacket {
+                session: self.session,
+                seq: self.next_seq,
+                fragment: fragment as u16,
+                fragments: count,
+                payload: payload.to_vec(),
+            });
+            self.next_seq += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Move queued packets in flight while the congestion window allows it
+    ///
+    /// Return the packets to send.
+    pub(crate) fn poll_transmit(&mut self, now: Instant) -> Vec<DataPacket> {
+        let mut packets = vec![];
+        while (self.in_flight.len() as u32) < self.cwnd {
+            let packet = match self.queue.pop_front() {
+                Some(packet) => packet,
+                None => break,
+            };
+            self.in_flight.insert(
+                packet.seq,
+                InFlight {
+                    packet: packet.clone(),
+                    sent_at: now,
+                    retransmissions: 0,
+                    skipped: 0,
+                },
+            );
+            packets.push(packet);
+        }
+        packets
+    }
+
+    /// Process an acknowledgement from the peer
+    ///
+    /// Return the packets to retransmit right away.
+    pub(crate) fn on_ack(&mut self, ack: &Ack, now: Instant) -> Vec<DataPacket> {
+        if ack.session != self.session {
+            return vec![];
+        }
+
+        let acknowledged: Vec<u64> = self
+            .in_flight
+            .keys()
+            .copied()
+            .filter(|seq| ack.acknowledges(*seq))
+            .collect();
+
+        // Only packets which were sent once give an unambiguous round-trip time
+        let mut rtt = None;
+        for seq in &acknowledged {
+            if let Some(in_flight) = self.in_flight.remove(seq) {
+                if in_flight.retransmissions == 0 {
+                    rtt = Some(now.saturating_duration_since(in_flight.sent_at));
+                }
+                self.grow();
+            }
+        }
+        if let Some(rtt) = rtt {
+            self.update_rto(rtt);
+        }
+
+        // Count the packets which overtook the first missing one
+        let overtaking = acknowledged.iter().filter(|seq| **seq > ack.next).count() as u32;
+        let mut retransmit = vec![];
+        if let Some(missing) = self.in_flight.get_mut(&ack.next) {
+            missing.skipped += overtaking;
+            if missing.retransmissions == 0 && missing.skipped >= FAST_RETRANSMIT_THRESHOLD {
+                missing.retransmissions += 1;
+                missing.sent_at = now;
+                retransmit.push(missing.packet.clone());
+            }
+        }
+        if !retransmit.is_empty() {
+            self.ssthresh = (self.cwnd / 2).max(2);
+            self.cwnd = self.ssthresh;
+            self.acknowledged = 0;
+        }
+
+        retransmit
+    }
+
+    /// Retransmit the packets whose retransmission timeout expired
+    ///
+    /// Fail when a packet was already retransmitted the maximum number of times,
+    /// the peer is then considered unreachable.
+    pub(crate) fn on_timeout(&mut self, now: Instant) -> Result<Vec<DataPacket>> {
+        let mut packets = vec![];
+        for in_flight in self.in_flight.values_mut() {
+            if in_flight.sent_at + self.rto > now {
+                continue;
+            }
+            if in_flight.retransmissions >= self.max_retransmissions {
+                return Err(TransportError::ConnectionDrop.into());
+            }
+            in_flight.retransmissions += 1;
+            in_flight.sent_at = now;
+            in_flight.skipped = 0;
+            packets.push(in_flight.packet.clone());
+        }
+
+        if !packets.is_empty() {
+            // Back off and start again from a single packet
+            self.ssthresh = (self.in_flight.len() as u32 / 2).max(2);
+            self.cwnd = 1;
+            self.acknowledged = 0;
+            self.rto = (self.rto * 2).min(MAX_RTO);
+        }
+
+        Ok(packets)
+    }
+
+    /// Time at which the next retransmission timeout expires, if packets are in flight
+    pub(crate) fn next_timeout(&self) -> Option<Instant> {
+        self.in_flight
+            .values()
+            .map(|in_flight| in_flight.sent_at + self.rto)
+            .min()
+    }
+
+    fn grow(&mut self) {
+        if self.cwnd < self.ssthresh {
+            self.cwnd += 1;
+        } else {
+            self.acknowledged += 1;
+            if self.acknowledged >= self.cwnd {
+                self.acknowledged = 0;
+                self.cwnd += 1;
+            }
+        }
+        self.cwnd = self.cwnd.min(self.max_window);
+    }
+
+    fn update_rto(&mut self, rtt: Duration) {
+        let srtt = match self.srtt {
+            None => {
+                self.rttvar = rtt / 2;
+                rtt
+            }
+            Some(srtt) => {
+                let delta = if srtt > rtt { srtt - rtt } else { rtt - srtt };
+                self.rttvar = (self.rttvar * 3 + delta) / 4;
+                (srtt * 7 + rtt) / 8
+            }
+        };
+        self.srtt = Some(srtt);
+        self.rto = (srtt + self.rttvar * 4).clamp(MIN_RTO, MAX_RTO);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> UdpReliabilityOptions {
+        UdpReliabilityOptions::new()
+            .with_mtu(64)
+            .with_initial_rto(Duration::from_secs(1))
+            .with_max_window(8)
+            .with_max_retransmissions(2)
+    }
+
+    fn ack(window: &SendWindow, next: u64, received: u64) -> Ack {
+        Ack {
+            session: window.session,
+            next,
+            received,
+        }
+    }
+
+    fn seqs(packets: &[DataPacket]) -> Vec<u64> {
+        packets.iter().map(|packet| packet.seq).collect()
+    }
+
+    #[test]
+    fn test_fragmentation() {
+        let options = options();
+        let mut window = SendWindow::new(&options);
+        let message = vec![7; options.max_payload() * 2 + 1];
+        window.push(&message).unwrap();
+        window.push(&[]).unwrap();
+
+        let packets = window.poll_transmit(Instant::now());
+        assert_eq!(seqs(&packets), vec![0, 1, 2, 3]);
+        assert!(packets[..3].iter().all(|packet| packet.fragments == 3));
+        assert_eq!(
+            packets[..3].iter().map(|p| p.fragment).collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+        assert_eq!(packets[2].payload.len(), 1);
+        assert_eq!((packets[3].fragment, packets[3].fragments), (0, 1));
+        assert!(packets[3].payload.is_empty());
+
+        let too_large = vec![0; options.max_payload() * (u16::MAX as usize + 1)];
+        assert!(window.push(&too_large).is_err());
+    }
+
+    #[test]
+    fn test_congestion_window() {
+        let mut window = SendWindow::new(&options());
+        for _ in 0..20 {
+            window.push(b"message").unwrap();
+        }
+        let now = Instant::now();
+
+        // The initial window limits the packets in flight
+        assert_eq!(seqs(&window.poll_transmit(now)), vec![0, 1, 2, 3]);
+        assert!(window.poll_transmit(now).is_empty());
+
+        // Each acknowledged packet grows the window by one packet during slow start
+        assert!(window.on_ack(&ack(&window, 2, 0), now).is_empty());
+        assert_eq!(seqs(&window.poll_transmit(now)), vec![4, 5, 6, 7]);
+
+        // The window never exceeds its maximum
+        assert!(window.on_ack(&ack(&window, 8, 0), now).is_empty());
+        assert_eq!(window.poll_transmit(now).len(), 8);
+
+        // Acknowledgements of another session are ignored
+        let other = Ack {
+            session: window.session.wrapping_add(1),
+            next: 16,
+            received: 0,
+        };
+        assert!(window.on_ack(&other, now).is_empty());
+        assert!(window.poll_transmit(now).is_empty());
+    }
+
+    #[test]
+    fn test_retransmission_timeout() {
+        let mut window = SendWindow::new(&options());
+        for _ in 0..3 {
+            window.push(b"message").unwrap();
+        }
+        let now = Instant::now();
+        window.poll_transmit(now);
+        assert_eq!(window.next_timeout(), Some(now + Duration::from_secs(1)));
+
+        // RTO = SRTT + 4 * RTTVAR = 500 + 4 * 250 milliseconds
+        let later = now + Duration::from_millis(500);
+        window.on_ack(&ack(&window, 0, 0b1), later);
+        assert!(window.on_timeout(later).unwrap().is_empty());
+        assert_eq!(
+            window.next_timeout(),
+            Some(now + Duration::from_millis(1500))
+        );
+
+        // Only the packets whose timeout expired are retransmitted
+        let later = now + Duration::from_millis(1500);
+        assert_eq!(seqs(&window.on_timeout(later).unwrap()), vec![0, 2]);
+
+        // The timeout backs off
+        assert_eq!(window.next_timeout(), Some(later + Duration::from_secs(3)));
+
+        // The peer is unreachable once the maximum number of retransmissions is reached
+        let later = later + Duration::from_secs(3);
+        assert_eq!(seqs(&window.on_timeout(later).unwrap()), vec![0, 2]);
+        let later = later + Duration::from_secs(6);
+        assert!(window.on_timeout(later).is_err());
+    }
+
+    #[test]
+    fn test_fast_retransmit() {
+        let mut window = SendWindow::new(&options());
+        for _ in 0..4 {
+            window.push(b"message").unwrap();
+        }
+        let now = Instant::now();
+        window.poll_transmit(now);
+
+        // Packet 0 is missing while the next ones are acknowledged
+        assert!(window.on_ack(&ack(&window, 0, 0b1), now).is_empty());
+        assert!(window.on_ack(&ack(&window, 0, 0b11), now).is_empty());
+        let retransmitted = window.on_ack(&ack(&window, 0, 0b111), now);
+        assert_eq!(seqs(&retransmitted), vec![0]);
+
+        // A packet is only retransmitted early once
+        assert!(window.on_ack(&ack(&window, 0, 0b111), now).is_empty());
+        assert!(window.on_ack(&ack(&window, 4, 0), now).is_empty());
+        assert_eq!(window.next_timeout(), None);
+    }
+
+    #[test]
+    fn test_rto_estimation() {
+        let mut window = SendWindow::new(&options());
+        window.push(b"message").unwrap();
+        let now = Instant::now();
+        window.poll_transmit(now);
+
+        // RTO = SRTT + 4 * RTTVAR = 100 + 4 * 50 milliseconds
+        let now = now + Duration::from_millis(100);
+        window.on_ack(&ack(&window, 1, 0), now);
+        window.push(b"message").unwrap();
+        window.poll_transmit(now);
+        assert_eq!(
+            window.next_timeout(),
+            Some(now + Duration::from_millis(300))
+        );
+
+        // The RTO has a lower bound
+        let now = now + Duration::from_millis(1);
+        window.on_ack(&ack(&window, 2, 0), now);
+        for _ in 0..20 {
+            window.push(b"message").unwrap();
+            window.poll_transmit(now);
+            window.on_ack(&ack(&window, window.next_seq, 0), now);
+        }
+        window.push(b"message").unwrap();
+        window.poll_transmit(now);
+        assert_eq!(window.next_timeout(), Some(now + MIN_RTO));
+    }
+}
@@ -1,6 +1,7 @@
+use crate::reliability::UdpReliabilityOptions;
 use crate::router::messages::{UdpRouterRequest, UdpRouterResponse};
 use crate::router::UdpRouterHandle;
-use crate::workers::{TransportMessageCodec, UdpListenProcessor, UdpSendWorker};
+use crate::workers::{UdpListenProcessor, UdpSendWorker};
 use futures_util::StreamExt;
 use ockam_core::{
     async_trait, Address, AllowAll, Any, Decodable, DenyAll, LocalMessage, Mailbox, Mailboxes,
@@ -11,6 +12,7 @@ use ockam_transport_core::TransportError;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::sync::Arc;
 use tokio::net::UdpSocket;
+use tokio_util::codec::BytesCodec;
 use tokio_util::udp::UdpFramed;
 use tracing::{debug, error, trace};
 
@@ -31,6 +33,8 @@ use tracing::{debug, error, trace};
 /// sender. 'server' messages bypass the router as listeners inject the
 /// sender's address into the return route of received messages.
 ///
+/// When reliability is enabled, it applies to every local socket.
+///
 /// This transport only supports IPv4.
 pub(crate) struct UdpRouter {
     ctx: Context,
@@ -38,11 +42,15 @@ pub(crate) struct UdpRouter {
     api_addr: Address,
     /// Sender for 'client' messages
     client_sender: Address,
+    reliability: Option<UdpReliabilityOptions>,
 }
 
 impl UdpRouter {
     /// Create and register a new UDP router with the node context
-    pub(crate) async fn register(ctx: &Context) -> Result<UdpRouterHandle> {
+    pub(crate) async fn register(
+        ctx: &Context,
+        reliability: Option<UdpReliabilityOptions>,
+    ) -> Result<UdpRouterHandle> {
         // This context is only used to start workers, doesn't need to send nor receive messages
         let child_ctx = ctx
             .new_detached(
@@ -62,6 +70,7 @@ impl UdpRouter {
         let client_sender = Self::create_sender_listener(
             &child_ctx,
             SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 0),
+            reliability.clone(),
         )
         .await?;
 
@@ -70,6 +79,7 @@ impl UdpRouter {
             main_addr: main_addr.clone(),
             api_addr: api_addr.clone(),
             client_sender,
+            reliability,
         };
 
         let main_mailbox = Mailbox::new(
@@ -104,7 +114,11 @@ impl UdpRouter {
     /// Create a sender, listener pair for the given socket address.
     ///
     /// Returns the address of the created sender.
-    async fn create_sender_listener(ctx: &Context, local_addr: SocketAddr) -> Result<Address> {
+    async fn create_sender_listener(
+        ctx: &Context,
+        local_addr: SocketAddr,
+        reliability: Option<UdpReliabilityOptions>,
+    ) -> Result<Address> {
         // This transport only supports IPv4
         if !local_addr.is_ipv4() {
             error!(local_addr = %local_addr, "This transport only supprts IPv4");
@@ -117,18 +131,38 @@ impl UdpRouter {
             .map_err(|_| TransportError::InvalidAddress)?;
 
         // Split socket into sink and stream
-        let (sink, stream) = UdpFramed::new(socket, TransportMessageCodec).split();
+        let (sink, stream) = UdpFramed::new(socket, BytesCodec::new()).split();
 
         debug!("Creating new sender and listener for {}", local_addr);
 
-        // Create sender
         let sender_addr = Address::random_tagged("UdpSendWorker");
-        let sender = UdpSendWorker::new(sink);
-        // FIXME: @ac
-        ctx.start_worker(sender_addr.clone(), sender).await?;
+        let sender_internal_addr = Address::random_tagged("UdpSendWorker.internal");
+        let listener_addr = Address::random_tagged("UdpListenProcessor");
+
+        // Only a reliable listener talks to the internal address of its sender
+        let listener_sender_internal_addr =
+            reliability.as_ref().map(|_| sender_internal_addr.clone());
+
+        // Create sender
+        UdpSendWorker::start(
+            ctx,
+            sink,
+            sender_addr.clone(),
+            sender_internal_addr,
+            listener_addr.clone(),
+            reliability,
+        )
+        .await?;
 
         // Create listener
-        UdpListenProcessor::start(ctx, stream, sender_addr.clone()).await?;
+        UdpListenProcessor::start(
+            ctx,
+            listener_addr,
+            stream,
+            sender_addr.clone(),
+            listener_sender_internal_addr,
+        )
+        .await?;
 
         Ok(sender_addr)
     }
@@ -163,7 +197,12 @@ impl Worker for UdpRouter {
             trace!("handle_message() API_ADDR: msg = {:?}", msg);
             match msg {
                 UdpRouterRequest::Listen { local_addr } => {
-                    let res = Self::create_sender_listener(&self.ctx, local_addr).await;
+                    let res = Self::create_sender_listener(
+                        &self.ctx,
+                        local_addr,
+                        self.reliability.clone(),
+                    )
+                    .await;
                     let res = res.map(|_| ());
                     ctx.send_from_address(return_route, UdpRouterResponse::Listen(res), msg_addr)
                         .await?;
@@ -1,3 +1,4 @@
+use crate::reliability::UdpReliabilityOptions;
 use crate::router::{UdpRouter, UdpRouterHandle};
 use ockam_core::{async_trait, Result};
 use ockam_node::{Context, HasContext};
@@ -15,7 +16,29 @@ pub struct UdpTransport {
 impl UdpTransport {
     /// Create a new UDP transport for the current node
     pub async fn create(ctx: &Context) -> Result<UdpTransport> {
-        let router_handle = UdpRouter::register(ctx).await?;
+        let router_handle = UdpRouter::register(ctx, None).await?;
+        Ok(Self { router_handle })
+    }
+
+    /// Create a new UDP transport for the current node, which delivers
+    /// messages reliably and in order
+    ///
+    /// Peers must also use a reliable UDP transport.
+    ///
+    /// ```rust
+    /// use ockam_transport_udp::{UdpReliabilityOptions, UdpTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::Result;
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let udp = UdpTransport::create_reliable(&ctx, UdpReliabilityOptions::new()).await?;
+    /// udp.listen("127.0.0.1:4000").await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn create_reliable(
+        ctx: &Context,
+        options: UdpReliabilityOptions,
+    ) -> Result<UdpTransport> {
+        let router_handle = UdpRouter::register(ctx, Some(options)).await?;
         Ok(Self { router_handle })
     }
 
@@ -37,6 +60,14 @@ pub trait UdpTransportExtension: HasContext {
     async fn create_udp_transport(&self) -> Result<UdpTransport> {
         UdpTransport::create(self.get_context()).await
     }
+
+    /// Create a UDP transport which delivers messages reliably and in order
+    async fn create_reliable_udp_transport(
+        &self,
+        options: UdpReliabilityOptions,
+    ) -> Result<UdpTransport> {
+        UdpTransport::create_reliable(self.get_context(), options).await
+    }
 }
 
 impl<A: HasContext> UdpTransportExtension for A {}
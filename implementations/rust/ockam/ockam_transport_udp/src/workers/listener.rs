@@ -1,12 +1,29 @@
-use super::TransportMessageCodec;
+use super::{TransportMessageCodec, UdpSendWorkerMessage};
+use crate::reliability::{Packet, ReceiveWindow};
 use crate::UDP;
 use futures_util::stream::SplitStream;
 use futures_util::StreamExt;
-use ockam_core::{async_trait, route, Address, AllowAll, LocalMessage, Processor, Result};
+use hashbrown::HashMap;
+use ockam_core::{
+    async_trait, route, Address, AllowAll, Decodable, LocalMessage, Processor, Result,
+    TransportMessage,
+};
 use ockam_node::Context;
+use std::net::SocketAddr;
+use tokio_util::codec::{BytesCodec, Decoder};
 use tokio_util::udp::UdpFramed;
 use tracing::{debug, warn};
 
+/// The read half of a UDP socket
+pub(crate) type UdpStream = SplitStream<UdpFramed<BytesCodec>>;
+
+/// State of the reliability layer of a listener
+struct Reliability {
+    /// Internal address of our sender counterpart, which sends acknowledgements
+    sender_internal_addr: Address,
+    windows: HashMap<SocketAddr, ReceiveWindow>,
+}
+
 /// A listener for the UDP transport
 ///
 /// This processor handles the reception of messages on a
@@ -15,31 +32,98 @@ use tracing::{debug, warn};
 /// When a message is received, the address of the paired sender
 /// ([`UdpSendWorker`](crate::workers::UdpSendWorker)) is injected into the message's
 /// return route so that replies are sent to the sender.
+///
+/// When reliability is enabled, data packets are reassembled into messages by a
+/// [`ReceiveWindow`] per peer, and the paired sender is asked to acknowledge them.
 pub(crate) struct UdpListenProcessor {
-    /// The read half of the udnerlying UDP socket.
-    stream: SplitStream<UdpFramed<TransportMessageCodec>>,
+    /// The read half of the underlying UDP socket.
+    stream: UdpStream,
     /// Address of our sender counterpart
     sender_addr: Address,
+    reliability: Option<Reliability>,
 }
 
 impl UdpListenProcessor {
+    /// Start a new `UdpListenProcessor`
+    ///
+    /// `sender_internal_addr` is only given when reliability is enabled.
     pub(crate) async fn start(
         ctx: &Context,
-        stream: SplitStream<UdpFramed<TransportMessageCodec>>,
+        addr: Address,
+        stream: UdpStream,
         sender_addr: Address,
+        sender_internal_addr: Option<Address>,
     ) -> Result<()> {
+        let reliability = sender_internal_addr.map(|sender_internal_addr| Reliability {
+            sender_internal_addr,
+            windows: HashMap::new(),
+        });
         let processor = Self {
             stream,
             sender_addr,
+            reliability,
         };
-        let addr = Address::random_tagged("UdpListenProcessor");
 
         // FIXME: @ac
-        ctx.start_processor_with_access_control(addr.clone(), processor, AllowAll, AllowAll)
+        ctx.start_processor_with_access_control(addr, processor, AllowAll, AllowAll)
             .await?;
 
         Ok(())
     }
+
+    /// Handle a datagram of the reliability layer
+    ///
+    /// Return the messages which can be delivered.
+    async fn receive_packet(
+        ctx: &Context,
+        reliability: &mut Reliability,
+        datagram: &[u8],
+        addr: SocketAddr,
+    ) -> Result<Vec<TransportMessage>> {
+        let packet = match Packet::decode(datagram) {
+            Ok(packet) => packet,
+            Err(e) => {
+                warn!("Failed to read packet from {}: {:?}", addr, e);
+                return Ok(vec![]);
+            }
+        };
+
+        let data = match packet {
+            Packet::Data(data) => data,
+            Packet::Ack(ack) => {
+                let msg = UdpSendWorkerMessage::Acknowledged { peer: addr, ack };
+                ctx.send(reliability.sender_internal_addr.clone(), msg)
+                    .await?;
+                return Ok(vec![]);
+            }
+        };
+
+        let session = data.session;
+        let window = reliability
+            .windows
+            .entry(addr)
+            .or_insert_with(|| ReceiveWindow::new(session));
+        let messages = window.on_data(data);
+
+        // Acknowledge every data packet, including duplicates whose acknowledgement was lost
+        let msg = UdpSendWorkerMessage::Acknowledge {
+            peer: addr,
+            ack: window.ack(),
+        };
+        ctx.send(reliability.sender_internal_addr.clone(), msg)
+            .await?;
+
+        Ok(messages
+            .iter()
+            .filter_map(|message| match TransportMessage::decode(message) {
+                Ok(msg) => Some(msg),
+                Err(e) => {
+                    warn!("Failed to decode message from {}: {:?}", addr, e);
+                    None
+                }
+            })
+            .collect())
+    }
 }
 
 #[async_trait]
@@ -52,9 +136,9 @@ impl Processor for UdpListenProcessor {
 
     async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
         debug!("Waiting for incoming UDP datagram...");
-        let (mut msg, addr) = match self.stream.next().await {
+        let (mut datagram, addr) = match self.stream.next().await {
             Some(res) => match res {
-                Ok((msg, addr)) => (msg, addr),
+                Ok((datagram, addr)) => (datagram, addr),
                 Err(e) => {
                     warn!(
                         "Failed to read message, will wait for next message: {:?}",
@@ -69,17 +153,33 @@ impl Processor for UdpListenProcessor {
             }
         };
 
-        // Set return route to go directly to paired sender, skipping the UDP router
-        msg.return_route = route![
-            self.sender_addr.clone(),
-            Address::new(UDP, addr.to_string()),
-            msg.return_route
-        ];
-
-        debug!(onward_route = %msg.onward_route,
-            return_route = %msg.return_route,
-            "Forwarding UDP message");
-        ctx.forward(LocalMessage::new(msg, vec![])).await?;
+        let messages = match &mut self.reliability {
+            Some(reliability) => Self::receive_packet(ctx, reliability, &datagram, addr).await?,
+            None => match TransportMessageCodec.decode(&mut datagram) {
+                Ok(msg) => msg.into_iter().collect(),
+                Err(e) => {
+                    warn!(
+                        "Failed to read message, will wait for next message: {:?}",
+                        e
+                    );
+                    return Ok(true);
+                }
+            },
+        };
+
+        for mut msg in messages {
+            // Set return route to go directly to paired sender, skipping the UDP router
+            msg.return_route = route![
+                self.sender_addr.clone(),
+                Address::new(UDP, addr.to_string()),
+                msg.return_route
+            ];
+
+            debug!(onward_route = %msg.onward_route,
+                return_route = %msg.return_route,
+                "Forwarding UDP message");
+            ctx.forward(LocalMessage::new(msg, vec![])).await?;
+        }
 
         Ok(true)
     }
@@ -1,28 +1,196 @@
 use super::TransportMessageCodec;
+use crate::reliability::{Ack, Packet, SendWindow, UdpReliabilityOptions};
 use crate::UDP;
+use bytes::{Bytes, BytesMut};
 use futures_util::{stream::SplitSink, SinkExt};
-use ockam_core::{async_trait, Any, Result, Routed, TransportMessage, Worker};
-use ockam_node::Context;
+use hashbrown::HashMap;
+use ockam_core::{
+    async_trait, Address, AllowAll, AllowSourceAddresses, Any, Decodable, DenyAll, Encodable,
+    Mailbox, Mailboxes, Message, Result, Routed, Worker,
+};
+use ockam_node::{Context, DelayedEvent, WorkerBuilder};
 use ockam_transport_core::TransportError;
+use serde::{Deserialize, Serialize};
 use std::net::{SocketAddr, ToSocketAddrs};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio_util::codec::{BytesCodec, Encoder};
 use tokio_util::udp::UdpFramed;
 use tracing::{error, trace, warn};
 
+/// The write half of a UDP socket
+pub(crate) type UdpSink = SplitSink<UdpFramed<BytesCodec>, (Bytes, SocketAddr)>;
+
+/// Messages driving the reliability layer of a [`UdpSendWorker`]
+///
+/// They are sent by the paired listener and by the retransmission timer
+/// to the internal address of the sender.
+#[derive(Serialize, Deserialize, Clone, Debug, Message)]
+pub(crate) enum UdpSendWorkerMessage {
+    /// The peer acknowledged data packets
+    Acknowledged { peer: SocketAddr, ack: Ack },
+    /// Acknowledge the data packets received from the peer
+    Acknowledge { peer: SocketAddr, ack: Ack },
+    /// The retransmission timeout of a packet in flight expired
+    Retransmit,
+}
+
+/// State of the reliability layer of a sender
+struct Reliability {
+    options: UdpReliabilityOptions,
+    windows: HashMap<SocketAddr, SendWindow>,
+    retransmit: DelayedEvent<UdpSendWorkerMessage>,
+}
+
 /// A sender for the UDP transport
 ///
 /// This worker handles the sending of messages on a
 /// local socket. See [`UdpRouter`](crate::router::UdpRouter) for more details.
+///
+/// When reliability is enabled, messages are sent as data packets through a
+/// [`SendWindow`] per peer, and the worker also sends the acknowledgements of
+/// the packets received by its paired listener.
 pub(crate) struct UdpSendWorker {
-    /// The read half of the udnerlying UDP socket.
-    sink: SplitSink<UdpFramed<TransportMessageCodec>, (TransportMessage, SocketAddr)>,
+    /// The write half of the underlying UDP socket.
+    sink: UdpSink,
+    /// Address receiving [`UdpSendWorkerMessage`]s
+    internal_addr: Address,
+    reliability: Option<Reliability>,
 }
 
 impl UdpSendWorker {
-    /// Create a new `UdpSendWorker`
-    pub(crate) fn new(
-        sink: SplitSink<UdpFramed<TransportMessageCodec>, (TransportMessage, SocketAddr)>,
-    ) -> Self {
-        Self { sink }
+    /// Start a new `UdpSendWorker`
+    ///
+    /// Only the paired listener and the retransmission timer can send
+    /// messages to the internal address of the worker.
+    pub(crate) async fn start(
+        ctx: &Context,
+        sink: UdpSink,
+        addr: Address,
+        internal_addr: Address,
+        listener_addr: Address,
+        reliability: Option<UdpReliabilityOptions>,
+    ) -> Result<()> {
+        let mut sources = vec![listener_addr];
+        let reliability = match reliability {
+            Some(options) => {
+                let retransmit = DelayedEvent::create(
+                    ctx,
+                    internal_addr.clone(),
+                    UdpSendWorkerMessage::Retransmit,
+                )
+                .await?;
+                sources.push(retransmit.address());
+                Some(Reliability {
+                    options,
+                    windows: HashMap::new(),
+                    retransmit,
+                })
+            }
+            None => None,
+        };
+
+        let main_mailbox = Mailbox::new(
+            addr,
+            Arc::new(AllowAll), // FIXME: @ac
+            Arc::new(AllowAll), // FIXME: @ac
+        );
+        let internal_mailbox = Mailbox::new(
+            internal_addr.clone(),
+            Arc::new(AllowSourceAddresses(sources)),
+            Arc::new(DenyAll),
+        );
+
+        let sender = Self {
+            sink,
+            internal_addr,
+            reliability,
+        };
+        WorkerBuilder::new(sender)
+            .with_mailboxes(Mailboxes::new(main_mailbox, vec![internal_mailbox]))
+            .start(ctx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Send a datagram to a peer
+    async fn send(&mut self, datagram: Bytes, addr: SocketAddr) -> Result<()> {
+        match self.sink.send((datagram, addr)).await {
+            Ok(()) => {
+                trace!("Successful send to {}", addr);
+                Ok(())
+            }
+            Err(e) => {
+                error!("Failed send to {}: {:?}", addr, e);
+                Err(TransportError::from(e).into())
+            }
+        }
+    }
+
+    /// Send packets, the reliability layer retransmits the ones which get lost
+    async fn send_packets(&mut self, packets: Vec<(Packet, SocketAddr)>) {
+        for (packet, addr) in packets {
+            let _ = self.send(packet.encode(), addr).await;
+        }
+    }
+
+    /// Schedule the retransmission timer for the earliest timeout of the packets in flight
+    async fn schedule_retransmit(&mut self) -> Result<()> {
+        if let Some(reliability) = &mut self.reliability {
+            let next = reliability
+                .windows
+                .values()
+                .filter_map(SendWindow::next_timeout)
+                .min();
+            match next {
+                Some(next) => {
+                    let delay = next.saturating_duration_since(Instant::now());
+                    reliability.retransmit.schedule(delay).await?
+                }
+                None => reliability.retransmit.cancel(),
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle_internal(&mut self, msg: UdpSendWorkerMessage) -> Result<()> {
+        let reliability = match &mut self.reliability {
+            Some(reliability) => reliability,
+            None => return Err(TransportError::Protocol.into()),
+        };
+
+        let now = Instant::now();
+        let mut packets = vec![];
+        match msg {
+            UdpSendWorkerMessage::Acknowledge { peer, ack } => {
+                packets.push((Packet::Ack(ack), peer));
+            }
+            UdpSendWorkerMessage::Acknowledged { peer, ack } => {
+                if let Some(window) = reliability.windows.get_mut(&peer) {
+                    let mut data = window.on_ack(&ack, now);
+                    data.extend(window.poll_transmit(now));
+                    packets.extend(data.into_iter().map(|p| (Packet::Data(p), peer)));
+                }
+            }
+            UdpSendWorkerMessage::Retransmit => {
+                reliability
+                    .windows
+                    .retain(|peer, window| match window.on_timeout(now) {
+                        Ok(data) => {
+                            packets.extend(data.into_iter().map(|p| (Packet::Data(p), *peer)));
+                            true
+                        }
+                        Err(_) => {
+                            warn!(peer = %peer, "Peer does not acknowledge packets, dropping its pending messages");
+                            false
+                        }
+                    });
+            }
+        }
+
+        self.send_packets(packets).await;
+        self.schedule_retransmit().await
     }
 }
 
@@ -36,6 +204,11 @@ impl Worker for UdpSendWorker {
         _ctx: &mut Context,
         msg: Routed<Self::Message>,
     ) -> Result<()> {
+        if msg.msg_addr() == self.internal_addr {
+            let msg = UdpSendWorkerMessage::decode(msg.payload())?;
+            return self.handle_internal(msg).await;
+        }
+
         // Parse message and remove our address from its routing
         let mut msg = msg.into_transport_message();
         msg.onward_route.step()?;
@@ -74,15 +247,28 @@ impl Worker for UdpSendWorker {
         }
 
         // Send
-        match self.sink.send((msg.clone(), addr)).await {
-            Ok(()) => {
-                trace!("Successful send to {}", addr);
-                Ok(())
-            }
-            Err(e) => {
-                error!("Failed send to {}: {:?}", addr, e);
-                Err(e.into())
+        let reliability = match &mut self.reliability {
+            Some(reliability) => reliability,
+            None => {
+                let mut datagram = BytesMut::new();
+                TransportMessageCodec.encode(msg, &mut datagram)?;
+                return self.send(datagram.freeze(), addr).await;
             }
-        }
+        };
+
+        let options = &reliability.options;
+        let window = reliability
+            .windows
+            .entry(addr)
+            .or_insert_with(|| SendWindow::new(options));
+        window.push(&msg.encode()?)?;
+        let packets = window
+            .poll_transmit(Instant::now())
+            .into_iter()
+            .map(|p| (Packet::Data(p), addr))
+            .collect();
+
+        self.send_packets(packets).await;
+        self.schedule_retransmit().await
     }
 }
@@ -1,15 +1,19 @@
 use ockam_core::compat::rand::{self, Rng};
 use ockam_core::{route, Address, AllowAll, Result, Routed, Worker};
 use ockam_node::{Context, MessageReceiveOptions, MessageSendReceiveOptions};
-use ockam_transport_udp::{UdpTransport, UDP};
+use ockam_transport_udp::{UdpReliabilityOptions, UdpTransport, UDP};
 use std::net::SocketAddr;
 use std::time::Duration;
+use tokio::net::UdpSocket;
 use tracing::{debug, error, trace};
 
 mod utils;
 
 const TIMEOUT: Duration = Duration::from_secs(5);
 
+/// Timeout of messages which may need several retransmissions
+const LOSSY_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// When acting as a server, the transport should reply using the same
 /// UDP port that we sent to.
 #[ockam_macros::test]
@@ -192,6 +196,106 @@ async fn send_receive(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+/// Start a relay for the datagrams exchanged with a server, which drops
+/// one datagram out of five and swaps the order of some others
+async fn start_lossy_relay(server_addr: SocketAddr) -> SocketAddr {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let relay_addr = socket.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        let mut client_addr = None;
+        let mut held_back: Option<(Vec<u8>, SocketAddr)> = None;
+        let mut buf = vec![0; 65536];
+        for count in 0u64.. {
+            let (len, from) = match socket.recv_from(&mut buf).await {
+                Ok(res) => res,
+                Err(_) => return,
+            };
+            let to = if from == server_addr {
+                match client_addr {
+                    Some(client_addr) => client_addr,
+                    None => continue,
+                }
+            } else {
+                client_addr = Some(from);
+                server_addr
+            };
+
+            match count % 5 {
+                0 => continue,
+                2 => {
+                    held_back = Some((buf[..len].to_vec(), to));
+                    continue;
+                }
+                _ => {}
+            }
+            let _ = socket.send_to(&buf[..len], to).await;
+            if let Some((datagram, to)) = held_back.take() {
+                let _ = socket.send_to(&datagram, to).await;
+            }
+        }
+    });
+
+    relay_addr
+}
+
+/// A reliable transport should deliver every message, in order, even when
+/// datagrams are lost or reordered and messages do not fit in a single datagram
+#[ockam_macros::test(timeout = 60000)]
+async fn reliable_delivery_over_lossy_path(ctx: &mut Context) -> Result<()> {
+    // Find an available port
+    let bind_addr = *utils::available_local_ports(1).await?.first().unwrap();
+    debug!("bind_addr = {:?}", bind_addr);
+
+    // Transport
+    let options = UdpReliabilityOptions::new().with_initial_rto(Duration::from_millis(200));
+    let transport = UdpTransport::create_reliable(ctx, options).await?;
+
+    // Listener
+    {
+        ctx.start_worker("echoer", Echoer::new()).await?;
+        transport.listen(bind_addr.to_string()).await?;
+    };
+
+    let relay_addr = start_lossy_relay(bind_addr).await;
+
+    // Sender
+    {
+        let messages: Vec<String> = (0..20)
+            .map(|i| {
+                // Some messages need several datagrams
+                let len = if i % 4 == 0 { 5000 } else { 64 };
+                rand::thread_rng()
+                    .sample_iter(&rand::distributions::Alphanumeric)
+                    .take(len)
+                    .map(char::from)
+                    .collect()
+            })
+            .collect();
+
+        let mut child_ctx = ctx
+            .new_detached(Address::random_tagged("App.detached"), AllowAll, AllowAll)
+            .await?;
+        for msg in &messages {
+            let r = route![(UDP, relay_addr.to_string()), "echoer"];
+            child_ctx.send(r, msg.clone()).await?;
+        }
+
+        for msg in &messages {
+            let reply = child_ctx
+                .receive_extended::<String>(
+                    MessageReceiveOptions::new().with_timeout(LOSSY_TIMEOUT),
+                )
+                .await?
+                .body();
+            assert_eq!(&reply, msg, "Should receive the same messages, in order");
+        }
+    };
+
+    ctx.stop().await?;
+    Ok(())
+}
+
 pub struct Echoer {
     prev_src_addr: Option<String>,
 }
@@ -0,0 +1,159 @@
+use core::fmt;
+use core::fmt::Formatter;
+use ockam_core::flow_control::FlowControlId;
+use ockam_core::Address;
+use std::os::unix::net::SocketAddr;
+
+/// UDS connection mode
+#[derive(Copy, Debug, Clone)]
+pub enum UdsConnectionMode {
+    /// Connection was initiated from our node
+    Outgoing,
+    /// Connection was accepted from a UDS listener
+    Incoming,
+}
+
+impl fmt::Display for UdsConnectionMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            UdsConnectionMode::Outgoing => write!(f, "outgoing"),
+            UdsConnectionMode::Incoming => write!(f, "incoming"),
+        }
+    }
+}
+
+/// Information about specific UDS sender (corresponds to one specific UDS connection)
+#[derive(Debug, Clone)]
+pub struct UdsSenderInfo {
+    address: Address,
+    receiver_address: Address,
+    socket_address: SocketAddr,
+    mode: UdsConnectionMode,
+    flow_control_id: FlowControlId,
+}
+
+impl UdsSenderInfo {
+    /// Constructor
+    pub fn new(
+        address: Address,
+        receiver_address: Address,
+        socket_address: SocketAddr,
+        mode: UdsConnectionMode,
+        flow_control_id: FlowControlId,
+    ) -> Self {
+        Self {
+            address,
+            receiver_address,
+            socket_address,
+            mode,
+            flow_control_id,
+        }
+    }
+
+    /// Address of the Sender worker
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+    /// Corresponding UDS Receiver Processor Address
+    pub fn receiver_address(&self) -> &Address {
+        &self.receiver_address
+    }
+    /// Corresponding socket address
+    pub fn socket_address(&self) -> &SocketAddr {
+        &self.socket_address
+    }
+    /// Corresponding [`FlowControlId`]
+    pub fn flow_control_id(&self) -> &FlowControlId {
+        &self.flow_control_id
+    }
+    /// [`UdsConnectionMode`] for this connection
+    pub fn mode(&self) -> &UdsConnectionMode {
+        &self.mode
+    }
+}
+
+/// Information about specific UDS receiver (corresponds to one specific UDS connection)
+#[derive(Debug, Clone)]
+pub struct UdsReceiverInfo {
+    address: Address,
+    sender_address: Address,
+    socket_address: SocketAddr,
+    mode: UdsConnectionMode,
+    flow_control_id: FlowControlId,
+}
+
+impl UdsReceiverInfo {
+    /// Constructor
+    pub fn new(
+        address: Address,
+        sender_address: Address,
+        socket_address: SocketAddr,
+        mode: UdsConnectionMode,
+        flow_control_id: FlowControlId,
+    ) -> Self {
+        Self {
+            address,
+            sender_address,
+            socket_address,
+            mode,
+            flow_control_id,
+        }
+    }
+
+    /// Address of the Receiver processor
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+    /// Corresponding Sender Worker Address
+    pub fn sender_address(&self) -> &Address {
+        &self.sender_address
+    }
+    /// Corresponding socket address
+    pub fn socket_address(&self) -> &SocketAddr {
+        &self.socket_address
+    }
+    /// Corresponding [`FlowControlId`]
+    pub fn flow_control_id(&self) -> &FlowControlId {
+        &self.flow_control_id
+    }
+    /// [`UdsConnectionMode`] for this connection
+    pub fn mode(&self) -> &UdsConnectionMode {
+        &self.mode
+    }
+}
+
+/// Information about specific UDS listener
+#[derive(Debug, Clone)]
+pub struct UdsListenerInfo {
+    address: Address,
+    socket_address: SocketAddr,
+    flow_control_id: FlowControlId,
+}
+
+impl UdsListenerInfo {
+    /// Constructor
+    pub fn new(
+        address: Address,
+        socket_address: SocketAddr,
+        flow_control_id: FlowControlId,
+    ) -> Self {
+        Self {
+            address,
+            socket_address,
+            flow_control_id,
+        }
+    }
+
+    /// Address of the Processor
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+    /// Corresponding socket address
+    pub fn socket_address(&self) -> &SocketAddr {
+        &self.socket_address
+    }
+    /// Corresponding [`FlowControlId`]
+    pub fn flow_control_id(&self) -> &FlowControlId {
+        &self.flow_control_id
+    }
+}
@@ -0,0 +1,35 @@
+use crate::{UdsListenerInfo, UdsReceiverInfo, UdsRegistry, UdsSenderInfo};
+use ockam_core::Address;
+
+impl UdsRegistry {
+    pub(crate) fn add_listener_processor(&self, info: UdsListenerInfo) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.add_listener_processor(info);
+        }
+    }
+    pub(crate) fn remove_listener_processor(&self, addr: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.remove_listener_processor(addr);
+        }
+    }
+    pub(crate) fn add_sender_worker(&self, info: UdsSenderInfo) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.add_sender_worker(info);
+        }
+    }
+    pub(crate) fn remove_sender_worker(&self, addr: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.remove_sender_worker(addr);
+        }
+    }
+    pub(crate) fn add_receiver_processor(&self, info: UdsReceiverInfo) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.add_receiver_processor(info);
+        }
+    }
+    pub(crate) fn remove_receiver_processor(&self, addr: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.remove_receiver_processor(addr);
+        }
+    }
+}
@@ -1,7 +0,0 @@
-mod handle;
-mod message;
-mod uds_router;
-
-pub(crate) use handle::*;
-pub(crate) use message::*;
-pub(crate) use uds_router::*;
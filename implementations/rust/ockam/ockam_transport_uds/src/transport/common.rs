@@ -0,0 +1,134 @@
+use crate::UdsConnectionMode;
+use core::fmt;
+use core::fmt::Formatter;
+use ockam_core::flow_control::FlowControlId;
+use ockam_core::Address;
+use std::os::unix::net::SocketAddr;
+
+/// Result of [`UdsTransport::connect`](crate::UdsTransport::connect) call.
+#[derive(Clone, Debug)]
+pub struct UdsConnection {
+    sender_address: Address,
+    receiver_address: Address,
+    socket_address: SocketAddr,
+    mode: UdsConnectionMode,
+    flow_control_id: FlowControlId,
+}
+
+impl fmt::Display for UdsConnection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Socket: {}, Worker: {}, Processor: {}, FlowId: {}",
+            socket_path(&self.socket_address),
+            self.sender_address,
+            self.receiver_address,
+            self.flow_control_id
+        )
+    }
+}
+
+impl From<UdsConnection> for Address {
+    fn from(value: UdsConnection) -> Self {
+        value.sender_address
+    }
+}
+
+impl UdsConnection {
+    /// Constructor
+    pub fn new(
+        sender_address: Address,
+        receiver_address: Address,
+        socket_address: SocketAddr,
+        mode: UdsConnectionMode,
+        flow_control_id: FlowControlId,
+    ) -> Self {
+        Self {
+            sender_address,
+            receiver_address,
+            socket_address,
+            mode,
+            flow_control_id,
+        }
+    }
+    /// Corresponding UDS sender worker [`Address`] that can be used
+    /// in a route to send messages to the other side of the UDS connection
+    pub fn sender_address(&self) -> &Address {
+        &self.sender_address
+    }
+    /// Corresponding UDS receiver processor [`Address`]
+    pub fn receiver_address(&self) -> &Address {
+        &self.receiver_address
+    }
+    /// Corresponding [`SocketAddr`]
+    pub fn socket_address(&self) -> &SocketAddr {
+        &self.socket_address
+    }
+    /// Generated fresh random [`FlowControlId`]
+    pub fn flow_control_id(&self) -> &FlowControlId {
+        &self.flow_control_id
+    }
+    /// Corresponding [`UdsConnectionMode`]
+    pub fn mode(&self) -> UdsConnectionMode {
+        self.mode
+    }
+}
+
+/// Result of [`UdsTransport::listen`](crate::UdsTransport::listen) call.
+#[derive(Clone, Debug)]
+pub struct UdsListener {
+    processor_address: Address,
+    socket_address: SocketAddr,
+    flow_control_id: FlowControlId,
+}
+
+impl fmt::Display for UdsListener {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Socket: {}, Processor: {}, FlowId: {}",
+            socket_path(&self.socket_address),
+            self.processor_address,
+            self.flow_control_id
+        )
+    }
+}
+
+impl UdsListener {
+    /// Constructor
+    pub fn new(
+        processor_address: Address,
+        socket_address: SocketAddr,
+        flow_control_id: FlowControlId,
+    ) -> Self {
+        Self {
+            processor_address,
+            socket_address,
+            flow_control_id,
+        }
+    }
+    /// Corresponding Worker [`Address`] that can be used to stop the Listener
+    pub fn processor_address(&self) -> &Address {
+        &self.processor_address
+    }
+    /// Corresponding [`SocketAddr`]
+    pub fn socket_address(&self) -> &SocketAddr {
+        &self.socket_address
+    }
+    /// Corresponding socket path in String format
+    pub fn socket_string(&self) -> String {
+        socket_path(&self.socket_address)
+    }
+    /// Generated fresh random [`FlowControlId`]
+    pub fn flow_control_id(&self) -> &FlowControlId {
+        &self.flow_control_id
+    }
+}
+
+/// Path of a socket address, or an empty string for an unnamed socket
+pub(crate) fn socket_path(socket_address: &SocketAddr) -> String {
+    socket_address
+        .as_pathname()
+        .map(|p| p.display().to_string())
+        .unwrap_or_default()
+}
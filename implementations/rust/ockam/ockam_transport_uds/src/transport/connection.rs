@@ -0,0 +1,84 @@
+use crate::transport::common::UdsConnection;
+use crate::workers::{Addresses, UdsRecvProcessor, UdsSendWorker};
+use crate::{parse_socket_addr, UdsConnectionMode, UdsConnectionOptions, UdsTransport};
+use ockam_core::{Address, Result};
+
+impl UdsTransport {
+    /// Establish an outgoing UDS connection.
+    ///
+    /// ```rust
+    /// use ockam_transport_uds::{UdsConnectionOptions, UdsTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::Result;
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let uds = UdsTransport::create(&ctx).await?;
+    /// let connection = uds.connect("/tmp/socket-name", UdsConnectionOptions::new()).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn connect(
+        &self,
+        peer: impl AsRef<str>,
+        options: UdsConnectionOptions,
+    ) -> Result<UdsConnection> {
+        let socket = parse_socket_addr(peer.as_ref())?;
+
+        let stream = UdsSendWorker::connect(&socket).await?;
+        let (read_half, write_half) = stream.into_split();
+
+        let mode = UdsConnectionMode::Outgoing;
+        let addresses = Addresses::generate(mode);
+
+        options.setup_flow_control(self.ctx.flow_controls(), &addresses);
+        let flow_control_id = options.flow_control_id.clone();
+        let access_control = options.create_access_control(self.ctx.flow_controls());
+
+        UdsSendWorker::start(
+            &self.ctx,
+            self.registry.clone(),
+            write_half,
+            &addresses,
+            socket.clone(),
+            mode,
+            access_control.sender_incoming_access_control,
+            &flow_control_id,
+        )
+        .await?;
+
+        UdsRecvProcessor::start(
+            &self.ctx,
+            self.registry.clone(),
+            read_half,
+            &addresses,
+            socket.clone(),
+            mode,
+            &flow_control_id,
+            access_control.receiver_outgoing_access_control,
+        )
+        .await?;
+
+        Ok(UdsConnection::new(
+            addresses.sender_address().clone(),
+            addresses.receiver_address().clone(),
+            socket,
+            mode,
+            flow_control_id,
+        ))
+    }
+
+    /// Interrupt an active UDS connection given its Sender `Address`
+    ///
+    /// ```rust
+    /// use ockam_transport_uds::{UdsConnectionOptions, UdsTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::Result;
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let uds = UdsTransport::create(&ctx).await?;
+    /// let connection = uds.connect("/tmp/socket-name", UdsConnectionOptions::new()).await?;
+    ///
+    /// uds.disconnect(connection.sender_address().clone()).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn disconnect(&self, address: impl Into<Address>) -> Result<()> {
+        self.ctx.stop_worker(address.into()).await
+    }
+}
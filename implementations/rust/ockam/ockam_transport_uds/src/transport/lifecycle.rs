@@ -0,0 +1,111 @@
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{async_trait, Address, AsyncTryClone, Error, Result, TransportType};
+use ockam_node::Context;
+use ockam_transport_core::Transport;
+use std::sync::Arc;
+
+use crate::{UdsConnectionOptions, UdsRegistry, UdsTransport, UDS};
+
+impl UdsTransport {
+    /// Create a UDS transport
+    ///
+    /// ```rust
+    /// use ockam_transport_uds::UdsTransport;
+    /// # use ockam_node::Context;
+    /// # use ockam_core::Result;
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let uds = UdsTransport::create(&ctx).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn create(ctx: &Context) -> Result<Self> {
+        let uds = Self {
+            ctx: ctx.async_try_clone().await?,
+            registry: UdsRegistry::default(),
+        };
+        // make the UDS transport available in the list of supported transports for
+        // later address resolution when socket addresses will need to be instantiated as UDS
+        // worker addresses
+        ctx.register_transport(Arc::new(uds.async_try_clone().await?));
+        Ok(uds)
+    }
+}
+
+impl UdsTransport {
+    /// Getter
+    pub fn ctx(&self) -> &Context {
+        &self.ctx
+    }
+    /// Registry of all active connections
+    pub fn registry(&self) -> &UdsRegistry {
+        &self.registry
+    }
+}
+
+#[async_trait]
+impl Transport for UdsTransport {
+    fn transport_type(&self) -> TransportType {
+        UDS
+    }
+
+    async fn resolve_address(&self, address: Address) -> Result<Address> {
+        if address.transport_type() == UDS {
+            Ok(self
+                .connect(address.address().to_string(), UdsConnectionOptions::new())
+                .await?
+                .into())
+        } else {
+            Err(Error::new(
+                Origin::Transport,
+                Kind::NotFound,
+                format!(
+                    "this address can not be resolved by a UDS transport {}",
+                    address
+                ),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UdsListenerOptions;
+
+    #[ockam_macros::test]
+    async fn test_resolve_address(ctx: &mut Context) -> Result<()> {
+        let uds = UdsTransport::create(ctx).await?;
+        let path = std::env::temp_dir().join(format!("{}.sock", Address::random_local().address()));
+        let listener = uds
+            .listen(path.to_str().unwrap(), UdsListenerOptions::new())
+            .await?;
+        let initial_workers = ctx.list_workers().await?;
+
+        let resolved = uds
+            .resolve_address(Address::new(UDS, listener.socket_string()))
+            .await?;
+
+        // the UDS address is replaced with the UDS sender worker address
+        let mut additional_workers = ctx.list_workers().await?;
+        additional_workers.retain(|w| !initial_workers.contains(w));
+        assert!(additional_workers.contains(&resolved));
+
+        // trying to resolve the address a second time should still work
+        let _route = uds
+            .resolve_address(Address::new(UDS, listener.socket_string()))
+            .await?;
+
+        let _ = std::fs::remove_file(path);
+        ctx.stop().await
+    }
+
+    #[ockam_macros::test]
+    async fn test_resolve_address_of_another_transport(ctx: &mut Context) -> Result<()> {
+        let uds = UdsTransport::create(ctx).await?;
+        let result = uds
+            .resolve_address(Address::new(TransportType::new(1), "127.0.0.1:4000"))
+            .await;
+
+        assert!(result.is_err());
+        ctx.stop().await
+    }
+}
@@ -0,0 +1,35 @@
+use crate::transport::common::UdsListener;
+use crate::workers::UdsListenProcessor;
+use crate::{parse_socket_addr, UdsListenerOptions, UdsTransport};
+use ockam_core::{Address, Result};
+
+impl UdsTransport {
+    /// Binds the [`UdsTransport`] to listen and accept incoming connection requests to the given socket.
+    ///
+    /// ```rust
+    /// use ockam_transport_uds::{UdsListenerOptions, UdsTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::Result;
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let uds = UdsTransport::create(&ctx).await?;
+    /// uds.listen("/tmp/socket-name", UdsListenerOptions::new()).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn listen(
+        &self,
+        bind_addr: impl AsRef<str>,
+        options: UdsListenerOptions,
+    ) -> Result<UdsListener> {
+        let flow_control_id = options.flow_control_id.clone();
+        let bind_addr = parse_socket_addr(bind_addr.as_ref())?;
+        let (socket_addr, address) =
+            UdsListenProcessor::start(&self.ctx, self.registry.clone(), bind_addr, options).await?;
+
+        Ok(UdsListener::new(address, socket_addr, flow_control_id))
+    }
+
+    /// Interrupt an active UDS listener given its `Address`
+    pub async fn stop_listener(&self, address: &Address) -> Result<()> {
+        self.ctx.stop_processor(address.clone()).await
+    }
+}
@@ -0,0 +1,67 @@
+pub(crate) mod common;
+mod connection;
+mod lifecycle;
+mod listener;
+
+pub use common::*;
+
+use crate::UdsRegistry;
+use ockam_core::{async_trait, AsyncTryClone, Result};
+use ockam_node::{Context, HasContext};
+
+/// High level management interface for UDS transports
+///
+/// Be aware that only one [`UdsTransport`] can exist per node, as it
+/// registers itself as the transport for the [`UDS`](crate::UDS) address type.  Multiple
+/// calls to [`UdsTransport::create`](crate::UdsTransport::create)
+/// will fail.
+///
+/// To listen for incoming connections use
+/// [`uds.listen()`](crate::UdsTransport::listen).
+///
+/// To register additional connections on an already initialised
+/// `UdsTransport`, use [`uds.connect()`](crate::UdsTransport::connect).
+/// This step is optional because routes containing `UDS` addresses are
+/// resolved to new connections by the node.
+///
+/// ```rust
+/// use ockam_transport_uds::{UdsConnectionOptions, UdsListenerOptions, UdsTransport};
+/// # use ockam_node::Context;
+/// # use ockam_core::Result;
+/// # async fn test(ctx: Context) -> Result<()> {
+/// let uds = UdsTransport::create(&ctx).await?;
+/// uds.listen("/tmp/example-socket", UdsListenerOptions::new()).await?; // Listen on socket `/tmp/example-socket`
+/// uds.connect("/tmp/other-socket", UdsConnectionOptions::new()).await?; // And connect to `/tmp/other-socket`
+/// # Ok(()) }
+/// ```
+///
+/// The same `UdsTransport` can also bind to multiple sockets.
+///
+/// ```rust
+/// use ockam_transport_uds::{UdsListenerOptions, UdsTransport};
+/// # use ockam_node::Context;
+/// # use ockam_core::Result;
+/// # async fn test(ctx: Context) -> Result<()> {
+/// let uds = UdsTransport::create(&ctx).await?;
+/// uds.listen("/tmp/socket-one", UdsListenerOptions::new()).await?; // Listen on `/tmp/socket-one`
+/// uds.listen("/tmp/socket-two", UdsListenerOptions::new()).await?; // Listen on `/tmp/socket-two`
+/// # Ok(()) }
+/// ```
+#[derive(AsyncTryClone)]
+#[async_try_clone(crate = "ockam_core")]
+pub struct UdsTransport {
+    ctx: Context,
+    registry: UdsRegistry,
+}
+
+/// This trait adds a `create_uds_transport` method to any struct returning a Context.
+/// This is the case for an ockam::Node, so you can write `node.create_uds_transport()`
+#[async_trait]
+pub trait UdsTransportExtension: HasContext {
+    /// Create a UDS transport
+    async fn create_uds_transport(&self) -> Result<UdsTransport> {
+        UdsTransport::create(self.get_context()).await
+    }
+}
+
+impl<A: HasContext> UdsTransportExtension for A {}
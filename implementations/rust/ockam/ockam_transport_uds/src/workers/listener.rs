@@ -1,24 +1,25 @@
 use std::os::unix::net::SocketAddr;
 
-use ockam_core::{
-    async_trait, compat::sync::Arc, Address, AllowSourceAddress, AsyncTryClone, DenyAll, Mailbox,
-    Mailboxes, Processor, Result,
-};
-
-use ockam_node::{Context, WorkerBuilder};
+use ockam_core::{async_trait, Address, Processor, Result};
+use ockam_node::Context;
 use ockam_transport_core::TransportError;
 use tokio::net::UnixListener;
-use tracing::{debug, error, trace};
+use tracing::{debug, error};
 
-use crate::{router::UdsRouterHandle, std_socket_addr_from_tokio, workers::UdsSendWorker};
+use crate::workers::{Addresses, UdsRecvProcessor, UdsSendWorker};
+use crate::{
+    std_socket_addr_from_tokio, UdsConnectionMode, UdsListenerInfo, UdsListenerOptions, UdsRegistry,
+};
 
 /// A UDS Listener Processor
 ///
 /// UDS Listen processors are created by `UdsTransport`
-/// after a call is made to [`UdsTransport::listen`](crate::transport::UdsTransport)
+/// after a call is made to [`UdsTransport::listen`](crate::UdsTransport::listen)
 pub(crate) struct UdsListenProcessor {
+    registry: UdsRegistry,
     inner: UnixListener,
-    router_handle: UdsRouterHandle,
+    socket_address: SocketAddr,
+    options: UdsListenerOptions,
 }
 
 impl UdsListenProcessor {
@@ -27,9 +28,10 @@ impl UdsListenProcessor {
     /// Starts a [`Processor`] which listens for incoming connections to accept.
     pub(crate) async fn start(
         ctx: &Context,
-        router_handle: UdsRouterHandle,
+        registry: UdsRegistry,
         addr: SocketAddr,
-    ) -> Result<SocketAddr> {
+        options: UdsListenerOptions,
+    ) -> Result<(SocketAddr, Address)> {
         let path = match addr.as_pathname() {
             Some(p) => p,
             None => {
@@ -44,15 +46,19 @@ impl UdsListenProcessor {
 
         let std_sock_addr = std_socket_addr_from_tokio(&tokio_sock_addr)?;
 
+        let address = Address::random_tagged("UdsListenProcessor");
+        options.setup_flow_control_for_listener(ctx.flow_controls(), &address);
+
         let processor = Self {
+            registry,
             inner,
-            router_handle,
+            socket_address: std_sock_addr.clone(),
+            options,
         };
 
-        ctx.start_processor(Address::random_tagged("UdsListenProcessor"), processor)
-            .await?;
+        ctx.start_processor(address.clone(), processor).await?;
 
-        Ok(std_sock_addr)
+        Ok((std_sock_addr, address))
     }
 }
 
@@ -61,12 +67,26 @@ impl Processor for UdsListenProcessor {
     type Context = Context;
 
     async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
-        ctx.set_cluster(crate::CLUSTER_NAME).await
+        ctx.set_cluster(crate::CLUSTER_NAME).await?;
+
+        self.registry.add_listener_processor(UdsListenerInfo::new(
+            ctx.address(),
+            self.socket_address.clone(),
+            self.options.flow_control_id.clone(),
+        ));
+
+        Ok(())
+    }
+
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        self.registry.remove_listener_processor(&ctx.address());
+
+        Ok(())
     }
 
     /// Listen for and accept incoming UDS connections.
     ///
-    /// Register the peers socket address, and create a worker to communicate with the peer.
+    /// Create a worker pair to communicate with the peer.
     async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
         debug!("Waiting for incoming UDS connection...");
 
@@ -74,39 +94,46 @@ impl Processor for UdsListenProcessor {
         let (stream, _peer) = self.inner.accept().await.map_err(TransportError::from)?;
         debug!("UDS connection accepted");
 
-        // Create a connection working
-        let handle_clone = self.router_handle.async_try_clone().await?;
+        // The peer of an accepted connection is usually unnamed, use the listener socket instead
         let local_addr = stream.local_addr().map_err(TransportError::from)?;
-        let std_sock_addr = std_socket_addr_from_tokio(&local_addr)?;
-        let (send_worker, pair) =
-            UdsSendWorker::new_pair(handle_clone, Some(stream), std_sock_addr, vec![]).await?;
-
-        self.router_handle.register(&pair).await?;
-        debug!("UDS connection registered");
-
-        trace! {
-            tx_addr = %pair.tx_addr(),
-            int_addr = %send_worker.internal_addr(),
-            "starting UDS connection worker"
-        };
-
-        let tx_mailbox = Mailbox::new(
-            pair.tx_addr(),
-            Arc::new(AllowSourceAddress(self.router_handle.main_addr().clone())),
-            Arc::new(DenyAll),
-        );
-
-        let internal_mailbox = Mailbox::new(
-            send_worker.internal_addr().clone(),
-            Arc::new(AllowSourceAddress(send_worker.rx_addr().clone())),
-            Arc::new(DenyAll),
-        );
-
-        let mailboxes = Mailboxes::new(tx_mailbox, vec![internal_mailbox]);
-        WorkerBuilder::new(send_worker)
-            .with_mailboxes(mailboxes)
-            .start(ctx)
-            .await?;
+        let socket_address = std_socket_addr_from_tokio(&local_addr)?;
+        let (read_half, write_half) = stream.into_split();
+
+        let mode = UdsConnectionMode::Incoming;
+        let addresses = Addresses::generate(mode);
+
+        let receiver_flow_control_id = self
+            .options
+            .setup_flow_control_for_connection(ctx.flow_controls(), &addresses);
+        let access_control = self
+            .options
+            .create_access_control(ctx.flow_controls(), receiver_flow_control_id.clone());
+
+        // Worker to receive messages from the Node and send them over the wire
+        UdsSendWorker::start(
+            ctx,
+            self.registry.clone(),
+            write_half,
+            &addresses,
+            socket_address.clone(),
+            mode,
+            access_control.sender_incoming_access_control,
+            &receiver_flow_control_id,
+        )
+        .await?;
+
+        // Processor to receive messages over the wire and forward them to the node
+        UdsRecvProcessor::start(
+            ctx,
+            self.registry.clone(),
+            read_half,
+            &addresses,
+            socket_address,
+            mode,
+            &receiver_flow_control_id,
+            access_control.receiver_outgoing_access_control,
+        )
+        .await?;
 
         Ok(true)
     }
@@ -1,7 +1,9 @@
+mod addresses;
 mod listener;
 mod receiver;
 mod sender;
 
+pub(crate) use addresses::*;
 pub(crate) use listener::*;
 pub(crate) use receiver::*;
 pub(crate) use sender::*;
@@ -1,34 +1,73 @@
-use crate::workers::UdsSendWorkerMsg;
+use std::os::unix::net::SocketAddr;
 
+use crate::transport::common::socket_path;
+use crate::workers::{Addresses, UdsSendWorkerMsg};
+use crate::{UdsConnectionMode, UdsReceiverInfo, UdsRegistry};
+
+use ockam_core::flow_control::FlowControlId;
 use ockam_core::{
-    async_trait, Address, Decodable, LocalMessage, Processor, Result, TransportMessage,
+    async_trait, compat::sync::Arc, AllowOnwardAddress, Decodable, DenyAll, LocalMessage, Mailbox,
+    Mailboxes, OutgoingAccessControl, Processor, Result, TransportMessage,
 };
-use ockam_node::Context;
+use ockam_node::{Context, ProcessorBuilder};
 use ockam_transport_core::TransportError;
 use tokio::{io::AsyncReadExt, net::unix::OwnedReadHalf};
 use tracing::{error, info, trace};
 
 /// A UDS receiving message processor
 ///
-/// Create this processor type by calling
-/// [`UdsSendWorker::start_pair`](crate::workers::UdsSendWorker)
-///
 /// This half of the worker is created when spawning a new connection
 /// worker pair, and listens for UDS packets which are relayed into
 /// the node messaging system.
 pub(crate) struct UdsRecvProcessor {
-    rx: OwnedReadHalf,
-    peer_addr: Address,
-    sender_internal_address: Address,
+    registry: UdsRegistry,
+    read_half: OwnedReadHalf,
+    socket_address: SocketAddr,
+    addresses: Addresses,
+    mode: UdsConnectionMode,
+    flow_control_id: FlowControlId,
 }
 
 impl UdsRecvProcessor {
-    pub fn new(rx: OwnedReadHalf, peer_addr: Address, sender_internal_address: Address) -> Self {
-        Self {
-            rx,
-            peer_addr,
-            sender_internal_address,
-        }
+    /// Start a [`UdsRecvProcessor`] reading from an established connection
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn start(
+        ctx: &Context,
+        registry: UdsRegistry,
+        read_half: OwnedReadHalf,
+        addresses: &Addresses,
+        socket_address: SocketAddr,
+        mode: UdsConnectionMode,
+        flow_control_id: &FlowControlId,
+        receiver_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    ) -> Result<()> {
+        let receiver = Self {
+            registry,
+            read_half,
+            socket_address,
+            addresses: addresses.clone(),
+            mode,
+            flow_control_id: flow_control_id.clone(),
+        };
+
+        let mailbox = Mailbox::new(
+            addresses.receiver_address().clone(),
+            Arc::new(DenyAll),
+            receiver_outgoing_access_control,
+        );
+        let internal = Mailbox::new(
+            addresses.receiver_internal_address().clone(),
+            Arc::new(DenyAll),
+            Arc::new(AllowOnwardAddress(
+                addresses.sender_internal_address().clone(),
+            )),
+        );
+        ProcessorBuilder::new(receiver)
+            .with_mailboxes(Mailboxes::new(mailbox, vec![internal]))
+            .start(ctx)
+            .await?;
+
+        Ok(())
     }
 }
 
@@ -37,26 +76,42 @@ impl Processor for UdsRecvProcessor {
     type Context = Context;
 
     async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
-        ctx.set_cluster(crate::CLUSTER_NAME).await
+        ctx.set_cluster(crate::CLUSTER_NAME).await?;
+
+        self.registry.add_receiver_processor(UdsReceiverInfo::new(
+            ctx.address(),
+            self.addresses.sender_address().clone(),
+            self.socket_address.clone(),
+            self.mode,
+            self.flow_control_id.clone(),
+        ));
+
+        Ok(())
+    }
+
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        self.registry.remove_receiver_processor(&ctx.address());
+
+        Ok(())
     }
 
     /// Get the next message from the connection if there are any
     /// available and forward it to the next hop in the route.
     async fn process(&mut self, ctx: &mut Context) -> Result<bool> {
-        // Run in a loop until UdsWorkerPair::stop() is called
         // First read a message length header...
-        let len = match self.rx.read_u16().await {
+        let len = match self.read_half.read_u16().await {
             Ok(len) => len,
             Err(_e) => {
                 info!(
                     "Connection to peer '{}' was closed; dropping stream",
-                    self.peer_addr
+                    socket_path(&self.socket_address)
                 );
 
                 // Notify sender tx is closed
-                ctx.send(
-                    self.sender_internal_address.clone(),
+                ctx.send_from_address(
+                    self.addresses.sender_internal_address().clone(),
                     UdsSendWorkerMsg::ConnectionClosed,
+                    self.addresses.receiver_internal_address().clone(),
                 )
                 .await?;
 
@@ -70,7 +125,7 @@ impl Processor for UdsRecvProcessor {
         let mut buf = vec![0; len as usize];
 
         // Then read into the buffer
-        match self.rx.read_exact(&mut buf).await {
+        match self.read_half.read_exact(&mut buf).await {
             Ok(_) => {}
             _ => {
                 error!("Failed to receive message of length: {}", len);
@@ -83,19 +138,28 @@ impl Processor for UdsRecvProcessor {
 
         // Heartbeat message
         if msg.onward_route.next().is_err() {
-            trace!("Got heartbeat message from: {}", self.peer_addr);
+            trace!(
+                "Got heartbeat message from: {}",
+                socket_path(&self.socket_address)
+            );
             return Ok(true);
         }
 
         // Insert the peer address into the return route so that
         // reply routing can be properly resolved
-        msg.return_route.modify().prepend(self.peer_addr.clone());
+        msg.return_route
+            .modify()
+            .prepend(self.addresses.sender_address().clone());
 
         trace!("Message onward route: {}", msg.onward_route);
         trace!("Message return route: {}", msg.return_route);
 
         // Forward the message to the next hop in the route
-        ctx.forward(LocalMessage::new(msg, vec![])).await?;
+        ctx.forward_from_address(
+            LocalMessage::new(msg, vec![]),
+            self.addresses.receiver_address().clone(),
+        )
+        .await?;
 
         Ok(true)
     }
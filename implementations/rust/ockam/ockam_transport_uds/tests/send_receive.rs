@@ -0,0 +1,95 @@
+use core::time::Duration;
+use ockam_core::{route, Address, Result, Routed, Worker};
+use ockam_node::Context;
+use ockam_transport_uds::{UdsConnectionOptions, UdsListenerOptions, UdsTransport, UDS};
+use std::path::PathBuf;
+
+fn socket_path() -> PathBuf {
+    std::env::temp_dir().join(format!("{}.sock", Address::random_local().address()))
+}
+
+#[ockam_macros::test]
+async fn send_receive(ctx: &mut Context) -> Result<()> {
+    let options = UdsListenerOptions::new();
+    ctx.flow_controls()
+        .add_consumer("echoer", &options.spawner_flow_control_id());
+    ctx.start_worker("echoer", Echoer).await?;
+
+    let path = socket_path();
+    let transport = UdsTransport::create(ctx).await?;
+    let listener = transport.listen(path.to_str().unwrap(), options).await?;
+
+    let connection = transport
+        .connect(listener.socket_string(), UdsConnectionOptions::new())
+        .await?;
+    let msg = "Hello over UDS".to_string();
+    let reply = ctx
+        .send_and_receive::<String>(route![connection, "echoer"], msg.clone())
+        .await?;
+    assert_eq!(reply, msg, "Should receive the same message");
+
+    // Routes with UDS addresses are resolved to new connections
+    let r = ctx
+        .resolve_transport_route(route![(UDS, listener.socket_string()), "echoer"])
+        .await?;
+    let reply = ctx.send_and_receive::<String>(r, msg.clone()).await?;
+    assert_eq!(reply, msg, "Should receive the same message");
+
+    let _ = std::fs::remove_file(path);
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn disconnect_and_stop_listener(ctx: &mut Context) -> Result<()> {
+    let path = socket_path();
+    let transport = UdsTransport::create(ctx).await?;
+    let listener = transport
+        .listen(path.to_str().unwrap(), UdsListenerOptions::new())
+        .await?;
+    let connection = transport
+        .connect(listener.socket_string(), UdsConnectionOptions::new())
+        .await?;
+    ctx.sleep(Duration::from_millis(250)).await;
+
+    assert_eq!(transport.registry().get_all_listeners().len(), 1);
+    // One sender for the outgoing connection and one for the accepted connection
+    assert_eq!(transport.registry().get_all_sender_workers().len(), 2);
+
+    transport
+        .disconnect(connection.sender_address().clone())
+        .await?;
+    transport
+        .stop_listener(listener.processor_address())
+        .await?;
+    ctx.sleep(Duration::from_millis(250)).await;
+
+    assert!(transport.registry().get_all_listeners().is_empty());
+    assert!(transport.registry().get_all_sender_workers().is_empty());
+    assert!(transport
+        .registry()
+        .get_all_receiver_processors()
+        .is_empty());
+
+    let _ = std::fs::remove_file(path);
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+pub struct Echoer;
+
+#[ockam_core::worker]
+impl Worker for Echoer {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        ctx.send(msg.return_route(), msg.body()).await
+    }
+}
@@ -23,18 +23,21 @@
 //!
 //! // Now we can write the main function that will run the previous worker. In this case, our worker will be listening for new connections on port 8000 until the process is manually killed.
 //!
-//! use ockam_transport_websocket::WebSocketTransport;
+//! use ockam_transport_websocket::{WebSocketListenerOptions, WebSocketTransport};
 //! use ockam_node::NodeBuilder;
 //! use ockam_macros::node;
 //!
 //! #[ockam_macros::node(crate = "ockam_node")]
 //! async fn main(mut ctx: Context) -> Result<()> {//!
 //!     let ws = WebSocketTransport::create(&ctx).await?;
-//!     ws.listen("localhost:8000").await?; // Listen on port 8000
+//!     let listener = ws.listen("127.0.0.1:8000", WebSocketListenerOptions::new()).await?; // Listen on port 8000
 //!
 //!     // Start a worker, of type MyWorker, at address "my_worker"
 //!     ctx.start_worker("my_worker", MyWorker).await?;
 //!
+//!     // Allow access to the worker via WebSocket connections from the listener
+//!     ctx.flow_controls().add_consumer("my_worker", listener.flow_control_id());
+//!
 //!     // Run worker indefinitely in the background
 //!     Ok(())
 //! }
@@ -43,7 +46,7 @@
 //! Finally, we can write another node that connects to the node that is hosting the `MyWorker` worker, and we are ready to send and receive messages between them.
 //!
 //! ```rust,no_run
-//! use ockam_transport_websocket::{WebSocketTransport, WS};
+//! use ockam_transport_websocket::{WebSocketConnectionOptions, WebSocketTransport};
 //! use ockam_core::{route, Result};
 //! use ockam_node::Context;
 //! use ockam_macros::node;
@@ -53,8 +56,9 @@
 //!     use ockam_node::MessageReceiveOptions;
 //! let ws = WebSocketTransport::create(&ctx).await?;
 //!
-//!     // Define the route to the server's worker.
-//!     let r = route![(WS, "localhost:8000"), "my_worker"];
+//!     // Connect to the server and define the route to the server's worker.
+//!     let connection = ws.connect("localhost:8000", WebSocketConnectionOptions::new()).await?;
+//!     let r = route![connection, "my_worker"];
 //!
 //!     // Now you can send messages to the worker.
 //!     ctx.send(r, "Hello Ockam!".to_string()).await?;
@@ -85,26 +89,25 @@ extern crate core;
 #[macro_use]
 extern crate tracing;
 
-use std::net::SocketAddr;
-
-use ockam_core::{Result, TransportType};
-use ockam_transport_core::TransportError;
+use ockam_core::TransportType;
+pub use options::{WebSocketConnectionOptions, WebSocketListenerOptions};
+pub use proxy::HttpProxy;
+pub use registry::*;
+#[cfg(feature = "tls")]
+pub use tls::{TlsClientOptions, TlsServerOptions};
 pub use transport::*;
 
-use crate::router::{WebSocketRouter, WebSocketRouterHandle};
-
 mod error;
-mod router;
+mod options;
+mod proxy;
+mod registry;
+#[cfg(feature = "tls")]
+mod tls;
 mod transport;
+mod url;
 mod workers;
 
 /// WebSocket address type constant.
 pub const WS: TransportType = TransportType::new(3);
 
 pub(crate) const CLUSTER_NAME: &str = "_internals.transport.ws";
-
-fn parse_socket_addr<S: AsRef<str>>(s: S) -> Result<SocketAddr> {
-    Ok(s.as_ref()
-        .parse()
-        .map_err(|_| TransportError::InvalidAddress)?)
-}
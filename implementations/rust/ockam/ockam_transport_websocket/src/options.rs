@@ -0,0 +1,297 @@
+#[cfg(feature = "tls")]
+use crate::tls::{TlsClientOptions, TlsServerOptions};
+use crate::url::WebSocketUrl;
+use crate::workers::{Addresses, ConnectionStream};
+use crate::HttpProxy;
+use ockam_core::compat::sync::Arc;
+#[cfg(not(feature = "tls"))]
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl, FlowControls};
+#[cfg(not(feature = "tls"))]
+use ockam_core::Error;
+use ockam_core::{Address, AllowAll, IncomingAccessControl, OutgoingAccessControl, Result};
+use tokio::net::TcpStream;
+
+pub(crate) struct WebSocketConnectionAccessControl {
+    pub sender_incoming_access_control: Arc<dyn IncomingAccessControl>,
+    pub receiver_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+}
+
+/// Trust Options for a WebSocket connection
+#[derive(Debug)]
+pub struct WebSocketConnectionOptions {
+    pub(super) consumer: Vec<FlowControlId>,
+    pub(crate) flow_control_id: FlowControlId,
+    pub(crate) origin: Option<String>,
+    pub(crate) proxy: Option<HttpProxy>,
+    #[cfg(feature = "tls")]
+    pub(crate) tls: Option<TlsClientOptions>,
+}
+
+impl WebSocketConnectionOptions {
+    #[allow(clippy::new_without_default)]
+    /// Mark this WebSocket Receiver as a Producer with a random [`FlowControlId`]
+    pub fn new() -> Self {
+        Self {
+            consumer: vec![],
+            flow_control_id: FlowControls::generate_flow_control_id(),
+            origin: None,
+            proxy: None,
+            #[cfg(feature = "tls")]
+            tls: None,
+        }
+    }
+
+    /// Mark that this Connection is a Consumer for to the given [`FlowControlId`]
+    pub fn as_consumer(mut self, id: &FlowControlId) -> Self {
+        self.consumer.push(id.clone());
+
+        self
+    }
+
+    /// Getter for freshly generated [`FlowControlId`]
+    pub fn flow_control_id(&self) -> FlowControlId {
+        self.flow_control_id.clone()
+    }
+
+    /// Send this `Origin` header with the opening handshake,
+    /// e.g. `https://app.example.com`
+    pub fn with_origin(mut self, origin: impl Into<String>) -> Self {
+        self.origin = Some(origin.into());
+
+        self
+    }
+
+    /// Tunnel the connection through an HTTP proxy
+    pub fn with_proxy(mut self, proxy: HttpProxy) -> Self {
+        self.proxy = Some(proxy);
+
+        self
+    }
+
+    /// Use a secure (`wss://`) connection authenticated with these TLS options.
+    /// `wss://` peers are otherwise authenticated with the platform root certificates
+    #[cfg(feature = "tls")]
+    pub fn with_tls(mut self, tls: TlsClientOptions) -> Self {
+        self.tls = Some(tls);
+
+        self
+    }
+}
+
+impl WebSocketConnectionOptions {
+    /// Parse the peer to connect to. Peers are secure when TLS options are set
+    pub(crate) fn url(&self, peer: &str) -> Result<WebSocketUrl> {
+        let url = WebSocketUrl::parse(peer)?;
+
+        #[cfg(feature = "tls")]
+        if self.tls.is_some() {
+            return Ok(url.into_secure());
+        }
+
+        Ok(url)
+    }
+
+    /// Perform a TLS handshake on the connected stream for `wss://` peers
+    pub(crate) async fn secure_stream(
+        &self,
+        url: &WebSocketUrl,
+        stream: TcpStream,
+    ) -> Result<ConnectionStream> {
+        if url.is_secure() {
+            #[cfg(feature = "tls")]
+            return match &self.tls {
+                Some(tls) => tls.connect(url.host(), stream).await,
+                None => {
+                    TlsClientOptions::new()
+                        .with_native_roots()?
+                        .connect(url.host(), stream)
+                        .await
+                }
+            };
+
+            #[cfg(not(feature = "tls"))]
+            return Err(Error::new(
+                Origin::Transport,
+                Kind::Unsupported,
+                format!("connecting to {url} requires the \"tls\" feature"),
+            ));
+        }
+
+        Ok(Box::new(stream))
+    }
+
+    pub(crate) fn setup_flow_control(&self, flow_controls: &FlowControls, addresses: &Addresses) {
+        flow_controls.add_producer(
+            addresses.receiver_address().clone(),
+            &self.flow_control_id,
+            None,
+            vec![addresses.sender_address().clone()],
+        );
+
+        for id in &self.consumer {
+            flow_controls.add_consumer(addresses.sender_address().clone(), id);
+        }
+    }
+
+    pub(crate) fn create_access_control(
+        self,
+        flow_controls: &FlowControls,
+    ) -> WebSocketConnectionAccessControl {
+        WebSocketConnectionAccessControl {
+            sender_incoming_access_control: Arc::new(AllowAll),
+            receiver_outgoing_access_control: Arc::new(FlowControlOutgoingAccessControl::new(
+                flow_controls,
+                self.flow_control_id,
+                None,
+            )),
+        }
+    }
+}
+
+/// Trust Options for a WebSocket listener
+#[derive(Debug)]
+pub struct WebSocketListenerOptions {
+    pub(crate) flow_control_id: FlowControlId,
+    pub(crate) path_prefix: Option<String>,
+    #[cfg(feature = "tls")]
+    pub(crate) tls: Option<TlsServerOptions>,
+}
+
+impl WebSocketListenerOptions {
+    /// Mark this WebSocket Listener as a Spawner with given [`FlowControlId`].
+    /// NOTE: Spawned connections get fresh random [`FlowControlId`], however they are still marked
+    /// with Spawner's [`FlowControlId`]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            flow_control_id: FlowControls::generate_flow_control_id(),
+            path_prefix: None,
+            #[cfg(feature = "tls")]
+            tls: None,
+        }
+    }
+
+    /// Getter for freshly generated [`FlowControlId`]
+    pub fn spawner_flow_control_id(&self) -> FlowControlId {
+        self.flow_control_id.clone()
+    }
+
+    /// Only accept WebSocket handshakes for paths under this prefix, e.g. `/ockam`.
+    /// Other requests are answered with `404 Not Found`, which lets the listener
+    /// sit behind an ingress routing other paths to other services
+    pub fn with_path_prefix(mut self, prefix: impl Into<String>) -> Self {
+        let prefix = prefix.into();
+        let prefix = prefix.trim_end_matches('/');
+        self.path_prefix = if prefix.is_empty() {
+            None
+        } else if prefix.starts_with('/') {
+            Some(prefix.to_string())
+        } else {
+            Some(format!("/{prefix}"))
+        };
+
+        self
+    }
+
+    /// Accept secure (`wss://`) connections only, with these TLS options
+    #[cfg(feature = "tls")]
+    pub fn with_tls(mut self, tls: TlsServerOptions) -> Self {
+        self.tls = Some(tls);
+
+        self
+    }
+}
+
+impl WebSocketListenerOptions {
+    /// Perform a TLS handshake on an accepted stream if the listener is secure
+    #[allow(unused_variables)]
+    pub(crate) async fn secure_stream(
+        &self,
+        peer: &str,
+        stream: TcpStream,
+    ) -> Result<ConnectionStream> {
+        #[cfg(feature = "tls")]
+        if let Some(tls) = &self.tls {
+            return tls.accept(peer, stream).await;
+        }
+
+        Ok(Box::new(stream))
+    }
+
+    /// Return true if a handshake request for this path can be accepted
+    pub(crate) fn accepts_path(&self, path: &str) -> bool {
+        match &self.path_prefix {
+            Some(prefix) => match path.strip_prefix(prefix.as_str()) {
+                Some(rest) => rest.is_empty() || rest.starts_with('/'),
+                None => false,
+            },
+            None => true,
+        }
+    }
+
+    pub(crate) fn setup_flow_control_for_listener(
+        &self,
+        flow_controls: &FlowControls,
+        address: &Address,
+    ) {
+        flow_controls.add_spawner(address.clone(), &self.flow_control_id);
+    }
+
+    pub(crate) fn setup_flow_control_for_connection(
+        &self,
+        flow_controls: &FlowControls,
+        addresses: &Addresses,
+    ) -> FlowControlId {
+        let flow_control_id = FlowControls::generate_flow_control_id();
+
+        flow_controls.add_producer(
+            addresses.receiver_address().clone(),
+            &flow_control_id,
+            Some(&self.flow_control_id),
+            vec![addresses.sender_address().clone()],
+        );
+
+        flow_control_id
+    }
+
+    pub(crate) fn create_access_control(
+        &self,
+        flow_controls: &FlowControls,
+        flow_control_id: FlowControlId,
+    ) -> WebSocketConnectionAccessControl {
+        WebSocketConnectionAccessControl {
+            sender_incoming_access_control: Arc::new(AllowAll),
+            receiver_outgoing_access_control: Arc::new(FlowControlOutgoingAccessControl::new(
+                flow_controls,
+                flow_control_id,
+                Some(self.flow_control_id.clone()),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WebSocketListenerOptions;
+
+    #[test]
+    fn test_accepts_path() {
+        let options = WebSocketListenerOptions::new();
+        assert!(options.accepts_path("/"));
+        assert!(options.accepts_path("/anything"));
+
+        for prefix in ["/ockam", "ockam", "/ockam/"] {
+            let options = WebSocketListenerOptions::new().with_path_prefix(prefix);
+            assert!(options.accepts_path("/ockam"));
+            assert!(options.accepts_path("/ockam/"));
+            assert!(options.accepts_path("/ockam/node"));
+            assert!(!options.accepts_path("/"));
+            assert!(!options.accepts_path("/ockamx"));
+            assert!(!options.accepts_path("/other/ockam"));
+        }
+
+        let options = WebSocketListenerOptions::new().with_path_prefix("/");
+        assert!(options.accepts_path("/anything"));
+    }
+}
@@ -0,0 +1,159 @@
+use core::fmt;
+use core::fmt::Formatter;
+use ockam_core::flow_control::FlowControlId;
+use ockam_core::Address;
+use std::net::SocketAddr;
+
+/// WebSocket connection mode
+#[derive(Copy, Debug, Clone)]
+pub enum WebSocketConnectionMode {
+    /// Connection was initiated from our node
+    Outgoing,
+    /// Connection was accepted from a WebSocket listener
+    Incoming,
+}
+
+impl fmt::Display for WebSocketConnectionMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            WebSocketConnectionMode::Outgoing => write!(f, "outgoing"),
+            WebSocketConnectionMode::Incoming => write!(f, "incoming"),
+        }
+    }
+}
+
+/// Information about specific WebSocket sender (corresponds to one specific WebSocket connection)
+#[derive(Debug, Clone)]
+pub struct WebSocketSenderInfo {
+    address: Address,
+    receiver_address: Address,
+    socket_address: SocketAddr,
+    mode: WebSocketConnectionMode,
+    flow_control_id: FlowControlId,
+}
+
+impl WebSocketSenderInfo {
+    /// Constructor
+    pub fn new(
+        address: Address,
+        receiver_address: Address,
+        socket_address: SocketAddr,
+        mode: WebSocketConnectionMode,
+        flow_control_id: FlowControlId,
+    ) -> Self {
+        Self {
+            address,
+            receiver_address,
+            socket_address,
+            mode,
+            flow_control_id,
+        }
+    }
+
+    /// Address of the Sender worker
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+    /// Corresponding WebSocket Receiver Processor Address
+    pub fn receiver_address(&self) -> &Address {
+        &self.receiver_address
+    }
+    /// Corresponding socket address
+    pub fn socket_address(&self) -> SocketAddr {
+        self.socket_address
+    }
+    /// Corresponding [`FlowControlId`]
+    pub fn flow_control_id(&self) -> &FlowControlId {
+        &self.flow_control_id
+    }
+    /// [`WebSocketConnectionMode`] for this connection
+    pub fn mode(&self) -> &WebSocketConnectionMode {
+        &self.mode
+    }
+}
+
+/// Information about specific WebSocket receiver (corresponds to one specific WebSocket connection)
+#[derive(Debug, Clone)]
+pub struct WebSocketReceiverInfo {
+    address: Address,
+    sender_address: Address,
+    socket_address: SocketAddr,
+    mode: WebSocketConnectionMode,
+    flow_control_id: FlowControlId,
+}
+
+impl WebSocketReceiverInfo {
+    /// Constructor
+    pub fn new(
+        address: Address,
+        sender_address: Address,
+        socket_address: SocketAddr,
+        mode: WebSocketConnectionMode,
+        flow_control_id: FlowControlId,
+    ) -> Self {
+        Self {
+            address,
+            sender_address,
+            socket_address,
+            mode,
+            flow_control_id,
+        }
+    }
+
+    /// Address of the Receiver processor
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+    /// Corresponding Sender Worker Address
+    pub fn sender_address(&self) -> &Address {
+        &self.sender_address
+    }
+    /// Corresponding socket address
+    pub fn socket_address(&self) -> SocketAddr {
+        self.socket_address
+    }
+    /// Corresponding [`FlowControlId`]
+    pub fn flow_control_id(&self) -> &FlowControlId {
+        &self.flow_control_id
+    }
+    /// [`WebSocketConnectionMode`] for this connection
+    pub fn mode(&self) -> &WebSocketConnectionMode {
+        &self.mode
+    }
+}
+
+/// Information about specific WebSocket listener
+#[derive(Debug, Clone)]
+pub struct WebSocketListenerInfo {
+    address: Address,
+    socket_address: SocketAddr,
+    flow_control_id: FlowControlId,
+}
+
+impl WebSocketListenerInfo {
+    /// Constructor
+    pub fn new(
+        address: Address,
+        socket_address: SocketAddr,
+        flow_control_id: FlowControlId,
+    ) -> Self {
+        Self {
+            address,
+            socket_address,
+            flow_control_id,
+        }
+    }
+
+    /// Address of the Processor
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+    /// Corresponding socket address
+    pub fn socket_address(&self) -> SocketAddr {
+        self.socket_address
+    }
+    /// Corresponding [`FlowControlId`]
+    pub fn flow_control_id(&self) -> &FlowControlId {
+        &self.flow_control_id
+    }
+}
@@ -0,0 +1,35 @@
+use crate::{WebSocketListenerInfo, WebSocketReceiverInfo, WebSocketRegistry, WebSocketSenderInfo};
+use ockam_core::Address;
+
+impl WebSocketRegistry {
+    pub(crate) fn add_listener_processor(&self, info: WebSocketListenerInfo) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.add_listener_processor(info);
+        }
+    }
+    pub(crate) fn remove_listener_processor(&self, addr: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.remove_listener_processor(addr);
+        }
+    }
+    pub(crate) fn add_sender_worker(&self, info: WebSocketSenderInfo) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.add_sender_worker(info);
+        }
+    }
+    pub(crate) fn remove_sender_worker(&self, addr: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.remove_sender_worker(addr);
+        }
+    }
+    pub(crate) fn add_receiver_processor(&self, info: WebSocketReceiverInfo) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.add_receiver_processor(info);
+        }
+    }
+    pub(crate) fn remove_receiver_processor(&self, addr: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.remove_receiver_processor(addr);
+        }
+    }
+}
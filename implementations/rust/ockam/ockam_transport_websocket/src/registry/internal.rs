@@ -0,0 +1,30 @@
+use crate::{WebSocketListenerInfo, WebSocketReceiverInfo, WebSocketSenderInfo};
+use ockam_core::Address;
+
+#[derive(Default)]
+pub(super) struct InternalRegistry {
+    pub(super) listener_processors: Vec<WebSocketListenerInfo>,
+    pub(super) sender_workers: Vec<WebSocketSenderInfo>,
+    pub(super) receiver_processors: Vec<WebSocketReceiverInfo>,
+}
+
+impl InternalRegistry {
+    pub(super) fn add_listener_processor(&mut self, info: WebSocketListenerInfo) {
+        self.listener_processors.push(info)
+    }
+    pub(super) fn remove_listener_processor(&mut self, addr: &Address) {
+        self.listener_processors.retain(|x| x.address() != addr);
+    }
+    pub(super) fn add_sender_worker(&mut self, info: WebSocketSenderInfo) {
+        self.sender_workers.push(info)
+    }
+    pub(super) fn remove_sender_worker(&mut self, addr: &Address) {
+        self.sender_workers.retain(|x| x.address() != addr);
+    }
+    pub(super) fn add_receiver_processor(&mut self, info: WebSocketReceiverInfo) {
+        self.receiver_processors.push(info)
+    }
+    pub(super) fn remove_receiver_processor(&mut self, addr: &Address) {
+        self.receiver_processors.retain(|x| x.address() != addr);
+    }
+}
@@ -0,0 +1,26 @@
+use crate::registry::internal::InternalRegistry;
+use crate::{WebSocketListenerInfo, WebSocketReceiverInfo, WebSocketSenderInfo};
+use ockam_core::compat::sync::{Arc, RwLock};
+
+/// Registry of all active workers and processors in WebSocket Transport to ease their lifecycle management
+#[derive(Default, Clone)]
+pub struct WebSocketRegistry {
+    pub(super) registry: Arc<RwLock<InternalRegistry>>,
+}
+
+impl WebSocketRegistry {
+    /// Return [`Address`]es of all active sender workers
+    pub fn get_all_sender_workers(&self) -> Vec<WebSocketSenderInfo> {
+        self.registry.read().unwrap().sender_workers.clone()
+    }
+
+    /// Return [`Address`]es of all active receiver processors
+    pub fn get_all_receiver_processors(&self) -> Vec<WebSocketReceiverInfo> {
+        self.registry.read().unwrap().receiver_processors.clone()
+    }
+
+    /// Return [`Address`]es of all active listeners
+    pub fn get_all_listeners(&self) -> Vec<WebSocketListenerInfo> {
+        self.registry.read().unwrap().listener_processors.clone()
+    }
+}
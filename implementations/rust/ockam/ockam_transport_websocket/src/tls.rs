@@ -0,0 +1,194 @@
+use crate::workers::ConnectionStream;
+use core::fmt;
+use ockam_core::compat::sync::Arc;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use ockam_transport_core::{read_pem_certificates, read_pem_private_key};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::server::AllowAnyAuthenticatedClient;
+use tokio_rustls::rustls::{
+    Certificate, ClientConfig, PrivateKey, RootCertStore, ServerConfig, ServerName,
+};
+use tokio_rustls::{TlsAcceptor, TlsConnector};
+
+/// TLS options of a secure (`wss://`) WebSocket connection
+///
+/// The server is authenticated with the configured root certificates.
+/// A client certificate can be presented for mutual authentication.
+#[derive(Clone)]
+pub struct TlsClientOptions {
+    server_name: Option<String>,
+    root_certificates: RootCertStore,
+    client_certificate: Option<(Vec<Certificate>, PrivateKey)>,
+}
+
+impl fmt::Debug for TlsClientOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TlsClientOptions")
+            .field("server_name", &self.server_name)
+            .field("root_certificates", &self.root_certificates.len())
+            .field("client_certificate", &self.client_certificate.is_some())
+            .finish()
+    }
+}
+
+impl TlsClientOptions {
+    /// Create options without any trusted root certificate
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            server_name: None,
+            root_certificates: RootCertStore::empty(),
+            client_certificate: None,
+        }
+    }
+
+    /// Trust the root certificates of the platform
+    pub fn with_native_roots(mut self) -> Result<Self> {
+        let certificates = rustls_native_certs::load_native_certs().map_err(|e| {
+            invalid(format!(
+                "failed to load the platform root certificates: {e}"
+            ))
+        })?;
+        for c in certificates {
+            // Ignore the platform certificates which can not be parsed
+            let _ = self.root_certificates.add(&Certificate(c.0));
+        }
+
+        Ok(self)
+    }
+
+    /// Trust the PEM encoded root certificates
+    pub fn with_root_certificates(mut self, pem: &[u8]) -> Result<Self> {
+        for c in read_pem_certificates(pem)? {
+            self.root_certificates
+                .add(&c)
+                .map_err(|e| invalid(format!("invalid root certificate: {e}")))?;
+        }
+
+        Ok(self)
+    }
+
+    /// Present the PEM encoded certificate chain and private key to the server
+    pub fn with_client_certificate(mut self, certificate_chain: &[u8], key: &[u8]) -> Result<Self> {
+        self.client_certificate = Some((
+            read_pem_certificates(certificate_chain)?,
+            read_pem_private_key(key)?,
+        ));
+
+        Ok(self)
+    }
+
+    /// Expect the server certificate to be valid for this name.
+    /// By default the host of the connected URL is used
+    pub fn with_server_name(mut self, server_name: impl Into<String>) -> Self {
+        self.server_name = Some(server_name.into());
+
+        self
+    }
+
+    /// Perform a TLS handshake with the server on a connected stream
+    pub(crate) async fn connect(&self, host: &str, stream: TcpStream) -> Result<ConnectionStream> {
+        let builder = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(self.root_certificates.clone());
+        let config = match &self.client_certificate {
+            Some((certificates, key)) => builder
+                .with_client_auth_cert(certificates.clone(), key.clone())
+                .map_err(|e| invalid(format!("invalid client certificate: {e}")))?,
+            None => builder.with_no_client_auth(),
+        };
+
+        let server_name = self.server_name.as_deref().unwrap_or(host);
+        let server_name = ServerName::try_from(server_name)
+            .map_err(|_| invalid(format!("invalid TLS server name: {server_name}")))?;
+
+        let stream = TlsConnector::from(Arc::new(config))
+            .connect(server_name, stream)
+            .await
+            .map_err(|e| handshake_failed(host, e))?;
+        debug!(%host, "TLS handshake completed");
+
+        Ok(Box::new(stream))
+    }
+}
+
+/// TLS options of a WebSocket listener
+///
+/// Clients can be required to present a certificate issued by one of the
+/// configured client root certificates.
+#[derive(Clone)]
+pub struct TlsServerOptions {
+    certificate_chain: Vec<Certificate>,
+    key: PrivateKey,
+    client_root_certificates: Option<RootCertStore>,
+}
+
+impl fmt::Debug for TlsServerOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TlsServerOptions")
+            .field("certificate_chain", &self.certificate_chain.len())
+            .field(
+                "client_authentication",
+                &self.client_root_certificates.is_some(),
+            )
+            .finish()
+    }
+}
+
+impl TlsServerOptions {
+    /// Create options from the PEM encoded certificate chain and private key of the server
+    pub fn new(certificate_chain: &[u8], key: &[u8]) -> Result<Self> {
+        Ok(Self {
+            certificate_chain: read_pem_certificates(certificate_chain)?,
+            key: read_pem_private_key(key)?,
+            client_root_certificates: None,
+        })
+    }
+
+    /// Require clients to present a certificate issued by one of the PEM encoded root certificates
+    pub fn with_client_authentication(mut self, root_certificates: &[u8]) -> Result<Self> {
+        let mut roots = RootCertStore::empty();
+        for c in read_pem_certificates(root_certificates)? {
+            roots
+                .add(&c)
+                .map_err(|e| invalid(format!("invalid client root certificate: {e}")))?;
+        }
+        self.client_root_certificates = Some(roots);
+
+        Ok(self)
+    }
+
+    /// Perform a TLS handshake with a client on an accepted stream
+    pub(crate) async fn accept(&self, peer: &str, stream: TcpStream) -> Result<ConnectionStream> {
+        let builder = ServerConfig::builder().with_safe_defaults();
+        let builder = match &self.client_root_certificates {
+            Some(roots) => builder
+                .with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots.clone()).boxed()),
+            None => builder.with_no_client_auth(),
+        };
+        let config = builder
+            .with_single_cert(self.certificate_chain.clone(), self.key.clone())
+            .map_err(|e| invalid(format!("invalid server certificate: {e}")))?;
+
+        let stream = TlsAcceptor::from(Arc::new(config))
+            .accept(stream)
+            .await
+            .map_err(|e| handshake_failed(peer, e))?;
+        debug!(%peer, "TLS handshake completed");
+
+        Ok(Box::new(stream))
+    }
+}
+
+fn invalid(msg: impl fmt::Display) -> Error {
+    Error::new(Origin::Transport, Kind::Invalid, msg)
+}
+
+fn handshake_failed(peer: &str, e: std::io::Error) -> Error {
+    Error::new(
+        Origin::Transport,
+        Kind::Protocol,
+        format!("TLS handshake with {peer} failed: {e}"),
+    )
+}
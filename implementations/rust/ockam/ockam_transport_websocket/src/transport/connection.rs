@@ -0,0 +1,78 @@
+use crate::transport::common::WebSocketConnection;
+use crate::workers::{Addresses, ConnectionStream, WebSocketRecvProcessor, WebSocketSendWorker};
+use crate::{WebSocketConnectionMode, WebSocketConnectionOptions, WebSocketTransport};
+use futures_util::StreamExt;
+use ockam_core::{Address, Result};
+
+impl WebSocketTransport {
+    /// Establish an outgoing WebSocket connection.
+    ///
+    /// The peer is either a `host:port` address or a `ws://` / `wss://` URL,
+    /// e.g. `wss://relay.example.com/ockam`. `wss://` URLs require the `tls` feature.
+    ///
+    /// ```rust
+    /// use ockam_transport_websocket::{WebSocketConnectionOptions, WebSocketListenerOptions, WebSocketTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::Result;
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let ws = WebSocketTransport::create(&ctx).await?;
+    /// ws.listen("127.0.0.1:8000", WebSocketListenerOptions::new()).await?; // Listen on port 8000
+    /// let connection = ws.connect("127.0.0.1:5000", WebSocketConnectionOptions::new()).await?; // and connect to port 5000
+    /// # Ok(()) }
+    /// ```
+    pub async fn connect(
+        &self,
+        peer: impl Into<String>,
+        options: WebSocketConnectionOptions,
+    ) -> Result<WebSocketConnection> {
+        let url = options.url(&peer.into())?;
+
+        let (stream, socket) =
+            WebSocketSendWorker::<ConnectionStream>::connect(&url, &options).await?;
+        let (ws_sink, ws_stream) = stream.split();
+
+        let mode = WebSocketConnectionMode::Outgoing;
+        let addresses = Addresses::generate(mode);
+
+        options.setup_flow_control(self.ctx.flow_controls(), &addresses);
+        let flow_control_id = options.flow_control_id.clone();
+        let access_control = options.create_access_control(self.ctx.flow_controls());
+
+        WebSocketSendWorker::start(
+            &self.ctx,
+            self.registry.clone(),
+            ws_sink,
+            &addresses,
+            socket,
+            mode,
+            access_control.sender_incoming_access_control,
+            &flow_control_id,
+        )
+        .await?;
+
+        WebSocketRecvProcessor::start(
+            &self.ctx,
+            self.registry.clone(),
+            ws_stream,
+            &addresses,
+            socket,
+            mode,
+            &flow_control_id,
+            access_control.receiver_outgoing_access_control,
+        )
+        .await?;
+
+        Ok(WebSocketConnection::new(
+            addresses.sender_address().clone(),
+            addresses.receiver_address().clone(),
+            socket,
+            mode,
+            flow_control_id,
+        ))
+    }
+
+    /// Interrupt an active WebSocket connection given its Sender `Address`
+    pub async fn disconnect(&self, address: impl Into<Address>) -> Result<()> {
+        self.ctx.stop_worker(address.into()).await
+    }
+}
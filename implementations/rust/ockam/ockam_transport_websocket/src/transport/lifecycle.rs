@@ -0,0 +1,112 @@
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{async_trait, Address, AsyncTryClone, Error, Result, TransportType};
+use ockam_node::Context;
+use ockam_transport_core::Transport;
+use std::sync::Arc;
+
+use crate::{WebSocketConnectionOptions, WebSocketRegistry, WebSocketTransport, WS};
+
+impl WebSocketTransport {
+    /// Create a WebSocket transport
+    ///
+    /// ```rust
+    /// use ockam_transport_websocket::WebSocketTransport;
+    /// # use ockam_node::Context;
+    /// # use ockam_core::Result;
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let ws = WebSocketTransport::create(&ctx).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn create(ctx: &Context) -> Result<Self> {
+        let ws = Self {
+            ctx: ctx.async_try_clone().await?,
+            registry: WebSocketRegistry::default(),
+        };
+        // make the WebSocket transport available in the list of supported transports for
+        // later address resolution when socket addresses will need to be instantiated as WS
+        // worker addresses
+        ctx.register_transport(Arc::new(ws.async_try_clone().await?));
+        Ok(ws)
+    }
+}
+
+impl WebSocketTransport {
+    /// Getter
+    pub fn ctx(&self) -> &Context {
+        &self.ctx
+    }
+    /// Registry of all active connections
+    pub fn registry(&self) -> &WebSocketRegistry {
+        &self.registry
+    }
+}
+
+#[async_trait]
+impl Transport for WebSocketTransport {
+    fn transport_type(&self) -> TransportType {
+        WS
+    }
+
+    async fn resolve_address(&self, address: Address) -> Result<Address> {
+        if address.transport_type() == WS {
+            Ok(self
+                .connect(
+                    address.address().to_string(),
+                    WebSocketConnectionOptions::new(),
+                )
+                .await?
+                .into())
+        } else {
+            Err(Error::new(
+                Origin::Transport,
+                Kind::NotFound,
+                format!(
+                    "this address can not be resolved by a WebSocket transport {}",
+                    address
+                ),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WebSocketListenerOptions;
+
+    #[ockam_macros::test]
+    async fn test_resolve_address(ctx: &mut Context) -> Result<()> {
+        let ws = WebSocketTransport::create(ctx).await?;
+        let listener = ws
+            .listen("127.0.0.1:0", WebSocketListenerOptions::new())
+            .await?;
+        let initial_workers = ctx.list_workers().await?;
+
+        let resolved = ws
+            .resolve_address(Address::new(WS, listener.socket_string()))
+            .await?;
+
+        // the WS address is replaced with the WS sender worker address
+        let mut additional_workers = ctx.list_workers().await?;
+        additional_workers.retain(|w| !initial_workers.contains(w));
+        assert!(additional_workers.contains(&resolved));
+
+        // trying to resolve the address a second time should still work
+        let _route = ws
+            .resolve_address(Address::new(WS, listener.socket_string()))
+            .await?;
+
+        ctx.stop().await
+    }
+
+    #[ockam_macros::test]
+    async fn test_resolve_address_of_another_transport(ctx: &mut Context) -> Result<()> {
+        let ws = WebSocketTransport::create(ctx).await?;
+        let result = ws
+            .resolve_address(Address::new(TransportType::new(1), "127.0.0.1:4000"))
+            .await;
+
+        assert!(result.is_err());
+        ctx.stop().await
+    }
+}
@@ -0,0 +1,67 @@
+pub(crate) mod common;
+mod connection;
+mod lifecycle;
+mod listener;
+
+pub use common::*;
+
+use crate::WebSocketRegistry;
+use ockam_core::{async_trait, AsyncTryClone, Result};
+use ockam_node::{Context, HasContext};
+
+/// High level management interface for WebSocket transports
+///
+/// Be aware that only one `WebSocketTransport` can exist per node, as it
+/// registers itself as the transport for the `WS` address type. Multiple
+/// calls to [`WebSocketTransport::create`](crate::WebSocketTransport::create)
+/// will fail.
+///
+/// To listen for incoming connections use
+/// [`ws.listen()`](crate::WebSocketTransport::listen).
+///
+/// To register additional connections on an already initialised
+/// `WebSocketTransport`, use [`ws.connect()`](crate::WebSocketTransport::connect).
+/// This step is optional because routes containing `WS` addresses are
+/// resolved to new connections by the node.
+///
+/// ```rust
+/// use ockam_transport_websocket::{WebSocketConnectionOptions, WebSocketListenerOptions, WebSocketTransport};
+/// # use ockam_node::Context;
+/// # use ockam_core::Result;
+/// # async fn test(ctx: Context) -> Result<()> {
+/// let ws = WebSocketTransport::create(&ctx).await?;
+/// ws.listen("127.0.0.1:8000", WebSocketListenerOptions::new()).await?; // Listen on port 8000
+/// ws.connect("127.0.0.1:5000", WebSocketConnectionOptions::new()).await?; // And connect to port 5000
+/// # Ok(()) }
+/// ```
+///
+/// The same `WebSocketTransport` can also bind to multiple ports.
+///
+/// ```rust
+/// use ockam_transport_websocket::{WebSocketListenerOptions, WebSocketTransport};
+/// # use ockam_node::Context;
+/// # use ockam_core::Result;
+/// # async fn test(ctx: Context) -> Result<()> {
+/// let ws = WebSocketTransport::create(&ctx).await?;
+/// ws.listen("127.0.0.1:8000", WebSocketListenerOptions::new()).await?; // Listen on port 8000
+/// ws.listen("127.0.0.1:9000", WebSocketListenerOptions::new()).await?; // Listen on port 9000
+/// # Ok(()) }
+/// ```
+#[derive(AsyncTryClone)]
+#[async_try_clone(crate = "ockam_core")]
+pub struct WebSocketTransport {
+    ctx: Context,
+    registry: WebSocketRegistry,
+}
+
+/// This trait adds a `create_web_socket_transport` method to any struct returning a Context.
+/// This is the case for an ockam::Node, so you can write `node.create_web_socket_transport()`
+#[async_trait]
+pub trait WebSocketTransportExtension: HasContext {
+    /// Create a WebSocket transport
+    async fn create_web_socket_transport(&self) -> Result<WebSocketTransport> {
+        WebSocketTransport::create(self.get_context()).await
+    }
+}
+
+impl<A: HasContext> WebSocketTransportExtension for A {}
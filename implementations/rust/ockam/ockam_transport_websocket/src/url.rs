@@ -0,0 +1,161 @@
+use core::fmt;
+use ockam_core::Result;
+use ockam_transport_core::TransportError;
+
+/// A WebSocket peer to connect to
+///
+/// Peers are given either as a `host:port` address or as a `ws://` or
+/// `wss://` URL which can carry a request path, e.g.
+/// `wss://relay.example.com/ockam`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct WebSocketUrl {
+    secure: bool,
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl WebSocketUrl {
+    /// Parse a `host:port` address or a `ws://` / `wss://` URL
+    pub(crate) fn parse(peer: &str) -> Result<Self> {
+        let (scheme, rest) = match peer.split_once("://") {
+            Some((scheme, rest)) => (Some(scheme), rest),
+            None => (None, peer),
+        };
+        let secure = match scheme {
+            None | Some("ws") => false,
+            Some("wss") => true,
+            Some(_) => return Err(TransportError::InvalidAddress.into()),
+        };
+
+        let (authority, path) = match rest.find(|c: char| c == '/' || c == '?') {
+            Some(i) if rest[i..].starts_with('?') => (&rest[..i], format!("/{}", &rest[i..])),
+            Some(i) => (&rest[..i], rest[i..].to_string()),
+            None => (rest, "/".to_string()),
+        };
+
+        let (host, port) = match authority.strip_prefix('[') {
+            Some(rest) => {
+                let (host, rest) = rest.split_once(']').ok_or(TransportError::InvalidAddress)?;
+                (host, rest.strip_prefix(':'))
+            }
+            None => match authority.rsplit_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (authority, None),
+            },
+        };
+        if host.is_empty() {
+            return Err(TransportError::InvalidAddress.into());
+        }
+
+        let port = match port {
+            Some(port) => port.parse().map_err(|_| TransportError::InvalidAddress)?,
+            // Only URLs have a default port
+            None if scheme.is_some() => {
+                if secure {
+                    443
+                } else {
+                    80
+                }
+            }
+            None => return Err(TransportError::InvalidAddress.into()),
+        };
+
+        Ok(Self {
+            secure,
+            host: host.to_string(),
+            port,
+            path,
+        })
+    }
+
+    /// Use a secure (`wss://`) connection to the same peer
+    #[cfg(feature = "tls")]
+    pub(crate) fn into_secure(mut self) -> Self {
+        self.secure = true;
+        self
+    }
+
+    /// Return true for `wss://` URLs
+    pub(crate) fn is_secure(&self) -> bool {
+        self.secure
+    }
+
+    /// Host name or IP address of the peer, without brackets for IPv6 addresses
+    #[cfg_attr(not(feature = "tls"), allow(dead_code))]
+    pub(crate) fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// `host:port` address of the peer
+    pub(crate) fn authority(&self) -> String {
+        if self.host.contains(':') {
+            format!("[{}]:{}", self.host, self.port)
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+}
+
+impl fmt::Display for WebSocketUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (scheme, default_port) = if self.secure {
+            ("wss", 443)
+        } else {
+            ("ws", 80)
+        };
+        // Keep the Host header of the handshake free of default ports
+        if self.port == default_port {
+            let host = if self.host.contains(':') {
+                format!("[{}]", self.host)
+            } else {
+                self.host.clone()
+            };
+            write!(f, "{}://{}{}", scheme, host, self.path)
+        } else {
+            write!(f, "{}://{}{}", scheme, self.authority(), self.path)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WebSocketUrl;
+
+    #[test]
+    fn test_parse_socket_address() {
+        let url = WebSocketUrl::parse("127.0.0.1:8000").unwrap();
+        assert!(!url.is_secure());
+        assert_eq!(url.host(), "127.0.0.1");
+        assert_eq!(url.authority(), "127.0.0.1:8000");
+        assert_eq!(url.to_string(), "ws://127.0.0.1:8000/");
+
+        let url = WebSocketUrl::parse("[::1]:8000").unwrap();
+        assert_eq!(url.host(), "::1");
+        assert_eq!(url.to_string(), "ws://[::1]:8000/");
+
+        assert!(WebSocketUrl::parse("localhost").is_err());
+        assert!(WebSocketUrl::parse("localhost:port").is_err());
+        assert!(WebSocketUrl::parse(":8000").is_err());
+    }
+
+    #[test]
+    fn test_parse_url() {
+        let url = WebSocketUrl::parse("wss://relay.example.com/ockam/ws?node=n1").unwrap();
+        assert!(url.is_secure());
+        assert_eq!(url.host(), "relay.example.com");
+        assert_eq!(url.authority(), "relay.example.com:443");
+        assert_eq!(url.to_string(), "wss://relay.example.com/ockam/ws?node=n1");
+
+        let url = WebSocketUrl::parse("ws://relay.example.com").unwrap();
+        assert!(!url.is_secure());
+        assert_eq!(url.authority(), "relay.example.com:80");
+        assert_eq!(url.to_string(), "ws://relay.example.com/");
+
+        let url = WebSocketUrl::parse("ws://localhost:4000?node=n1").unwrap();
+        assert_eq!(url.to_string(), "ws://localhost:4000/?node=n1");
+
+        assert!(WebSocketUrl::parse("http://relay.example.com").is_err());
+        assert!(WebSocketUrl::parse("wss://[::1/ockam").is_err());
+    }
+}
@@ -1,46 +1,97 @@
 use std::net::SocketAddr;
+use std::time::Duration;
 
-use tokio::net::TcpListener;
+use futures_util::StreamExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::timeout;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
 
-use ockam_core::{async_trait, Address, AllowAll, Processor, Result};
+use ockam_core::{async_trait, Address, Processor, Result};
 use ockam_node::Context;
 use ockam_transport_core::TransportError;
 
-use crate::{error::WebSocketError, workers::WorkerPair, WebSocketRouterHandle};
+use crate::error::WebSocketError;
+use crate::workers::{
+    Addresses, ConnectionStream, WebSocketRecvProcessor, WebSocketSendWorker, WebSocketStream,
+};
+use crate::{
+    WebSocketConnectionMode, WebSocketListenerInfo, WebSocketListenerOptions, WebSocketRegistry,
+};
+
+/// Maximum duration of the TLS and WebSocket handshakes of an accepted connection
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// A worker that runs in the background as a `Processor` waiting for incoming
 /// clients' connections.
 ///
-/// When a new connection is established, a new `WorkerPair` is spawned and
-/// registered by the router.
+/// When a new connection is established, a new pair of sender worker and
+/// receiver processor is spawned for it.
 pub(crate) struct WebSocketListenProcessor {
+    registry: WebSocketRegistry,
     inner: TcpListener,
-    router_handle: WebSocketRouterHandle,
+    socket_address: SocketAddr,
+    options: WebSocketListenerOptions,
 }
 
 impl WebSocketListenProcessor {
     /// Create and start a new instance bound to the given `addr`.
     pub(crate) async fn start(
         ctx: &Context,
-        router_handle: WebSocketRouterHandle,
+        registry: WebSocketRegistry,
         addr: SocketAddr,
-    ) -> Result<SocketAddr> {
+        options: WebSocketListenerOptions,
+    ) -> Result<(SocketAddr, Address)> {
         debug!("Binding WebSocketListener to {}", addr);
         let inner = TcpListener::bind(addr)
             .await
             .map_err(TransportError::from)?;
         let saddr = inner.local_addr().map_err(TransportError::from)?;
+
+        let address = Address::random_tagged("WebSocketListenProcessor");
+        options.setup_flow_control_for_listener(ctx.flow_controls(), &address);
+
         let processor = Self {
+            registry,
             inner,
-            router_handle,
+            socket_address: saddr,
+            options,
         };
-        let waddr = Address::random_tagged("WebSocketListenProcessor");
-        ctx.start_processor_with_access_control(
-            waddr, processor, AllowAll, // FIXME: @ac
-            AllowAll, // FIXME: @ac
-        )
-        .await?;
-        Ok(saddr)
+
+        ctx.start_processor(address.clone(), processor).await?;
+
+        Ok((saddr, address))
+    }
+
+    /// Perform the TLS and WebSocket handshakes of an accepted connection.
+    ///
+    /// Handshake requests for a path outside of the configured path prefix
+    /// are answered with `404 Not Found`.
+    async fn accept(
+        &self,
+        stream: TcpStream,
+        peer: SocketAddr,
+    ) -> Result<WebSocketStream<ConnectionStream>> {
+        let stream = self
+            .options
+            .secure_stream(&peer.to_string(), stream)
+            .await?;
+
+        let check_path = |request: &Request, response: Response| {
+            let path = request.uri().path();
+            if self.options.accepts_path(path) {
+                Ok(response)
+            } else {
+                debug!(%peer, %path, "Rejecting WebSocket handshake for an unknown path");
+                let mut response = ErrorResponse::new(Some("Not Found".to_string()));
+                *response.status_mut() = StatusCode::NOT_FOUND;
+                Err(response)
+            }
+        };
+
+        tokio_tungstenite::accept_hdr_async(stream, check_path)
+            .await
+            .map_err(|e| WebSocketError::from(e).into())
     }
 }
 
@@ -49,7 +100,22 @@ impl Processor for WebSocketListenProcessor {
     type Context = Context;
 
     async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
-        ctx.set_cluster(crate::CLUSTER_NAME).await
+        ctx.set_cluster(crate::CLUSTER_NAME).await?;
+
+        self.registry
+            .add_listener_processor(WebSocketListenerInfo::new(
+                ctx.address(),
+                self.socket_address,
+                self.options.flow_control_id.clone(),
+            ));
+
+        Ok(())
+    }
+
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        self.registry.remove_listener_processor(&ctx.address());
+
+        Ok(())
     }
 
     async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
@@ -57,17 +123,57 @@ impl Processor for WebSocketListenProcessor {
 
         // Wait for an incoming connection
         let (tcp_stream, peer) = self.inner.accept().await.map_err(TransportError::from)?;
-        let ws_stream = tokio_tungstenite::accept_async(tcp_stream)
-            .await
-            .map_err(WebSocketError::from)?;
         debug!("TCP connection accepted");
 
-        // Spawn a connection worker for it
-        let pair = WorkerPair::from_server(ctx, ws_stream, peer, vec![]).await?;
+        // A failed handshake only drops this connection
+        let ws_stream = match timeout(HANDSHAKE_TIMEOUT, self.accept(tcp_stream, peer)).await {
+            Ok(Ok(ws_stream)) => ws_stream,
+            Ok(Err(e)) => {
+                warn!(%peer, "Dropping incoming WebSocket connection: {e}");
+                return Ok(true);
+            }
+            Err(_) => {
+                warn!(%peer, "Dropping incoming WebSocket connection: handshake timed out");
+                return Ok(true);
+            }
+        };
+        let (ws_sink, ws_stream) = ws_stream.split();
+
+        let mode = WebSocketConnectionMode::Incoming;
+        let addresses = Addresses::generate(mode);
 
-        // Register the connection with the local TcpRouter
-        self.router_handle.register(&pair).await?;
-        debug!("TCP connection registered");
+        let receiver_flow_control_id = self
+            .options
+            .setup_flow_control_for_connection(ctx.flow_controls(), &addresses);
+        let access_control = self
+            .options
+            .create_access_control(ctx.flow_controls(), receiver_flow_control_id.clone());
+
+        // Worker to receive messages from the Node and send them over the wire
+        WebSocketSendWorker::start(
+            ctx,
+            self.registry.clone(),
+            ws_sink,
+            &addresses,
+            peer,
+            mode,
+            access_control.sender_incoming_access_control,
+            &receiver_flow_control_id,
+        )
+        .await?;
+
+        // Processor to receive messages over the wire and forward them to the node
+        WebSocketRecvProcessor::start(
+            ctx,
+            self.registry.clone(),
+            ws_stream,
+            &addresses,
+            peer,
+            mode,
+            &receiver_flow_control_id,
+            access_control.receiver_outgoing_access_control,
+        )
+        .await?;
 
         Ok(true)
     }
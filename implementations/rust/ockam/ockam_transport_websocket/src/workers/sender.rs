@@ -1,131 +1,33 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
-use std::time::Duration;
 
-use futures_util::stream::{SplitSink, SplitStream};
-use futures_util::{SinkExt, StreamExt};
+use futures_util::stream::SplitSink;
+use futures_util::SinkExt;
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::header::{HeaderValue, ORIGIN};
 use tokio_tungstenite::tungstenite::protocol::Message as WebSocketMessage;
 
 use crate::error::WebSocketError;
+use ockam_core::flow_control::FlowControlId;
 use ockam_core::{
-    async_trait, route, Address, AllowAll, Any, Decodable, Encodable, LocalMessage, Mailbox,
-    Mailboxes, Result, Routed, TransportMessage, Worker,
+    async_trait, AllowSourceAddress, Any, Decodable, DenyAll, Encodable, IncomingAccessControl,
+    Mailbox, Mailboxes, Message, Result, Routed, Worker,
 };
-use ockam_node::{Context, DelayedEvent, WorkerBuilder};
+use ockam_node::{Context, WorkerBuilder};
 use ockam_transport_core::TransportError;
 
-use crate::workers::{
-    AsyncStream, TcpClientStream, TcpServerStream, WebSocketRecvProcessor, WebSocketStream,
+use crate::transport::common::resolve_peer;
+use crate::url::WebSocketUrl;
+use crate::workers::{Addresses, AsyncStream, ConnectionStream, WebSocketStream};
+use crate::{
+    WebSocketConnectionMode, WebSocketConnectionOptions, WebSocketRegistry, WebSocketSenderInfo,
 };
-use crate::WebSocketAddress;
 
-/// Transmit and receive peers of a WebSocket connection.
-#[derive(Debug)]
-pub(crate) struct WorkerPair {
-    hostnames: Vec<String>,
-    peer: Address,
-    tx_addr: Address,
-}
-
-impl WorkerPair {
-    pub(crate) fn hostnames(&self) -> &[String] {
-        &self.hostnames
-    }
-    pub(crate) fn peer(&self) -> Address {
-        self.peer.clone()
-    }
-    pub(crate) fn tx_addr(&self) -> Address {
-        self.tx_addr.clone()
-    }
-
-    /// Spawn instances of `WebSocketSendWorker` and `WebSocketRecvProcessor` and
-    /// returns a `WorkerPair` instance that will be registered by the `WebSocketRouter`.
-    ///
-    /// The WebSocket stream is created when the `WebSocketSendWorker` is initialized.
-    pub(crate) async fn from_client(
-        ctx: &Context,
-        peer: SocketAddr,
-        hostnames: Vec<String>,
-    ) -> Result<WorkerPair> {
-        trace!("Creating new WS worker pair");
-
-        let internal_addr = Address::random_tagged("WebSocketSender.internal.from_client");
-        let sender = WebSocketSendWorker::<TcpClientStream>::new(
-            peer,
-            internal_addr.clone(),
-            DelayedEvent::create(ctx, internal_addr.clone(), vec![]).await?,
-        );
-
-        let tx_addr = Address::random_tagged("WebSocketSender.tx_addr.from_client");
-
-        let mailboxes = Mailboxes::new(
-            Mailbox::new(
-                tx_addr.clone(),
-                Arc::new(AllowAll), // FIXME: @ac
-                Arc::new(AllowAll), // FIXME: @ac
-            ),
-            vec![Mailbox::new(
-                internal_addr,
-                Arc::new(AllowAll), // FIXME: @ac
-                Arc::new(AllowAll), // FIXME: @ac
-            )],
-        );
-        WorkerBuilder::new(sender)
-            .with_mailboxes(mailboxes)
-            .start(ctx)
-            .await?;
-
-        // Return a handle to the worker pair
-        Ok(WorkerPair {
-            hostnames,
-            peer: WebSocketAddress::from(peer).into(),
-            tx_addr,
-        })
-    }
-
-    /// Spawn instances of `WebSocketSendWorker` and `WebSocketRecvProcessor` and
-    /// returns a `WorkerPair` instance that will be registered by the `WebSocketRouter`.
-    pub(crate) async fn from_server(
-        ctx: &Context,
-        stream: WebSocketStream<TcpServerStream>,
-        peer: SocketAddr,
-        hostnames: Vec<String>,
-    ) -> Result<WorkerPair> {
-        trace!("Creating new WS worker pair");
-
-        let internal_addr = Address::random_tagged("WebSocketSender.internal.from_server");
-        let sender = WebSocketSendWorker::<TcpServerStream>::new(
-            stream,
-            peer,
-            internal_addr.clone(),
-            DelayedEvent::create(ctx, internal_addr.clone(), vec![]).await?,
-        );
-
-        let tx_addr = Address::random_tagged("WebSocketSender.tx_addr.from_server");
-        let mailboxes = Mailboxes::new(
-            Mailbox::new(
-                tx_addr.clone(),
-                Arc::new(AllowAll), // FIXME: @ac
-                Arc::new(AllowAll), // FIXME: @ac
-            ),
-            vec![Mailbox::new(
-                internal_addr,
-                Arc::new(AllowAll), // FIXME: @ac
-                Arc::new(AllowAll), // FIXME: @ac
-            )],
-        );
-        WorkerBuilder::new(sender)
-            .with_mailboxes(mailboxes)
-            .start(ctx)
-            .await?;
-
-        // Return a handle to the worker pair
-        Ok(WorkerPair {
-            hostnames,
-            peer: WebSocketAddress::from(peer).into(),
-            tx_addr,
-        })
-    }
+#[derive(Serialize, Deserialize, Message, Clone)]
+pub(crate) enum WebSocketSendWorkerMsg {
+    ConnectionClosed,
 }
 
 /// A WebSocket sending message worker.
@@ -137,175 +39,200 @@ pub(crate) struct WebSocketSendWorker<S>
 where
     S: AsyncStream,
 {
-    ws_stream: Option<SplitStream<WebSocketStream<S>>>,
-    ws_sink: Option<SplitSink<WebSocketStream<S>, WebSocketMessage>>,
-    peer: SocketAddr,
-    internal_addr: Address,
-    heartbeat: DelayedEvent<Vec<u8>>,
-    heartbeat_interval: Option<Duration>,
+    registry: WebSocketRegistry,
+    ws_sink: SplitSink<WebSocketStream<S>, WebSocketMessage>,
+    socket_address: SocketAddr,
+    addresses: Addresses,
+    mode: WebSocketConnectionMode,
+    receiver_flow_control_id: FlowControlId,
+    rx_should_be_stopped: bool,
 }
 
 impl<S> WebSocketSendWorker<S>
 where
     S: AsyncStream,
 {
-    async fn handle_initialize(&mut self, ctx: &mut Context) -> Result<()> {
-        if let Some(ws_stream) = self.ws_stream.take() {
-            let rx_addr = Address::random_tagged("WebSocketSendWorker.rx_addr");
-            let receiver = WebSocketRecvProcessor::new(ws_stream, self.peer);
-            ctx.start_processor_with_access_control(
-                rx_addr.clone(),
-                receiver,
-                AllowAll, // FIXME: @ac
-                AllowAll, // FIXME: @ac
-            )
-            .await?;
-        } else {
-            return Err(TransportError::GenericIo.into());
-        }
-
-        ctx.set_cluster(crate::CLUSTER_NAME).await?;
-        self.schedule_heartbeat().await?;
-        Ok(())
-    }
-
-    async fn schedule_heartbeat(&mut self) -> Result<()> {
-        let heartbeat_interval = match &self.heartbeat_interval {
-            Some(hi) => *hi,
-            None => return Ok(()),
-        };
-
-        self.heartbeat.schedule(heartbeat_interval).await
-    }
-
-    /// Receive messages from the `WebSocketRouter` to send
-    /// across the `WebSocketStream` to the next remote peer.
-    async fn handle_msg(&mut self, ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
-        self.heartbeat.cancel();
-
-        let ws_sink = if let Some(ws_sink) = &mut self.ws_sink {
-            ws_sink
-        } else {
-            return Err(TransportError::PeerNotFound.into());
+    /// Start a `WebSocketSendWorker` writing to the sink of an established connection
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn start(
+        ctx: &Context,
+        registry: WebSocketRegistry,
+        ws_sink: SplitSink<WebSocketStream<S>, WebSocketMessage>,
+        addresses: &Addresses,
+        socket_address: SocketAddr,
+        mode: WebSocketConnectionMode,
+        sender_incoming_access_control: Arc<dyn IncomingAccessControl>,
+        receiver_flow_control_id: &FlowControlId,
+    ) -> Result<()> {
+        trace!("Creating new WS sender");
+        let sender_worker = Self {
+            registry,
+            ws_sink,
+            socket_address,
+            addresses: addresses.clone(),
+            mode,
+            receiver_flow_control_id: receiver_flow_control_id.clone(),
+            rx_should_be_stopped: true,
         };
 
-        let recipient = msg.msg_addr();
-        if recipient == self.internal_addr {
-            let msg = TransportMessage::v1(route![], route![], vec![]);
-            // Sending empty heartbeat
-            if ws_sink
-                .send(WebSocketMessage::from(msg.encode()?))
-                .await
-                .is_err()
-            {
-                warn!("Failed to send heartbeat to peer {}", self.peer);
-                ctx.stop_worker(ctx.address()).await?;
-
-                return Ok(());
-            }
-            debug!("Sent heartbeat to peer {}", self.peer);
-        } else {
-            let mut msg = LocalMessage::decode(msg.payload())?.into_transport_message();
-
-            // Remove our own address from the route so the other end
-            // knows what to do with the incoming message
-            msg.onward_route.step()?;
+        let main_mailbox = Mailbox::new(
+            addresses.sender_address().clone(),
+            sender_incoming_access_control,
+            Arc::new(DenyAll),
+        );
 
-            let msg = WebSocketMessage::from(msg.encode()?);
-            if ws_sink.send(msg).await.is_err() {
-                warn!("Failed to send message to peer {}", self.peer);
-                ctx.stop_worker(ctx.address()).await?;
-                return Ok(());
-            }
-            debug!("Sent message to peer {}", self.peer);
-        }
+        let internal_mailbox = Mailbox::new(
+            addresses.sender_internal_address().clone(),
+            Arc::new(AllowSourceAddress(
+                addresses.receiver_internal_address().clone(),
+            )),
+            Arc::new(DenyAll),
+        );
 
-        self.schedule_heartbeat().await?;
+        WorkerBuilder::new(sender_worker)
+            .with_mailboxes(Mailboxes::new(main_mailbox, vec![internal_mailbox]))
+            .start(ctx)
+            .await?;
 
         Ok(())
     }
-}
 
-impl WebSocketSendWorker<TcpServerStream> {
-    fn new(
-        stream: WebSocketStream<TcpServerStream>,
-        peer: SocketAddr,
-        internal_addr: Address,
-        heartbeat: DelayedEvent<Vec<u8>>,
-    ) -> Self {
-        let (ws_sink, ws_stream) = stream.split();
-        Self {
-            ws_sink: Some(ws_sink),
-            ws_stream: Some(ws_stream),
-            peer,
-            internal_addr,
-            heartbeat,
-            heartbeat_interval: None,
-        }
+    async fn stop(&self, ctx: &Context) -> Result<()> {
+        ctx.stop_worker(self.addresses.sender_address().clone())
+            .await?;
+
+        Ok(())
     }
 }
 
-impl WebSocketSendWorker<TcpClientStream> {
-    fn new(peer: SocketAddr, internal_addr: Address, heartbeat: DelayedEvent<Vec<u8>>) -> Self {
-        Self {
-            ws_stream: None,
-            ws_sink: None,
-            peer,
-            internal_addr,
-            heartbeat,
-            heartbeat_interval: None,
+impl WebSocketSendWorker<ConnectionStream> {
+    /// Open a WebSocket connection to the given peer
+    ///
+    /// Return the stream and the address of the connected socket,
+    /// which is the address of the proxy if the connection is tunnelled
+    pub(crate) async fn connect(
+        url: &WebSocketUrl,
+        options: &WebSocketConnectionOptions,
+    ) -> Result<(WebSocketStream<ConnectionStream>, SocketAddr)> {
+        debug!(%url, "Connecting");
+        let tcp_stream = match &options.proxy {
+            Some(proxy) => proxy.connect(&url.authority()).await?,
+            None => {
+                let socket_address = resolve_peer(url.authority())?;
+                TcpStream::connect(socket_address)
+                    .await
+                    .map_err(TransportError::from)?
+            }
+        };
+        let socket_address = tcp_stream.peer_addr().map_err(TransportError::from)?;
+        let stream = options.secure_stream(url, tcp_stream).await?;
+
+        let mut request = url
+            .to_string()
+            .into_client_request()
+            .map_err(WebSocketError::from)?;
+        if let Some(origin) = &options.origin {
+            let origin =
+                HeaderValue::from_str(origin).map_err(|_| TransportError::InvalidAddress)?;
+            request.headers_mut().insert(ORIGIN, origin);
         }
-    }
 
-    async fn initialize_stream(&mut self) -> Result<()> {
-        if self.ws_stream.is_none() {
-            let peer = WebSocketAddress::from(self.peer).to_string();
-            let (stream, _) = tokio_tungstenite::connect_async(peer)
-                .await
-                .map_err(WebSocketError::from)?;
-            let (ws_sink, ws_stream) = stream.split();
-            self.ws_sink = Some(ws_sink);
-            self.ws_stream = Some(ws_stream);
+        match tokio_tungstenite::client_async(request, stream).await {
+            Ok((stream, _)) => {
+                debug!(%url, "Connected");
+                Ok((stream, socket_address))
+            }
+            Err(e) => {
+                debug!(%url, err = %e, "Failed to connect");
+                Err(WebSocketError::from(e).into())
+            }
         }
-        Ok(())
     }
 }
 
 #[async_trait]
-impl Worker for WebSocketSendWorker<TcpServerStream> {
+impl<S> Worker for WebSocketSendWorker<S>
+where
+    S: AsyncStream,
+{
     type Message = Any;
     type Context = Context;
 
     async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
-        self.handle_initialize(ctx).await?;
+        ctx.set_cluster(crate::CLUSTER_NAME).await?;
+
+        self.registry.add_sender_worker(WebSocketSenderInfo::new(
+            self.addresses.sender_address().clone(),
+            self.addresses.receiver_address().clone(),
+            self.socket_address,
+            self.mode,
+            self.receiver_flow_control_id.clone(),
+        ));
+
         Ok(())
     }
 
-    async fn handle_message(
-        &mut self,
-        ctx: &mut Context,
-        msg: Routed<Self::Message>,
-    ) -> Result<()> {
-        self.handle_msg(ctx, msg).await
-    }
-}
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        self.registry
+            .remove_sender_worker(self.addresses.sender_address());
 
-#[async_trait]
-impl Worker for WebSocketSendWorker<TcpClientStream> {
-    type Message = Any;
-    type Context = Context;
+        let _ = self.ws_sink.close().await;
+
+        if self.rx_should_be_stopped {
+            let _ = ctx
+                .stop_processor(self.addresses.receiver_address().clone())
+                .await;
+        }
 
-    async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
-        self.initialize_stream().await?;
-        self.handle_initialize(ctx).await?;
         Ok(())
     }
 
+    /// Receive messages from the node to send across the
+    /// `WebSocketStream` to the remote peer.
     async fn handle_message(
         &mut self,
         ctx: &mut Context,
         msg: Routed<Self::Message>,
     ) -> Result<()> {
-        self.handle_msg(ctx, msg).await
+        let recipient = msg.msg_addr();
+        if &recipient == self.addresses.sender_internal_address() {
+            let msg = WebSocketSendWorkerMsg::decode(msg.payload())?;
+
+            match msg {
+                WebSocketSendWorkerMsg::ConnectionClosed => {
+                    info!(
+                        "Stopping sender due to closed connection {}",
+                        self.socket_address
+                    );
+                    // No need to stop Receiver as it notified us about connection drop and will
+                    // stop itself
+                    self.rx_should_be_stopped = false;
+                    self.stop(ctx).await?;
+
+                    return Ok(());
+                }
+            }
+        } else {
+            let mut msg = msg.into_transport_message();
+            // Remove our own address from the route so the other end
+            // knows what to do with the incoming message
+            msg.onward_route.step()?;
+
+            let msg = msg.encode().map_err(|_| TransportError::SendBadMessage)?;
+            if self
+                .ws_sink
+                .send(WebSocketMessage::from(msg))
+                .await
+                .is_err()
+            {
+                warn!("Failed to send message to peer {}", self.socket_address);
+                self.stop(ctx).await?;
+
+                return Ok(());
+            }
+            debug!("Sent message to peer {}", self.socket_address);
+        }
+
+        Ok(())
     }
 }
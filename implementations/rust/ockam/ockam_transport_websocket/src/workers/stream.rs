@@ -1,12 +1,6 @@
 /// Type alias for `tokio_tungstenite::WebSocketStream`.
 pub(crate) type WebSocketStream<S> = tokio_tungstenite::WebSocketStream<S>;
 
-/// Stream created when a server accepts a new connection.
-pub(crate) type TcpServerStream = tokio::net::TcpStream;
-
-/// Stream created when a client connects to a server.
-pub(crate) type TcpClientStream = tokio_tungstenite::MaybeTlsStream<TcpServerStream>;
-
 /// Trait alias to define an AsyncStream returned
 /// when creating or accepting WebSocket connections.
 ///
@@ -17,6 +11,11 @@ pub(crate) trait AsyncStream:
 {
 }
 
-impl AsyncStream for TcpClientStream {}
+impl<T> AsyncStream for T where
+    T: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static + ?Sized
+{
+}
 
-impl AsyncStream for TcpServerStream {}
+/// Stream carrying a WebSocket connection, either a plain TCP stream,
+/// a TCP stream tunnelled through a proxy or a TLS stream.
+pub(crate) type ConnectionStream = Box<dyn AsyncStream>;
@@ -0,0 +1,128 @@
+use ockam_core::{route, Result, Routed, Worker};
+use ockam_node::Context;
+use ockam_transport_websocket::{
+    HttpProxy, WebSocketConnectionOptions, WebSocketListenerOptions, WebSocketTransport,
+};
+use std::net::SocketAddr;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Start a local stand-in for an HTTP proxy which accepts a single `CONNECT` request.
+///
+/// The proxy requires the given `Proxy-Authorization` header, if any,
+/// and returns the request it received.
+async fn start_proxy(
+    authorization: Option<&'static str>,
+) -> (SocketAddr, tokio::task::JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let handle = tokio::spawn(async move {
+        let (mut client, _) = listener.accept().await.unwrap();
+        let mut head = Vec::new();
+        while !head.ends_with(b"\r\n\r\n") {
+            head.push(client.read_u8().await.unwrap());
+        }
+        let head = String::from_utf8(head).unwrap();
+
+        if let Some(authorization) = authorization {
+            if !head.contains(&format!("\r\nProxy-Authorization: {authorization}\r\n")) {
+                client
+                    .write_all(b"HTTP/1.1 407 Proxy Authentication Required\r\n\r\n")
+                    .await
+                    .unwrap();
+                return head;
+            }
+        }
+
+        let target = head.split_whitespace().nth(1).unwrap().to_string();
+        let mut upstream = TcpStream::connect(target).await.unwrap();
+        client
+            .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+            .await
+            .unwrap();
+        tokio::spawn(async move {
+            let _ = tokio::io::copy_bidirectional(&mut client, &mut upstream).await;
+        });
+
+        head
+    });
+
+    (address, handle)
+}
+
+#[ockam_macros::test]
+async fn send_receive_through_proxy(ctx: &mut Context) -> Result<()> {
+    let options = WebSocketListenerOptions::new();
+    ctx.flow_controls()
+        .add_consumer("echoer", &options.spawner_flow_control_id());
+    ctx.start_worker("echoer", Echoer).await?;
+
+    let transport = WebSocketTransport::create(ctx).await?;
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+
+    let (proxy_address, proxy_task) = start_proxy(Some("Basic YWxpY2U6czNjcmV0")).await;
+    let proxy = HttpProxy::parse(&format!("http://alice:s3cret@{proxy_address}"))?;
+    let connection = transport
+        .connect(
+            listener.socket_string(),
+            WebSocketConnectionOptions::new().with_proxy(proxy),
+        )
+        .await?;
+    // The connection socket is the socket of the proxy
+    assert_eq!(connection.socket_address(), &proxy_address);
+
+    let msg = "Hello through a proxy".to_string();
+    let reply = ctx
+        .send_and_receive::<String>(route![connection, "echoer"], msg.clone())
+        .await?;
+    assert_eq!(reply, msg, "Should receive the same message");
+
+    let request = proxy_task.await.unwrap();
+    assert!(request.starts_with(&format!(
+        "CONNECT {} HTTP/1.1\r\n",
+        listener.socket_string()
+    )));
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn proxy_authentication_failure(ctx: &mut Context) -> Result<()> {
+    let transport = WebSocketTransport::create(ctx).await?;
+    let listener = transport
+        .listen("127.0.0.1:0", WebSocketListenerOptions::new())
+        .await?;
+
+    let (proxy_address, _proxy) = start_proxy(Some("Basic YWxpY2U6czNjcmV0")).await;
+    let proxy = HttpProxy::new(proxy_address.to_string()).with_basic_auth("alice", "wrong");
+    let res = transport
+        .connect(
+            listener.socket_string(),
+            WebSocketConnectionOptions::new().with_proxy(proxy),
+        )
+        .await;
+    assert!(res.is_err());
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+pub struct Echoer;
+
+#[ockam_core::worker]
+impl Worker for Echoer {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        ctx.send(msg.return_route(), msg.body()).await
+    }
+}
@@ -1,15 +1,23 @@
+use core::time::Duration;
 use ockam_core::compat::rand::{self, Rng};
 use ockam_core::{route, Result, Routed, Worker};
 use ockam_node::Context;
-use ockam_transport_websocket::{WebSocketTransport, WS};
+use ockam_transport_websocket::{
+    WebSocketConnectionOptions, WebSocketListenerOptions, WebSocketTransport, WS,
+};
+use tokio::io::AsyncReadExt;
+use tokio::net::TcpListener;
 
-#[ignore]
 #[ockam_macros::test]
 async fn send_receive(ctx: &mut Context) -> Result<()> {
-    let transport = WebSocketTransport::create(ctx).await?;
-    let listener_address = transport.listen("127.0.0.1:0").await?;
+    let options = WebSocketListenerOptions::new();
+    ctx.flow_controls()
+        .add_consumer("echoer", &options.spawner_flow_control_id());
     ctx.start_worker("echoer", Echoer).await?;
 
+    let transport = WebSocketTransport::create(ctx).await?;
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+
     // Sender
     {
         let msg: String = rand::thread_rng()
@@ -17,7 +25,10 @@ async fn send_receive(ctx: &mut Context) -> Result<()> {
             .take(256)
             .map(char::from)
             .collect();
-        let r = route![(WS, listener_address.to_string()), "echoer"];
+        let connection = transport
+            .connect(listener.socket_string(), WebSocketConnectionOptions::new())
+            .await?;
+        let r = route![connection, "echoer"];
         let reply = ctx.send_and_receive::<String>(r, msg.clone()).await?;
 
         assert_eq!(reply, msg, "Should receive the same message");
@@ -30,6 +41,135 @@ async fn send_receive(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn send_receive_over_resolved_route(ctx: &mut Context) -> Result<()> {
+    let options = WebSocketListenerOptions::new();
+    ctx.flow_controls()
+        .add_consumer("echoer", &options.spawner_flow_control_id());
+    ctx.start_worker("echoer", Echoer).await?;
+
+    let transport = WebSocketTransport::create(ctx).await?;
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+
+    let r = ctx
+        .resolve_transport_route(route![(WS, listener.socket_string()), "echoer"])
+        .await?;
+    let msg = "Hello over WebSocket".to_string();
+    let reply = ctx.send_and_receive::<String>(r, msg.clone()).await?;
+    assert_eq!(reply, msg, "Should receive the same message");
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn disconnect_and_stop_listener(ctx: &mut Context) -> Result<()> {
+    let transport = WebSocketTransport::create(ctx).await?;
+    let listener = transport
+        .listen("127.0.0.1:0", WebSocketListenerOptions::new())
+        .await?;
+    let connection = transport
+        .connect(listener.socket_string(), WebSocketConnectionOptions::new())
+        .await?;
+    ctx.sleep(Duration::from_millis(250)).await;
+
+    assert_eq!(transport.registry().get_all_listeners().len(), 1);
+    // One sender for the outgoing connection and one for the accepted connection
+    assert_eq!(transport.registry().get_all_sender_workers().len(), 2);
+
+    transport
+        .disconnect(connection.sender_address().clone())
+        .await?;
+    transport
+        .stop_listener(listener.processor_address())
+        .await?;
+    ctx.sleep(Duration::from_millis(250)).await;
+
+    assert!(transport.registry().get_all_listeners().is_empty());
+    assert!(transport.registry().get_all_sender_workers().is_empty());
+    assert!(transport
+        .registry()
+        .get_all_receiver_processors()
+        .is_empty());
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn listener_path_prefix(ctx: &mut Context) -> Result<()> {
+    let options = WebSocketListenerOptions::new().with_path_prefix("/ockam");
+    ctx.flow_controls()
+        .add_consumer("echoer", &options.spawner_flow_control_id());
+    ctx.start_worker("echoer", Echoer).await?;
+
+    let transport = WebSocketTransport::create(ctx).await?;
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+
+    // Handshakes for other paths are rejected
+    for path in ["", "/", "/other", "/ockamx"] {
+        let url = format!("ws://{}{path}", listener.socket_string());
+        let res = transport
+            .connect(url, WebSocketConnectionOptions::new())
+            .await;
+        assert!(res.is_err(), "{path} should be rejected");
+    }
+
+    let url = format!("ws://{}/ockam/node", listener.socket_string());
+    let connection = transport
+        .connect(url, WebSocketConnectionOptions::new())
+        .await?;
+    let msg = "Hello over a routed path".to_string();
+    let reply = ctx
+        .send_and_receive::<String>(route![connection, "echoer"], msg.clone())
+        .await?;
+    assert_eq!(reply, msg, "Should receive the same message");
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn origin_header_is_sent(ctx: &mut Context) -> Result<()> {
+    // Record the opening handshake and close the connection
+    let server = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let server_address = server.local_addr().unwrap();
+    let handshake = tokio::spawn(async move {
+        let (mut stream, _) = server.accept().await.unwrap();
+        let mut head = Vec::new();
+        while !head.ends_with(b"\r\n\r\n") {
+            head.push(stream.read_u8().await.unwrap());
+        }
+        String::from_utf8(head).unwrap().to_lowercase()
+    });
+
+    let transport = WebSocketTransport::create(ctx).await?;
+    let options = WebSocketConnectionOptions::new().with_origin("https://app.example.com");
+    let res = transport
+        .connect(format!("ws://{server_address}/ockam"), options)
+        .await;
+    assert!(res.is_err());
+
+    let handshake = handshake.await.unwrap();
+    assert!(handshake.starts_with("get /ockam http/1.1\r\n"));
+    assert!(handshake.contains("\r\norigin: https://app.example.com\r\n"));
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
 pub struct Echoer;
 
 #[ockam_core::worker]
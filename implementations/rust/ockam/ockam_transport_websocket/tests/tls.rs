@@ -0,0 +1,97 @@
+#![cfg(feature = "tls")]
+
+use ockam_core::{route, Result, Routed, Worker};
+use ockam_node::Context;
+use ockam_transport_websocket::{
+    TlsClientOptions, TlsServerOptions, WebSocketConnectionOptions, WebSocketListenerOptions,
+    WebSocketTransport,
+};
+
+const CA: &[u8] = include_bytes!("../../ockam_transport_core/tests/certificates/ca.pem");
+const SERVER_CERTIFICATE: &[u8] =
+    include_bytes!("../../ockam_transport_core/tests/certificates/server.pem");
+const SERVER_KEY: &[u8] =
+    include_bytes!("../../ockam_transport_core/tests/certificates/server.key");
+const CLIENT_CERTIFICATE: &[u8] =
+    include_bytes!("../../ockam_transport_core/tests/certificates/client.pem");
+const CLIENT_KEY: &[u8] =
+    include_bytes!("../../ockam_transport_core/tests/certificates/client.key");
+
+pub struct Echoer;
+
+#[ockam_core::worker]
+impl Worker for Echoer {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        ctx.send(msg.return_route(), msg.body()).await
+    }
+}
+
+#[ockam_macros::test]
+async fn send_receive_over_wss(ctx: &mut Context) -> Result<()> {
+    let tls =
+        TlsServerOptions::new(SERVER_CERTIFICATE, SERVER_KEY)?.with_client_authentication(CA)?;
+    let options = WebSocketListenerOptions::new()
+        .with_tls(tls)
+        .with_path_prefix("/ockam");
+    ctx.flow_controls()
+        .add_consumer("echoer", &options.spawner_flow_control_id());
+    ctx.start_worker("echoer", Echoer).await?;
+
+    let transport = WebSocketTransport::create(ctx).await?;
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+
+    // The server certificate is valid for "localhost"
+    let url = format!("wss://localhost:{}/ockam", listener.socket_address().port());
+    let tls = TlsClientOptions::new()
+        .with_root_certificates(CA)?
+        .with_client_certificate(CLIENT_CERTIFICATE, CLIENT_KEY)?;
+    let connection = transport
+        .connect(url, WebSocketConnectionOptions::new().with_tls(tls))
+        .await?;
+
+    let msg = "Hello over wss".to_string();
+    let reply = ctx
+        .send_and_receive::<String>(route![connection, "echoer"], msg.clone())
+        .await?;
+    assert_eq!(reply, msg, "Should receive the same message");
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn untrusted_server_is_rejected(ctx: &mut Context) -> Result<()> {
+    let tls = TlsServerOptions::new(SERVER_CERTIFICATE, SERVER_KEY)?;
+    let transport = WebSocketTransport::create(ctx).await?;
+    let listener = transport
+        .listen("127.0.0.1:0", WebSocketListenerOptions::new().with_tls(tls))
+        .await?;
+
+    // The client does not trust the CA which issued the server certificate
+    let tls = TlsClientOptions::new();
+    let res = transport
+        .connect(
+            listener.socket_string(),
+            WebSocketConnectionOptions::new().with_tls(tls),
+        )
+        .await;
+    assert!(res.is_err());
+
+    // A plain WebSocket connection can not be established either
+    let res = transport
+        .connect(listener.socket_string(), WebSocketConnectionOptions::new())
+        .await;
+    assert!(res.is_err());
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}